@@ -1,5 +1,6 @@
 pub mod media;
 pub mod once_off;
+pub mod osm;
 pub mod poi;
 pub mod ride;
 pub mod route;
@@ -8,6 +9,7 @@ pub mod trip;
 pub mod user;
 
 pub use media::MediaCommands;
+pub use osm::OsmCommands;
 pub use poi::POICommands;
 pub use ride::RideCommands;
 pub use route::RouteCommands;
@@ -11,11 +11,20 @@ pub async fn handle(
                 user_repo,
                 ride_repo,
                 ride_points_repo,
+                ride_localities_repo,
+                ride_sensors_repo,
+                ride_weather_repo,
                 trip_repo,
                 media_repo,
+                osm_feature_repo,
                 route_repo,
+                route_course_points_repo,
                 route_points_repo,
+                route_localities_repo,
+                sync_audit_entry_repo,
                 point_of_interest_repo,
+                point_of_interest_visit_repo,
+                water_beta_repo,
             },
         job_storage,
     }: Context,
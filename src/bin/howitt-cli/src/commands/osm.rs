@@ -0,0 +1,138 @@
+use std::{
+    collections::{HashMap, HashSet},
+    path::PathBuf,
+};
+
+use clap::{Args, Subcommand};
+use howitt::{
+    models::osm_feature::{OsmFeature, OsmFeatureId, OsmFeatureType},
+    repos::Repo,
+    services::import::{osm_feature_from_element, osm_way_geometry},
+};
+use howitt_postgresql::PostgresRepos;
+use osmpbf::{Element, ElementReader};
+
+use crate::Context;
+
+const UPSERT_BATCH_SIZE: usize = 1_000;
+
+#[derive(Subcommand)]
+pub enum OsmCommands {
+    Import(OsmImportArgs),
+}
+
+#[derive(Args)]
+pub struct OsmImportArgs {
+    /// Path to an OSM PBF extract, e.g. from Geofabrik
+    file: PathBuf,
+}
+
+struct PendingWay {
+    id: OsmFeatureId,
+    refs: Vec<i64>,
+    tags: HashMap<String, String>,
+}
+
+/// Reads whitelisted features from a PBF extract. Nodes carry their own
+/// coordinates, but ways only reference nodes, so a second pass resolves the
+/// node positions for any whitelisted ways.
+fn read_osm_features(file: &PathBuf) -> Result<Vec<OsmFeature>, anyhow::Error> {
+    let mut features = vec![];
+    let mut pending_ways = vec![];
+
+    ElementReader::from_path(file)?.for_each(|element| match element {
+        Element::Node(node) => features.extend(osm_feature_from_element(
+            OsmFeatureId::node(node.id()),
+            geo::Geometry::Point(geo::Point::new(node.lon(), node.lat())),
+            node.tags(),
+        )),
+        Element::DenseNode(node) => features.extend(osm_feature_from_element(
+            OsmFeatureId::node(node.id()),
+            geo::Geometry::Point(geo::Point::new(node.lon(), node.lat())),
+            node.tags(),
+        )),
+        Element::Way(way) => {
+            let tags: HashMap<String, String> = way
+                .tags()
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .collect();
+
+            if OsmFeatureType::from_tags(&tags).is_some() {
+                pending_ways.push(PendingWay {
+                    id: OsmFeatureId::way(way.id()),
+                    refs: way.refs().collect(),
+                    tags,
+                });
+            }
+        }
+        Element::Relation(_) => {}
+    })?;
+
+    if pending_ways.is_empty() {
+        return Ok(features);
+    }
+
+    let wanted: HashSet<i64> = pending_ways
+        .iter()
+        .flat_map(|way| way.refs.iter().copied())
+        .collect();
+
+    let mut node_points = HashMap::new();
+
+    ElementReader::from_path(file)?.for_each(|element| match element {
+        Element::Node(node) if wanted.contains(&node.id()) => {
+            node_points.insert(node.id(), geo::Point::new(node.lon(), node.lat()));
+        }
+        Element::DenseNode(node) if wanted.contains(&node.id()) => {
+            node_points.insert(node.id(), geo::Point::new(node.lon(), node.lat()));
+        }
+        _ => {}
+    })?;
+
+    for PendingWay { id, refs, tags } in pending_ways {
+        let Some(geometry) = osm_way_geometry(&refs, &node_points) else {
+            println!("Skipping {id}, nodes missing from extract");
+            continue;
+        };
+
+        features.extend(osm_feature_from_element(
+            id,
+            geometry,
+            tags.iter()
+                .map(|(key, value)| (key.as_str(), value.as_str())),
+        ));
+    }
+
+    Ok(features)
+}
+
+pub async fn handle(
+    command: &OsmCommands,
+    Context {
+        repos: PostgresRepos {
+            osm_feature_repo, ..
+        },
+        ..
+    }: Context,
+) -> Result<(), anyhow::Error> {
+    match command {
+        OsmCommands::Import(OsmImportArgs { file }) => {
+            let features = read_osm_features(file)?;
+            let total = features.len();
+
+            for (index, chunk) in features.chunks(UPSERT_BATCH_SIZE).enumerate() {
+                osm_feature_repo.put_batch(chunk.to_vec()).await?;
+
+                println!(
+                    "Upserted {}/{} features",
+                    (index * UPSERT_BATCH_SIZE + chunk.len()),
+                    total
+                );
+            }
+
+            println!("Imported {total} OSM features");
+
+            Ok(())
+        }
+    }
+}
@@ -1,21 +1,43 @@
-use clap::Subcommand;
-use howitt::repos::Repo;
+use std::{path::PathBuf, sync::Arc};
+
+use clap::{Args, Subcommand};
+use derive_more::derive::{Display, From, Into};
+use gtfs::GtfsArchive;
+use howitt::{
+    models::user::User,
+    repos::Repo,
+    services::import::{import_gtfs_stops, ImportGtfsStopsParams},
+};
 use howitt_postgresql::PostgresRepos;
+use inquire::Select;
+use itertools::Itertools;
 
 use crate::Context;
 
 #[derive(Subcommand)]
 pub enum POICommands {
     List,
+    ImportGtfs(POIImportGtfsArgs),
+}
+
+#[derive(Args)]
+pub struct POIImportGtfsArgs {
+    file: PathBuf,
+    /// Name of the feed the stop ids belong to, e.g. "vline". Must be the same
+    /// on every import of a feed so stops are matched up.
+    #[arg(long)]
+    feed: String,
 }
 
 pub async fn handle(
     command: &POICommands,
     Context {
-        repos: PostgresRepos {
-            point_of_interest_repo,
-            ..
-        },
+        repos:
+            PostgresRepos {
+                point_of_interest_repo,
+                user_repo,
+                ..
+            },
         ..
     }: Context,
 ) -> Result<(), anyhow::Error> {
@@ -25,5 +47,40 @@ pub async fn handle(
             dbg!(pois);
             Ok(())
         }
+        POICommands::ImportGtfs(POIImportGtfsArgs { file, feed }) => {
+            #[derive(Display, From, Into)]
+            #[display("{}", _0.username)]
+            struct UserOption(User);
+
+            let users = user_repo.all().await?;
+
+            let UserOption(user) = Select::new(
+                "Select user:",
+                users.into_iter().map(UserOption).collect_vec(),
+            )
+            .prompt()?;
+
+            let mut archive =
+                GtfsArchive::new(std::io::BufReader::new(std::fs::File::open(file)?))?;
+            let stops = archive.stops()?.collect::<Result<Vec<_>, _>>()?;
+
+            let summary = import_gtfs_stops(ImportGtfsStopsParams {
+                point_of_interest_repo: Arc::new(point_of_interest_repo),
+                user_id: user.id,
+                feed: feed.clone(),
+                stops,
+            })
+            .await?;
+
+            println!(
+                "Imported {} stops: {} created, {} updated, {} unchanged",
+                summary.created + summary.updated + summary.unchanged,
+                summary.created,
+                summary.updated,
+                summary.unchanged
+            );
+
+            Ok(())
+        }
     }
 }
@@ -1,10 +1,19 @@
+use std::{path::PathBuf, sync::Arc};
+
 use chrono_tz::Australia::Melbourne;
 use clap::{Args, Subcommand};
+use derive_more::derive::{Display, From, Into};
 use howitt::{
+    models::user::User,
     repos::AnyhowRepo,
-    services::simplify_points::{simplify_points_v2, DetailLevel},
+    services::{
+        import::{import_fit_ride, import_gpx_ride, ImportFitRideParams, ImportGpxRideParams},
+        simplify_points::{simplify_points_v2, DetailLevel},
+    },
 };
 use howitt_postgresql::PostgresRepos;
+use inquire::Select;
+use itertools::Itertools;
 use serde_json::json;
 
 use crate::Context;
@@ -14,6 +23,7 @@ pub enum RideCommands {
     List,
     Detail(RideDetailArgs),
     PreviewPoints(RideDetailArgs),
+    Import(RideImportArgs),
 }
 
 #[derive(Args)]
@@ -21,12 +31,24 @@ pub struct RideDetailArgs {
     ride_id: String,
 }
 
+#[derive(Args)]
+pub struct RideImportArgs {
+    file: PathBuf,
+    #[arg(long)]
+    name: Option<String>,
+}
+
 pub async fn handle(
     command: &RideCommands,
     Context {
-        repos: PostgresRepos {
-            ride_points_repo, ..
-        },
+        repos:
+            PostgresRepos {
+                ride_repo,
+                ride_points_repo,
+                ride_sensors_repo,
+                user_repo,
+                ..
+            },
         ..
     }: Context,
 ) -> Result<(), anyhow::Error> {
@@ -54,6 +76,49 @@ pub async fn handle(
 
             Ok(())
         }
+        RideCommands::Import(RideImportArgs { file, name }) => {
+            #[derive(Display, From, Into)]
+            #[display("{}", _0.username)]
+            struct UserOption(User);
+
+            let users = user_repo.all().await?;
+
+            let UserOption(user) = Select::new(
+                "Select user:",
+                users.into_iter().map(UserOption).collect_vec(),
+            )
+            .prompt()?;
+
+            let data = std::io::BufReader::new(std::fs::File::open(file)?);
+
+            let ride = match file.extension().and_then(|ext| ext.to_str()) {
+                Some("fit") => {
+                    import_fit_ride(ImportFitRideParams {
+                        ride_repo: Arc::new(ride_repo),
+                        ride_points_repo: Arc::new(ride_points_repo),
+                        ride_sensors_repo: Arc::new(ride_sensors_repo),
+                        user_id: user.id,
+                        name: name.clone(),
+                        data,
+                    })
+                    .await?
+                }
+                _ => {
+                    import_gpx_ride(ImportGpxRideParams {
+                        ride_repo: Arc::new(ride_repo),
+                        ride_points_repo: Arc::new(ride_points_repo),
+                        user_id: user.id,
+                        name: name.clone(),
+                        data,
+                    })
+                    .await?
+                }
+            };
+
+            println!("Imported ride {} ({})", ride.name, ride.id);
+
+            Ok(())
+        }
         _ => Ok(()), // Placeholder - implement actual handlers
     }
 }
@@ -1,14 +1,25 @@
+use std::{path::PathBuf, sync::Arc};
+
 use clap::{Args, Subcommand};
+use derive_more::derive::{Display, From, Into};
 use description::generate_description;
 use howitt::{
-    models::route::{RouteFilter, RouteId},
+    models::{
+        course_point::RouteCoursePointsFilter,
+        route::{RouteFilter, RouteId},
+        user::User,
+    },
     repos::Repo,
     services::{
+        export::{export_document, ExportDocument, ExportFormat},
         generate_cuesheet::generate_cuesheet,
+        import::{import_gpx_route, ImportGpxRouteParams},
         simplify_points::{simplify_points_v2, DetailLevel},
+        water_resupply::{plan_water_resupply, WaterConsumptionModel},
     },
 };
 use howitt_postgresql::PostgresRepos;
+use inquire::Select;
 use itertools::Itertools;
 use prettytable::{row, Table};
 use uuid::Uuid;
@@ -24,6 +35,9 @@ pub enum RouteCommands {
     Detail(RouteDetailArgs),
     GenerateCuesheet(RouteDetailArgs),
     GenerateDescription,
+    Import(RouteImportArgs),
+    Export(RouteExportArgs),
+    WaterPlan(RouteWaterPlanArgs),
 }
 
 #[derive(Args)]
@@ -31,6 +45,39 @@ pub struct RouteDetailArgs {
     route_id: String,
 }
 
+#[derive(Args)]
+pub struct RouteExportArgs {
+    route_id: String,
+    /// One of gpx, geojson or kml
+    #[arg(long, default_value = "gpx")]
+    format: String,
+    /// Defaults to writing to stdout
+    #[arg(long)]
+    output: Option<PathBuf>,
+}
+
+#[derive(Args)]
+pub struct RouteWaterPlanArgs {
+    route_id: String,
+    /// Litres of water carried
+    #[arg(long)]
+    capacity: f64,
+    #[arg(long, default_value_t = 0.6)]
+    litres_per_hour: f64,
+    #[arg(long, default_value_t = 15.0)]
+    speed_kmh: f64,
+    /// Extra riding time for every 100m climbed
+    #[arg(long, default_value_t = 10.0)]
+    minutes_per_100m_climb: f64,
+}
+
+#[derive(Args)]
+pub struct RouteImportArgs {
+    file: PathBuf,
+    #[arg(long)]
+    name: Option<String>,
+}
+
 pub async fn handle(
     command: &RouteCommands,
     Context {
@@ -38,7 +85,9 @@ pub async fn handle(
             PostgresRepos {
                 route_repo,
                 route_points_repo,
+                route_course_points_repo,
                 point_of_interest_repo,
+                user_repo,
                 ..
             },
         ..
@@ -64,11 +113,68 @@ pub async fn handle(
             let route_points = route_points_repo.get(route_id).await?;
             let points = route_points.iter_elevation_points().cloned().collect_vec();
             let pois = point_of_interest_repo.all().await?;
+            let course_points = route_course_points_repo
+                .find_model(RouteCoursePointsFilter::Ids(vec![route_id]))
+                .await?
+                .map(|route_course_points| route_course_points.course_points)
+                .unwrap_or_default();
 
-            let cuesheet = generate_cuesheet(&points, &pois);
+            let cuesheet = generate_cuesheet(&points, &pois, &course_points);
             dbg!(cuesheet);
             Ok(())
         }
+        RouteCommands::WaterPlan(RouteWaterPlanArgs {
+            route_id,
+            capacity,
+            litres_per_hour,
+            speed_kmh,
+            minutes_per_100m_climb,
+        }) => {
+            let route_id = RouteId::from(Uuid::parse_str(route_id)?);
+            let route_points = route_points_repo.get(route_id).await?;
+            let points = route_points.iter_elevation_points().cloned().collect_vec();
+            let pois = point_of_interest_repo.all().await?;
+
+            let plan = plan_water_resupply(
+                &points,
+                &pois,
+                *capacity,
+                &WaterConsumptionModel {
+                    litres_per_hour: *litres_per_hour,
+                    speed_kmh: *speed_kmh,
+                    minutes_per_100m_climb: *minutes_per_100m_climb,
+                },
+            );
+
+            let mut table = Table::new();
+            table.add_row(row!["from", "to", r->"km", r->"climb m", r->"litres", r->"dry at km"]);
+
+            for segment in plan.segments.iter() {
+                let dry_at = segment
+                    .runs_dry_at_m
+                    .map(|distance_m| format!("{:.1}", distance_m / 1000.0))
+                    .unwrap_or_default();
+
+                table.add_row(row![
+                    segment.origin,
+                    segment.destination,
+                    r->format!("{:.1}", segment.summary.distance_m / 1000.0),
+                    r->format!("{:.0}", segment.summary.data.elevation_ascent_m),
+                    r->format!("{:.1}", segment.litres_needed),
+                    r->dry_at
+                ]);
+            }
+
+            table.printstd();
+
+            println!(
+                "{} of {} segments would run dry carrying {capacity}l",
+                plan.dry_segments().count(),
+                plan.segments.len()
+            );
+
+            Ok(())
+        }
         RouteCommands::ListStarred => {
             let routes = route_repo.filter_models(RouteFilter::Starred).await?;
 
@@ -87,6 +193,54 @@ pub async fn handle(
             table.printstd();
             Ok(())
         }
+        RouteCommands::Import(RouteImportArgs { file, name }) => {
+            #[derive(Display, From, Into)]
+            #[display("{}", _0.username)]
+            struct UserOption(User);
+
+            let users = user_repo.all().await?;
+
+            let UserOption(user) = Select::new(
+                "Select user:",
+                users.into_iter().map(UserOption).collect_vec(),
+            )
+            .prompt()?;
+
+            let route = import_gpx_route(ImportGpxRouteParams {
+                route_repo: Arc::new(route_repo),
+                route_points_repo: Arc::new(route_points_repo),
+                user_id: user.id,
+                name: name.clone(),
+                data: std::io::BufReader::new(std::fs::File::open(file)?),
+            })
+            .await?;
+
+            println!("Imported route {} ({})", route.name, route.id);
+
+            Ok(())
+        }
+        RouteCommands::Export(RouteExportArgs {
+            route_id,
+            format,
+            output,
+        }) => {
+            let format = ExportFormat::from_extension(format)
+                .ok_or_else(|| anyhow::anyhow!("Unsupported export format: {format}"))?;
+
+            let route_id = RouteId::from(Uuid::parse_str(route_id)?);
+            let route = route_repo.get(route_id).await?;
+            let route_points = route_points_repo.get(route_id).await?;
+
+            let exported =
+                export_document(&ExportDocument::from_route(&route, &route_points), format)?;
+
+            match output {
+                Some(output) => std::fs::write(output, exported)?,
+                None => println!("{exported}"),
+            }
+
+            Ok(())
+        }
         RouteCommands::List => {
             let routes = route_repo.all().await?;
             dbg!(routes);
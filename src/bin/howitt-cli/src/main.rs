@@ -10,7 +10,8 @@ mod commands;
 mod utils;
 
 use commands::{
-    MediaCommands, POICommands, RideCommands, RouteCommands, TripCommands, UserCommands,
+    MediaCommands, OsmCommands, POICommands, RideCommands, RouteCommands, TripCommands,
+    UserCommands,
 };
 
 #[derive(Parser)]
@@ -37,6 +38,8 @@ enum Commands {
     Trip(TripCommands),
     #[clap(subcommand)]
     Media(MediaCommands),
+    #[clap(subcommand)]
+    Osm(OsmCommands),
     OnceOff,
 }
 
@@ -82,6 +85,7 @@ async fn main() -> Result<(), anyhow::Error> {
         Commands::Rwgps(cmd) => commands::rwgps::handle(cmd, context).await?,
         Commands::Trip(cmd) => commands::trip::handle(cmd, context).await?,
         Commands::Media(cmd) => commands::media::handle(cmd, context).await?,
+        Commands::Osm(cmd) => commands::osm::handle(cmd, context).await?,
         Commands::OnceOff => commands::once_off::handle(context).await?,
     }
 
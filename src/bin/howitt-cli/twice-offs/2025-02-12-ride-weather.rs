@@ -87,8 +87,8 @@ pub async fn handle(
                 HourlyVariable::WindDirection10m,
             ]),
             daily: None,
-            latitude: point.as_geo_point().y(),
-            longitude: point.as_geo_point().x(),
+            latitude: vec![point.as_geo_point().y()],
+            longitude: vec![point.as_geo_point().x()],
             temperature_unit: None,
             wind_speed_unit: None,
             timeformat: None,
@@ -98,7 +98,11 @@ pub async fn handle(
         let weather = client.get_historical_weather(params).await?;
 
         // Find matching hour in response
-        if let Some(hourly) = weather.hourly {
+        if let Some(hourly) = weather
+            .into_iter()
+            .next()
+            .and_then(|weather| weather.hourly)
+        {
             for (i, time) in hourly.time.iter().enumerate() {
                 let weather_time = chrono::NaiveDateTime::parse_from_str(time, "%Y-%m-%dT%H:%M")
                     .unwrap()
@@ -116,7 +120,7 @@ pub async fn handle(
                         hourly
                             .temperature_2m
                             .as_ref()
-                            .map(|t| t[i])
+                            .and_then(|t| t[i])
                             .unwrap_or_default()
                     );
                     println!(
@@ -124,7 +128,7 @@ pub async fn handle(
                         hourly
                             .relative_humidity_2m
                             .as_ref()
-                            .map(|h| h[i])
+                            .and_then(|h| h[i])
                             .unwrap_or_default()
                     );
                     println!(
@@ -132,7 +136,7 @@ pub async fn handle(
                         hourly
                             .wind_speed_10m
                             .as_ref()
-                            .map(|w| w[i])
+                            .and_then(|w| w[i])
                             .unwrap_or_default()
                     );
                     println!(
@@ -140,7 +144,7 @@ pub async fn handle(
                         hourly
                             .wind_direction_10m
                             .as_ref()
-                            .map(|d| d[i])
+                            .and_then(|d| d[i])
                             .unwrap_or_default()
                     );
                     println!();
@@ -1,14 +1,21 @@
+use std::sync::Arc;
+
 use async_graphql::dataloader::DataLoader;
 use howitt::{
     jobs::Job,
     repos::Repos,
     services::{
-        fetchers::{SimplifiedRidePointsFetcher, SimplifiedTripElevationPointsFetcher},
+        fetchers::{
+            ElevationFetcher, SimplifiedRidePointsFetcher, SimplifiedTripElevationPointsFetcher,
+        },
+        transport_access::TransportTimetables,
         user::auth::{Login, UserAuthService},
     },
 };
-use howitt_clients::RedisClient;
+use howitt_clients::{RedisClient, S3BucketClient};
 use howitt_jobs::storage::LockFreeStorage;
+use mapbox_geocoding::client::MapboxGeocodingClient;
+use open_meteo::OpenMeteoClient;
 use tzf_rs::DefaultFinder;
 
 use super::loaders::{
@@ -19,6 +26,7 @@ pub struct SchemaData {
     pub repos: Repos,
     pub simplified_ride_points_fetcher: SimplifiedRidePointsFetcher<RedisClient>,
     pub simplified_trip_elevation_points_fetcher: SimplifiedTripElevationPointsFetcher<RedisClient>,
+    pub elevation_fetcher: ElevationFetcher<RedisClient>,
     pub ride_loader: DataLoader<RideLoader>,
     pub user_loader: DataLoader<UserLoader>,
     pub route_points_loader: DataLoader<RoutePointsLoader>,
@@ -26,6 +34,10 @@ pub struct SchemaData {
     pub user_auth_service: UserAuthService,
     pub job_storage: LockFreeStorage<Job>,
     pub tz_finder: DefaultFinder,
+    pub transport_timetables: Arc<TransportTimetables>,
+    pub open_meteo_client: OpenMeteoClient,
+    pub mapbox_geocoding_client: MapboxGeocodingClient,
+    pub bucket_client: Arc<S3BucketClient>,
 }
 
 pub struct RequestData {
@@ -0,0 +1,50 @@
+use async_graphql::connection::{Connection, Edge};
+use async_graphql::OutputType;
+use howitt::models::filters::{Page, PageRequest};
+
+use super::ModelId;
+
+/// Upper bound on `first`/`last`, also used when neither is given.
+pub const MAX_PAGE_SIZE: usize = 100;
+
+pub type ModelConnection<ID, Node> = Connection<ModelId<ID>, Node>;
+
+pub fn page_request<ID: howitt::models::ModelId>(
+    after: Option<ModelId<ID>>,
+    before: Option<ModelId<ID>>,
+    first: Option<usize>,
+    last: Option<usize>,
+) -> PageRequest<ID> {
+    // Unbounded requests get the first page rather than everything
+    let first = match (first, last) {
+        (None, None) => Some(MAX_PAGE_SIZE),
+        (first, _) => first.map(|first| first.min(MAX_PAGE_SIZE)),
+    };
+
+    PageRequest {
+        after: after.map(|id| id.0),
+        before: before.map(|id| id.0),
+        first,
+        last: last.map(|last| last.min(MAX_PAGE_SIZE)),
+    }
+}
+
+pub fn model_connection<ID, T, Node>(
+    page: Page<T>,
+    id: impl Fn(&T) -> ID,
+    node: impl Fn(T) -> Node,
+) -> ModelConnection<ID, Node>
+where
+    ID: howitt::models::ModelId + serde::de::DeserializeOwned,
+    Node: OutputType,
+{
+    let mut connection = Connection::new(page.has_previous_page, page.has_next_page);
+
+    connection.edges.extend(
+        page.items
+            .into_iter()
+            .map(|item| Edge::new(ModelId(id(&item)), node(item))),
+    );
+
+    connection
+}
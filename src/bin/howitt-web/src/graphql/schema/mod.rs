@@ -1,5 +1,6 @@
 use super::context::SchemaData;
 
+pub mod connection;
 pub mod interfaces;
 pub mod mutation;
 pub mod objects;
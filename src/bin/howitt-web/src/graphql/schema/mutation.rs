@@ -3,14 +3,30 @@ use chrono::{DateTime, Datelike, Utc};
 use howitt::jobs::rwgps::RwgpsJob;
 use howitt::jobs::Job;
 use howitt::models::media::MediaId;
+use howitt::models::osm_feature::OsmFeatureId;
 use howitt::models::point_of_interest::{PointOfInterest as PoiModel, PointOfInterestId};
+use howitt::models::point_of_interest_visit::{
+    PointOfInterestVisit as PoiVisitModel, PointOfInterestVisitId, VisitConfirmation, VisitEdit,
+};
 use howitt::models::ride::{RideFilter, RideId};
-use howitt::models::trip::{Trip as TripModel, TripId, TripNote};
+use howitt::models::trip::{Trip as TripModel, TripFilter, TripId, TripNote};
+use howitt::models::user::UserId;
+use howitt::models::water_beta::{WaterBeta as WaterBetaModel, WaterBetaId};
 use howitt::repos::Repos;
+use howitt::services::media::media_object_keys;
 use howitt::services::slug::generate_slug;
+use howitt_client_types::BucketClient;
 
 use crate::graphql::context::{RequestData, SchemaData};
-use crate::graphql::schema::{point_of_interest::PointOfInterest, trip::Trip, ModelId};
+use crate::graphql::schema::{
+    point_of_interest::PointOfInterest,
+    point_of_interest_visit::{
+        POICondition, PointOfInterestVisit, VisitConfirmation as VisitConfirmationEnum,
+    },
+    trip::Trip,
+    water_beta::{WaterBeta, WaterFlowStatus},
+    ModelId,
+};
 
 use super::point_of_interest::PointOfInterestType;
 use super::viewer::Viewer;
@@ -96,6 +112,115 @@ pub struct UpdatePointOfInterestOutput {
     pub point_of_interest: Option<PointOfInterest>,
 }
 
+#[derive(InputObject)]
+pub struct CreatePointOfInterestVisitInput {
+    pub point_of_interest_id: ModelId<PointOfInterestId>,
+    pub visited_at: DateTime<Utc>,
+    pub condition: Option<POICondition>,
+    pub comment: Option<String>,
+    pub media_ids: Vec<ModelId<MediaId>>,
+}
+
+#[derive(InputObject)]
+pub struct UpdatePointOfInterestVisitInput {
+    pub visit_id: ModelId<PointOfInterestVisitId>,
+    pub visited_at: DateTime<Utc>,
+    pub condition: Option<POICondition>,
+    pub comment: Option<String>,
+    pub media_ids: Vec<ModelId<MediaId>>,
+}
+
+#[derive(InputObject)]
+pub struct ConfirmPointOfInterestVisitInput {
+    pub visit_id: ModelId<PointOfInterestVisitId>,
+    pub confirmation: VisitConfirmationEnum,
+}
+
+#[derive(SimpleObject)]
+pub struct PointOfInterestVisitOutput {
+    pub point_of_interest_visit: PointOfInterestVisit,
+}
+
+#[derive(InputObject)]
+pub struct ReportWaterBetaInput {
+    pub osm_feature_id: ModelId<OsmFeatureId>,
+    pub observed_at: DateTime<Utc>,
+    pub flow_status: WaterFlowStatus,
+    pub treatment_needed: bool,
+    pub comment: Option<String>,
+    pub media_ids: Vec<ModelId<MediaId>>,
+}
+
+#[derive(InputObject)]
+pub struct UpdateWaterBetaInput {
+    pub water_beta_id: ModelId<WaterBetaId>,
+    pub observed_at: DateTime<Utc>,
+    pub flow_status: WaterFlowStatus,
+    pub treatment_needed: bool,
+    pub comment: Option<String>,
+    pub media_ids: Vec<ModelId<MediaId>>,
+}
+
+#[derive(SimpleObject)]
+pub struct WaterBetaOutput {
+    pub water_beta: WaterBeta,
+}
+
+#[derive(InputObject)]
+pub struct DeleteTripInput {
+    pub trip_id: ModelId<TripId>,
+}
+
+#[derive(SimpleObject)]
+pub struct DeleteTripOutput {
+    pub trip_id: ModelId<TripId>,
+}
+
+#[derive(InputObject)]
+pub struct DeletePointOfInterestInput {
+    pub point_of_interest_id: ModelId<PointOfInterestId>,
+}
+
+#[derive(SimpleObject)]
+pub struct DeletePointOfInterestOutput {
+    pub point_of_interest_id: ModelId<PointOfInterestId>,
+}
+
+#[derive(InputObject)]
+pub struct DeleteMediaInput {
+    pub media_id: ModelId<MediaId>,
+}
+
+#[derive(SimpleObject)]
+pub struct DeleteMediaOutput {
+    pub media_id: ModelId<MediaId>,
+}
+
+#[derive(InputObject)]
+pub struct DeleteRideInput {
+    pub ride_id: ModelId<RideId>,
+}
+
+#[derive(SimpleObject)]
+pub struct DeleteRideOutput {
+    pub ride_id: ModelId<RideId>,
+}
+
+/// Ensures every media item exists and was uploaded by the given user.
+async fn verify_media_ownership(
+    repos: &Repos,
+    user_id: UserId,
+    media_ids: &[MediaId],
+) -> Result<(), Error> {
+    let media = repos.media_repo.get_batch(media_ids.to_vec()).await?;
+
+    if media.len() != media_ids.len() || media.iter().any(|media| media.user_id != user_id) {
+        return Err(Error::new("Not authorized to attach this media"));
+    }
+
+    Ok(())
+}
+
 pub struct Mutation;
 
 #[Object]
@@ -326,6 +451,7 @@ impl Mutation {
             point,
             point_of_interest_type: input.point_of_interest_type.into(),
             description: input.description,
+            external_ref: None,
         };
 
         point_of_interest_repo.put(poi.clone()).await?;
@@ -377,6 +503,374 @@ impl Mutation {
         })
     }
 
+    async fn create_point_of_interest_visit(
+        &self,
+        ctx: &Context<'_>,
+        input: CreatePointOfInterestVisitInput,
+    ) -> Result<PointOfInterestVisitOutput, Error> {
+        let SchemaData { repos, .. } = ctx.data()?;
+        let RequestData { login } = ctx.data()?;
+
+        let login = login
+            .as_ref()
+            .ok_or_else(|| Error::new("Authentication required"))?;
+
+        let poi = repos
+            .point_of_interest_repo
+            .get(input.point_of_interest_id.0)
+            .await?;
+
+        let media_ids: Vec<MediaId> = input.media_ids.into_iter().map(|id| id.0).collect();
+        verify_media_ownership(repos, login.session.user_id, &media_ids).await?;
+
+        // Visits by the POI owner don't need confirming
+        let confirmation = if poi.user_id == login.session.user_id {
+            VisitConfirmation::Confirmed
+        } else {
+            VisitConfirmation::Pending
+        };
+
+        let visit = PoiVisitModel {
+            id: PointOfInterestVisitId::new(),
+            created_at: Utc::now(),
+            user_id: login.session.user_id,
+            point_of_interest_id: poi.id,
+            visited_at: input.visited_at,
+            confirmation,
+            condition: input.condition.map(Into::into),
+            comment: input.comment,
+            media_ids,
+        };
+
+        repos
+            .point_of_interest_visit_repo
+            .put(visit.clone())
+            .await?;
+
+        Ok(PointOfInterestVisitOutput {
+            point_of_interest_visit: PointOfInterestVisit(visit),
+        })
+    }
+
+    async fn update_point_of_interest_visit(
+        &self,
+        ctx: &Context<'_>,
+        input: UpdatePointOfInterestVisitInput,
+    ) -> Result<PointOfInterestVisitOutput, Error> {
+        let SchemaData { repos, .. } = ctx.data()?;
+        let RequestData { login } = ctx.data()?;
+
+        let login = login
+            .as_ref()
+            .ok_or_else(|| Error::new("Authentication required"))?;
+
+        let mut visit = repos
+            .point_of_interest_visit_repo
+            .get(input.visit_id.0)
+            .await?;
+
+        if visit.user_id != login.session.user_id {
+            return Err(Error::new("Not authorized to update this visit"));
+        }
+
+        let media_ids: Vec<MediaId> = input.media_ids.into_iter().map(|id| id.0).collect();
+        verify_media_ownership(repos, login.session.user_id, &media_ids).await?;
+
+        let poi = repos
+            .point_of_interest_repo
+            .get(visit.point_of_interest_id)
+            .await?;
+
+        visit.edit(
+            VisitEdit {
+                visited_at: input.visited_at,
+                condition: input.condition.map(Into::into),
+                comment: input.comment,
+                media_ids,
+            },
+            login.session.user_id,
+            poi.user_id,
+        );
+
+        repos
+            .point_of_interest_visit_repo
+            .put(visit.clone())
+            .await?;
+
+        Ok(PointOfInterestVisitOutput {
+            point_of_interest_visit: PointOfInterestVisit(visit),
+        })
+    }
+
+    async fn confirm_point_of_interest_visit(
+        &self,
+        ctx: &Context<'_>,
+        input: ConfirmPointOfInterestVisitInput,
+    ) -> Result<PointOfInterestVisitOutput, Error> {
+        let SchemaData {
+            repos:
+                Repos {
+                    point_of_interest_repo,
+                    point_of_interest_visit_repo,
+                    ..
+                },
+            ..
+        } = ctx.data()?;
+        let RequestData { login } = ctx.data()?;
+
+        let login = login
+            .as_ref()
+            .ok_or_else(|| Error::new("Authentication required"))?;
+
+        let mut visit = point_of_interest_visit_repo.get(input.visit_id.0).await?;
+        let poi = point_of_interest_repo
+            .get(visit.point_of_interest_id)
+            .await?;
+
+        if poi.user_id != login.session.user_id {
+            return Err(Error::new("Not authorized to confirm this visit"));
+        }
+
+        visit
+            .set_confirmation(input.confirmation.into())
+            .map_err(|e| Error::new(e.to_string()))?;
+
+        point_of_interest_visit_repo.put(visit.clone()).await?;
+
+        Ok(PointOfInterestVisitOutput {
+            point_of_interest_visit: PointOfInterestVisit(visit),
+        })
+    }
+
+    async fn report_water_beta(
+        &self,
+        ctx: &Context<'_>,
+        input: ReportWaterBetaInput,
+    ) -> Result<WaterBetaOutput, Error> {
+        let SchemaData { repos, .. } = ctx.data()?;
+        let RequestData { login } = ctx.data()?;
+
+        let login = login
+            .as_ref()
+            .ok_or_else(|| Error::new("Authentication required"))?;
+
+        let osm_feature = repos.osm_feature_repo.get(input.osm_feature_id.0).await?;
+
+        if !osm_feature
+            .feature_type()
+            .is_some_and(|feature_type| feature_type.is_water_source())
+        {
+            return Err(Error::new("Feature is not a water source"));
+        }
+
+        let media_ids: Vec<MediaId> = input.media_ids.into_iter().map(|id| id.0).collect();
+        verify_media_ownership(repos, login.session.user_id, &media_ids).await?;
+
+        let water_beta = WaterBetaModel {
+            id: WaterBetaId::new(),
+            created_at: Utc::now(),
+            user_id: login.session.user_id,
+            osm_feature_id: osm_feature.id,
+            observed_at: input.observed_at,
+            flow_status: input.flow_status.into(),
+            treatment_needed: input.treatment_needed,
+            comment: input.comment,
+            media_ids,
+        };
+
+        repos.water_beta_repo.put(water_beta.clone()).await?;
+
+        Ok(WaterBetaOutput {
+            water_beta: WaterBeta(water_beta),
+        })
+    }
+
+    async fn update_water_beta(
+        &self,
+        ctx: &Context<'_>,
+        input: UpdateWaterBetaInput,
+    ) -> Result<WaterBetaOutput, Error> {
+        let SchemaData { repos, .. } = ctx.data()?;
+        let RequestData { login } = ctx.data()?;
+
+        let login = login
+            .as_ref()
+            .ok_or_else(|| Error::new("Authentication required"))?;
+
+        let mut water_beta = repos.water_beta_repo.get(input.water_beta_id.0).await?;
+
+        if water_beta.user_id != login.session.user_id {
+            return Err(Error::new("Not authorized to update this report"));
+        }
+
+        let media_ids: Vec<MediaId> = input.media_ids.into_iter().map(|id| id.0).collect();
+        verify_media_ownership(repos, login.session.user_id, &media_ids).await?;
+
+        water_beta.observed_at = input.observed_at;
+        water_beta.flow_status = input.flow_status.into();
+        water_beta.treatment_needed = input.treatment_needed;
+        water_beta.comment = input.comment;
+        water_beta.media_ids = media_ids;
+
+        repos.water_beta_repo.put(water_beta.clone()).await?;
+
+        Ok(WaterBetaOutput {
+            water_beta: WaterBeta(water_beta),
+        })
+    }
+
+    async fn delete_trip(
+        &self,
+        ctx: &Context<'_>,
+        input: DeleteTripInput,
+    ) -> Result<DeleteTripOutput, Error> {
+        let SchemaData {
+            repos: Repos { trip_repo, .. },
+            simplified_trip_elevation_points_fetcher,
+            ..
+        } = ctx.data()?;
+        let RequestData { login } = ctx.data()?;
+
+        let login = login
+            .as_ref()
+            .ok_or_else(|| Error::new("Authentication required"))?;
+
+        let trip = trip_repo.get(input.trip_id.0).await?;
+
+        if trip.user_id != login.session.user_id {
+            return Err(Error::new("Not authorized to delete this trip"));
+        }
+
+        trip_repo.delete(trip.id).await?;
+
+        simplified_trip_elevation_points_fetcher
+            .invalidate(trip.id)
+            .await?;
+
+        Ok(DeleteTripOutput {
+            trip_id: input.trip_id,
+        })
+    }
+
+    async fn delete_point_of_interest(
+        &self,
+        ctx: &Context<'_>,
+        input: DeletePointOfInterestInput,
+    ) -> Result<DeletePointOfInterestOutput, Error> {
+        let SchemaData {
+            repos: Repos {
+                point_of_interest_repo,
+                ..
+            },
+            ..
+        } = ctx.data()?;
+        let RequestData { login } = ctx.data()?;
+
+        let login = login
+            .as_ref()
+            .ok_or_else(|| Error::new("Authentication required"))?;
+
+        let poi = point_of_interest_repo
+            .get(input.point_of_interest_id.0)
+            .await?;
+
+        if poi.user_id != login.session.user_id {
+            return Err(Error::new(
+                "Not authorized to delete this point of interest",
+            ));
+        }
+
+        point_of_interest_repo.delete(poi.id).await?;
+
+        Ok(DeletePointOfInterestOutput {
+            point_of_interest_id: input.point_of_interest_id,
+        })
+    }
+
+    async fn delete_media(
+        &self,
+        ctx: &Context<'_>,
+        input: DeleteMediaInput,
+    ) -> Result<DeleteMediaOutput, Error> {
+        let SchemaData {
+            repos: Repos { media_repo, .. },
+            bucket_client,
+            ..
+        } = ctx.data()?;
+        let RequestData { login } = ctx.data()?;
+
+        let login = login
+            .as_ref()
+            .ok_or_else(|| Error::new("Authentication required"))?;
+
+        let media = media_repo.get(input.media_id.0).await?;
+
+        if media.user_id != login.session.user_id {
+            return Err(Error::new("Not authorized to delete this media"));
+        }
+
+        // Remove the rows first so a failure part way through the bucket
+        // cleanup leaves orphaned objects rather than media pointing at nothing
+        media_repo.delete(media.id).await?;
+
+        for key in media_object_keys(&media) {
+            bucket_client.delete_object(&key).await?;
+        }
+
+        Ok(DeleteMediaOutput {
+            media_id: input.media_id,
+        })
+    }
+
+    async fn delete_ride(
+        &self,
+        ctx: &Context<'_>,
+        input: DeleteRideInput,
+    ) -> Result<DeleteRideOutput, Error> {
+        let SchemaData {
+            repos:
+                Repos {
+                    ride_repo,
+                    trip_repo,
+                    ..
+                },
+            simplified_ride_points_fetcher,
+            simplified_trip_elevation_points_fetcher,
+            ..
+        } = ctx.data()?;
+        let RequestData { login } = ctx.data()?;
+
+        let login = login
+            .as_ref()
+            .ok_or_else(|| Error::new("Authentication required"))?;
+
+        let ride = ride_repo.get(input.ride_id.0).await?;
+
+        if ride.user_id != login.session.user_id {
+            return Err(Error::new("Not authorized to delete this ride"));
+        }
+
+        let trips = trip_repo
+            .filter_models(TripFilter::User(ride.user_id))
+            .await?
+            .into_iter()
+            .filter(|trip| trip.ride_ids.contains(&ride.id));
+
+        ride_repo.delete(ride.id).await?;
+
+        simplified_ride_points_fetcher.invalidate(ride.id).await?;
+
+        for trip in trips {
+            simplified_trip_elevation_points_fetcher
+                .invalidate(trip.id)
+                .await?;
+        }
+
+        Ok(DeleteRideOutput {
+            ride_id: input.ride_id,
+        })
+    }
+
     async fn clear_rwgps_connection(&self, ctx: &Context<'_>) -> Result<Viewer, Error> {
         // Get required context data
         let SchemaData {
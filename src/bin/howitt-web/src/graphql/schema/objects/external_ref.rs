@@ -1,10 +1,13 @@
-use async_graphql::Object;
+use async_graphql::{Error, Object};
 
 pub struct ExternalRef(pub howitt::models::external_ref::ExternalRef);
 
 #[Object]
 impl ExternalRef {
-    async fn canonical_url(&self) -> url::Url {
-        self.0.id.canonical_url()
+    async fn canonical_url(&self) -> Result<url::Url, Error> {
+        self.0
+            .id
+            .canonical_url()
+            .ok_or_else(|| Error::new("External ref has no canonical URL"))
     }
 }
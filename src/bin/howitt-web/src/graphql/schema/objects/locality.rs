@@ -0,0 +1,63 @@
+use async_graphql::{Context, Object};
+use howitt::{
+    models::{
+        locality::{RideLocalities, RideLocalitiesFilter, RouteLocalities, RouteLocalitiesFilter},
+        ride::RideId,
+        route::RouteId,
+    },
+    repos::Repos,
+};
+
+use crate::graphql::context::SchemaData;
+
+pub struct Locality(pub howitt::models::locality::Locality);
+
+#[Object]
+impl Locality {
+    /// Town, suburb or locality
+    async fn name(&self) -> Option<&str> {
+        self.0.name.as_deref()
+    }
+    /// State or province
+    async fn region(&self) -> Option<&str> {
+        self.0.region.as_deref()
+    }
+    /// Name and region together, e.g. "Harrietville, Victoria"
+    async fn label(&self) -> Option<String> {
+        self.0.label()
+    }
+}
+
+pub async fn route_localities<'ctx>(
+    ctx: &Context<'ctx>,
+    route_id: RouteId,
+) -> Result<Option<RouteLocalities>, async_graphql::Error> {
+    let SchemaData {
+        repos: Repos {
+            route_localities_repo,
+            ..
+        },
+        ..
+    } = ctx.data()?;
+
+    Ok(route_localities_repo
+        .find_model(RouteLocalitiesFilter::Ids(vec![route_id]))
+        .await?)
+}
+
+pub async fn ride_localities<'ctx>(
+    ctx: &Context<'ctx>,
+    ride_id: RideId,
+) -> Result<Option<RideLocalities>, async_graphql::Error> {
+    let SchemaData {
+        repos: Repos {
+            ride_localities_repo,
+            ..
+        },
+        ..
+    } = ctx.data()?;
+
+    Ok(ride_localities_repo
+        .find_model(RideLocalitiesFilter::Ids(vec![ride_id]))
+        .await?)
+}
@@ -1,13 +1,21 @@
 pub mod cue;
 pub mod external_ref;
 pub mod geo;
+pub mod locality;
 pub mod media;
 pub mod note;
 pub mod point_of_interest;
 pub mod point_of_interest_visit;
 pub mod ride;
+pub mod ride_weather;
 pub mod route;
+pub mod route_forecast;
+pub mod search;
+pub mod sync_run;
+pub mod transport_access;
 pub mod trip;
 pub mod user;
 pub mod user_rwgps_connection;
 pub mod viewer;
+pub mod water_beta;
+pub mod water_resupply;
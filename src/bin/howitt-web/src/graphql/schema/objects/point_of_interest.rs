@@ -1,6 +1,9 @@
 use async_graphql::{Context, Enum, Object};
 use howitt::{
-    models::{media::MediaFilter, point_of_interest::PointOfInterestId, Model},
+    models::{
+        media::MediaFilter, point_of_interest::PointOfInterestId,
+        point_of_interest_visit::PointOfInterestVisitFilter, Model,
+    },
     repos::Repos,
 };
 
@@ -64,8 +67,21 @@ impl PointOfInterest {
 
     pub async fn visits<'ctx>(
         &self,
-        _ctx: &Context<'ctx>,
+        ctx: &Context<'ctx>,
     ) -> Result<Vec<PointOfInterestVisit>, async_graphql::Error> {
-        Ok(vec![])
+        let SchemaData {
+            repos:
+                Repos {
+                    point_of_interest_visit_repo,
+                    ..
+                },
+            ..
+        } = ctx.data()?;
+
+        let visits = point_of_interest_visit_repo
+            .filter_models(PointOfInterestVisitFilter::ForPointOfInterest(self.0.id))
+            .await?;
+
+        Ok(visits.into_iter().map(PointOfInterestVisit).collect())
     }
 }
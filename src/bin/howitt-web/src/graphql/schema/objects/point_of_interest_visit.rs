@@ -1,8 +1,11 @@
 use async_graphql::{Context, Enum, Object};
 use chrono::{DateTime, Utc};
-use howitt::{models::media::MediaFilter, repos::Repos};
+use howitt::{
+    models::{media::MediaFilter, point_of_interest_visit::PointOfInterestVisitId},
+    repos::Repos,
+};
 
-use crate::graphql::context::SchemaData;
+use crate::graphql::{context::SchemaData, schema::ModelId};
 
 use super::{media::Media, point_of_interest::PointOfInterest, user::UserProfile};
 
@@ -25,6 +28,14 @@ pub struct PointOfInterestVisit(pub howitt::models::point_of_interest_visit::Poi
 
 #[Object]
 impl PointOfInterestVisit {
+    async fn id(&self) -> ModelId<PointOfInterestVisitId> {
+        ModelId::from(self.0.id)
+    }
+
+    async fn created_at(&self) -> DateTime<Utc> {
+        self.0.created_at
+    }
+
     async fn visited_at(&self) -> DateTime<Utc> {
         self.0.visited_at
     }
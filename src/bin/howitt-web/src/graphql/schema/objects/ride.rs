@@ -8,9 +8,10 @@ use howitt::{
             Point,
         },
         ride::RideId,
+        ride_weather::RideWeatherFilter,
     },
     repos::Repos,
-    services::simplify_points::DetailLevel,
+    services::{simplify_points::DetailLevel, wind::analyse_wind},
 };
 use itertools::Itertools;
 
@@ -18,7 +19,9 @@ use crate::graphql::context::SchemaData;
 
 use crate::graphql::schema::{user::UserProfile, IsoDate, ModelId};
 
+use super::locality::{ride_localities, Locality};
 use super::media::Media;
+use super::ride_weather::{RideWeather, RideWind};
 
 #[derive(Enum, Copy, Clone, Eq, PartialEq)]
 pub enum PointsDetail {
@@ -65,6 +68,28 @@ impl Ride {
     async fn finished_at(&self) -> DateTime<Utc> {
         self.0.finished_at
     }
+    /// The town the ride started in, once it's been geocoded
+    async fn start_locality<'ctx>(
+        &self,
+        ctx: &Context<'ctx>,
+    ) -> Result<Option<Locality>, async_graphql::Error> {
+        let localities = ride_localities(ctx, self.0.id).await?;
+
+        Ok(localities
+            .and_then(|localities| localities.start)
+            .map(Locality))
+    }
+    /// The town the ride finished in, once it's been geocoded
+    async fn finish_locality<'ctx>(
+        &self,
+        ctx: &Context<'ctx>,
+    ) -> Result<Option<Locality>, async_graphql::Error> {
+        let localities = ride_localities(ctx, self.0.id).await?;
+
+        Ok(localities
+            .and_then(|localities| localities.finish)
+            .map(Locality))
+    }
 
     pub async fn tz<'ctx>(
         &self,
@@ -185,6 +210,55 @@ impl Ride {
 
         Ok(media.into_iter().map(Media).collect())
     }
+    /// Hourly weather along the ride, once it's been looked up
+    pub async fn weather<'ctx>(
+        &self,
+        ctx: &Context<'ctx>,
+    ) -> Result<Option<RideWeather>, async_graphql::Error> {
+        let SchemaData {
+            repos: Repos {
+                ride_weather_repo, ..
+            },
+            ..
+        } = ctx.data()?;
+
+        let ride_weather = ride_weather_repo
+            .find_model(RideWeatherFilter::Ids(vec![self.0.id]))
+            .await?;
+
+        Ok(ride_weather.map(RideWeather))
+    }
+    /// How the wind met the rider, in segments of `segment_length` metres
+    pub async fn wind<'ctx>(
+        &self,
+        ctx: &Context<'ctx>,
+        #[graphql(default = 1000.0)] segment_length: f64,
+    ) -> Result<Option<RideWind>, async_graphql::Error> {
+        let SchemaData {
+            repos: Repos {
+                ride_weather_repo, ..
+            },
+            simplified_ride_points_fetcher,
+            ..
+        } = ctx.data()?;
+
+        let Some(ride_weather) = ride_weather_repo
+            .find_model(RideWeatherFilter::Ids(vec![self.0.id]))
+            .await?
+        else {
+            return Ok(None);
+        };
+
+        let points = simplified_ride_points_fetcher
+            .fetch(self.0.id, DetailLevel::High)
+            .await?;
+
+        Ok(Some(RideWind(analyse_wind(
+            points,
+            &ride_weather,
+            segment_length,
+        ))))
+    }
     pub async fn content_at(&self) -> DateTime<Utc> {
         self.0.started_at.clone()
     }
@@ -0,0 +1,143 @@
+use async_graphql::{Enum, Object};
+use chrono::{DateTime, Utc};
+
+#[derive(Enum, Copy, Clone, Eq, PartialEq)]
+#[graphql(remote = "howitt::models::ride_weather::WindRelation")]
+pub enum WindRelation {
+    Headwind,
+    Crosswind,
+    Tailwind,
+}
+
+pub struct HourlyWeather(pub howitt::models::ride_weather::HourlyWeather);
+
+#[Object]
+impl HourlyWeather {
+    async fn datetime(&self) -> DateTime<Utc> {
+        self.0.datetime
+    }
+
+    /// Where the rider was at the start of the hour
+    async fn point(&self) -> Vec<f64> {
+        vec![self.0.point.x(), self.0.point.y()]
+    }
+
+    async fn bearing(&self) -> Option<f64> {
+        self.0.bearing
+    }
+
+    async fn temperature_celsius(&self) -> f64 {
+        self.0.temperature
+    }
+
+    async fn precipitation_mm(&self) -> f64 {
+        self.0.precipitation
+    }
+
+    async fn wind_speed_kmh(&self) -> f64 {
+        self.0.wind_speed
+    }
+
+    /// Degrees the wind is blowing from
+    async fn wind_direction(&self) -> f64 {
+        self.0.wind_direction
+    }
+
+    /// Null when the wind was calm or the rider wasn't going anywhere
+    async fn wind_relation(&self) -> Option<WindRelation> {
+        self.0.wind_relation().map(WindRelation::from)
+    }
+}
+
+pub struct RideWeather(pub howitt::models::ride_weather::RideWeather);
+
+#[Object]
+impl RideWeather {
+    async fn hours(&self) -> Vec<HourlyWeather> {
+        self.0.hours.iter().cloned().map(HourlyWeather).collect()
+    }
+
+    async fn max_temperature_celsius(&self) -> Option<f64> {
+        self.0.max_temperature()
+    }
+
+    async fn min_temperature_celsius(&self) -> Option<f64> {
+        self.0.min_temperature()
+    }
+
+    async fn total_precipitation_mm(&self) -> f64 {
+        self.0.total_precipitation()
+    }
+
+    async fn max_wind_speed_kmh(&self) -> Option<f64> {
+        self.0.max_wind_speed()
+    }
+
+    /// Share of the ride's moving hours spent riding into a headwind, from 0 to 1
+    async fn headwind_share(&self) -> Option<f64> {
+        self.0.headwind_share()
+    }
+}
+
+pub struct WindSegment(pub howitt::services::wind::WindSegment);
+
+#[Object]
+impl WindSegment {
+    /// Distance along the ride where the segment starts
+    async fn start_distance_meters(&self) -> f64 {
+        self.0.start_distance_m
+    }
+
+    async fn distance_meters(&self) -> f64 {
+        self.0.distance_m
+    }
+
+    async fn elapsed_seconds(&self) -> i64 {
+        self.0.elapsed.num_seconds()
+    }
+
+    /// Moving time within the segment spent riding into a headwind
+    async fn headwind_seconds(&self) -> i64 {
+        self.0.headwind_elapsed.num_seconds()
+    }
+
+    async fn bearing(&self) -> Option<f64> {
+        self.0.bearing
+    }
+
+    async fn wind_speed_kmh(&self) -> Option<f64> {
+        self.0.wind.map(|wind| wind.speed_kmh)
+    }
+
+    /// Degrees the wind is blowing from
+    async fn wind_direction(&self) -> Option<f64> {
+        self.0.wind.map(|wind| wind.direction)
+    }
+
+    async fn wind_relation(&self) -> Option<WindRelation> {
+        self.0.relation().map(WindRelation::from)
+    }
+
+    /// Component of the wind against the rider, negative when it's behind them
+    async fn headwind_kmh(&self) -> Option<f64> {
+        self.0.headwind_kmh()
+    }
+
+    async fn crosswind_kmh(&self) -> Option<f64> {
+        self.0.crosswind_kmh()
+    }
+}
+
+pub struct RideWind(pub howitt::services::wind::WindAnalysis);
+
+#[Object]
+impl RideWind {
+    async fn segments(&self) -> Vec<WindSegment> {
+        self.0.segments.iter().cloned().map(WindSegment).collect()
+    }
+
+    /// Moving time over the whole ride spent riding into a headwind
+    async fn headwind_seconds(&self) -> i64 {
+        self.0.headwind_elapsed.num_seconds()
+    }
+}
@@ -1,20 +1,35 @@
+use std::collections::HashMap;
+
 use anyhow::anyhow;
 use async_graphql::{Context, Enum, Object};
+use chrono::{DateTime, Utc};
+use futures::future::join_all;
 use howitt::{
     models::{
+        course_point::RouteCoursePointsFilter,
         media::MediaFilter,
         point::{
             delta::{BearingDelta, Delta, DistanceDelta, ElevationDelta},
             progress::{DistanceElevationProgress, DistanceProgress, Progress},
             ElevationPoint,
         },
+        point_of_interest::PointOfInterestFilter,
         route::RouteId,
         tag::Tag,
     },
     repos::Repos,
-    services::generate_cuesheet::generate_cuesheet,
+    services::{
+        generate_cuesheet::generate_cuesheet,
+        route_forecast::{build_route_forecast, sample_route_hours},
+        transport_access::{
+            nearest_transport_stops, transport_access, TransportAccessParams, MAX_STOP_DISTANCE_M,
+        },
+        water_resupply::{plan_water_resupply, WaterConsumptionModel},
+        water_sources::{water_sources_near_route, WaterSourcesNearRouteParams},
+    },
 };
 use itertools::Itertools;
+use open_meteo::{schema::ElevationParams, OpenMeteoClient};
 
 use crate::graphql::context::SchemaData;
 
@@ -26,7 +41,36 @@ use crate::graphql::schema::{
     ModelId,
 };
 
-use super::media::Media;
+use super::{
+    locality::{route_localities, Locality},
+    media::Media,
+    route_forecast::{fetch_forecasts, RouteForecastHour},
+    transport_access::TransportAccess,
+    water_beta::WaterSource,
+    water_resupply::WaterResupplyPlan,
+};
+
+/// How far either side of a route water sources are looked for. Wider
+/// corridors turn into a scan of every OSM feature.
+const MAX_WATER_SOURCE_DISTANCE_M: f64 = 5_000.0;
+
+async fn fetch_elevation(
+    open_meteo_client: &OpenMeteoClient,
+    point: geo::Point,
+) -> Result<f64, anyhow::Error> {
+    let response = open_meteo_client
+        .get_elevation(ElevationParams {
+            latitude: vec![point.y()],
+            longitude: vec![point.x()],
+        })
+        .await?;
+
+    response
+        .elevation
+        .first()
+        .copied()
+        .ok_or(anyhow!("No elevation returned"))
+}
 
 #[derive(Enum, Copy, Clone, Eq, PartialEq)]
 #[graphql(remote = "howitt::models::route_description::DifficultyRating")]
@@ -153,6 +197,90 @@ impl Terminus {
         SlopeEnd::from(terminus.elevation().slope_end)
     }
 
+    /// The town the terminus is in, once the route has been geocoded
+    async fn locality<'ctx>(
+        &self,
+        ctx: &Context<'ctx>,
+    ) -> Result<Option<Locality>, async_graphql::Error> {
+        let Terminus { terminus, route } = self;
+
+        let localities = route_localities(ctx, route.id()).await?;
+
+        Ok(localities
+            .and_then(|localities| match TerminusEnd::from(terminus.end) {
+                TerminusEnd::Start => localities.start,
+                TerminusEnd::End => localities.end,
+            })
+            .map(Locality))
+    }
+
+    /// The closest public transport stops to this terminus, with their next departures
+    async fn transport_access<'ctx>(
+        &self,
+        ctx: &Context<'ctx>,
+    ) -> Result<Vec<TransportAccess>, async_graphql::Error> {
+        let Terminus { terminus, .. } = self;
+
+        let SchemaData {
+            repos: Repos {
+                point_of_interest_repo,
+                ..
+            },
+            transport_timetables,
+            open_meteo_client,
+            elevation_fetcher,
+            tz_finder,
+            ..
+        } = ctx.data()?;
+
+        let point = terminus.point();
+        let pois = point_of_interest_repo
+            .filter_models(PointOfInterestFilter::Near {
+                point: point.point,
+                distance_m: MAX_STOP_DISTANCE_M,
+            })
+            .await?;
+        let stops = nearest_transport_stops(point, &pois);
+
+        // Stops don't carry an elevation, so look them up. A failed lookup
+        // shouldn't hide the rest of the transport info.
+        let stop_elevations: HashMap<_, _> = join_all(stops.iter().map(|stop| async move {
+            let elevation = elevation_fetcher
+                .fetch(stop.point_of_interest.point, |point| {
+                    fetch_elevation(open_meteo_client, point)
+                })
+                .await;
+
+            match elevation {
+                Ok(elevation) => Some((stop.point_of_interest.id, elevation)),
+                Err(e) => {
+                    tracing::warn!("Failed to fetch stop elevation: {}", e);
+                    None
+                }
+            }
+        }))
+        .await
+        .into_iter()
+        .flatten()
+        .collect();
+
+        let tz: chrono_tz::Tz = tz_finder
+            .get_tz_name(point.point.x(), point.point.y())
+            .parse()
+            .unwrap_or(chrono_tz::Australia::Melbourne);
+
+        Ok(transport_access(TransportAccessParams {
+            point,
+            pois: &pois,
+            timetables: transport_timetables,
+            stop_elevations: &stop_elevations,
+            now: Utc::now().with_timezone(&tz).naive_local(),
+        })
+        .into_iter()
+        .map(TransportAccess)
+        .collect())
+    }
+
     async fn nearby_routes<'ctx>(
         &self,
         ctx: &Context<'ctx>,
@@ -279,6 +407,28 @@ impl Route {
             })
             .collect_vec()
     }
+    /// The town the route starts in, once it's been geocoded
+    async fn start_locality<'ctx>(
+        &self,
+        ctx: &Context<'ctx>,
+    ) -> Result<Option<Locality>, async_graphql::Error> {
+        let localities = route_localities(ctx, self.0.id()).await?;
+
+        Ok(localities
+            .and_then(|localities| localities.start)
+            .map(Locality))
+    }
+    /// The town the route ends in, once it's been geocoded
+    async fn end_locality<'ctx>(
+        &self,
+        ctx: &Context<'ctx>,
+    ) -> Result<Option<Locality>, async_graphql::Error> {
+        let localities = route_localities(ctx, self.0.id()).await?;
+
+        Ok(localities
+            .and_then(|localities| localities.end)
+            .map(Locality))
+    }
     async fn description(&self) -> Option<&str> {
         self.route_description()?.description.as_deref()
     }
@@ -426,6 +576,73 @@ impl Route {
         Ok(serde_json::to_string(&self.distance_points(ctx).await?)?)
     }
     async fn cues<'ctx>(&self, ctx: &Context<'ctx>) -> Result<Vec<Cue>, async_graphql::Error> {
+        let SchemaData {
+            repos:
+                Repos {
+                    point_of_interest_repo,
+                    route_course_points_repo,
+                    ..
+                },
+            route_points_loader,
+            ..
+        } = ctx.data()?;
+        let route_points = route_points_loader
+            .load_one(self.0.id())
+            .await?
+            .ok_or(anyhow!("Points not found"))?;
+
+        let points = route_points.iter_elevation_points().cloned().collect_vec();
+        let pois = point_of_interest_repo.all().await?;
+        // Only routes synced from RWGPS have course points
+        let course_points = route_course_points_repo
+            .find_model(RouteCoursePointsFilter::Ids(vec![self.0.id]))
+            .await?
+            .map(|route_course_points| route_course_points.course_points)
+            .unwrap_or_default();
+
+        let cuesheet = generate_cuesheet(&points, &pois, &course_points);
+
+        Ok(cuesheet.cues.into_iter().map(Cue::from).collect_vec())
+    }
+    /// The forecast at the start of each hour for a rider setting out at
+    /// `departs_at` and averaging `speed_kmh`. Hours past the end of the
+    /// forecast are left out.
+    async fn forecast<'ctx>(
+        &self,
+        ctx: &Context<'ctx>,
+        departs_at: DateTime<Utc>,
+        #[graphql(default = 15.0)] speed_kmh: f64,
+    ) -> Result<Vec<RouteForecastHour>, async_graphql::Error> {
+        let SchemaData {
+            route_points_loader,
+            open_meteo_client,
+            ..
+        } = ctx.data()?;
+        let route_points = route_points_loader
+            .load_one(self.0.id())
+            .await?
+            .ok_or(anyhow!("Points not found"))?;
+
+        let points = route_points.iter_elevation_points().cloned().collect_vec();
+        let samples = sample_route_hours(&points, departs_at, speed_kmh);
+
+        let observations = fetch_forecasts(open_meteo_client, &samples).await;
+
+        Ok(build_route_forecast(samples.into_iter().zip(observations))
+            .into_iter()
+            .map(RouteForecastHour)
+            .collect())
+    }
+    /// Where a rider carrying `capacity_litres` would run out of water between
+    /// water source POIs. Climbing adds riding time at `minutes_per_100m_climb`.
+    async fn water_resupply_plan<'ctx>(
+        &self,
+        ctx: &Context<'ctx>,
+        capacity_litres: f64,
+        litres_per_hour: f64,
+        #[graphql(default = 15.0)] speed_kmh: f64,
+        #[graphql(name = "minutesPer100mClimb", default = 10.0)] minutes_per_100m_climb: f64,
+    ) -> Result<WaterResupplyPlan, async_graphql::Error> {
         let SchemaData {
             repos: Repos {
                 point_of_interest_repo,
@@ -442,9 +659,47 @@ impl Route {
         let points = route_points.iter_elevation_points().cloned().collect_vec();
         let pois = point_of_interest_repo.all().await?;
 
-        let cuesheet = generate_cuesheet(&points, &pois);
+        let plan = plan_water_resupply(
+            &points,
+            &pois,
+            capacity_litres,
+            &WaterConsumptionModel {
+                litres_per_hour,
+                speed_kmh,
+                minutes_per_100m_climb,
+            },
+        );
 
-        Ok(cuesheet.cues.into_iter().map(Cue::from).collect_vec())
+        Ok(WaterResupplyPlan::from(plan))
+    }
+    /// Water sources within `max_distance` metres of the route, up to 5km, in
+    /// order along it
+    async fn water_sources<'ctx>(
+        &self,
+        ctx: &Context<'ctx>,
+        #[graphql(default = 500.0)] max_distance: f64,
+    ) -> Result<Vec<WaterSource>, async_graphql::Error> {
+        let SchemaData {
+            repos:
+                Repos {
+                    osm_feature_repo,
+                    water_beta_repo,
+                    route_points_repo,
+                    ..
+                },
+            ..
+        } = ctx.data()?;
+
+        let sources = water_sources_near_route(WaterSourcesNearRouteParams {
+            osm_feature_repo: osm_feature_repo.clone(),
+            water_beta_repo: water_beta_repo.clone(),
+            route_points_repo: route_points_repo.clone(),
+            route_id: self.0.id(),
+            max_distance_m: max_distance.clamp(0.0, MAX_WATER_SOURCE_DISTANCE_M),
+        })
+        .await?;
+
+        Ok(sources.into_iter().map(WaterSource).collect())
     }
     async fn user<'ctx>(&self, ctx: &Context<'ctx>) -> Result<UserProfile, async_graphql::Error> {
         let SchemaData {
@@ -0,0 +1,93 @@
+use async_graphql::Object;
+use chrono::{TimeDelta, Utc};
+use howitt::services::{ride_weather::HourlyObservation, route_forecast::RouteForecastSample};
+use itertools::Itertools;
+use open_meteo::{
+    schema::{ForecastParams, HOUR_FORMAT, MAX_FORECAST_DAYS},
+    OpenMeteoClient,
+};
+
+use super::ride_weather::HourlyWeather;
+
+pub struct RouteForecastHour(pub howitt::services::route_forecast::RouteForecastHour);
+
+#[Object]
+impl RouteForecastHour {
+    /// How far along the route the rider is expected to be at the start of the hour
+    async fn distance_meters(&self) -> f64 {
+        self.0.distance_m
+    }
+
+    async fn elevation_meters(&self) -> f64 {
+        self.0.elevation_m
+    }
+
+    async fn weather(&self) -> HourlyWeather {
+        HourlyWeather(self.0.weather.clone())
+    }
+}
+
+/// Looks up the forecast for every sample's place and hour in a single
+/// request, in the same order as `samples`. Hours past the end of the
+/// forecast, and a failed lookup, come back empty rather than failing the
+/// whole route.
+pub async fn fetch_forecasts(
+    client: &OpenMeteoClient,
+    samples: &[RouteForecastSample],
+) -> Vec<Option<HourlyObservation>> {
+    let forecast_ends = Utc::now() + TimeDelta::days(MAX_FORECAST_DAYS);
+
+    let forecastable = samples
+        .iter()
+        .take_while(|sample| sample.sample.hour <= forecast_ends)
+        .collect_vec();
+
+    let (Some(first), Some(last)) = (forecastable.first(), forecastable.last()) else {
+        return vec![None; samples.len()];
+    };
+
+    let format_hour =
+        |sample: &RouteForecastSample| sample.sample.hour.format(HOUR_FORMAT).to_string();
+
+    let forecasts = client
+        .get_forecast(ForecastParams {
+            latitude: forecastable
+                .iter()
+                .map(|sample| sample.sample.point.y())
+                .collect(),
+            longitude: forecastable
+                .iter()
+                .map(|sample| sample.sample.point.x())
+                .collect(),
+            hourly: Some(HourlyObservation::variables()),
+            daily: None,
+            start_hour: Some(format_hour(*first)),
+            end_hour: Some(format_hour(*last)),
+            forecast_days: None,
+            temperature_unit: None,
+            wind_speed_unit: None,
+            timeformat: None,
+            timezone: Some(String::from("UTC")),
+        })
+        .await;
+
+    let forecasts = match forecasts {
+        Ok(forecasts) => forecasts,
+        Err(e) => {
+            tracing::warn!("Failed to fetch forecast: {}", e);
+            vec![]
+        }
+    };
+
+    samples
+        .iter()
+        .enumerate()
+        .map(|(index, sample)| {
+            forecasts
+                .get(index)?
+                .hourly
+                .as_ref()?
+                .observation(&format_hour(sample))
+        })
+        .collect()
+}
@@ -0,0 +1,88 @@
+use async_graphql::{Object, Union};
+use mapbox_geocoding::{client::MapboxGeocodingClient, schema::ForwardGeocodingParams};
+
+use super::{point_of_interest::PointOfInterest, route::Route, trip::Trip};
+
+pub struct Place(pub howitt::services::search::Place);
+
+#[Object]
+impl Place {
+    async fn name(&self) -> &str {
+        &self.0.name
+    }
+    /// The rest of the address, e.g. "Victoria, Australia"
+    async fn address(&self) -> Option<&str> {
+        self.0.address.as_deref()
+    }
+    async fn point(&self) -> Vec<f64> {
+        vec![self.0.point.x(), self.0.point.y()]
+    }
+}
+
+#[derive(Union)]
+pub enum SearchResult {
+    Route(Route),
+    Trip(Trip),
+    PointOfInterest(PointOfInterest),
+    Place(Place),
+}
+
+impl From<howitt::services::search::SearchResult> for SearchResult {
+    fn from(result: howitt::services::search::SearchResult) -> SearchResult {
+        use howitt::services::search::SearchResult as Result;
+
+        match result {
+            Result::Route(route) => SearchResult::Route(Route(*route)),
+            Result::Trip(trip) => SearchResult::Trip(Trip(trip)),
+            Result::PointOfInterest(poi) => SearchResult::PointOfInterest(PointOfInterest(poi)),
+            Result::Place(place) => SearchResult::Place(Place(place)),
+        }
+    }
+}
+
+/// Forward geocodes the query, biased towards `near`. A failed lookup comes
+/// back empty rather than failing the whole search.
+pub async fn geocode_places(
+    client: &MapboxGeocodingClient,
+    query: &str,
+    near: Option<&geo::Point>,
+    limit: usize,
+) -> Vec<howitt::services::search::Place> {
+    let response = client
+        .forward_geocode(ForwardGeocodingParams {
+            q: String::from(query),
+            access_token: client.access_token().to_string(),
+            // Results are only shown, never stored
+            permanent: None,
+            autocomplete: Some(true),
+            bbox: None,
+            country: None,
+            format: None,
+            language: Some(String::from("en")),
+            limit: Some(limit.clamp(1, 10) as u8),
+            proximity: near.map(|near| format!("{},{}", near.x(), near.y())),
+            types: None,
+            worldview: None,
+        })
+        .await;
+
+    match response {
+        Ok(response) => response
+            .features
+            .into_iter()
+            .map(|feature| {
+                let [longitude, latitude] = feature.geometry.coordinates;
+
+                howitt::services::search::Place {
+                    name: feature.properties.name,
+                    address: feature.properties.place_formatted,
+                    point: geo::Point::new(longitude, latitude),
+                }
+            })
+            .collect(),
+        Err(e) => {
+            tracing::warn!("Failed to geocode search query: {}", e);
+            vec![]
+        }
+    }
+}
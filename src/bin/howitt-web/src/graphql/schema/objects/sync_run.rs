@@ -0,0 +1,51 @@
+use async_graphql::{Enum, Object};
+use chrono::{DateTime, Utc};
+use howitt::models::sync_run::SyncRunId;
+
+use crate::graphql::schema::ModelId;
+
+#[derive(Enum, Copy, Clone, Eq, PartialEq)]
+#[graphql(remote = "howitt::models::external_ref::ExternalProvider")]
+pub enum ExternalProvider {
+    Rwgps,
+    Gtfs,
+    Strava,
+}
+
+pub struct SyncRun(pub howitt::models::sync_run::SyncRun);
+
+#[Object]
+impl SyncRun {
+    async fn id(&self) -> ModelId<SyncRunId> {
+        ModelId::from(self.0.id)
+    }
+
+    async fn created_at(&self) -> DateTime<Utc> {
+        self.0.created_at
+    }
+
+    async fn provider(&self) -> ExternalProvider {
+        ExternalProvider::from(self.0.provider)
+    }
+
+    /// Whether the run only looked at items changed since the last one
+    async fn is_incremental(&self) -> bool {
+        self.0.since.is_some()
+    }
+
+    async fn queued(&self) -> usize {
+        self.0.queued
+    }
+
+    async fn succeeded(&self) -> usize {
+        self.0.succeeded
+    }
+
+    async fn failed(&self) -> usize {
+        self.0.failed
+    }
+
+    async fn finished_at(&self) -> Option<DateTime<Utc>> {
+        self.0.finished_at
+    }
+}
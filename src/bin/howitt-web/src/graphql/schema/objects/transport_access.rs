@@ -0,0 +1,45 @@
+use async_graphql::{Object, SimpleObject};
+
+use super::point_of_interest::PointOfInterest;
+
+#[derive(SimpleObject)]
+pub struct TransportDeparture {
+    pub route_name: Option<String>,
+    pub headsign: Option<String>,
+    /// Local time formatted as HH:MM:SS
+    pub departure_time: String,
+}
+
+pub struct TransportAccess(pub howitt::services::transport_access::TransportAccess);
+
+#[Object]
+impl TransportAccess {
+    async fn point_of_interest(&self) -> PointOfInterest {
+        PointOfInterest(self.0.point_of_interest.clone())
+    }
+
+    async fn distance(&self) -> f64 {
+        self.0.distance
+    }
+
+    async fn bearing(&self) -> f64 {
+        self.0.bearing
+    }
+
+    /// Stop elevation minus terminus elevation. Negative when the stop is below the terminus.
+    async fn elevation_gain_to_stop(&self) -> Option<f64> {
+        self.0.elevation_gain_to_stop
+    }
+
+    async fn next_departures(&self) -> Vec<TransportDeparture> {
+        self.0
+            .next_departures
+            .iter()
+            .map(|departure| TransportDeparture {
+                route_name: departure.route_name.clone(),
+                headsign: departure.headsign.clone(),
+                departure_time: departure.departure_time.to_string(),
+            })
+            .collect()
+    }
+}
@@ -1,10 +1,15 @@
-use async_graphql::{Context, Object};
+use async_graphql::{connection, Context, Object};
 use chrono::{Duration, Utc};
-use howitt::models::{ride::RideFilter, user::UserId};
+use howitt::models::{
+    ride::{RideFilter, RideId},
+    trip::{TripFilter, TripId},
+    user::UserId,
+};
 use howitt::repos::Repos;
 use itertools::Itertools;
 
 use crate::graphql::context::{RequestData, SchemaData};
+use crate::graphql::schema::connection::{model_connection, page_request, ModelConnection};
 use crate::graphql::schema::{ride::Ride, route::Route, trip::Trip, IsoDate, ModelId};
 
 use super::point_of_interest::PointOfInterest;
@@ -57,6 +62,44 @@ impl UserProfile {
         Ok(rides)
     }
 
+    /// The user's rides in the order they were ridden, a page at a time.
+    async fn rides_connection<'ctx>(
+        &self,
+        ctx: &Context<'ctx>,
+        after: Option<String>,
+        before: Option<String>,
+        first: Option<i32>,
+        last: Option<i32>,
+    ) -> Result<ModelConnection<RideId, Ride>, async_graphql::Error> {
+        let SchemaData {
+            repos: Repos { ride_repo, .. },
+            ..
+        } = ctx.data()?;
+
+        let user_id = self.0.id;
+
+        connection::query(
+            after,
+            before,
+            first,
+            last,
+            |after, before, first, last| async move {
+                let page = ride_repo
+                    .filter_page(
+                        RideFilter::ForUser {
+                            user_id,
+                            started_at: None,
+                        },
+                        page_request(after, before, first, last),
+                    )
+                    .await?;
+
+                Ok::<_, async_graphql::Error>(model_connection(page, |ride| ride.id, Ride))
+            },
+        )
+        .await
+    }
+
     async fn recent_rides<'ctx>(
         &self,
         ctx: &Context<'ctx>,
@@ -92,6 +135,41 @@ impl UserProfile {
 
         Ok(trips.into_iter().map(Trip).collect())
     }
+
+    async fn trips_connection<'ctx>(
+        &self,
+        ctx: &Context<'ctx>,
+        after: Option<String>,
+        before: Option<String>,
+        first: Option<i32>,
+        last: Option<i32>,
+    ) -> Result<ModelConnection<TripId, Trip>, async_graphql::Error> {
+        let SchemaData {
+            repos: Repos { trip_repo, .. },
+            ..
+        } = ctx.data()?;
+
+        let user_id = self.0.id;
+
+        connection::query(
+            after,
+            before,
+            first,
+            last,
+            |after, before, first, last| async move {
+                let page = trip_repo
+                    .filter_page(
+                        TripFilter::User(user_id),
+                        page_request(after, before, first, last),
+                    )
+                    .await?;
+
+                Ok::<_, async_graphql::Error>(model_connection(page, |trip| trip.id, Trip))
+            },
+        )
+        .await
+    }
+
     async fn rides_with_date<'ctx>(
         &self,
         ctx: &Context<'ctx>,
@@ -1,8 +1,17 @@
-use async_graphql::Object;
+use async_graphql::{Enum, Object};
 use chrono::{DateTime, Utc};
 use howitt::models::user::UserRwgpsConnection as ModelUserRwgpsConnection;
 use uuid::Uuid;
 
+#[derive(Enum, Copy, Clone, Eq, PartialEq)]
+#[graphql(remote = "howitt::models::user::RwgpsConnectionStatus")]
+pub enum RwgpsConnectionStatus {
+    Ok,
+    /// The user needs to reconnect RWGPS before anything else syncs
+    Unauthorised,
+    RateLimited,
+}
+
 pub struct UserRwgpsConnection(pub ModelUserRwgpsConnection);
 
 #[Object]
@@ -15,6 +24,10 @@ impl UserRwgpsConnection {
         self.0.rwgps_user_id
     }
 
+    async fn status(&self) -> RwgpsConnectionStatus {
+        self.0.status.into()
+    }
+
     async fn created_at(&self) -> DateTime<Utc> {
         self.0.created_at
     }
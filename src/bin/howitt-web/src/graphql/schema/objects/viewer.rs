@@ -1,12 +1,19 @@
 use anyhow::anyhow;
 use async_graphql::{Context, Object};
 use chrono::Utc;
-use howitt::services::user::auth::Login;
+use howitt::{
+    models::{point_of_interest_visit::PointOfInterestVisitFilter, sync_run::SyncRunFilter},
+    repos::Repos,
+    services::user::auth::Login,
+};
 use url::Url;
 
 use crate::graphql::context::SchemaData;
 
-use super::{user::UserProfile, user_rwgps_connection::UserRwgpsConnection};
+use super::{
+    point_of_interest_visit::PointOfInterestVisit, sync_run::SyncRun, user::UserProfile,
+    user_rwgps_connection::UserRwgpsConnection,
+};
 pub struct Viewer(pub Login);
 
 #[Object]
@@ -43,6 +50,49 @@ impl Viewer {
         Ok(user.rwgps_connection.map(UserRwgpsConnection))
     }
 
+    async fn pending_point_of_interest_visits<'ctx>(
+        &self,
+        ctx: &Context<'ctx>,
+    ) -> Result<Vec<PointOfInterestVisit>, async_graphql::Error> {
+        let SchemaData {
+            repos:
+                Repos {
+                    point_of_interest_visit_repo,
+                    ..
+                },
+            ..
+        } = ctx.data()?;
+
+        let visits = point_of_interest_visit_repo
+            .filter_models(PointOfInterestVisitFilter::PendingForOwner(
+                self.0.session.user_id,
+            ))
+            .await?;
+
+        Ok(visits.into_iter().map(PointOfInterestVisit).collect())
+    }
+
+    /// The latest `limit` history syncs, most recent first
+    async fn sync_runs<'ctx>(
+        &self,
+        ctx: &Context<'ctx>,
+        #[graphql(default = 10)] limit: usize,
+    ) -> Result<Vec<SyncRun>, async_graphql::Error> {
+        let SchemaData {
+            repos: Repos { sync_run_repo, .. },
+            ..
+        } = ctx.data()?;
+
+        let runs = sync_run_repo
+            .filter_models(SyncRunFilter::ForUser {
+                user_id: self.0.session.user_id,
+                limit: Some(limit.clamp(1, 50)),
+            })
+            .await?;
+
+        Ok(runs.into_iter().map(SyncRun).collect())
+    }
+
     async fn rwgps_auth_request_url<'ctx>(
         &self,
         ctx: &Context<'ctx>,
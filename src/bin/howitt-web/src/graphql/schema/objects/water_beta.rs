@@ -0,0 +1,132 @@
+use async_graphql::{Context, Enum, Object};
+use chrono::{DateTime, Utc};
+use howitt::{
+    models::{media::MediaFilter, osm_feature::OsmFeatureId, water_beta::WaterBetaId},
+    repos::Repos,
+};
+
+use crate::graphql::{context::SchemaData, schema::ModelId};
+
+use super::{media::Media, user::UserProfile};
+
+#[derive(Enum, Copy, Clone, Eq, PartialEq)]
+#[graphql(remote = "howitt::models::water_beta::WaterFlowStatus")]
+pub enum WaterFlowStatus {
+    Flowing,
+    Trickle,
+    Dry,
+}
+
+#[derive(Enum, Copy, Clone, Eq, PartialEq)]
+#[graphql(remote = "howitt::models::osm_feature::OsmFeatureType")]
+pub enum OsmFeatureType {
+    DrinkingWater,
+    Shelter,
+    CampSite,
+    Toilets,
+    Spring,
+}
+
+pub struct WaterBeta(pub howitt::models::water_beta::WaterBeta);
+
+#[Object]
+impl WaterBeta {
+    async fn id(&self) -> ModelId<WaterBetaId> {
+        ModelId::from(self.0.id)
+    }
+
+    async fn created_at(&self) -> DateTime<Utc> {
+        self.0.created_at
+    }
+
+    async fn osm_feature_id(&self) -> ModelId<OsmFeatureId> {
+        ModelId::from(self.0.osm_feature_id)
+    }
+
+    async fn observed_at(&self) -> DateTime<Utc> {
+        self.0.observed_at
+    }
+
+    async fn flow_status(&self) -> WaterFlowStatus {
+        WaterFlowStatus::from(self.0.flow_status)
+    }
+
+    async fn treatment_needed(&self) -> bool {
+        self.0.treatment_needed
+    }
+
+    async fn comment(&self) -> Option<&str> {
+        self.0.comment.as_deref()
+    }
+
+    async fn user<'ctx>(&self, ctx: &Context<'ctx>) -> Result<UserProfile, async_graphql::Error> {
+        let SchemaData { user_loader, .. } = ctx.data()?;
+
+        let user = user_loader
+            .load_one(self.0.user_id)
+            .await?
+            .ok_or(anyhow::anyhow!("User not found"))?;
+
+        Ok(UserProfile(user))
+    }
+
+    async fn media<'ctx>(&self, ctx: &Context<'ctx>) -> Result<Vec<Media>, async_graphql::Error> {
+        let SchemaData {
+            repos: Repos { media_repo, .. },
+            ..
+        } = ctx.data()?;
+
+        let media = media_repo
+            .filter_models(MediaFilter::Ids(self.0.media_ids.clone()))
+            .await?;
+
+        Ok(media.into_iter().map(Media).collect())
+    }
+}
+
+pub struct WaterSource(pub howitt::services::water_sources::WaterSource);
+
+#[Object]
+impl WaterSource {
+    async fn osm_feature_id(&self) -> ModelId<OsmFeatureId> {
+        ModelId::from(self.0.osm_feature.id)
+    }
+
+    async fn osm_url(&self) -> String {
+        self.0.osm_feature.id.osm_url()
+    }
+
+    async fn name(&self) -> Option<&str> {
+        self.0.osm_feature.name.as_deref()
+    }
+
+    async fn feature_type(&self) -> Option<OsmFeatureType> {
+        self.0.osm_feature.feature_type().map(OsmFeatureType::from)
+    }
+
+    async fn point(&self) -> Option<Vec<f64>> {
+        self.0
+            .osm_feature
+            .point()
+            .map(|point| vec![point.x(), point.y()])
+    }
+
+    /// Distance along the route to the point closest to the source, in metres
+    async fn distance_along_route(&self) -> f64 {
+        self.0.distance_along_route_m
+    }
+
+    /// Distance off the route to the source, in metres
+    async fn distance_from_route(&self) -> f64 {
+        self.0.distance_from_route_m
+    }
+
+    async fn latest_report(&self) -> Option<WaterBeta> {
+        self.0.latest_report().cloned().map(WaterBeta)
+    }
+
+    /// Reports for the source, most recently observed first
+    async fn reports(&self) -> Vec<WaterBeta> {
+        self.0.reports.iter().cloned().map(WaterBeta).collect()
+    }
+}
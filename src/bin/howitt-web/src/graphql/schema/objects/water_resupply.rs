@@ -0,0 +1,56 @@
+use async_graphql::SimpleObject;
+
+#[derive(SimpleObject, Clone)]
+pub struct WaterSegment {
+    origin: String,
+    destination: String,
+    start_distance_meters: f64,
+    distance_meters: f64,
+    elevation_ascent_meters: f64,
+    elevation_descent_meters: f64,
+    litres_needed: f64,
+    /// Distance along the route where the rider would run out of water, if
+    /// they can't carry enough to reach the destination
+    runs_dry_at_meters: Option<f64>,
+}
+
+impl From<howitt::services::water_resupply::WaterSegment> for WaterSegment {
+    fn from(value: howitt::services::water_resupply::WaterSegment) -> Self {
+        WaterSegment {
+            origin: value.origin.to_string(),
+            destination: value.destination.to_string(),
+            start_distance_meters: value.start_distance_m,
+            distance_meters: value.summary.distance_m,
+            elevation_ascent_meters: value.summary.data.elevation_ascent_m,
+            elevation_descent_meters: value.summary.data.elevation_descent_m,
+            litres_needed: value.litres_needed,
+            runs_dry_at_meters: value.runs_dry_at_m,
+        }
+    }
+}
+
+#[derive(SimpleObject)]
+pub struct WaterResupplyPlan {
+    capacity_litres: f64,
+    /// Legs of the route between water sources, in order
+    segments: Vec<WaterSegment>,
+    /// Segments where the rider would run out of water
+    dry_segments: Vec<WaterSegment>,
+}
+
+impl From<howitt::services::water_resupply::WaterResupplyPlan> for WaterResupplyPlan {
+    fn from(value: howitt::services::water_resupply::WaterResupplyPlan) -> Self {
+        let segments: Vec<WaterSegment> =
+            value.segments.into_iter().map(WaterSegment::from).collect();
+
+        WaterResupplyPlan {
+            capacity_litres: value.capacity_litres,
+            dry_segments: segments
+                .iter()
+                .filter(|segment| segment.runs_dry_at_meters.is_some())
+                .cloned()
+                .collect(),
+            segments,
+        }
+    }
+}
@@ -1,17 +1,27 @@
 use async_graphql::*;
+use howitt::models::point_of_interest::{PointOfInterestFilter, PointOfInterestId};
+use howitt::models::ride::RideFilter;
 use howitt::models::route::{RouteFilter, RouteId};
 use howitt::models::tag::Tag;
 use howitt::models::trip::{TripFilter, TripId};
-use howitt::models::user::UserFilter;
+use howitt::models::user::{UserFilter, UserId};
 use howitt::repos::Repos;
+use howitt::services::search::{
+    merge_search_results, nearby_points_of_interest, search_terms, SearchSources,
+    NEARBY_POINT_OF_INTEREST_RADIUS_M,
+};
 use itertools::Itertools;
 
 use crate::graphql::context::{RequestData, SchemaData};
+use crate::graphql::schema::connection::{
+    model_connection, page_request, ModelConnection, MAX_PAGE_SIZE,
+};
 use crate::graphql::schema::ModelId;
 
 use super::point_of_interest::PointOfInterest;
 use super::ride::Ride;
 use super::route::Route;
+use super::search::{geocode_places, SearchResult};
 use super::trip::Trip;
 use super::user::UserProfile;
 use super::viewer::Viewer;
@@ -74,6 +84,35 @@ impl Query {
         // Ok(routes.into_iter().map(|route| Route(route)).collect())
     }
 
+    async fn routes_connection<'ctx>(
+        &self,
+        ctx: &Context<'ctx>,
+        after: Option<String>,
+        before: Option<String>,
+        first: Option<i32>,
+        last: Option<i32>,
+    ) -> Result<ModelConnection<RouteId, Route>, async_graphql::Error> {
+        let SchemaData {
+            repos: Repos { route_repo, .. },
+            ..
+        } = ctx.data()?;
+
+        connection::query(
+            after,
+            before,
+            first,
+            last,
+            |after, before, first, last| async move {
+                let page = route_repo
+                    .filter_page(RouteFilter::All, page_request(after, before, first, last))
+                    .await?;
+
+                Ok::<_, async_graphql::Error>(model_connection(page, |route| route.id, Route))
+            },
+        )
+        .await
+    }
+
     async fn starred_routes<'ctx>(
         &self,
         ctx: &Context<'ctx>,
@@ -118,7 +157,7 @@ impl Query {
 
         let route = route_repo.get(id.0).await?;
 
-        Ok(Some(Route(route)))
+        Ok(route.archived_at.is_none().then_some(Route(route)))
     }
 
     async fn trip<'ctx>(
@@ -151,6 +190,35 @@ impl Query {
             .collect())
     }
 
+    async fn trips_connection<'ctx>(
+        &self,
+        ctx: &Context<'ctx>,
+        after: Option<String>,
+        before: Option<String>,
+        first: Option<i32>,
+        last: Option<i32>,
+    ) -> Result<ModelConnection<TripId, Trip>, async_graphql::Error> {
+        let SchemaData {
+            repos: Repos { trip_repo, .. },
+            ..
+        } = ctx.data()?;
+
+        connection::query(
+            after,
+            before,
+            first,
+            last,
+            |after, before, first, last| async move {
+                let page = trip_repo
+                    .filter_page(TripFilter::All, page_request(after, before, first, last))
+                    .await?;
+
+                Ok::<_, async_graphql::Error>(model_connection(page, |trip| trip.id, Trip))
+            },
+        )
+        .await
+    }
+
     async fn published_trips<'ctx>(
         &self,
         ctx: &Context<'ctx>,
@@ -189,7 +257,7 @@ impl Query {
             repos: Repos { ride_repo, .. },
             ..
         } = ctx.data()?;
-        let rides = ride_repo.all().await?;
+        let rides = ride_repo.filter_models(RideFilter::All).await?;
 
         Ok(rides
             .into_iter()
@@ -215,6 +283,41 @@ impl Query {
         Ok(pois.into_iter().map(PointOfInterest).collect())
     }
 
+    async fn points_of_interest_connection<'ctx>(
+        &self,
+        ctx: &Context<'ctx>,
+        after: Option<String>,
+        before: Option<String>,
+        first: Option<i32>,
+        last: Option<i32>,
+    ) -> Result<ModelConnection<PointOfInterestId, PointOfInterest>, async_graphql::Error> {
+        let SchemaData {
+            repos: Repos {
+                point_of_interest_repo,
+                ..
+            },
+            ..
+        } = ctx.data()?;
+
+        connection::query(
+            after,
+            before,
+            first,
+            last,
+            |after, before, first, last| async move {
+                let page = point_of_interest_repo
+                    .filter_page(
+                        PointOfInterestFilter::All,
+                        page_request(after, before, first, last),
+                    )
+                    .await?;
+
+                Ok::<_, async_graphql::Error>(model_connection(page, |poi| poi.id, PointOfInterest))
+            },
+        )
+        .await
+    }
+
     async fn point_of_interest_with_slug<'ctx>(
         &self,
         ctx: &Context<'ctx>,
@@ -264,4 +367,123 @@ impl Query {
 
         Ok(users.into_iter().map(UserProfile).collect_vec())
     }
+
+    async fn public_users_connection<'ctx>(
+        &self,
+        ctx: &Context<'ctx>,
+        after: Option<String>,
+        before: Option<String>,
+        first: Option<i32>,
+        last: Option<i32>,
+    ) -> Result<ModelConnection<UserId, UserProfile>, async_graphql::Error> {
+        let SchemaData {
+            repos: Repos { user_repo, .. },
+            ..
+        } = ctx.data()?;
+
+        connection::query(
+            after,
+            before,
+            first,
+            last,
+            |after, before, first, last| async move {
+                let page = user_repo
+                    .filter_page(UserFilter::Public, page_request(after, before, first, last))
+                    .await?;
+
+                Ok::<_, async_graphql::Error>(model_connection(page, |user| user.id, UserProfile))
+            },
+        )
+        .await
+    }
+
+    /// Routes, trips and POIs matching the query by name, POIs of a matching
+    /// type around `near`, and geocoded places, in that order. `near` is a
+    /// [longitude, latitude] pair that POIs and places are ranked by distance
+    /// from. At most `limit` results are returned in all, capped at 100.
+    async fn search<'ctx>(
+        &self,
+        ctx: &Context<'ctx>,
+        query: String,
+        near: Option<Vec<f64>>,
+        #[graphql(default = 10)] limit: usize,
+    ) -> Result<Vec<SearchResult>, async_graphql::Error> {
+        let SchemaData {
+            repos:
+                Repos {
+                    route_repo,
+                    trip_repo,
+                    point_of_interest_repo,
+                    ..
+                },
+            mapbox_geocoding_client,
+            ..
+        } = ctx.data()?;
+        let RequestData { login } = ctx.data()?;
+
+        let limit = limit.min(MAX_PAGE_SIZE);
+
+        let near = match near.as_deref() {
+            Some([x, y]) => Some(geo::Point::new(*x, *y)),
+            Some(_) => return Err(Error::new("near must be a [longitude, latitude] pair")),
+            None => None,
+        };
+
+        let terms = search_terms(&query);
+
+        if terms.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let viewer_id = login.as_ref().map(|login| login.session.user_id);
+        let is_viewer = |user_id: UserId| Some(user_id) == viewer_id;
+
+        let routes = route_repo
+            .filter_models(RouteFilter::Search(query.clone()))
+            .await?
+            .into_iter()
+            .filter(|route| route.published_at().is_some() || is_viewer(route.user_id))
+            .collect();
+
+        let trips = trip_repo
+            .filter_models(TripFilter::Search(query.clone()))
+            .await?
+            .into_iter()
+            .filter(|trip| trip.is_published || is_viewer(trip.user_id))
+            .collect();
+
+        let points_of_interest = point_of_interest_repo
+            .filter_models(PointOfInterestFilter::Search(query.clone()))
+            .await?;
+
+        let nearby_points_of_interest = match &near {
+            Some(near) => {
+                let points_of_interest = point_of_interest_repo
+                    .filter_models(PointOfInterestFilter::Near {
+                        point: *near,
+                        distance_m: NEARBY_POINT_OF_INTEREST_RADIUS_M,
+                    })
+                    .await?;
+
+                nearby_points_of_interest(&points_of_interest, &terms, near)
+            }
+            None => vec![],
+        };
+
+        let places = geocode_places(mapbox_geocoding_client, &query, near.as_ref(), limit).await;
+
+        let results = merge_search_results(
+            SearchSources {
+                routes,
+                trips,
+                points_of_interest,
+                nearby_points_of_interest,
+                places,
+            },
+            near.as_ref(),
+            limit,
+        );
+
+        Ok(results.into_iter().map(SearchResult::from).collect())
+    }
 }
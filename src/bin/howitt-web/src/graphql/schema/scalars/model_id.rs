@@ -1,18 +1,38 @@
+use async_graphql::connection::CursorType;
 use async_graphql::scalar;
 use derive_more::derive::From;
 use howitt::models::{
-    media::MediaId, note::NoteId, point_of_interest::PointOfInterestId, ride::RideId,
-    route::RouteId, trip::TripId, user::UserId,
+    media::MediaId, note::NoteId, osm_feature::OsmFeatureId, point_of_interest::PointOfInterestId,
+    point_of_interest_visit::PointOfInterestVisitId, ride::RideId, route::RouteId,
+    sync_run::SyncRunId, trip::TripId, user::UserId, water_beta::WaterBetaId,
 };
-use serde::{Deserialize, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, From)]
 pub struct ModelId<ID: howitt::models::ModelId>(pub ID);
 
 scalar!(ModelId<MediaId>, "MediaId");
 scalar!(ModelId<PointOfInterestId>, "PointOfInterestId");
+scalar!(ModelId<PointOfInterestVisitId>, "PointOfInterestVisitId");
 scalar!(ModelId<RideId>, "RideId");
 scalar!(ModelId<RouteId>, "RouteId");
 scalar!(ModelId<TripId>, "TripId");
 scalar!(ModelId<UserId>, "UserId");
 scalar!(ModelId<NoteId>, "NoteId");
+scalar!(ModelId<OsmFeatureId>, "OsmFeatureId");
+scalar!(ModelId<WaterBetaId>, "WaterBetaId");
+scalar!(ModelId<SyncRunId>, "SyncRunId");
+
+/// Connection cursors are the ids themselves, so a cursor can be fed straight
+/// back into a keyset query.
+impl<ID: howitt::models::ModelId + DeserializeOwned> CursorType for ModelId<ID> {
+    type Error = serde_json::Error;
+
+    fn decode_cursor(s: &str) -> Result<Self, Self::Error> {
+        serde_json::from_value(serde_json::Value::String(s.to_string())).map(ModelId)
+    }
+
+    fn encode_cursor(&self) -> String {
+        self.0.to_string()
+    }
+}
@@ -0,0 +1,198 @@
+use axum::{
+    extract::{Path, State},
+    http::{header, StatusCode},
+    response::IntoResponse,
+    Json,
+};
+use howitt::{
+    models::{
+        ride::RideId,
+        route::RouteFilter,
+        trip::TripFilter,
+        user::{UserFilter, UserId},
+    },
+    repos::Repos,
+    services::{
+        export::{
+            build_trip_export_document, export_document, BuildTripExportDocumentParams,
+            ExportDocument, ExportFormat,
+        },
+        user::auth::Login,
+    },
+};
+use serde_json::json;
+
+use crate::app_state::AppState;
+
+type ExportErrorResponse = (StatusCode, Json<serde_json::Value>);
+
+fn not_found() -> ExportErrorResponse {
+    (StatusCode::NOT_FOUND, Json(json!({"error": "Not found"})))
+}
+
+fn internal_error(e: impl std::fmt::Display) -> ExportErrorResponse {
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        Json(json!({"error": e.to_string()})),
+    )
+}
+
+/// Splits a path segment like `some-route.gpx` into its name and export format.
+fn parse_filename(filename: &str) -> Result<(&str, ExportFormat), ExportErrorResponse> {
+    filename
+        .rsplit_once('.')
+        .and_then(|(name, extension)| Some((name, ExportFormat::from_extension(extension)?)))
+        .ok_or_else(not_found)
+}
+
+fn is_owner(login: &Option<Login>, user_id: UserId) -> bool {
+    login
+        .as_ref()
+        .is_some_and(|login| login.session.user_id == user_id)
+}
+
+fn export_response(
+    document: &ExportDocument,
+    filename: &str,
+    format: ExportFormat,
+) -> Result<impl IntoResponse, ExportErrorResponse> {
+    let body = export_document(document, format).map_err(internal_error)?;
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, format.content_type().to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!(
+                    "attachment; filename=\"{}.{}\"",
+                    filename,
+                    format.extension()
+                ),
+            ),
+        ],
+        body,
+    ))
+}
+
+pub async fn export_route_handler(
+    State(AppState {
+        repos:
+            Repos {
+                route_repo,
+                route_points_repo,
+                ..
+            },
+        ..
+    }): State<AppState>,
+    login: Option<Login>,
+    Path(filename): Path<String>,
+) -> Result<impl IntoResponse, ExportErrorResponse> {
+    let (slug, format) = parse_filename(&filename)?;
+
+    let route = route_repo
+        .find_model(RouteFilter::Slug(slug.to_string()))
+        .await
+        .map_err(internal_error)?
+        .ok_or_else(not_found)?;
+
+    if route.published_at().is_none() && !is_owner(&login, route.user_id) {
+        return Err(not_found());
+    }
+
+    let route_points = route_points_repo
+        .get(route.id)
+        .await
+        .map_err(internal_error)?;
+
+    export_response(
+        &ExportDocument::from_route(&route, &route_points),
+        &route.slug,
+        format,
+    )
+}
+
+pub async fn export_ride_handler(
+    State(AppState {
+        repos: Repos {
+            ride_repo,
+            ride_points_repo,
+            ..
+        },
+        ..
+    }): State<AppState>,
+    login: Option<Login>,
+    Path(filename): Path<String>,
+) -> Result<impl IntoResponse, ExportErrorResponse> {
+    let (ride_id, format) = parse_filename(&filename)?;
+    let ride_id = uuid::Uuid::parse_str(ride_id)
+        .map(RideId::from)
+        .map_err(|_| not_found())?;
+
+    let ride = ride_repo.get(ride_id).await.map_err(|_| not_found())?;
+
+    if ride.archived_at.is_some() || !is_owner(&login, ride.user_id) {
+        return Err(not_found());
+    }
+
+    let ride_points = ride_points_repo
+        .get(ride.id)
+        .await
+        .map_err(internal_error)?;
+
+    export_response(
+        &ExportDocument::from_ride(&ride, &ride_points),
+        &ride.id.as_uuid().to_string(),
+        format,
+    )
+}
+
+pub async fn export_trip_handler(
+    State(AppState {
+        repos:
+            Repos {
+                ride_repo,
+                ride_points_repo,
+                media_repo,
+                trip_repo,
+                user_repo,
+                ..
+            },
+        ..
+    }): State<AppState>,
+    login: Option<Login>,
+    Path((username, filename)): Path<(String, String)>,
+) -> Result<impl IntoResponse, ExportErrorResponse> {
+    let (slug, format) = parse_filename(&filename)?;
+
+    let user = user_repo
+        .find_model(UserFilter::Username(username))
+        .await
+        .map_err(internal_error)?
+        .ok_or_else(not_found)?;
+
+    let trip = trip_repo
+        .find_model(TripFilter::WithUserAndSlug {
+            user_id: user.id,
+            slug: slug.to_string(),
+        })
+        .await
+        .map_err(internal_error)?
+        .ok_or_else(not_found)?;
+
+    if !trip.is_published && !is_owner(&login, trip.user_id) {
+        return Err(not_found());
+    }
+
+    let document = build_trip_export_document(
+        &trip,
+        BuildTripExportDocumentParams {
+            ride_repo,
+            ride_points_repo,
+            media_repo,
+        },
+    )
+    .await
+    .map_err(internal_error)?;
+
+    export_response(&document, &trip.slug, format)
+}
@@ -1,4 +1,5 @@
 pub mod auth;
+pub mod export;
 pub mod graphql;
 pub mod rwgps;
 pub mod upload;
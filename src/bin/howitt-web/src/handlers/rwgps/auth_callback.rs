@@ -4,7 +4,7 @@ use axum::{extract::State, response::IntoResponse, Json};
 use chrono::Utc;
 use howitt::jobs::rwgps::RwgpsJob;
 use howitt::jobs::Job;
-use howitt::models::user::UserRwgpsConnection;
+use howitt::models::user::{RwgpsConnectionStatus, UserRwgpsConnection};
 use howitt::repos::Repos;
 use http::StatusCode;
 use oauth2::basic::{
@@ -151,6 +151,8 @@ pub async fn rwgps_callback_handler(
         access_token: token.access_token().secret().to_string(),
         created_at: Utc::now(),
         updated_at: Utc::now(),
+        status: RwgpsConnectionStatus::Ok,
+        sync_cursor: None,
     };
 
     tracing::info!(user_id = %login.session.user_id, "Fetching user record");
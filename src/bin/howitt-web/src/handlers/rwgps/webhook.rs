@@ -3,9 +3,11 @@ use axum::{
     http::StatusCode,
 };
 use howitt::jobs::{rwgps::RwgpsJob, Job};
+use howitt::services::sync::provider::SyncProvider;
+use howitt::services::sync::rwgps_v2::provider::RwgpsProvider;
 use http::request::Parts;
 use ring::hmac;
-use rwgps_types::webhook::RwgpsWebhookPayload;
+use rwgps::RwgpsClient;
 
 use crate::app_state::AppState;
 
@@ -65,11 +67,15 @@ pub async fn rwgps_webhook_handler(
         return StatusCode::UNAUTHORIZED;
     }
 
-    // Parse and log the webhook payload
-    let payload = match serde_json::from_str::<RwgpsWebhookPayload>(&body) {
-        Ok(payload) => {
-            tracing::info!("Received RWGPS webhook: {:?}", payload);
-            payload
+    let provider = RwgpsProvider {
+        client: RwgpsClient::new(),
+    };
+
+    // Parse and log the webhook events
+    let events = match provider.parse_webhook(body.as_bytes()) {
+        Ok(events) => {
+            tracing::info!("Received RWGPS webhook: {:?}", events);
+            events
         }
         Err(e) => {
             tracing::error!("Failed to parse webhook payload: {}", e);
@@ -77,10 +83,8 @@ pub async fn rwgps_webhook_handler(
         }
     };
 
-    for notification in payload.notifications.into_iter() {
-        let res = job_storage
-            .push(Job::Rwgps(RwgpsJob::Webhook(notification)))
-            .await;
+    for event in events.into_iter() {
+        let res = job_storage.push(Job::Rwgps(RwgpsJob::Webhook(event))).await;
 
         if let Err(e) = res {
             tracing::error!("Failed to push job: {}", e);
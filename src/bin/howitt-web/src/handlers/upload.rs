@@ -2,10 +2,14 @@ use axum::{body::Bytes, extract::State, http::StatusCode, Json};
 use axum_typed_multipart::{FieldData, TryFromMultipart, TypedMultipart};
 use exif::{parse_exif, ParsedExifData};
 use howitt::{
-    jobs::{media::MediaJob, Job},
+    jobs::{geocode::GeocodeJob, media::MediaJob, poi::PoiJob, Job},
     models::media::{Media, MediaId, MediaRelationId},
     repos::Repos,
     services::{
+        import::{
+            import_fit_ride, import_gpx_ride, import_gpx_route, FitImportError, GpxImportError,
+            ImportFitRideParams, ImportGpxRideParams, ImportGpxRouteParams,
+        },
         media::{generate_media_key, GenerateMediaKeyParams},
         user::auth::Login,
     },
@@ -92,6 +96,7 @@ pub async fn upload_media_handler(
         relation_ids,
         point: point.clone(),
         captured_at,
+        external_ref: None,
     };
 
     // Save to database
@@ -131,3 +136,190 @@ pub async fn upload_media_handler(
         })),
     ))
 }
+
+#[derive(TryFromMultipart)]
+pub struct UploadTrackRequest {
+    #[form_data(limit = "unlimited")]
+    pub file: FieldData<Bytes>,
+    pub name: Option<String>,
+}
+
+fn gpx_import_error_response(e: GpxImportError) -> (StatusCode, Json<serde_json::Value>) {
+    let status = match e {
+        GpxImportError::Repo(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        _ => StatusCode::BAD_REQUEST,
+    };
+
+    (status, Json(json!({"error": e.to_string()})))
+}
+
+pub async fn upload_ride_gpx_handler(
+    State(AppState {
+        repos: Repos {
+            ride_repo,
+            ride_points_repo,
+            ..
+        },
+        job_storage,
+        ..
+    }): State<AppState>,
+    login: Login,
+    TypedMultipart(upload): TypedMultipart<UploadTrackRequest>,
+) -> Result<(StatusCode, Json<serde_json::Value>), (StatusCode, Json<serde_json::Value>)> {
+    let ride = import_gpx_ride(ImportGpxRideParams {
+        ride_repo,
+        ride_points_repo,
+        user_id: login.session.user_id,
+        name: upload.name,
+        data: std::io::Cursor::new(upload.file.contents),
+    })
+    .await
+    .map_err(gpx_import_error_response)?;
+
+    job_storage
+        .push(Job::from(PoiJob::DetectVisits(ride.id)))
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": format!("Failed to enqueue job: {}", e)})),
+            )
+        })?;
+
+    job_storage.push(Job::Weather(ride.id)).await.map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({"error": format!("Failed to enqueue job: {}", e)})),
+        )
+    })?;
+
+    job_storage
+        .push(Job::from(GeocodeJob::Ride(ride.id)))
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": format!("Failed to enqueue job: {}", e)})),
+            )
+        })?;
+
+    Ok((
+        StatusCode::OK,
+        Json(json!({
+            "ride_id": ride.id.to_string()
+        })),
+    ))
+}
+
+pub async fn upload_route_gpx_handler(
+    State(AppState {
+        repos:
+            Repos {
+                route_repo,
+                route_points_repo,
+                ..
+            },
+        job_storage,
+        ..
+    }): State<AppState>,
+    login: Login,
+    TypedMultipart(upload): TypedMultipart<UploadTrackRequest>,
+) -> Result<(StatusCode, Json<serde_json::Value>), (StatusCode, Json<serde_json::Value>)> {
+    let route = import_gpx_route(ImportGpxRouteParams {
+        route_repo,
+        route_points_repo,
+        user_id: login.session.user_id,
+        name: upload.name,
+        data: std::io::Cursor::new(upload.file.contents),
+    })
+    .await
+    .map_err(gpx_import_error_response)?;
+
+    job_storage
+        .push(Job::from(GeocodeJob::Route(route.id)))
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": format!("Failed to enqueue job: {}", e)})),
+            )
+        })?;
+
+    Ok((
+        StatusCode::OK,
+        Json(json!({
+            "route_id": route.id.to_string()
+        })),
+    ))
+}
+
+fn fit_import_error_response(e: FitImportError) -> (StatusCode, Json<serde_json::Value>) {
+    let status = match e {
+        FitImportError::Repo(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        FitImportError::Duplicate(_) => StatusCode::CONFLICT,
+        _ => StatusCode::BAD_REQUEST,
+    };
+
+    (status, Json(json!({"error": e.to_string()})))
+}
+
+pub async fn upload_ride_fit_handler(
+    State(AppState {
+        repos:
+            Repos {
+                ride_repo,
+                ride_points_repo,
+                ride_sensors_repo,
+                ..
+            },
+        job_storage,
+        ..
+    }): State<AppState>,
+    login: Login,
+    TypedMultipart(upload): TypedMultipart<UploadTrackRequest>,
+) -> Result<(StatusCode, Json<serde_json::Value>), (StatusCode, Json<serde_json::Value>)> {
+    let ride = import_fit_ride(ImportFitRideParams {
+        ride_repo,
+        ride_points_repo,
+        ride_sensors_repo,
+        user_id: login.session.user_id,
+        name: upload.name,
+        data: std::io::Cursor::new(upload.file.contents),
+    })
+    .await
+    .map_err(fit_import_error_response)?;
+
+    job_storage
+        .push(Job::from(PoiJob::DetectVisits(ride.id)))
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": format!("Failed to enqueue job: {}", e)})),
+            )
+        })?;
+
+    job_storage.push(Job::Weather(ride.id)).await.map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({"error": format!("Failed to enqueue job: {}", e)})),
+        )
+    })?;
+
+    job_storage
+        .push(Job::from(GeocodeJob::Ride(ride.id)))
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": format!("Failed to enqueue job: {}", e)})),
+            )
+        })?;
+
+    Ok((
+        StatusCode::OK,
+        Json(json!({
+            "ride_id": ride.id.to_string()
+        })),
+    ))
+}
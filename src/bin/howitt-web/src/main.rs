@@ -1,5 +1,6 @@
 #![feature(async_closure)]
 use std::net::SocketAddr;
+use std::path::PathBuf;
 use std::sync::Arc;
 
 use apalis_redis::RedisStorage;
@@ -11,9 +12,12 @@ use axum::{
 };
 use howitt::{
     jobs::Job,
-    repos::Repos,
+    repos::{PointOfInterestRepo, Repos},
     services::{
-        fetchers::{SimplifiedRidePointsFetcher, SimplifiedTripElevationPointsFetcher},
+        fetchers::{
+            ElevationFetcher, SimplifiedRidePointsFetcher, SimplifiedTripElevationPointsFetcher,
+        },
+        transport_access::TransportTimetables,
         user::{auth::UserAuthService, signup::UserSignupService},
     },
 };
@@ -22,6 +26,8 @@ use howitt_clients::{RedisClient, S3BucketClient};
 use howitt_jobs::storage::LockFreeStorage;
 use howitt_postgresql::{PostgresClient, PostgresRepos};
 use http::{header, Method};
+use mapbox_geocoding::client::MapboxGeocodingClient;
+use open_meteo::OpenMeteoClient;
 use tower_http::{
     compression::CompressionLayer,
     cors::{Any, CorsLayer},
@@ -42,6 +48,49 @@ use graphql::{
 };
 use tzf_rs::DefaultFinder;
 
+/// Parses `GTFS_FEEDS`, formatted like `vline=/data/vline.zip,metro=/data/metro.zip`.
+/// Feed names must match the names used with `howitt-cli poi import-gtfs`.
+fn gtfs_feeds_from_env() -> Vec<(String, PathBuf)> {
+    std::env::var("GTFS_FEEDS")
+        .unwrap_or_default()
+        .split(',')
+        .filter_map(|feed| feed.split_once('='))
+        .map(|(name, path)| (name.trim().to_string(), PathBuf::from(path.trim())))
+        .collect()
+}
+
+/// How often GTFS timetables are reloaded, to pick up stops imported since.
+const TRANSPORT_TIMETABLE_REFRESH: std::time::Duration = std::time::Duration::from_secs(60 * 60);
+
+async fn reload_transport_timetables(
+    timetables: Arc<TransportTimetables>,
+    point_of_interest_repo: &PointOfInterestRepo,
+) -> Result<(), anyhow::Error> {
+    let pois = point_of_interest_repo.all().await?;
+
+    tokio::task::spawn_blocking(move || timetables.reload(gtfs_feeds_from_env(), &pois)).await??;
+
+    Ok(())
+}
+
+/// Keeps the GTFS timetables up to date with the imported stops. A feed that
+/// fails to load is logged and retried on the next refresh rather than taking
+/// the server down.
+async fn refresh_transport_timetables(
+    timetables: Arc<TransportTimetables>,
+    point_of_interest_repo: PointOfInterestRepo,
+) {
+    loop {
+        if let Err(e) =
+            reload_transport_timetables(timetables.clone(), &point_of_interest_repo).await
+        {
+            tracing::error!("Failed to load GTFS timetables: {:?}", e);
+        }
+
+        tokio::time::sleep(TRANSPORT_TIMETABLE_REFRESH).await;
+    }
+}
+
 #[tokio::main]
 async fn main() -> Result<(), anyhow::Error> {
     dotenv::dotenv().ok();
@@ -81,10 +130,19 @@ async fn main() -> Result<(), anyhow::Error> {
     let simplified_trip_elevation_points_fetcher = SimplifiedTripElevationPointsFetcher::new(
         repos.ride_repo.clone(),
         repos.ride_points_repo.clone(),
-        redis,
+        redis.clone(),
     );
 
-    let bucket_client = S3BucketClient::new_from_env(BucketName::Media);
+    let elevation_fetcher = ElevationFetcher::new(redis);
+
+    let bucket_client = Arc::new(S3BucketClient::new_from_env(BucketName::Media));
+
+    // Departures are missing until the first load finishes
+    let transport_timetables = Arc::new(TransportTimetables::default());
+    tokio::spawn(refresh_transport_timetables(
+        transport_timetables.clone(),
+        repos.point_of_interest_repo.clone(),
+    ));
 
     let schema = build_schema(SchemaData {
         ride_loader: DataLoader::new(RideLoader::new(repos.ride_repo.clone()), tokio::spawn),
@@ -95,11 +153,18 @@ async fn main() -> Result<(), anyhow::Error> {
         ),
         simplified_ride_points_fetcher,
         simplified_trip_elevation_points_fetcher,
+        elevation_fetcher,
         rwgps_client_id: std::env::var("RWGPS_CLIENT_ID").expect("RWGPS_CLIENT_ID must be set"),
         user_auth_service: user_auth_service.clone(),
         repos: repos.clone(),
         job_storage: job_storage.clone(),
         tz_finder: DefaultFinder::new(),
+        transport_timetables,
+        open_meteo_client: OpenMeteoClient::new(),
+        mapbox_geocoding_client: MapboxGeocodingClient::new(
+            std::env::var("MAPBOX_ACCESS_TOKEN").unwrap_or_default(),
+        ),
+        bucket_client: bucket_client.clone(),
     });
 
     let app_state = app_state::AppState {
@@ -107,7 +172,7 @@ async fn main() -> Result<(), anyhow::Error> {
         user_auth_service,
         user_signup_service,
         repos,
-        bucket_client: Arc::new(bucket_client),
+        bucket_client,
         job_storage,
         rwgps: app_state::RwgpsConfig {
             client_id: std::env::var("RWGPS_CLIENT_ID").expect("RWGPS_CLIENT_ID must be set"),
@@ -133,6 +198,33 @@ async fn main() -> Result<(), anyhow::Error> {
             post(handlers::upload::upload_media_handler)
                 .layer(DefaultBodyLimit::max(1024 * 1024 * 100)),
         )
+        .route(
+            "/upload/ride/gpx",
+            post(handlers::upload::upload_ride_gpx_handler)
+                .layer(DefaultBodyLimit::max(1024 * 1024 * 100)),
+        )
+        .route(
+            "/upload/ride/fit",
+            post(handlers::upload::upload_ride_fit_handler)
+                .layer(DefaultBodyLimit::max(1024 * 1024 * 100)),
+        )
+        .route(
+            "/upload/route/gpx",
+            post(handlers::upload::upload_route_gpx_handler)
+                .layer(DefaultBodyLimit::max(1024 * 1024 * 100)),
+        )
+        .route(
+            "/routes/{filename}",
+            get(handlers::export::export_route_handler),
+        )
+        .route(
+            "/rides/{filename}",
+            get(handlers::export::export_ride_handler),
+        )
+        .route(
+            "/trips/{username}/{filename}",
+            get(handlers::export::export_trip_handler),
+        )
         .route(
             "/webhooks/rwgps",
             post(handlers::rwgps::rwgps_webhook_handler),
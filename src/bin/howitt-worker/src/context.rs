@@ -1,18 +1,38 @@
 use std::sync::Arc;
 
 use apalis_redis::RedisStorage;
-use howitt::{jobs::Job, repos::Repos};
+use howitt::{
+    jobs::Job,
+    repos::Repos,
+    services::{
+        fetchers::{
+            LocalityFetcher, SimplifiedRidePointsFetcher, SimplifiedTripElevationPointsFetcher,
+        },
+        sync::rwgps_v2::remove_item::RemovalPolicy,
+    },
+};
 use howitt_client_types::BucketName;
-use howitt_clients::S3BucketClient;
+use howitt_clients::{RedisClient, ReqwestHttpClient, S3BucketClient};
 use howitt_jobs::storage::LockFreeStorage;
 use howitt_postgresql::{PostgresClient, PostgresRepos};
+use mapbox_geocoding::client::MapboxGeocodingClient;
+use open_meteo::{OpenMeteoClient, OpenMeteoHistoryClient};
 use rwgps::RwgpsClient;
 
 #[derive(Clone)]
 pub struct Context {
     pub repos: Repos,
     pub bucket_client: Arc<S3BucketClient>,
+    pub http_client: Arc<ReqwestHttpClient>,
     pub rwgps_client: RwgpsClient,
+    pub rwgps_removal_policy: RemovalPolicy,
+    pub open_meteo_history_client: OpenMeteoHistoryClient,
+    pub open_meteo_client: OpenMeteoClient,
+    pub mapbox_geocoding_client: MapboxGeocodingClient,
+    pub locality_fetcher: Arc<LocalityFetcher<RedisClient>>,
+    pub simplified_ride_points_fetcher: Arc<SimplifiedRidePointsFetcher<RedisClient>>,
+    pub simplified_trip_elevation_points_fetcher:
+        Arc<SimplifiedTripElevationPointsFetcher<RedisClient>>,
     pub image_processing_semaphore: Arc<tokio::sync::Semaphore>,
     pub job_storage: LockFreeStorage<Job>,
 }
@@ -25,12 +45,45 @@ impl Context {
         )
         .await?;
 
+        let redis_client = RedisClient::connect(
+            std::env::var("REDIS_URL").unwrap_or(String::from("redis://localhost:6379")),
+        )
+        .await?;
+
         let bucket_client = S3BucketClient::new_from_env(BucketName::Media);
 
+        let rwgps_removal_policy = match std::env::var("RWGPS_REMOVAL_POLICY") {
+            Ok(policy) => policy.parse()?,
+            Err(_) => RemovalPolicy::default(),
+        };
+
+        let repos = Repos::from(PostgresRepos::new(postgres_client));
+
+        let simplified_ride_points_fetcher =
+            SimplifiedRidePointsFetcher::new(repos.ride_points_repo.clone(), redis_client.clone());
+
+        let simplified_trip_elevation_points_fetcher = SimplifiedTripElevationPointsFetcher::new(
+            repos.ride_repo.clone(),
+            repos.ride_points_repo.clone(),
+            redis_client.clone(),
+        );
+
         Ok(Self {
-            repos: Repos::from(PostgresRepos::new(postgres_client)),
+            repos,
             bucket_client: Arc::new(bucket_client),
+            http_client: Arc::new(ReqwestHttpClient::new()),
             rwgps_client: RwgpsClient::new(),
+            rwgps_removal_policy,
+            open_meteo_history_client: OpenMeteoHistoryClient::new(),
+            open_meteo_client: OpenMeteoClient::new(),
+            mapbox_geocoding_client: MapboxGeocodingClient::new(
+                std::env::var("MAPBOX_ACCESS_TOKEN").unwrap_or_default(),
+            ),
+            locality_fetcher: Arc::new(LocalityFetcher::new(redis_client)),
+            simplified_ride_points_fetcher: Arc::new(simplified_ride_points_fetcher),
+            simplified_trip_elevation_points_fetcher: Arc::new(
+                simplified_trip_elevation_points_fetcher,
+            ),
             image_processing_semaphore: Arc::new(tokio::sync::Semaphore::new(4)),
             job_storage: LockFreeStorage::new(job_storage),
         })
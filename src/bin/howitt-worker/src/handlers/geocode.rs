@@ -0,0 +1,313 @@
+use howitt::jobs::geocode::GeocodeJob;
+use howitt::models::locality::{Locality, RideLocalities, RouteLocalities};
+use howitt::models::point::Point;
+use howitt::repos::Repos;
+use howitt::services::fetchers::LocalityFetcher;
+use howitt_client_types::RedisClient;
+use mapbox_geocoding::client::MapboxGeocodingClient;
+use mapbox_geocoding::schema::{
+    Context as GeocodingContext, ContextItem, Feature, GeocodingResponse, ReverseGeocodingParams,
+};
+use thiserror::Error;
+use tracing;
+
+use crate::context::Context;
+
+#[derive(Debug, Error)]
+pub enum GeocodeJobError {
+    #[error("Failed to process geocode job: {0}")]
+    Processing(#[from] anyhow::Error),
+}
+
+/// Name of the `feature_type` component of a feature, which is either the
+/// feature itself or one of its parents in the context.
+fn component(
+    feature: &Feature,
+    feature_type: &str,
+    from_context: fn(&GeocodingContext) -> &Option<ContextItem>,
+) -> Option<String> {
+    let properties = &feature.properties;
+
+    if properties.feature_type == feature_type {
+        return Some(properties.name.clone());
+    }
+
+    from_context(properties.context.as_ref()?)
+        .as_ref()
+        .map(|item| item.name.clone())
+}
+
+fn locality_from_response(response: &GeocodingResponse) -> Option<Locality> {
+    let name = response.features.iter().find_map(|feature| {
+        component(feature, "locality", |context| &context.locality)
+            .or_else(|| component(feature, "place", |context| &context.place))
+    });
+    let region = response
+        .features
+        .iter()
+        .find_map(|feature| component(feature, "region", |context| &context.region));
+
+    match (name, region) {
+        (None, None) => None,
+        (name, region) => Some(Locality { name, region }),
+    }
+}
+
+async fn reverse_geocode(
+    client: &MapboxGeocodingClient,
+    point: geo::Point,
+) -> Result<Option<Locality>, anyhow::Error> {
+    let response = client
+        .reverse_geocode(ReverseGeocodingParams {
+            longitude: point.x(),
+            latitude: point.y(),
+            access_token: client.access_token().to_string(),
+            // Mapbox only allows results to be stored with permanent geocoding
+            permanent: Some(true),
+            country: None,
+            language: Some(String::from("en")),
+            limit: None,
+            types: None,
+            worldview: None,
+        })
+        .await?;
+
+    Ok(locality_from_response(&response))
+}
+
+fn endpoints<P: Point>(points: &[P]) -> Option<(geo::Point, geo::Point)> {
+    Some((
+        *points.first()?.as_geo_point(),
+        *points.last()?.as_geo_point(),
+    ))
+}
+
+async fn geocode<Redis: RedisClient>(
+    job: GeocodeJob,
+    repos: &Repos,
+    client: &MapboxGeocodingClient,
+    locality_fetcher: &LocalityFetcher<Redis>,
+) -> Result<(), anyhow::Error> {
+    let Repos {
+        ride_points_repo,
+        ride_localities_repo,
+        route_points_repo,
+        route_localities_repo,
+        ..
+    } = repos;
+
+    let locality = |point| locality_fetcher.fetch(point, |point| reverse_geocode(client, point));
+
+    match job {
+        GeocodeJob::Route(route_id) => {
+            tracing::info!(route_id = %route_id, "Geocoding route termini");
+
+            let route_points = route_points_repo.get(route_id).await?;
+
+            let Some((start, end)) = endpoints(&route_points.points) else {
+                tracing::warn!(route_id = %route_id, "Route has no points to geocode");
+                return Ok(());
+            };
+
+            route_localities_repo
+                .put(RouteLocalities {
+                    id: route_id,
+                    start: locality(start).await?,
+                    end: locality(end).await?,
+                })
+                .await?;
+        }
+        GeocodeJob::Ride(ride_id) => {
+            tracing::info!(ride_id = %ride_id, "Geocoding ride start and finish");
+
+            let ride_points = ride_points_repo.get(ride_id).await?;
+
+            let Some((start, finish)) = endpoints(&ride_points.points) else {
+                tracing::warn!(ride_id = %ride_id, "Ride has no points to geocode");
+                return Ok(());
+            };
+
+            ride_localities_repo
+                .put(RideLocalities {
+                    id: ride_id,
+                    start: locality(start).await?,
+                    finish: locality(finish).await?,
+                })
+                .await?;
+        }
+    }
+
+    Ok(())
+}
+
+pub async fn handle_geocode_job(
+    job: GeocodeJob,
+    Context {
+        repos,
+        mapbox_geocoding_client,
+        locality_fetcher,
+        ..
+    }: Context,
+) -> Result<(), GeocodeJobError> {
+    geocode(job, &repos, &mapbox_geocoding_client, &locality_fetcher).await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use chrono::{TimeDelta, TimeZone, Utc};
+    use howitt::models::point::{ElevationPoint, TemporalElevationPoint};
+    use howitt::models::ride::{RideId, RidePoints};
+    use howitt::models::route::{RouteId, RoutePoints};
+    use howitt_memory::{serve_http_stub, MemoryRedisClient, MemoryRepos};
+
+    use super::*;
+
+    const HARRIETVILLE: (f64, f64) = (146.9397, -36.8917);
+    const FALLS_CREEK: (f64, f64) = (147.2803, -36.8655);
+
+    /// A reverse geocoding response for a point on a street, which is how
+    /// Mapbox answers for most trailheads.
+    fn feature_collection(locality: &str) -> String {
+        format!(
+            r#"{{
+                "type": "FeatureCollection",
+                "attribution": "NOTICE: © 2025 Mapbox and its suppliers.",
+                "features": [{{
+                    "id": "dXJuOm1ieGFkcjo",
+                    "type": "Feature",
+                    "geometry": {{ "type": "Point", "coordinates": [146.94, -36.89] }},
+                    "properties": {{
+                        "mapbox_id": "dXJuOm1ieGFkcjo",
+                        "feature_type": "street",
+                        "name": "Great Alpine Road",
+                        "coordinates": {{ "longitude": 146.94, "latitude": -36.89 }},
+                        "context": {{
+                            "locality": {{ "mapbox_id": "dXJuOm1ieGxvYw", "name": "{locality}" }},
+                            "region": {{ "mapbox_id": "dXJuOm1ieHJlZw", "name": "Victoria" }}
+                        }}
+                    }}
+                }}]
+            }}"#
+        )
+    }
+
+    /// Serves canned reverse geocoding responses, answering with Falls Creek
+    /// east of 147° and Harrietville otherwise. Returns the base URL to point
+    /// the client at.
+    async fn serve_stub(requests: Arc<Mutex<Vec<String>>>) -> String {
+        let addr = serve_http_stub(move |request| {
+            let request_line = request.lines().next().unwrap_or_default();
+            requests.lock().unwrap().push(request_line.to_string());
+
+            if request_line.contains("longitude=147") {
+                feature_collection("Falls Creek")
+            } else {
+                feature_collection("Harrietville")
+            }
+        })
+        .await;
+
+        format!("http://{addr}/search/geocode/v6/")
+    }
+
+    fn locality(name: &str) -> Option<Locality> {
+        Some(Locality {
+            name: Some(String::from(name)),
+            region: Some(String::from("Victoria")),
+        })
+    }
+
+    #[tokio::test]
+    async fn test_geocode_route_and_ride() {
+        let requests = Arc::new(Mutex::new(vec![]));
+        let client = MapboxGeocodingClient::with_base_url(
+            String::from("test-token"),
+            serve_stub(requests.clone()).await.parse().unwrap(),
+        );
+        let locality_fetcher = LocalityFetcher::new(MemoryRedisClient::new());
+        let repos = Repos::from(MemoryRepos::default());
+
+        let route_id = RouteId::new();
+        repos
+            .route_points_repo
+            .put(RoutePoints {
+                id: route_id,
+                points: [HARRIETVILLE, (147.1, -36.95), FALLS_CREEK]
+                    .into_iter()
+                    .map(|(x, y)| ElevationPoint {
+                        point: geo::Point::new(x, y),
+                        elevation: 500.0,
+                    })
+                    .collect(),
+            })
+            .await
+            .unwrap();
+
+        // A loop from the same trailhead
+        let ride_id = RideId::new();
+        let started_at = Utc.with_ymd_and_hms(2025, 2, 12, 8, 0, 0).unwrap();
+        repos
+            .ride_points_repo
+            .put(RidePoints {
+                id: ride_id,
+                points: [HARRIETVILLE, (147.0, -36.9), HARRIETVILLE]
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, (x, y))| TemporalElevationPoint {
+                        datetime: started_at + TimeDelta::hours(i as i64),
+                        point: geo::Point::new(x, y),
+                        elevation: 500.0,
+                    })
+                    .collect(),
+            })
+            .await
+            .unwrap();
+
+        geocode(
+            GeocodeJob::Route(route_id),
+            &repos,
+            &client,
+            &locality_fetcher,
+        )
+        .await
+        .unwrap();
+        geocode(
+            GeocodeJob::Ride(ride_id),
+            &repos,
+            &client,
+            &locality_fetcher,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            repos.route_localities_repo.get(route_id).await.unwrap(),
+            RouteLocalities {
+                id: route_id,
+                start: locality("Harrietville"),
+                end: locality("Falls Creek"),
+            }
+        );
+        assert_eq!(
+            repos.ride_localities_repo.get(ride_id).await.unwrap(),
+            RideLocalities {
+                id: ride_id,
+                start: locality("Harrietville"),
+                finish: locality("Harrietville"),
+            }
+        );
+
+        // The ride starts and finishes where the route does, so it's all cached
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests.len(), 2);
+        assert!(requests.iter().all(|request| {
+            request.starts_with("GET /search/geocode/v6/reverse?")
+                && request.contains("access_token=test-token")
+                && request.contains("permanent=true")
+        }));
+    }
+}
@@ -6,18 +6,30 @@ use howitt::jobs::Job;
 
 use crate::context::Context;
 
+mod geocode;
 mod media;
+mod poi;
 mod rwgps;
+mod weather;
 
 pub async fn handle_job(job: Job, ctx: Data<Context>) -> Result<(), Error> {
     let ctx = (*ctx).clone();
 
     match job {
+        Job::Geocode(geocode_job) => geocode::handle_geocode_job(geocode_job, ctx)
+            .await
+            .map_err(|e| Error::Failed(Arc::new(Box::new(e)))),
         Job::Media(media_job) => media::handle_media_job(media_job, ctx)
             .await
             .map_err(|e| Error::Failed(Arc::new(Box::new(e)))),
+        Job::Poi(poi_job) => poi::handle_poi_job(poi_job, ctx)
+            .await
+            .map_err(|e| Error::Failed(Arc::new(Box::new(e)))),
         Job::Rwgps(rwgps_job) => rwgps::handle_rwgps_job(rwgps_job, ctx)
             .await
             .map_err(|e| Error::Failed(Arc::new(Box::new(e)))),
+        Job::Weather(ride_id) => weather::handle_weather_job(ride_id, ctx)
+            .await
+            .map_err(|e| Error::Failed(Arc::new(Box::new(e)))),
     }
 }
@@ -0,0 +1,47 @@
+use howitt::jobs::poi::PoiJob;
+use howitt::repos::Repos;
+use howitt::services::detect_poi_visits::{detect_ride_visits, DetectRideVisitsParams};
+use thiserror::Error;
+use tracing;
+
+use crate::context::Context;
+
+#[derive(Debug, Error)]
+pub enum PoiJobError {
+    #[error("Failed to process POI job: {0}")]
+    Processing(#[from] anyhow::Error),
+}
+
+pub async fn handle_poi_job(
+    job: PoiJob,
+    Context {
+        repos:
+            Repos {
+                ride_repo,
+                ride_points_repo,
+                point_of_interest_repo,
+                point_of_interest_visit_repo,
+                media_repo,
+                ..
+            },
+        ..
+    }: Context,
+) -> Result<(), PoiJobError> {
+    match job {
+        PoiJob::DetectVisits(ride_id) => {
+            tracing::info!(ride_id = %ride_id, "Detecting POI visits");
+
+            detect_ride_visits(DetectRideVisitsParams {
+                ride_repo,
+                ride_points_repo,
+                point_of_interest_repo,
+                point_of_interest_visit_repo,
+                media_repo,
+                ride_id,
+            })
+            .await?;
+        }
+    }
+
+    Ok(())
+}
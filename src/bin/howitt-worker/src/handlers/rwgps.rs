@@ -1,16 +1,22 @@
+use chrono::{DateTime, Utc};
+use howitt::jobs::geocode::GeocodeJob;
+use howitt::jobs::media::MediaJob;
+use howitt::jobs::poi::PoiJob;
 use howitt::jobs::rwgps::RwgpsJob;
 use howitt::jobs::Job;
-use howitt::models::user::UserFilter;
-use howitt::repos::Repos;
-use howitt::services::sync::rwgps_v2::select_historical_route_sync_candidates::{
-    select_historical_route_sync_candidates, SyncRouteHistoryParams,
-};
-use howitt::services::sync::rwgps_v2::select_historical_trip_sync_candidates::{
-    select_historical_trip_sync_candidates, SyncTripHistoryParams,
-};
+use howitt::models::external_ref::{ExternalId, ExternalProvider, RwgpsId};
+use howitt::models::sync_run::{SyncCursor, SyncRun, SyncRunId};
+use howitt::models::user::{RwgpsConnectionStatus, UserFilter, UserId, UserRwgpsConnection};
+use howitt::repos::{Repos, UserRepo};
+use howitt::services::sync::provider::{WebhookAction, WebhookEvent};
+use howitt::services::sync::rwgps_v2::provider::RwgpsProvider;
+use howitt::services::sync::rwgps_v2::remove_item::{remove_item, RemoveItemParams};
 use howitt::services::sync::rwgps_v2::sync_route::{sync_route, SyncRouteParams};
-use howitt::services::sync::rwgps_v2::sync_trip::{sync_trip, SyncTripParams};
-use rwgps_types::webhook::ItemType;
+use howitt::services::sync::rwgps_v2::sync_trip::{sync_trip, SyncTripParams, SyncedTrip};
+use howitt::services::sync::select_sync_candidates::{
+    select_sync_candidates, SelectSyncCandidatesParams, SyncCandidates,
+};
+use rwgps::RwgpsError;
 use thiserror::Error;
 use tracing;
 
@@ -22,7 +28,167 @@ pub enum RwgpsJobError {
     Processing(#[from] anyhow::Error),
 }
 
-pub async fn handle_rwgps_job(
+/// What a failed job says about the connection, if RWGPS turned a request
+/// away.
+fn connection_status(error: &anyhow::Error) -> Option<RwgpsConnectionStatus> {
+    error
+        .chain()
+        .find_map(|error| match error.downcast_ref::<RwgpsError>()? {
+            RwgpsError::Unauthorised => Some(RwgpsConnectionStatus::Unauthorised),
+            RwgpsError::RateLimited => Some(RwgpsConnectionStatus::RateLimited),
+            _ => None,
+        })
+}
+
+/// Whether a job can run with the user's stored connection.
+#[derive(Debug)]
+enum ConnectionState {
+    Active(UserRwgpsConnection),
+    /// Rate limited, so the job has to wait until the pause is up
+    PausedUntil(DateTime<Utc>),
+    /// Turned away until the user reconnects, or disconnected since the job
+    /// was queued
+    Inactive,
+}
+
+async fn connection_state(
+    user_repo: &UserRepo,
+    user_id: UserId,
+    now: DateTime<Utc>,
+) -> Result<ConnectionState, anyhow::Error> {
+    let Some(connection) = user_repo.get(user_id).await?.rwgps_connection else {
+        tracing::warn!(user_id = %user_id, "User has disconnected RWGPS");
+        return Ok(ConnectionState::Inactive);
+    };
+
+    if !connection.is_paused(now) {
+        return Ok(ConnectionState::Active(connection));
+    }
+
+    tracing::warn!(
+        user_id = %user_id,
+        status = ?connection.status,
+        "RWGPS sync is paused for user"
+    );
+
+    Ok(match connection.resumes_at() {
+        Some(resumes_at) => ConnectionState::PausedUntil(resumes_at),
+        None => ConnectionState::Inactive,
+    })
+}
+
+async fn set_sync_cursor(
+    user_repo: &UserRepo,
+    user_id: UserId,
+    cursor: SyncCursor,
+) -> Result<(), anyhow::Error> {
+    tracing::info!(user_id = %user_id, ?cursor, "Moving RWGPS sync cursor");
+
+    user_repo.set_rwgps_sync_cursor(user_id, cursor).await
+}
+
+/// Counts a queued item's result on its sync run. The last result in moves
+/// the connection's cursor up, if nothing failed.
+async fn record_sync_result(
+    Repos {
+        sync_run_repo,
+        user_repo,
+        ..
+    }: &Repos,
+    sync_run_id: SyncRunId,
+    succeeded: bool,
+) -> Result<(), anyhow::Error> {
+    let run = sync_run_repo
+        .record_result(sync_run_id, succeeded, Utc::now())
+        .await?;
+
+    if let Some(cursor) = run.completed_cursor() {
+        tracing::info!(
+            sync_run_id = %run.id,
+            succeeded = run.succeeded,
+            "Completed RWGPS sync run"
+        );
+        set_sync_cursor(user_repo, run.user_id, cursor).await?;
+    }
+
+    Ok(())
+}
+
+/// Runs the job with the user's stored connection, keeping track of how RWGPS
+/// answers. Jobs for a rate limited user are queued again for when the pause
+/// is up. Jobs for a user who has to reconnect are skipped, and count as
+/// failures on their sync run; reconnecting queues a history sync that picks
+/// them up.
+pub async fn handle_rwgps_job(mut job: RwgpsJob, ctx: Context) -> Result<(), RwgpsJobError> {
+    let sync_run_id = job.sync_run_id();
+
+    let Some(job_connection) = job.rwgps_connection_mut() else {
+        return process_rwgps_job(job, ctx).await;
+    };
+    let user_id = job_connection.user_id;
+
+    let Context {
+        repos, job_storage, ..
+    } = ctx.clone();
+
+    let connection = match connection_state(&repos.user_repo, user_id, Utc::now()).await? {
+        ConnectionState::Active(connection) => connection,
+        ConnectionState::PausedUntil(resumes_at) => {
+            tracing::info!(user_id = %user_id, %resumes_at, "Queueing RWGPS job for later");
+
+            job_storage
+                .schedule(Job::from(job), resumes_at)
+                .await
+                .map_err(|e| RwgpsJobError::Processing(e.into()))?;
+
+            return Ok(());
+        }
+        ConnectionState::Inactive => {
+            if let Some(sync_run_id) = sync_run_id {
+                record_sync_result(&repos, sync_run_id, false).await?;
+            }
+            return Ok(());
+        }
+    };
+
+    // The job may have been queued before the user reconnected, in which
+    // case its token is stale
+    *job_connection = connection.clone();
+
+    let result = process_rwgps_job(job, ctx).await;
+
+    if let Some(sync_run_id) = sync_run_id {
+        record_sync_result(&repos, sync_run_id, result.is_ok()).await?;
+    }
+
+    match &result {
+        Err(RwgpsJobError::Processing(error)) => {
+            if let Some(status) = connection_status(error) {
+                tracing::warn!(
+                    user_id = %user_id,
+                    ?status,
+                    "RWGPS turned the connection away, pausing sync"
+                );
+                repos
+                    .user_repo
+                    .set_rwgps_status(user_id, status, Utc::now())
+                    .await?;
+            }
+        }
+        Ok(()) if connection.status != RwgpsConnectionStatus::Ok => {
+            tracing::info!(user_id = %user_id, "RWGPS connection recovered");
+            repos
+                .user_repo
+                .set_rwgps_status(user_id, RwgpsConnectionStatus::Ok, Utc::now())
+                .await?;
+        }
+        Ok(()) => {}
+    }
+
+    result
+}
+
+async fn process_rwgps_job(
     job: RwgpsJob,
     Context {
         repos:
@@ -30,39 +196,49 @@ pub async fn handle_rwgps_job(
                 user_repo,
                 route_repo,
                 route_points_repo,
+                route_course_points_repo,
                 ride_repo,
                 ride_points_repo,
+                trip_repo,
+                media_repo,
+                sync_audit_entry_repo,
+                sync_run_repo,
                 ..
             },
+        bucket_client,
+        http_client,
         rwgps_client,
+        rwgps_removal_policy,
+        simplified_ride_points_fetcher,
+        simplified_trip_elevation_points_fetcher,
         job_storage,
         ..
     }: Context,
 ) -> Result<(), RwgpsJobError> {
     match job {
-        RwgpsJob::Webhook(notification) => {
-            tracing::info!(
-                    item_type = ?notification.item_type,
-                    item_id = notification.item_id,
-            user_id = notification.user_id,
-                    action = ?notification.action,
-                    "Processing RWGPS {:?} webhook", notification.item_type
-                );
-
+        RwgpsJob::Webhook(WebhookEvent {
+            owner_id,
+            id,
+            action,
+        }) => {
             tracing::info!(
-                user_id = notification.user_id,
-                "Looking up user by RWGPS ID"
+                user_id = owner_id,
+                item_id = ?id,
+                action = ?action,
+                "Processing RWGPS webhook"
             );
 
+            tracing::info!(user_id = owner_id, "Looking up user by RWGPS ID");
+
             // Get user from repo
             let user = user_repo
-                .find_model(UserFilter::RwgpsId(notification.user_id as usize))
+                .find_model(UserFilter::RwgpsId(owner_id))
                 .await?
                 .ok_or(RwgpsJobError::Processing(anyhow::anyhow!(
                     "No user found with RWGPS ID"
                 )))?;
             tracing::info!(
-                user_id = notification.user_id,
+                user_id = owner_id,
                 howitt_user_id = user.id.to_string(),
                 "Found user, checking for RWGPS connection"
             );
@@ -73,58 +249,75 @@ pub async fn handle_rwgps_job(
                 .ok_or_else(|| anyhow::anyhow!("User has no RWGPS connection"))?;
 
             tracing::debug!(
-                user_id = notification.user_id,
+                user_id = owner_id,
                 howitt_user_id = user.id.to_string(),
                 "Found RWGPS connection"
             );
 
-            match notification.item_type {
-                ItemType::Route => {
-                    job_storage
-                        .push(Job::Rwgps(RwgpsJob::SyncRoute {
-                            rwgps_route_id: notification.item_id as usize,
-                            connection,
-                        }))
-                        .await
-                        .map_err(|e| RwgpsJobError::Processing(e.into()))?;
-
-                    tracing::info!(
-                        route_id = notification.item_id,
-                        "Successfully processed RWGPS route webhook"
-                    );
+            let job = match (id.clone(), action) {
+                (ExternalId::Rwgps(RwgpsId::Route(rwgps_route_id)), WebhookAction::Upsert) => {
+                    RwgpsJob::SyncRoute {
+                        rwgps_route_id,
+                        connection,
+                        sync_run_id: None,
+                    }
                 }
-                ItemType::Trip => {
-                    job_storage
-                        .push(Job::Rwgps(RwgpsJob::SyncTrip {
-                            rwgps_trip_id: notification.item_id as usize,
-                            connection,
-                        }))
-                        .await
-                        .map_err(|e| RwgpsJobError::Processing(e.into()))?;
-
-                    tracing::info!(
-                        trip_id = notification.item_id,
-                        "Successfully processed RWGPS trip webhook"
-                    );
+                (ExternalId::Rwgps(RwgpsId::Route(rwgps_route_id)), WebhookAction::Remove) => {
+                    RwgpsJob::RemoveRoute {
+                        rwgps_route_id,
+                        connection,
+                    }
                 }
-            }
+                (ExternalId::Rwgps(RwgpsId::Trip(rwgps_trip_id)), WebhookAction::Upsert) => {
+                    RwgpsJob::SyncTrip {
+                        rwgps_trip_id,
+                        connection,
+                        sync_run_id: None,
+                    }
+                }
+                (ExternalId::Rwgps(RwgpsId::Trip(rwgps_trip_id)), WebhookAction::Remove) => {
+                    RwgpsJob::RemoveTrip {
+                        rwgps_trip_id,
+                        connection,
+                    }
+                }
+                (id, _) => {
+                    return Err(RwgpsJobError::Processing(anyhow::anyhow!(
+                        "Unexpected item in RWGPS webhook: {id:?}"
+                    )))
+                }
+            };
+
+            job_storage
+                .push(Job::Rwgps(job))
+                .await
+                .map_err(|e| RwgpsJobError::Processing(e.into()))?;
+
+            tracing::info!(item_id = ?id, "Successfully processed RWGPS webhook");
         }
         RwgpsJob::SyncRoute {
             rwgps_route_id,
             connection,
+            ..
         } => {
             tracing::info!(route_id = rwgps_route_id, "Processing RWGPS route sync");
 
             // Sync the route
-            sync_route(SyncRouteParams {
+            let route_id = sync_route(SyncRouteParams {
                 client: rwgps_client,
                 route_repo,
                 route_points_repo,
+                route_course_points_repo,
                 rwgps_route_id,
                 connection,
             })
             .await?;
 
+            job_storage
+                .push(Job::from(GeocodeJob::Route(route_id)))
+                .await
+                .map_err(|e| RwgpsJobError::Processing(e.into()))?;
+
             tracing::info!(
                 route_id = rwgps_route_id,
                 "Successfully processed RWGPS route sync"
@@ -133,24 +326,108 @@ pub async fn handle_rwgps_job(
         RwgpsJob::SyncTrip {
             rwgps_trip_id,
             connection,
+            ..
         } => {
             tracing::info!(trip_id = rwgps_trip_id, "Processing RWGPS trip sync");
 
             // Sync the trip
-            sync_trip(SyncTripParams {
+            let SyncedTrip {
+                ride_id,
+                imported_media,
+            } = sync_trip(SyncTripParams {
                 client: rwgps_client,
+                http_client,
+                bucket_client,
                 ride_repo,
                 ride_points_repo,
+                media_repo,
                 rwgps_trip_id,
                 connection,
             })
             .await?;
 
+            for media in imported_media {
+                job_storage
+                    .push(Job::from(MediaJob::Process(media.id)))
+                    .await
+                    .map_err(|e| RwgpsJobError::Processing(e.into()))?;
+
+                if media.point.is_none() {
+                    job_storage
+                        .push(Job::from(MediaJob::InferLocation(media.id)))
+                        .await
+                        .map_err(|e| RwgpsJobError::Processing(e.into()))?;
+                }
+            }
+
+            job_storage
+                .push(Job::Poi(PoiJob::DetectVisits(ride_id)))
+                .await
+                .map_err(|e| RwgpsJobError::Processing(e.into()))?;
+
+            job_storage
+                .push(Job::Weather(ride_id))
+                .await
+                .map_err(|e| RwgpsJobError::Processing(e.into()))?;
+
+            job_storage
+                .push(Job::from(GeocodeJob::Ride(ride_id)))
+                .await
+                .map_err(|e| RwgpsJobError::Processing(e.into()))?;
+
             tracing::info!(
                 trip_id = rwgps_trip_id,
                 "Successfully processed RWGPS trip sync"
             );
         }
+        RwgpsJob::RemoveRoute {
+            rwgps_route_id,
+            connection,
+        } => {
+            tracing::info!(route_id = rwgps_route_id, "Processing RWGPS route removal");
+
+            remove_item(RemoveItemParams {
+                route_repo,
+                ride_repo,
+                trip_repo,
+                sync_audit_entry_repo,
+                simplified_ride_points_fetcher,
+                simplified_trip_elevation_points_fetcher,
+                rwgps_id: RwgpsId::Route(rwgps_route_id),
+                connection,
+                policy: rwgps_removal_policy,
+            })
+            .await?;
+
+            tracing::info!(
+                route_id = rwgps_route_id,
+                "Successfully processed RWGPS route removal"
+            );
+        }
+        RwgpsJob::RemoveTrip {
+            rwgps_trip_id,
+            connection,
+        } => {
+            tracing::info!(trip_id = rwgps_trip_id, "Processing RWGPS trip removal");
+
+            remove_item(RemoveItemParams {
+                route_repo,
+                ride_repo,
+                trip_repo,
+                sync_audit_entry_repo,
+                simplified_ride_points_fetcher,
+                simplified_trip_elevation_points_fetcher,
+                rwgps_id: RwgpsId::Trip(rwgps_trip_id),
+                connection,
+                policy: rwgps_removal_policy,
+            })
+            .await?;
+
+            tracing::info!(
+                trip_id = rwgps_trip_id,
+                "Successfully processed RWGPS trip removal"
+            );
+        }
         RwgpsJob::SyncHistory { connection } => {
             tracing::info!(
                 user_id = connection.user_id.to_string(),
@@ -158,71 +435,273 @@ pub async fn handle_rwgps_job(
                 "Processing RWGPS history sync"
             );
 
-            let route_task = async {
-                tracing::info!("Finding historical routes to sync");
-                let route_candidates =
-                    select_historical_route_sync_candidates(SyncRouteHistoryParams {
-                        client: rwgps_client.clone(),
-                        route_repo,
-                        connection: connection.clone(),
-                    })
-                    .await?;
+            tracing::info!(
+                since = ?connection.sync_cursor,
+                "Finding historical routes and trips to sync"
+            );
+            let SyncCandidates { ids, since, cursor } =
+                select_sync_candidates(SelectSyncCandidatesParams {
+                    provider: &RwgpsProvider {
+                        client: rwgps_client,
+                    },
+                    connection: &connection,
+                    user_id: connection.user_id,
+                    since: connection.sync_cursor,
+                    route_repo,
+                    ride_repo,
+                })
+                .await?;
 
-                tracing::info!(
-                    count = route_candidates.len(),
-                    "Found historical routes to sync"
-                );
+            let sync_run_id = SyncRunId::new();
 
-                for route in route_candidates {
-                    tracing::debug!(
-                        route_id = route.rwgps_route_id,
-                        "Queuing historical route sync"
-                    );
-                    job_storage
-                        .push(Job::Rwgps(RwgpsJob::SyncRoute {
-                            rwgps_route_id: route.rwgps_route_id,
+            let jobs: Vec<_> = ids
+                .into_iter()
+                .filter_map(|id| match id {
+                    ExternalId::Rwgps(RwgpsId::Route(rwgps_route_id)) => {
+                        Some(RwgpsJob::SyncRoute {
+                            rwgps_route_id,
                             connection: connection.clone(),
-                        }))
-                        .await
-                        .map_err(|e| RwgpsJobError::Processing(e.into()))?;
-                }
+                            sync_run_id: Some(sync_run_id),
+                        })
+                    }
+                    ExternalId::Rwgps(RwgpsId::Trip(rwgps_trip_id)) => Some(RwgpsJob::SyncTrip {
+                        rwgps_trip_id,
+                        connection: connection.clone(),
+                        sync_run_id: Some(sync_run_id),
+                    }),
+                    id => {
+                        tracing::warn!(?id, "Skipping unexpected history sync candidate");
+                        None
+                    }
+                })
+                .collect();
 
-                Ok::<_, RwgpsJobError>(())
-            };
+            tracing::info!(
+                count = jobs.len(),
+                "Found historical routes and trips to sync"
+            );
 
-            let trip_task = async {
-                tracing::info!("Finding historical trips to sync");
-                let trip_candidates =
-                    select_historical_trip_sync_candidates(SyncTripHistoryParams {
-                        client: rwgps_client.clone(),
-                        ride_repo,
-                        connection: connection.clone(),
-                    })
-                    .await?;
+            let run = SyncRun {
+                id: sync_run_id,
+                ..SyncRun::new(
+                    connection.user_id,
+                    ExternalProvider::Rwgps,
+                    since,
+                    cursor,
+                    jobs.len(),
+                    Utc::now(),
+                )
+            };
 
-                tracing::info!(
-                    count = trip_candidates.len(),
-                    "Found historical trips to sync"
-                );
+            // Saved before the jobs are queued, so they can count against it
+            sync_run_repo.put(run.clone()).await?;
 
-                for trip in trip_candidates {
-                    tracing::debug!(trip_id = trip.rwgps_trip_id, "Queuing historical trip sync");
-                    job_storage
-                        .push(Job::Rwgps(RwgpsJob::SyncTrip {
-                            rwgps_trip_id: trip.rwgps_trip_id,
-                            connection: connection.clone(),
-                        }))
-                        .await
-                        .map_err(|e| RwgpsJobError::Processing(e.into()))?;
-                }
+            // Nothing changed, so the run is already done
+            if let Some(cursor) = run.completed_cursor() {
+                set_sync_cursor(&user_repo, connection.user_id, cursor).await?;
+            }
 
-                Ok::<_, RwgpsJobError>(())
-            };
+            for job in jobs {
+                job_storage
+                    .push(Job::Rwgps(job))
+                    .await
+                    .map_err(|e| RwgpsJobError::Processing(e.into()))?;
+            }
 
-            let (_, _) = tokio::try_join!(route_task, trip_task)?;
-            tracing::info!("Completed RWGPS history sync");
+            tracing::info!(sync_run_id = %run.id, "Completed RWGPS history sync");
         }
     }
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, TimeZone, Utc};
+    use howitt::models::sync_run::SyncRunFilter;
+    use howitt::models::user::{User, UserId, UserRwgpsConnection};
+    use howitt::services::user::password::hash_password;
+    use howitt_memory::MemoryRepos;
+
+    use super::*;
+
+    fn connection(user_id: UserId) -> UserRwgpsConnection {
+        UserRwgpsConnection {
+            id: uuid::Uuid::now_v7(),
+            user_id,
+            rwgps_user_id: 1,
+            access_token: String::from("token"),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            status: RwgpsConnectionStatus::Ok,
+            sync_cursor: None,
+        }
+    }
+
+    async fn create_user(repos: &Repos, status: RwgpsConnectionStatus) -> User {
+        let id = UserId::new();
+
+        let user = User {
+            id,
+            username: String::from("user"),
+            password: hash_password("hunter2").unwrap(),
+            email: String::from("user@example.com"),
+            created_at: Utc::now(),
+            rwgps_connection: Some(UserRwgpsConnection {
+                status,
+                ..connection(id)
+            }),
+        };
+
+        repos.user_repo.put(user.clone()).await.unwrap();
+
+        user
+    }
+
+    #[test]
+    fn test_connection_status() {
+        let unauthorised = anyhow::Error::from(RwgpsError::Unauthorised).context("Fetching trip");
+        assert_eq!(
+            connection_status(&unauthorised),
+            Some(RwgpsConnectionStatus::Unauthorised)
+        );
+
+        assert_eq!(
+            connection_status(&anyhow::Error::from(RwgpsError::RateLimited)),
+            Some(RwgpsConnectionStatus::RateLimited)
+        );
+
+        assert_eq!(connection_status(&anyhow::anyhow!("Trip not found")), None);
+    }
+
+    #[tokio::test]
+    async fn test_connection_state() {
+        let repos = Repos::from(MemoryRepos::default());
+        let now = Utc::now();
+
+        let user = create_user(&repos, RwgpsConnectionStatus::Ok).await;
+        let state = connection_state(&repos.user_repo, user.id, now)
+            .await
+            .unwrap();
+        assert!(matches!(
+            state,
+            ConnectionState::Active(UserRwgpsConnection {
+                status: RwgpsConnectionStatus::Ok,
+                ..
+            })
+        ));
+
+        // Unauthorised connections stay paused until the user reconnects
+        let user = create_user(&repos, RwgpsConnectionStatus::Unauthorised).await;
+        let state = connection_state(&repos.user_repo, user.id, now + Duration::days(7))
+            .await
+            .unwrap();
+        assert!(matches!(state, ConnectionState::Inactive));
+
+        // Rate limited connections are retried once the pause is up
+        let user = create_user(&repos, RwgpsConnectionStatus::RateLimited).await;
+        let state = connection_state(&repos.user_repo, user.id, now)
+            .await
+            .unwrap();
+        assert!(matches!(
+            state,
+            ConnectionState::PausedUntil(resumes_at) if resumes_at > now
+        ));
+
+        let state = connection_state(&repos.user_repo, user.id, now + Duration::hours(1))
+            .await
+            .unwrap();
+        assert!(matches!(state, ConnectionState::Active(_)));
+    }
+
+    #[tokio::test]
+    async fn test_set_connection_status() {
+        let repos = Repos::from(MemoryRepos::default());
+        let user = create_user(&repos, RwgpsConnectionStatus::Ok).await;
+        let connection = user.rwgps_connection.clone().unwrap();
+
+        repos
+            .user_repo
+            .set_rwgps_status(user.id, RwgpsConnectionStatus::Unauthorised, Utc::now())
+            .await
+            .unwrap();
+
+        let stored = repos
+            .user_repo
+            .get(user.id)
+            .await
+            .unwrap()
+            .rwgps_connection
+            .unwrap();
+
+        assert_eq!(stored.status, RwgpsConnectionStatus::Unauthorised);
+        assert_eq!(stored.access_token, connection.access_token);
+        assert!(stored.updated_at >= connection.updated_at);
+    }
+
+    async fn sync_cursor(repos: &Repos, user_id: UserId) -> Option<SyncCursor> {
+        repos
+            .user_repo
+            .get(user_id)
+            .await
+            .unwrap()
+            .rwgps_connection
+            .unwrap()
+            .sync_cursor
+    }
+
+    #[tokio::test]
+    async fn test_record_sync_result() {
+        let repos = Repos::from(MemoryRepos::default());
+        let user = create_user(&repos, RwgpsConnectionStatus::Ok).await;
+        let cursor = SyncCursor {
+            updated_at: Utc.with_ymd_and_hms(2025, 3, 1, 0, 0, 0).unwrap(),
+            sync_version: 2,
+        };
+
+        let run = |queued| {
+            SyncRun::new(
+                user.id,
+                ExternalProvider::Rwgps,
+                None,
+                Some(cursor),
+                queued,
+                Utc::now(),
+            )
+        };
+
+        // A failed item holds the cursor back
+        let failing_run = run(2);
+        repos.sync_run_repo.put(failing_run.clone()).await.unwrap();
+
+        for succeeded in [true, false] {
+            record_sync_result(&repos, failing_run.id, succeeded)
+                .await
+                .unwrap();
+        }
+
+        let failing_run = repos.sync_run_repo.get(failing_run.id).await.unwrap();
+        assert_eq!((failing_run.succeeded, failing_run.failed), (1, 1));
+        assert!(failing_run.finished_at.is_some());
+        assert_eq!(sync_cursor(&repos, user.id).await, None);
+
+        let run = run(2);
+        repos.sync_run_repo.put(run.clone()).await.unwrap();
+
+        record_sync_result(&repos, run.id, true).await.unwrap();
+        assert_eq!(sync_cursor(&repos, user.id).await, None);
+
+        record_sync_result(&repos, run.id, true).await.unwrap();
+        assert_eq!(sync_cursor(&repos, user.id).await, Some(cursor));
+
+        let runs = repos
+            .sync_run_repo
+            .filter_models(SyncRunFilter::ForUser {
+                user_id: user.id,
+                limit: None,
+            })
+            .await
+            .unwrap();
+        assert_eq!(runs.len(), 2);
+    }
+}
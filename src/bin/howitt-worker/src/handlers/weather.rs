@@ -0,0 +1,123 @@
+use chrono::{DateTime, TimeDelta, Utc};
+use howitt::models::ride::RideId;
+use howitt::repos::Repos;
+use howitt::services::ride_weather::{
+    build_ride_weather, sample_hours, HourSample, HourlyObservation,
+};
+use open_meteo::schema::{ForecastParams, HistoricalWeatherParams, HourlyData, HOUR_FORMAT};
+use open_meteo::{OpenMeteoClient, OpenMeteoHistoryClient};
+use thiserror::Error;
+use tracing;
+
+use crate::context::Context;
+
+/// The archive runs roughly five days behind. Rides more recent than this are
+/// looked up in the forecast API instead, which keeps the last few months.
+const ARCHIVE_LAG: TimeDelta = TimeDelta::days(7);
+
+#[derive(Debug, Error)]
+pub enum WeatherJobError {
+    #[error("Failed to process weather job: {0}")]
+    Processing(#[from] anyhow::Error),
+}
+
+fn format_hour(hour: &DateTime<Utc>) -> String {
+    hour.format(HOUR_FORMAT).to_string()
+}
+
+/// The hourly data at every sample's point over the whole ride, in one
+/// request. Comes back in the same order as `samples`.
+async fn fetch_hourly(
+    history_client: &OpenMeteoHistoryClient,
+    client: &OpenMeteoClient,
+    samples: &[HourSample],
+) -> Result<Vec<Option<HourlyData>>, anyhow::Error> {
+    let (Some(first), Some(last)) = (samples.first(), samples.last()) else {
+        return Ok(vec![]);
+    };
+
+    let latitude = samples.iter().map(|sample| sample.point.y()).collect();
+    let longitude = samples.iter().map(|sample| sample.point.x()).collect();
+
+    if Utc::now() - last.hour < ARCHIVE_LAG {
+        let forecasts = client
+            .get_forecast(ForecastParams {
+                latitude,
+                longitude,
+                hourly: Some(HourlyObservation::variables()),
+                daily: None,
+                start_hour: Some(format_hour(&first.hour)),
+                end_hour: Some(format_hour(&last.hour)),
+                forecast_days: None,
+                temperature_unit: None,
+                wind_speed_unit: None,
+                timeformat: None,
+                timezone: Some(String::from("UTC")),
+            })
+            .await?;
+
+        Ok(forecasts
+            .into_iter()
+            .map(|forecast| forecast.hourly)
+            .collect())
+    } else {
+        let weather = history_client
+            .get_historical_weather(HistoricalWeatherParams {
+                start_date: first.hour.date_naive().to_string(),
+                end_date: last.hour.date_naive().to_string(),
+                hourly: Some(HourlyObservation::variables()),
+                daily: None,
+                latitude,
+                longitude,
+                temperature_unit: None,
+                wind_speed_unit: None,
+                timeformat: None,
+                timezone: Some(String::from("UTC")),
+            })
+            .await?;
+
+        Ok(weather.into_iter().map(|weather| weather.hourly).collect())
+    }
+}
+
+pub async fn handle_weather_job(
+    ride_id: RideId,
+    Context {
+        repos:
+            Repos {
+                ride_points_repo,
+                ride_weather_repo,
+                ..
+            },
+        open_meteo_history_client,
+        open_meteo_client,
+        ..
+    }: Context,
+) -> Result<(), WeatherJobError> {
+    tracing::info!(ride_id = %ride_id, "Fetching ride weather");
+
+    let ride_points = ride_points_repo.get(ride_id).await?;
+
+    let samples = sample_hours(&ride_points.points);
+    let hourly = fetch_hourly(&open_meteo_history_client, &open_meteo_client, &samples).await?;
+
+    let samples = samples.into_iter().enumerate().map(|(index, sample)| {
+        let observation = hourly
+            .get(index)
+            .and_then(Option::as_ref)
+            .and_then(|hourly| hourly.observation(&format_hour(&sample.hour)));
+
+        (sample, observation)
+    });
+
+    let ride_weather = build_ride_weather(ride_id, samples);
+
+    if ride_weather.hours.is_empty() {
+        tracing::warn!(ride_id = %ride_id, "No weather available for ride");
+        return Ok(());
+    }
+
+    ride_weather_repo.put(ride_weather).await?;
+
+    Ok(())
+}
@@ -0,0 +1,74 @@
+use std::io::{Read, Seek};
+
+use serde::de::DeserializeOwned;
+use zip::{result::ZipError, ZipArchive};
+
+use crate::{
+    schema::{GtfsCalendar, GtfsCalendarDate, GtfsRoute, GtfsStop, GtfsStopTime, GtfsTrip},
+    GtfsParseError,
+};
+
+/// Streams records out of a GTFS zip one row at a time, so large files like
+/// `stop_times.txt` never need to be held in memory all at once.
+pub struct GtfsArchive<R> {
+    zip: ZipArchive<R>,
+}
+
+pub type GtfsRecords<'a, T> = Box<dyn Iterator<Item = Result<T, GtfsParseError>> + 'a>;
+
+impl<R: Read + Seek> GtfsArchive<R> {
+    pub fn new(data: R) -> Result<GtfsArchive<R>, GtfsParseError> {
+        Ok(GtfsArchive {
+            zip: ZipArchive::new(data)?,
+        })
+    }
+
+    fn records<T: DeserializeOwned + 'static>(
+        &mut self,
+        name: &str,
+    ) -> Result<GtfsRecords<'_, T>, GtfsParseError> {
+        let file = self.zip.by_name(name)?;
+
+        Ok(Box::new(
+            csv::Reader::from_reader(file)
+                .into_deserialize()
+                .map(|record| record.map_err(GtfsParseError::from)),
+        ))
+    }
+
+    /// Like `records`, but treats a missing file as empty. Feeds may omit
+    /// either `calendar.txt` or `calendar_dates.txt`.
+    fn optional_records<T: DeserializeOwned + 'static>(
+        &mut self,
+        name: &str,
+    ) -> Result<GtfsRecords<'_, T>, GtfsParseError> {
+        match self.records(name) {
+            Err(GtfsParseError::Zip(ZipError::FileNotFound)) => Ok(Box::new(std::iter::empty())),
+            result => result,
+        }
+    }
+
+    pub fn stops(&mut self) -> Result<GtfsRecords<'_, GtfsStop>, GtfsParseError> {
+        self.records("stops.txt")
+    }
+
+    pub fn routes(&mut self) -> Result<GtfsRecords<'_, GtfsRoute>, GtfsParseError> {
+        self.records("routes.txt")
+    }
+
+    pub fn trips(&mut self) -> Result<GtfsRecords<'_, GtfsTrip>, GtfsParseError> {
+        self.records("trips.txt")
+    }
+
+    pub fn stop_times(&mut self) -> Result<GtfsRecords<'_, GtfsStopTime>, GtfsParseError> {
+        self.records("stop_times.txt")
+    }
+
+    pub fn calendar(&mut self) -> Result<GtfsRecords<'_, GtfsCalendar>, GtfsParseError> {
+        self.optional_records("calendar.txt")
+    }
+
+    pub fn calendar_dates(&mut self) -> Result<GtfsRecords<'_, GtfsCalendarDate>, GtfsParseError> {
+        self.optional_records("calendar_dates.txt")
+    }
+}
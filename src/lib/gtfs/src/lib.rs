@@ -1,8 +1,15 @@
 use std::io::{Read, Seek};
 
-use serde::Deserialize;
 use thiserror::Error;
 
+pub mod archive;
+pub mod schema;
+pub mod timetable;
+
+pub use archive::*;
+pub use schema::*;
+pub use timetable::*;
+
 #[derive(Error, Debug)]
 #[error("Data parse failed")]
 pub enum GtfsParseError {
@@ -17,20 +24,10 @@ pub struct GtfsZip {
 }
 impl GtfsZip {
     pub fn parse(data: impl Read + Seek) -> Result<GtfsZip, GtfsParseError> {
-        let mut zip = zip::ZipArchive::new(data)?;
+        let mut archive = GtfsArchive::new(data)?;
 
-        let stops = csv::Reader::from_reader(zip.by_name("stops.txt")?)
-            .into_deserialize()
-            .collect::<Result<Vec<GtfsStop>, _>>()?;
+        let stops = archive.stops()?.collect::<Result<Vec<GtfsStop>, _>>()?;
 
         Ok(GtfsZip { stops })
     }
 }
-
-#[derive(Debug, Deserialize)]
-pub struct GtfsStop {
-    pub stop_id: String,
-    pub stop_name: String,
-    pub stop_lat: f64,
-    pub stop_lon: f64,
-}
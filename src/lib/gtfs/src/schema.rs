@@ -0,0 +1,192 @@
+use std::fmt;
+
+use chrono::{Datelike, NaiveDate, Weekday};
+use serde::{de, Deserialize, Deserializer};
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct GtfsStop {
+    pub stop_id: String,
+    pub stop_name: String,
+    pub stop_lat: f64,
+    pub stop_lon: f64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct GtfsRoute {
+    pub route_id: String,
+    #[serde(default)]
+    pub agency_id: Option<String>,
+    #[serde(default)]
+    pub route_short_name: Option<String>,
+    #[serde(default)]
+    pub route_long_name: Option<String>,
+    pub route_type: u16,
+}
+
+impl GtfsRoute {
+    pub fn name(&self) -> &str {
+        self.route_short_name
+            .as_deref()
+            .filter(|name| !name.is_empty())
+            .or(self.route_long_name.as_deref())
+            .unwrap_or(&self.route_id)
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct GtfsTrip {
+    pub route_id: String,
+    pub service_id: String,
+    pub trip_id: String,
+    #[serde(default)]
+    pub trip_headsign: Option<String>,
+    #[serde(default)]
+    pub direction_id: Option<u8>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct GtfsStopTime {
+    pub trip_id: String,
+    #[serde(default, deserialize_with = "deserialize_optional_time")]
+    pub arrival_time: Option<GtfsTime>,
+    #[serde(default, deserialize_with = "deserialize_optional_time")]
+    pub departure_time: Option<GtfsTime>,
+    pub stop_id: String,
+    pub stop_sequence: u32,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct GtfsCalendar {
+    pub service_id: String,
+    #[serde(deserialize_with = "deserialize_bool")]
+    pub monday: bool,
+    #[serde(deserialize_with = "deserialize_bool")]
+    pub tuesday: bool,
+    #[serde(deserialize_with = "deserialize_bool")]
+    pub wednesday: bool,
+    #[serde(deserialize_with = "deserialize_bool")]
+    pub thursday: bool,
+    #[serde(deserialize_with = "deserialize_bool")]
+    pub friday: bool,
+    #[serde(deserialize_with = "deserialize_bool")]
+    pub saturday: bool,
+    #[serde(deserialize_with = "deserialize_bool")]
+    pub sunday: bool,
+    #[serde(deserialize_with = "deserialize_date")]
+    pub start_date: NaiveDate,
+    #[serde(deserialize_with = "deserialize_date")]
+    pub end_date: NaiveDate,
+}
+
+impl GtfsCalendar {
+    pub fn runs_on(&self, date: NaiveDate) -> bool {
+        if date < self.start_date || date > self.end_date {
+            return false;
+        }
+
+        match date.weekday() {
+            Weekday::Mon => self.monday,
+            Weekday::Tue => self.tuesday,
+            Weekday::Wed => self.wednesday,
+            Weekday::Thu => self.thursday,
+            Weekday::Fri => self.friday,
+            Weekday::Sat => self.saturday,
+            Weekday::Sun => self.sunday,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GtfsExceptionType {
+    Added,
+    Removed,
+}
+
+impl<'de> Deserialize<'de> for GtfsExceptionType {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        match u8::deserialize(deserializer)? {
+            1 => Ok(GtfsExceptionType::Added),
+            2 => Ok(GtfsExceptionType::Removed),
+            value => Err(de::Error::custom(format!(
+                "invalid exception_type: {value}"
+            ))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct GtfsCalendarDate {
+    pub service_id: String,
+    #[serde(deserialize_with = "deserialize_date")]
+    pub date: NaiveDate,
+    pub exception_type: GtfsExceptionType,
+}
+
+/// A time of day as seconds since midnight of the service day. GTFS allows
+/// hours past 24 for trips which run after midnight.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct GtfsTime(pub u32);
+
+impl GtfsTime {
+    pub fn from_hms(hours: u32, minutes: u32, seconds: u32) -> GtfsTime {
+        GtfsTime(hours * 3600 + minutes * 60 + seconds)
+    }
+
+    pub fn parse(s: &str) -> Option<GtfsTime> {
+        let mut parts = s.trim().split(':');
+
+        let hours = parts.next()?.parse().ok()?;
+        let minutes = parts.next()?.parse().ok()?;
+        let seconds = parts.next()?.parse().ok()?;
+
+        if parts.next().is_some() || minutes >= 60 || seconds >= 60 {
+            return None;
+        }
+
+        Some(GtfsTime::from_hms(hours, minutes, seconds))
+    }
+
+    pub fn seconds(&self) -> u32 {
+        self.0
+    }
+}
+
+impl fmt::Display for GtfsTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:02}:{:02}:{:02}",
+            self.0 / 3600,
+            (self.0 / 60) % 60,
+            self.0 % 60
+        )
+    }
+}
+
+fn deserialize_optional_time<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<GtfsTime>, D::Error> {
+    let value = Option::<String>::deserialize(deserializer)?;
+
+    match value.as_deref().map(str::trim) {
+        None | Some("") => Ok(None),
+        Some(value) => GtfsTime::parse(value)
+            .map(Some)
+            .ok_or_else(|| de::Error::custom(format!("invalid time: {value}"))),
+    }
+}
+
+fn deserialize_date<'de, D: Deserializer<'de>>(deserializer: D) -> Result<NaiveDate, D::Error> {
+    let value = String::deserialize(deserializer)?;
+
+    NaiveDate::parse_from_str(value.trim(), "%Y%m%d")
+        .map_err(|_| de::Error::custom(format!("invalid date: {value}")))
+}
+
+fn deserialize_bool<'de, D: Deserializer<'de>>(deserializer: D) -> Result<bool, D::Error> {
+    match u8::deserialize(deserializer)? {
+        0 => Ok(false),
+        1 => Ok(true),
+        value => Err(de::Error::custom(format!("invalid boolean: {value}"))),
+    }
+}
@@ -0,0 +1,478 @@
+use std::collections::{HashMap, HashSet};
+use std::io::{Read, Seek};
+
+use chrono::{Days, NaiveDate};
+
+use crate::{
+    archive::GtfsArchive,
+    schema::{
+        GtfsCalendar, GtfsCalendarDate, GtfsExceptionType, GtfsRoute, GtfsStop, GtfsTime, GtfsTrip,
+    },
+    GtfsParseError,
+};
+
+const SECONDS_PER_DAY: u32 = 24 * 60 * 60;
+
+/// A stop time with its trip and stop interned, keeping state-wide feeds small.
+#[derive(Debug, Clone, Copy)]
+struct StopVisit {
+    trip_idx: u32,
+    stop_sequence: u32,
+    arrival_time: Option<GtfsTime>,
+    departure_time: Option<GtfsTime>,
+}
+
+impl StopVisit {
+    fn departure_time(&self) -> Option<GtfsTime> {
+        self.departure_time.or(self.arrival_time)
+    }
+
+    fn arrival_time(&self) -> Option<GtfsTime> {
+        self.arrival_time.or(self.departure_time)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct GtfsDeparture<'a> {
+    pub trip: &'a GtfsTrip,
+    pub route: Option<&'a GtfsRoute>,
+    pub departure_time: GtfsTime,
+}
+
+#[derive(Debug, Clone)]
+pub struct GtfsConnection<'a> {
+    pub trip: &'a GtfsTrip,
+    pub route: Option<&'a GtfsRoute>,
+    pub departure_time: GtfsTime,
+    pub arrival_time: GtfsTime,
+}
+
+#[derive(Debug, Default)]
+pub struct GtfsTimetable {
+    stops: Vec<GtfsStop>,
+    stop_idxs: HashMap<String, u32>,
+    routes: HashMap<String, GtfsRoute>,
+    trips: Vec<GtfsTrip>,
+    calendars: HashMap<String, GtfsCalendar>,
+    calendar_dates: HashMap<(String, NaiveDate), GtfsExceptionType>,
+    visits_by_stop: HashMap<u32, Vec<StopVisit>>,
+}
+
+impl GtfsTimetable {
+    pub fn from_archive<R: Read + Seek>(
+        archive: &mut GtfsArchive<R>,
+    ) -> Result<GtfsTimetable, GtfsParseError> {
+        GtfsTimetable::load(archive, None)
+    }
+
+    /// Only keeps stop times at the given stops. Useful when just a handful of
+    /// stops near a route are of interest.
+    pub fn from_archive_for_stops<R: Read + Seek>(
+        archive: &mut GtfsArchive<R>,
+        stop_ids: &HashSet<String>,
+    ) -> Result<GtfsTimetable, GtfsParseError> {
+        GtfsTimetable::load(archive, Some(stop_ids))
+    }
+
+    fn load<R: Read + Seek>(
+        archive: &mut GtfsArchive<R>,
+        stop_ids: Option<&HashSet<String>>,
+    ) -> Result<GtfsTimetable, GtfsParseError> {
+        let mut timetable = GtfsTimetable::default();
+
+        for stop in archive.stops()? {
+            let stop = stop?;
+            timetable
+                .stop_idxs
+                .insert(stop.stop_id.clone(), timetable.stops.len() as u32);
+            timetable.stops.push(stop);
+        }
+
+        for route in archive.routes()? {
+            let route = route?;
+            timetable.routes.insert(route.route_id.clone(), route);
+        }
+
+        let mut trip_idxs: HashMap<String, u32> = HashMap::new();
+        for trip in archive.trips()? {
+            let trip = trip?;
+            trip_idxs.insert(trip.trip_id.clone(), timetable.trips.len() as u32);
+            timetable.trips.push(trip);
+        }
+
+        for calendar in archive.calendar()? {
+            let calendar = calendar?;
+            timetable
+                .calendars
+                .insert(calendar.service_id.clone(), calendar);
+        }
+
+        for calendar_date in archive.calendar_dates()? {
+            let GtfsCalendarDate {
+                service_id,
+                date,
+                exception_type,
+            } = calendar_date?;
+            timetable
+                .calendar_dates
+                .insert((service_id, date), exception_type);
+        }
+
+        for stop_time in archive.stop_times()? {
+            let stop_time = stop_time?;
+
+            if stop_ids.is_some_and(|stop_ids| !stop_ids.contains(&stop_time.stop_id)) {
+                continue;
+            }
+
+            let (Some(stop_idx), Some(trip_idx)) = (
+                timetable.stop_idxs.get(&stop_time.stop_id),
+                trip_idxs.get(&stop_time.trip_id),
+            ) else {
+                continue;
+            };
+
+            timetable
+                .visits_by_stop
+                .entry(*stop_idx)
+                .or_default()
+                .push(StopVisit {
+                    trip_idx: *trip_idx,
+                    stop_sequence: stop_time.stop_sequence,
+                    arrival_time: stop_time.arrival_time,
+                    departure_time: stop_time.departure_time,
+                });
+        }
+
+        Ok(timetable)
+    }
+
+    pub fn stops(&self) -> impl Iterator<Item = &GtfsStop> {
+        self.stops.iter()
+    }
+
+    pub fn stop(&self, stop_id: &str) -> Option<&GtfsStop> {
+        self.stop_idxs
+            .get(stop_id)
+            .map(|idx| &self.stops[*idx as usize])
+    }
+
+    pub fn route(&self, route_id: &str) -> Option<&GtfsRoute> {
+        self.routes.get(route_id)
+    }
+
+    /// Whether a service runs on the given date, applying `calendar_dates.txt`
+    /// exceptions on top of the weekly `calendar.txt` pattern.
+    pub fn service_runs_on(&self, service_id: &str, date: NaiveDate) -> bool {
+        match self.calendar_dates.get(&(service_id.to_string(), date)) {
+            Some(GtfsExceptionType::Added) => true,
+            Some(GtfsExceptionType::Removed) => false,
+            None => self
+                .calendars
+                .get(service_id)
+                .is_some_and(|calendar| calendar.runs_on(date)),
+        }
+    }
+
+    /// Service days which can have trips running on `date`, along with the
+    /// offset to subtract from their times. Trips from the previous day's
+    /// service can run past midnight.
+    fn service_days(date: NaiveDate) -> impl Iterator<Item = (NaiveDate, u32)> {
+        [
+            Some((date, 0)),
+            date.checked_sub_days(Days::new(1))
+                .map(|previous| (previous, SECONDS_PER_DAY)),
+        ]
+        .into_iter()
+        .flatten()
+    }
+
+    fn visits_at(&self, stop_id: &str) -> &[StopVisit] {
+        self.stop_idxs
+            .get(stop_id)
+            .and_then(|idx| self.visits_by_stop.get(idx))
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    fn trip(&self, visit: &StopVisit) -> &GtfsTrip {
+        &self.trips[visit.trip_idx as usize]
+    }
+
+    /// Departures from a stop between two times (inclusive) on the given date,
+    /// ordered by departure time.
+    pub fn departures(
+        &self,
+        stop_id: &str,
+        date: NaiveDate,
+        from: GtfsTime,
+        to: GtfsTime,
+    ) -> Vec<GtfsDeparture<'_>> {
+        let mut departures = GtfsTimetable::service_days(date)
+            .flat_map(|(service_date, offset)| {
+                self.visits_at(stop_id).iter().filter_map(move |visit| {
+                    let trip = self.trip(visit);
+                    let departure_time = visit.departure_time()?.0.checked_sub(offset)?;
+
+                    if departure_time < from.0
+                        || departure_time > to.0
+                        || !self.service_runs_on(&trip.service_id, service_date)
+                    {
+                        return None;
+                    }
+
+                    Some(GtfsDeparture {
+                        trip,
+                        route: self.route(&trip.route_id),
+                        departure_time: GtfsTime(departure_time),
+                    })
+                })
+            })
+            .collect::<Vec<_>>();
+
+        departures.sort_by_key(|departure| departure.departure_time);
+        departures
+    }
+
+    /// Trips on the given date which stop at `from_stop_id` and later at
+    /// `to_stop_id`, ordered by departure time.
+    pub fn services_between(
+        &self,
+        from_stop_id: &str,
+        to_stop_id: &str,
+        date: NaiveDate,
+    ) -> Vec<GtfsConnection<'_>> {
+        let arrivals: HashMap<u32, &StopVisit> = self
+            .visits_at(to_stop_id)
+            .iter()
+            .map(|visit| (visit.trip_idx, visit))
+            .collect();
+
+        let mut connections = GtfsTimetable::service_days(date)
+            .flat_map(|(service_date, offset)| {
+                let arrivals = &arrivals;
+
+                self.visits_at(from_stop_id)
+                    .iter()
+                    .filter_map(move |departure| {
+                        let arrival = arrivals.get(&departure.trip_idx)?;
+
+                        if arrival.stop_sequence <= departure.stop_sequence {
+                            return None;
+                        }
+
+                        let trip = self.trip(departure);
+                        if !self.service_runs_on(&trip.service_id, service_date) {
+                            return None;
+                        }
+
+                        Some(GtfsConnection {
+                            trip,
+                            route: self.route(&trip.route_id),
+                            departure_time: GtfsTime(
+                                departure.departure_time()?.0.checked_sub(offset)?,
+                            ),
+                            arrival_time: GtfsTime(arrival.arrival_time()?.0.checked_sub(offset)?),
+                        })
+                    })
+            })
+            .collect::<Vec<_>>();
+
+        connections.sort_by_key(|connection| connection.departure_time);
+        connections
+    }
+
+    pub fn has_service_between(
+        &self,
+        from_stop_id: &str,
+        to_stop_id: &str,
+        date: NaiveDate,
+    ) -> bool {
+        !self
+            .services_between(from_stop_id, to_stop_id, date)
+            .is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Cursor, Write};
+
+    use zip::{write::SimpleFileOptions, ZipWriter};
+
+    use super::*;
+
+    fn create_test_archive() -> GtfsArchive<Cursor<Vec<u8>>> {
+        let files = [
+            (
+                "stops.txt",
+                "stop_id,stop_name,stop_lat,stop_lon\n\
+                 A,Southern Cross,-37.818,144.952\n\
+                 B,Seymour,-37.026,145.139\n\
+                 C,Albury,-36.084,146.924\n",
+            ),
+            (
+                "routes.txt",
+                "route_id,agency_id,route_short_name,route_long_name,route_type\n\
+                 R1,V,,Albury Line,2\n",
+            ),
+            (
+                "trips.txt",
+                "route_id,service_id,trip_id,trip_headsign,direction_id\n\
+                 R1,WEEKDAY,T1,Albury,0\n\
+                 R1,WEEKDAY,T2,Albury,0\n\
+                 R1,WEEKEND,T3,Southern Cross,1\n",
+            ),
+            (
+                "stop_times.txt",
+                "trip_id,arrival_time,departure_time,stop_id,stop_sequence\n\
+                 T1,07:00:00,07:00:00,A,1\n\
+                 T1,08:15:00,08:16:00,B,2\n\
+                 T1,10:30:00,10:30:00,C,3\n\
+                 T2,23:30:00,23:30:00,A,1\n\
+                 T2,24:45:00,24:46:00,B,2\n\
+                 T2,27:00:00,27:00:00,C,3\n\
+                 T3,09:00:00,09:00:00,C,1\n\
+                 T3,11:15:00,11:16:00,B,2\n\
+                 T3,12:30:00,12:30:00,A,3\n",
+            ),
+            (
+                "calendar.txt",
+                "service_id,monday,tuesday,wednesday,thursday,friday,saturday,sunday,start_date,end_date\n\
+                 WEEKDAY,1,1,1,1,1,0,0,20240101,20241231\n\
+                 WEEKEND,0,0,0,0,0,1,1,20240101,20241231\n",
+            ),
+            (
+                "calendar_dates.txt",
+                "service_id,date,exception_type\n\
+                 WEEKDAY,20240101,2\n\
+                 WEEKEND,20240101,1\n",
+            ),
+        ];
+
+        let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+        for (name, contents) in files {
+            writer
+                .start_file(name, SimpleFileOptions::default())
+                .unwrap();
+            writer.write_all(contents.as_bytes()).unwrap();
+        }
+
+        GtfsArchive::new(Cursor::new(writer.finish().unwrap().into_inner())).unwrap()
+    }
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn test_parse_time_past_midnight() {
+        assert_eq!(GtfsTime::parse("24:45:00"), Some(GtfsTime(89100)));
+        assert_eq!(
+            GtfsTime::parse("7:05:09"),
+            Some(GtfsTime::from_hms(7, 5, 9))
+        );
+        assert_eq!(GtfsTime::parse("07:65:00"), None);
+        assert_eq!(GtfsTime::from_hms(24, 45, 0).to_string(), "24:45:00");
+    }
+
+    #[test]
+    fn test_service_runs_on_applies_exceptions() {
+        let timetable = GtfsTimetable::from_archive(&mut create_test_archive()).unwrap();
+
+        // Tuesday
+        assert!(timetable.service_runs_on("WEEKDAY", date(2024, 1, 2)));
+        assert!(!timetable.service_runs_on("WEEKEND", date(2024, 1, 2)));
+
+        // New Year's Day (a Monday) runs the weekend timetable
+        assert!(!timetable.service_runs_on("WEEKDAY", date(2024, 1, 1)));
+        assert!(timetable.service_runs_on("WEEKEND", date(2024, 1, 1)));
+
+        // Outside the calendar range
+        assert!(!timetable.service_runs_on("WEEKDAY", date(2025, 1, 2)));
+    }
+
+    #[test]
+    fn test_departures() {
+        let timetable = GtfsTimetable::from_archive(&mut create_test_archive()).unwrap();
+
+        let departures = timetable.departures(
+            "B",
+            date(2024, 1, 2),
+            GtfsTime::from_hms(0, 0, 0),
+            GtfsTime::from_hms(12, 0, 0),
+        );
+
+        assert_eq!(
+            departures
+                .iter()
+                .map(|departure| (departure.trip.trip_id.as_str(), departure.departure_time))
+                .collect::<Vec<_>>(),
+            vec![("T1", GtfsTime::from_hms(8, 16, 0))]
+        );
+        assert_eq!(departures[0].route.unwrap().name(), "Albury Line");
+
+        // The late train from Tuesday's service reaches Seymour early Wednesday
+        let departures = timetable.departures(
+            "B",
+            date(2024, 1, 3),
+            GtfsTime::from_hms(0, 0, 0),
+            GtfsTime::from_hms(9, 0, 0),
+        );
+
+        assert_eq!(
+            departures
+                .iter()
+                .map(|departure| (departure.trip.trip_id.as_str(), departure.departure_time))
+                .collect::<Vec<_>>(),
+            vec![
+                ("T2", GtfsTime::from_hms(0, 46, 0)),
+                ("T1", GtfsTime::from_hms(8, 16, 0))
+            ]
+        );
+    }
+
+    #[test]
+    fn test_services_between() {
+        let timetable = GtfsTimetable::from_archive(&mut create_test_archive()).unwrap();
+
+        let connections = timetable.services_between("A", "C", date(2024, 1, 2));
+        assert_eq!(
+            connections
+                .iter()
+                .map(|connection| (
+                    connection.trip.trip_id.as_str(),
+                    connection.departure_time,
+                    connection.arrival_time
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    "T1",
+                    GtfsTime::from_hms(7, 0, 0),
+                    GtfsTime::from_hms(10, 30, 0)
+                ),
+                (
+                    "T2",
+                    GtfsTime::from_hms(23, 30, 0),
+                    GtfsTime::from_hms(27, 0, 0)
+                ),
+            ]
+        );
+
+        // Wrong direction on a weekday
+        assert!(!timetable.has_service_between("C", "A", date(2024, 1, 2)));
+        assert!(timetable.has_service_between("C", "A", date(2024, 1, 6)));
+    }
+
+    #[test]
+    fn test_from_archive_for_stops() {
+        let stop_ids = HashSet::from([String::from("A"), String::from("B")]);
+        let timetable =
+            GtfsTimetable::from_archive_for_stops(&mut create_test_archive(), &stop_ids).unwrap();
+
+        assert!(timetable.has_service_between("A", "B", date(2024, 1, 2)));
+        assert!(!timetable.has_service_between("A", "C", date(2024, 1, 2)));
+        assert!(timetable.stop("C").is_some());
+    }
+}
@@ -33,6 +33,8 @@ pub trait BucketClient {
     ) -> Result<(), Self::Error>;
 
     async fn get_object(&self, key: &str) -> Result<Option<bytes::Bytes>, Self::Error>;
+
+    async fn delete_object(&self, key: &str) -> Result<(), Self::Error>;
 }
 
 pub struct HttpResponse {
@@ -52,4 +54,5 @@ pub trait RedisClient {
 
     async fn get_bytes(&self, key: &str) -> Result<Option<bytes::Bytes>, Self::Error>;
     async fn set_bytes(&self, key: &str, bytes: bytes::Bytes) -> Result<(), Self::Error>;
+    async fn delete_prefix(&self, prefix: &str) -> Result<(), Self::Error>;
 }
@@ -64,6 +64,13 @@ impl BucketClient for S3BucketClient {
             Err(e) => Err(e),
         }
     }
+
+    async fn delete_object(&self, key: &str) -> Result<(), Self::Error> {
+        match self.client.delete(&key.into()).await {
+            Ok(_) | Err(object_store::Error::NotFound { .. }) => Ok(()),
+            Err(e) => Err(e),
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -129,4 +136,22 @@ impl howitt_client_types::RedisClient for RedisClient {
     async fn set_bytes(&self, key: &str, bytes: bytes::Bytes) -> Result<(), Self::Error> {
         Ok(self.conn().set(key, bytes.to_vec()).await?)
     }
+    async fn delete_prefix(&self, prefix: &str) -> Result<(), Self::Error> {
+        let mut conn = self.conn();
+
+        let keys: Vec<String> = {
+            let mut iter = conn.scan_match::<_, String>(format!("{prefix}*")).await?;
+            let mut keys = vec![];
+            while let Some(key) = iter.next_item().await {
+                keys.push(key);
+            }
+            keys
+        };
+
+        if !keys.is_empty() {
+            conn.del::<_, ()>(keys).await?;
+        }
+
+        Ok(())
+    }
 }
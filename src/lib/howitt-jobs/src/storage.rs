@@ -1,6 +1,7 @@
 use apalis::prelude::*;
 use apalis_core::request::Parts;
 use apalis_redis::RedisStorage;
+use chrono::{DateTime, Utc};
 use thiserror::Error;
 use tokio::sync::{mpsc, oneshot};
 
@@ -11,6 +12,8 @@ pub struct LockFreeStorage<Job> {
 
 pub struct StorageMessage<Job> {
     job: Job,
+    /// When the job should run, or `None` to run it straight away
+    run_at: Option<DateTime<Utc>>,
     response_tx: oneshot::Sender<PushResult>,
 }
 
@@ -30,8 +33,16 @@ impl LockFreeStorage<howitt::jobs::Job> {
 
         // Spawn background task to handle storage operations
         tokio::spawn(async move {
-            while let Some(StorageMessage { job, response_tx }) = rx.recv().await {
-                let result = storage.push(job).await;
+            while let Some(StorageMessage {
+                job,
+                run_at,
+                response_tx,
+            }) = rx.recv().await
+            {
+                let result = match run_at {
+                    Some(run_at) => storage.schedule(job, run_at.timestamp()).await,
+                    None => storage.push(job).await,
+                };
                 let _ = response_tx.send(result);
             }
         });
@@ -42,12 +53,33 @@ impl LockFreeStorage<howitt::jobs::Job> {
     pub async fn push(
         &self,
         job: howitt::jobs::Job,
+    ) -> Result<Parts<apalis_redis::RedisContext>, LockFreeStorageError> {
+        self.send(job, None).await
+    }
+
+    /// Queues the job to run once `run_at` has passed.
+    pub async fn schedule(
+        &self,
+        job: howitt::jobs::Job,
+        run_at: DateTime<Utc>,
+    ) -> Result<Parts<apalis_redis::RedisContext>, LockFreeStorageError> {
+        self.send(job, Some(run_at)).await
+    }
+
+    async fn send(
+        &self,
+        job: howitt::jobs::Job,
+        run_at: Option<DateTime<Utc>>,
     ) -> Result<Parts<apalis_redis::RedisContext>, LockFreeStorageError> {
         let (response_tx, response_rx) = oneshot::channel();
 
         // Send job and response channel to worker task
         self.sender
-            .send(StorageMessage { job, response_tx })
+            .send(StorageMessage {
+                job,
+                run_at,
+                response_tx,
+            })
             .await
             .map_err(|e| LockFreeStorageError::TokioChannel(e.into()))?;
 
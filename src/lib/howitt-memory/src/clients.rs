@@ -0,0 +1,159 @@
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+
+use bytes::Bytes;
+use howitt_client_types::{BucketClient, HttpClient, HttpResponse, ObjectParams, RedisClient};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+/// Serves canned JSON over plain HTTP, for pointing real API clients at in
+/// tests. `respond` is handed the raw request and returns the body to answer
+/// it with. Returns the address the stub listens on.
+pub async fn serve_http_stub<F>(respond: F) -> SocketAddr
+where
+    F: Fn(&str) -> String + Send + Sync + 'static,
+{
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let respond = Arc::new(respond);
+
+    tokio::spawn(async move {
+        loop {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let respond = respond.clone();
+
+            tokio::spawn(async move {
+                let mut buf = vec![0; 8192];
+                let n = socket.read(&mut buf).await.unwrap();
+                let body = respond(&String::from_utf8_lossy(&buf[..n]));
+
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                );
+                socket.write_all(response.as_bytes()).await.unwrap();
+            });
+        }
+    });
+
+    addr
+}
+
+/// Answers every request with the same body and keeps the requested urls.
+#[derive(Debug)]
+pub struct StubHttpClient {
+    body: Bytes,
+    urls: Mutex<Vec<String>>,
+}
+
+impl StubHttpClient {
+    pub fn new(body: Bytes) -> StubHttpClient {
+        StubHttpClient {
+            body,
+            urls: Mutex::new(vec![]),
+        }
+    }
+
+    /// Every url requested so far, oldest first
+    pub fn urls(&self) -> Vec<String> {
+        self.urls.lock().unwrap().clone()
+    }
+}
+
+#[async_trait::async_trait]
+impl HttpClient for StubHttpClient {
+    type Error = std::convert::Infallible;
+
+    async fn get(&self, url: url::Url) -> Result<HttpResponse, Self::Error> {
+        self.urls.lock().unwrap().push(url.to_string());
+
+        Ok(HttpResponse {
+            body: self.body.clone(),
+        })
+    }
+}
+
+/// A bucket that keeps its objects in a map.
+#[derive(Debug, Default)]
+pub struct MemoryBucketClient {
+    objects: Mutex<HashMap<String, Bytes>>,
+}
+
+impl MemoryBucketClient {
+    pub fn new() -> MemoryBucketClient {
+        MemoryBucketClient::default()
+    }
+
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.objects.lock().unwrap().contains_key(key)
+    }
+}
+
+#[async_trait::async_trait]
+impl BucketClient for MemoryBucketClient {
+    type Error = std::convert::Infallible;
+
+    async fn key_exists(&self, key: &str) -> Result<bool, Self::Error> {
+        Ok(self.contains_key(key))
+    }
+
+    async fn put_object(
+        &self,
+        key: &str,
+        body: Bytes,
+        _params: ObjectParams,
+    ) -> Result<(), Self::Error> {
+        self.objects.lock().unwrap().insert(key.to_string(), body);
+        Ok(())
+    }
+
+    async fn get_object(&self, key: &str) -> Result<Option<Bytes>, Self::Error> {
+        Ok(self.objects.lock().unwrap().get(key).cloned())
+    }
+
+    async fn delete_object(&self, key: &str) -> Result<(), Self::Error> {
+        self.objects.lock().unwrap().remove(key);
+        Ok(())
+    }
+}
+
+/// A cache that keeps its values in a map, for testing `CacheFetcher` based
+/// fetchers.
+#[derive(Debug, Default)]
+pub struct MemoryRedisClient {
+    values: Mutex<HashMap<String, Bytes>>,
+}
+
+impl MemoryRedisClient {
+    pub fn new() -> MemoryRedisClient {
+        MemoryRedisClient::default()
+    }
+
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.values.lock().unwrap().contains_key(key)
+    }
+}
+
+#[async_trait::async_trait]
+impl RedisClient for MemoryRedisClient {
+    type Error = std::convert::Infallible;
+
+    async fn get_bytes(&self, key: &str) -> Result<Option<Bytes>, Self::Error> {
+        Ok(self.values.lock().unwrap().get(key).cloned())
+    }
+
+    async fn set_bytes(&self, key: &str, bytes: Bytes) -> Result<(), Self::Error> {
+        self.values.lock().unwrap().insert(key.to_string(), bytes);
+        Ok(())
+    }
+
+    async fn delete_prefix(&self, prefix: &str) -> Result<(), Self::Error> {
+        self.values
+            .lock()
+            .unwrap()
+            .retain(|key, _| !key.starts_with(prefix));
+        Ok(())
+    }
+}
@@ -0,0 +1,1927 @@
+//! Behavioural checks shared by every `Repos` implementation. Each check
+//! creates its own users and fixtures so it can run against a database that
+//! already holds data; filters that span all users only assert membership.
+//!
+//! Use [`conformance_tests!`](crate::conformance_tests) to generate a test per
+//! check for a given `Repos`.
+
+use std::collections::{HashMap, HashSet};
+
+use anyhow::ensure;
+use chrono::{DateTime, Duration, NaiveDate, TimeZone, Utc};
+use geo::{Distance, Haversine};
+use howitt::models::course_point::{CoursePoint, RouteCoursePoints, RouteCoursePointsFilter};
+use howitt::models::external_ref::{ExternalId, ExternalProvider, ExternalRef, RwgpsId, StravaId};
+use howitt::models::filters::{PageRequest, TemporalFilter};
+use howitt::models::locality::{
+    Locality, RideLocalities, RideLocalitiesFilter, RouteLocalities, RouteLocalitiesFilter,
+};
+use howitt::models::media::{Media, MediaFilter, MediaId, MediaRelationId};
+use howitt::models::osm_feature::{OsmFeature, OsmFeatureFilter, OsmFeatureId};
+use howitt::models::point::ElevationPoint;
+use howitt::models::point_of_interest::{
+    PointOfInterest, PointOfInterestFilter, PointOfInterestId, PointOfInterestType,
+};
+use howitt::models::point_of_interest_visit::{
+    PointOfInterestVisit, PointOfInterestVisitFilter, PointOfInterestVisitId, VisitConfirmation,
+};
+use howitt::models::ride::{Ride, RideFilter, RideId};
+use howitt::models::ride_weather::{HourlyWeather, RideWeather, RideWeatherFilter};
+use howitt::models::route::{Route, RouteFilter, RouteId, RoutePoints, RoutePointsFilter};
+use howitt::models::route_description::RouteDescription;
+use howitt::models::sync_audit_entry::{
+    SyncAuditAction, SyncAuditEntry, SyncAuditEntryFilter, SyncAuditEntryId,
+};
+use howitt::models::sync_run::{SyncCursor, SyncRun, SyncRunFilter};
+use howitt::models::tag::Tag;
+use howitt::models::trip::{Trip, TripFilter, TripId};
+use howitt::models::user::{RwgpsConnectionStatus, User, UserFilter, UserId, UserRwgpsConnection};
+use howitt::models::water_beta::{WaterBeta, WaterBetaFilter, WaterBetaId, WaterFlowStatus};
+use howitt::repos::Repos;
+use howitt::services::user::password::hash_password;
+use itertools::Itertools;
+
+/// Generates a `#[tokio::test]` for every conformance check. The expression is
+/// evaluated once per test, inside the test's runtime.
+///
+/// ```ignore
+/// howitt_memory::conformance_tests!(Repos::from(MemoryRepos::default()));
+/// ```
+///
+/// A single attribute, such as `#[ignore]`, can be put before the expression
+/// to apply it to every test.
+#[macro_export]
+macro_rules! conformance_tests {
+    (@checks [$(#[$attr:meta])*] $repos:expr) => {
+        $crate::conformance_tests!(@tests [$(#[$attr])*] $repos;
+            ride_filters,
+            ride_pages,
+            model_pages,
+            ride_weather_filters,
+            trip_filters,
+            media_filters,
+            route_filters,
+            archived_filters,
+            route_points_filters,
+            route_course_points_filters,
+            locality_filters,
+            search_filters,
+            point_of_interest_filters,
+            user_filters,
+            point_of_interest_visit_filters,
+            water_beta_filters,
+            osm_feature_filters,
+            sync_audit_entry_filters,
+            sync_run_filters,
+            delete_cascades,
+        );
+    };
+    (@tests $attrs:tt $repos:expr; $($check:ident,)*) => {
+        $(
+            $crate::conformance_tests!(@test $attrs $repos; $check);
+        )*
+    };
+    (@test [$(#[$attr:meta])*] $repos:expr; $check:ident) => {
+        #[tokio::test]
+        $(#[$attr])*
+        async fn $check() -> anyhow::Result<()> {
+            let repos: howitt::repos::Repos = $repos;
+            $crate::conformance::$check(&repos).await
+        }
+    };
+    (#[$attr:meta] $repos:expr) => {
+        $crate::conformance_tests!(@checks [#[$attr]] $repos);
+    };
+    ($repos:expr) => {
+        $crate::conformance_tests!(@checks [] $repos);
+    };
+}
+
+/// A random-ish number below `2^31`, for external ids that must be unique
+/// across runs. The tail of a v7 uuid is random.
+fn unique_number() -> usize {
+    (uuid::Uuid::now_v7().as_u128() as u32 >> 1) as usize
+}
+
+fn unique_name(prefix: &str) -> String {
+    format!("{prefix}-{}", uuid::Uuid::now_v7().simple())
+}
+
+fn datetime(year: i32, month: u32, day: u32, hour: u32) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(year, month, day, hour, 0, 0).unwrap()
+}
+
+fn id_set<Id: std::hash::Hash + Eq>(ids: impl IntoIterator<Item = Id>) -> HashSet<Id> {
+    ids.into_iter().collect()
+}
+
+async fn create_user(repos: &Repos, rwgps_user_id: Option<i32>) -> anyhow::Result<User> {
+    let id = UserId::new();
+    let now = datetime(2024, 1, 1, 0);
+    let username = unique_name("user");
+
+    let user = User {
+        id,
+        email: format!("{username}@example.com"),
+        username,
+        password: hash_password("hunter2")?,
+        created_at: now,
+        rwgps_connection: rwgps_user_id.map(|rwgps_user_id| UserRwgpsConnection {
+            id: uuid::Uuid::now_v7(),
+            user_id: id,
+            rwgps_user_id,
+            access_token: String::from("token"),
+            created_at: now,
+            updated_at: now,
+            status: RwgpsConnectionStatus::Ok,
+            sync_cursor: None,
+        }),
+    };
+
+    repos.user_repo.put(user.clone()).await?;
+
+    Ok(user)
+}
+
+async fn create_ride(
+    repos: &Repos,
+    user_id: UserId,
+    started_at: DateTime<Utc>,
+) -> anyhow::Result<Ride> {
+    let ride = Ride {
+        id: RideId::new(),
+        name: unique_name("ride"),
+        user_id,
+        distance: 12345.0,
+        started_at,
+        finished_at: started_at + Duration::hours(2),
+        external_ref: None,
+        archived_at: None,
+    };
+
+    repos.ride_repo.put(ride.clone()).await?;
+
+    Ok(ride)
+}
+
+async fn create_trip(repos: &Repos, user_id: UserId, is_published: bool) -> anyhow::Result<Trip> {
+    let trip = Trip {
+        id: TripId::new(),
+        created_at: datetime(2024, 1, 1, 0),
+        user_id,
+        name: String::from("Trip"),
+        slug: unique_name("trip"),
+        year: 2024,
+        description: None,
+        notes: vec![],
+        ride_ids: vec![],
+        media_ids: vec![],
+        is_published,
+    };
+
+    repos.trip_repo.put(trip.clone()).await?;
+
+    Ok(trip)
+}
+
+async fn create_route(repos: &Repos, user_id: UserId, tags: HashSet<Tag>) -> anyhow::Result<Route> {
+    let route = Route {
+        id: RouteId::new(),
+        name: String::from("Route"),
+        slug: unique_name("route"),
+        user_id,
+        distance: 5000.7,
+        sample_points: Some(vec![]),
+        description: Some(RouteDescription {
+            tags: vec![String::from("gravel")],
+            ..RouteDescription::default()
+        }),
+        external_ref: Some(ExternalRef {
+            id: ExternalId::Rwgps(RwgpsId::Route(unique_number())),
+            updated_at: datetime(2024, 1, 1, 0),
+            sync_version: None,
+        }),
+        tags,
+        archived_at: None,
+    };
+
+    repos.route_repo.put(route.clone()).await?;
+
+    Ok(route)
+}
+
+async fn create_point_of_interest(
+    repos: &Repos,
+    user_id: UserId,
+) -> anyhow::Result<PointOfInterest> {
+    let point_of_interest = PointOfInterest {
+        id: PointOfInterestId::new(),
+        user_id,
+        name: String::from("Hut"),
+        slug: unique_name("hut"),
+        point: geo::Point::new(146.6, -37.0),
+        point_of_interest_type: PointOfInterestType::Hut,
+        description: None,
+        external_ref: None,
+    };
+
+    repos
+        .point_of_interest_repo
+        .put(point_of_interest.clone())
+        .await?;
+
+    Ok(point_of_interest)
+}
+
+async fn create_media(
+    repos: &Repos,
+    user_id: UserId,
+    relation_ids: Vec<MediaRelationId>,
+) -> anyhow::Result<Media> {
+    let media = Media {
+        id: MediaId::new(),
+        created_at: datetime(2024, 1, 1, 0),
+        user_id,
+        path: unique_name("media"),
+        relation_ids,
+        point: None,
+        captured_at: None,
+        external_ref: None,
+    };
+
+    repos.media_repo.put(media.clone()).await?;
+
+    Ok(media)
+}
+
+async fn create_osm_feature(repos: &Repos, point: geo::Point<f64>) -> anyhow::Result<OsmFeature> {
+    let feature = OsmFeature {
+        id: OsmFeatureId::node(unique_number() as i64 + (1 << 40)),
+        geometry: geo::Geometry::Point(point),
+        name: Some(String::from("Spring")),
+        tags: HashMap::from([(String::from("natural"), String::from("spring"))]),
+    };
+
+    repos.osm_feature_repo.put(feature.clone()).await?;
+
+    Ok(feature)
+}
+
+pub async fn ride_filters(repos: &Repos) -> anyhow::Result<()> {
+    let user = create_user(repos, None).await?;
+    let other_user = create_user(repos, None).await?;
+
+    // 2024-01-15 11:00 and 2024-01-16 01:00 in Melbourne
+    let first = create_ride(repos, user.id, datetime(2024, 1, 15, 0)).await?;
+    let second = create_ride(repos, user.id, datetime(2024, 1, 15, 14)).await?;
+    let third = create_ride(repos, user.id, datetime(2024, 1, 17, 0)).await?;
+    let other = create_ride(repos, other_user.id, datetime(2024, 1, 15, 0)).await?;
+
+    let fetched = repos.ride_repo.get(first.id).await?;
+    ensure!(fetched.distance == 12345.0, "distance should round trip");
+
+    let rides = repos
+        .ride_repo
+        .filter_models(RideFilter::Ids(vec![first.id, other.id]))
+        .await?;
+    ensure!(id_set(rides.iter().map(|ride| ride.id)) == id_set([first.id, other.id]));
+
+    let rides = repos
+        .ride_repo
+        .filter_models(RideFilter::ForUser {
+            user_id: user.id,
+            started_at: None,
+        })
+        .await?;
+    ensure!(id_set(rides.iter().map(|ride| ride.id)) == id_set([first.id, second.id, third.id]));
+
+    let rides = repos
+        .ride_repo
+        .filter_models(RideFilter::ForUser {
+            user_id: user.id,
+            started_at: Some(TemporalFilter::Before {
+                before: third.started_at,
+                last: None,
+            }),
+        })
+        .await?;
+    ensure!(
+        rides.iter().map(|ride| ride.id).collect_vec() == vec![second.id, first.id],
+        "before should be newest first"
+    );
+
+    let rides = repos
+        .ride_repo
+        .filter_models(RideFilter::ForUser {
+            user_id: user.id,
+            started_at: Some(TemporalFilter::Before {
+                before: third.started_at,
+                last: Some(1),
+            }),
+        })
+        .await?;
+    ensure!(rides.iter().map(|ride| ride.id).collect_vec() == vec![second.id]);
+
+    let rides = repos
+        .ride_repo
+        .filter_models(RideFilter::ForUser {
+            user_id: user.id,
+            started_at: Some(TemporalFilter::After {
+                after: first.started_at,
+                first: None,
+            }),
+        })
+        .await?;
+    ensure!(
+        rides.iter().map(|ride| ride.id).collect_vec() == vec![second.id, third.id],
+        "after should be oldest first"
+    );
+
+    let rides = repos
+        .ride_repo
+        .filter_models(RideFilter::ForUser {
+            user_id: user.id,
+            started_at: Some(TemporalFilter::After {
+                after: first.started_at,
+                first: Some(1),
+            }),
+        })
+        .await?;
+    ensure!(rides.iter().map(|ride| ride.id).collect_vec() == vec![second.id]);
+
+    let rides = repos
+        .ride_repo
+        .filter_models(RideFilter::ForUserWithDate {
+            user_id: user.id,
+            date: NaiveDate::from_ymd_opt(2024, 1, 15).unwrap(),
+        })
+        .await?;
+    ensure!(
+        rides.iter().map(|ride| ride.id).collect_vec() == vec![first.id],
+        "dates are Melbourne days"
+    );
+
+    let mut trip = create_trip(repos, user.id, false).await?;
+    trip.ride_ids = vec![third.id, first.id];
+    repos.trip_repo.put(trip.clone()).await?;
+
+    let rides = repos
+        .ride_repo
+        .filter_models(RideFilter::ForTrip(trip.id))
+        .await?;
+    ensure!(
+        rides.iter().map(|ride| ride.id).collect_vec() == vec![first.id, third.id],
+        "trip rides should be in start order"
+    );
+
+    let rwgps_id = unique_number();
+    let rwgps_ride = Ride {
+        id: RideId::new(),
+        external_ref: Some(ExternalRef {
+            id: ExternalId::Rwgps(RwgpsId::Trip(rwgps_id)),
+            updated_at: datetime(2024, 1, 1, 0),
+            sync_version: Some(1),
+        }),
+        distance: 100.9,
+        ..first.clone()
+    };
+    repos.ride_repo.put(rwgps_ride.clone()).await?;
+
+    let rides = repos
+        .ride_repo
+        .filter_models(RideFilter::RwgpsId(rwgps_id))
+        .await?;
+    ensure!(rides.iter().map(|ride| ride.id).collect_vec() == vec![rwgps_ride.id]);
+    ensure!(
+        rides[0].distance == 100.0,
+        "distance is stored in whole metres"
+    );
+
+    let rides = repos
+        .ride_repo
+        .filter_models(RideFilter::ExternalId(ExternalId::Rwgps(RwgpsId::Trip(
+            rwgps_id,
+        ))))
+        .await?;
+    ensure!(rides.iter().map(|ride| ride.id).collect_vec() == vec![rwgps_ride.id]);
+
+    let rides = repos
+        .ride_repo
+        .filter_models(RideFilter::ExternalId(ExternalId::Strava(
+            StravaId::Activity(rwgps_id),
+        )))
+        .await?;
+    ensure!(rides.is_empty(), "ids are matched per provider");
+
+    Ok(())
+}
+
+pub async fn ride_pages(repos: &Repos) -> anyhow::Result<()> {
+    let user = create_user(repos, None).await?;
+
+    // Imported out of order, so pages follow started_at rather than ids
+    let mut rides = vec![];
+    for day in [3, 1, 5, 2, 4] {
+        rides.push(create_ride(repos, user.id, datetime(2024, 7, day, 0)).await?);
+    }
+    let ids = rides
+        .iter()
+        .sorted_by_key(|ride| ride.started_at)
+        .map(|ride| ride.id)
+        .collect_vec();
+
+    let filter = RideFilter::ForUser {
+        user_id: user.id,
+        started_at: None,
+    };
+
+    let page = repos
+        .ride_repo
+        .filter_page(
+            filter.clone(),
+            PageRequest {
+                first: Some(2),
+                ..PageRequest::default()
+            },
+        )
+        .await?;
+    ensure!(page.items.iter().map(|ride| ride.id).collect_vec() == ids[0..2]);
+    ensure!(page.has_next_page);
+
+    let page = repos
+        .ride_repo
+        .filter_page(
+            filter.clone(),
+            PageRequest {
+                after: Some(ids[1]),
+                first: Some(2),
+                ..PageRequest::default()
+            },
+        )
+        .await?;
+    ensure!(page.items.iter().map(|ride| ride.id).collect_vec() == ids[2..4]);
+    ensure!(page.has_next_page);
+
+    let page = repos
+        .ride_repo
+        .filter_page(
+            filter.clone(),
+            PageRequest {
+                after: Some(ids[3]),
+                first: Some(2),
+                ..PageRequest::default()
+            },
+        )
+        .await?;
+    ensure!(page.items.iter().map(|ride| ride.id).collect_vec() == ids[4..5]);
+    ensure!(!page.has_next_page);
+
+    let page = repos
+        .ride_repo
+        .filter_page(
+            filter.clone(),
+            PageRequest {
+                before: Some(ids[4]),
+                last: Some(3),
+                ..PageRequest::default()
+            },
+        )
+        .await?;
+    ensure!(page.items.iter().map(|ride| ride.id).collect_vec() == ids[1..4]);
+    ensure!(page.has_previous_page);
+
+    // Paging on from a ride archived since keeps its place
+    let archived = rides.iter().find(|ride| ride.id == ids[1]).unwrap();
+    repos
+        .ride_repo
+        .put(Ride {
+            archived_at: Some(datetime(2024, 8, 1, 0)),
+            ..archived.clone()
+        })
+        .await?;
+
+    let after_archived = PageRequest {
+        after: Some(ids[1]),
+        first: Some(2),
+        ..PageRequest::default()
+    };
+    let page = repos
+        .ride_repo
+        .filter_page(filter.clone(), after_archived)
+        .await?;
+    ensure!(page.items.iter().map(|ride| ride.id).collect_vec() == ids[2..4]);
+
+    // A deleted ride is no cursor at all, rather than the end of the list
+    repos.ride_repo.delete(ids[2]).await?;
+
+    let after_deleted = PageRequest {
+        after: Some(ids[2]),
+        first: Some(2),
+        ..PageRequest::default()
+    };
+    ensure!(repos
+        .ride_repo
+        .filter_page(filter.clone(), after_deleted)
+        .await
+        .is_err());
+
+    let before_deleted = PageRequest {
+        before: Some(ids[2]),
+        last: Some(2),
+        ..PageRequest::default()
+    };
+    ensure!(repos
+        .ride_repo
+        .filter_page(filter, before_deleted)
+        .await
+        .is_err());
+
+    Ok(())
+}
+
+pub async fn model_pages(repos: &Repos) -> anyhow::Result<()> {
+    let user = create_user(repos, Some(unique_number() as i32)).await?;
+
+    let mut trips = vec![];
+    for _ in 0..3 {
+        trips.push(create_trip(repos, user.id, false).await?);
+    }
+    let trip_ids = trips.iter().map(|trip| trip.id).collect_vec();
+
+    let page = repos
+        .trip_repo
+        .filter_page(
+            TripFilter::User(user.id),
+            PageRequest {
+                after: Some(trip_ids[0]),
+                first: Some(1),
+                ..PageRequest::default()
+            },
+        )
+        .await?;
+    ensure!(page.items.iter().map(|trip| trip.id).collect_vec() == trip_ids[1..2]);
+    ensure!(page.has_next_page);
+
+    let page = repos
+        .trip_repo
+        .filter_page(
+            TripFilter::User(user.id),
+            PageRequest {
+                before: Some(trip_ids[2]),
+                last: Some(2),
+                ..PageRequest::default()
+            },
+        )
+        .await?;
+    ensure!(page.items.iter().map(|trip| trip.id).collect_vec() == trip_ids[0..2]);
+    ensure!(!page.has_previous_page);
+
+    // Other checks add rows concurrently, so these only look at their own
+    let mut routes = vec![];
+    let mut pois = vec![];
+    let mut users = vec![user.clone()];
+    for _ in 0..2 {
+        routes.push(create_route(repos, user.id, HashSet::new()).await?);
+        pois.push(create_point_of_interest(repos, user.id).await?);
+        users.push(create_user(repos, Some(unique_number() as i32)).await?);
+    }
+    let unconnected_user = create_user(repos, None).await?;
+
+    let page = repos
+        .route_repo
+        .filter_page(
+            RouteFilter::All,
+            PageRequest {
+                after: Some(routes[0].id),
+                ..PageRequest::default()
+            },
+        )
+        .await?;
+    ensure!(page.items.iter().any(|route| route.id == routes[1].id));
+    ensure!(!page.items.iter().any(|route| route.id == routes[0].id));
+    ensure!(page.items.is_sorted_by_key(|route| route.id));
+
+    let page = repos
+        .point_of_interest_repo
+        .filter_page(
+            PointOfInterestFilter::All,
+            PageRequest {
+                after: Some(pois[0].id),
+                ..PageRequest::default()
+            },
+        )
+        .await?;
+    ensure!(page.items.iter().any(|poi| poi.id == pois[1].id));
+    ensure!(!page.items.iter().any(|poi| poi.id == pois[0].id));
+    ensure!(page.items.is_sorted_by_key(|poi| poi.id));
+
+    let page = repos
+        .user_repo
+        .filter_page(
+            UserFilter::Public,
+            PageRequest {
+                after: Some(users[0].id),
+                ..PageRequest::default()
+            },
+        )
+        .await?;
+    let page_ids = page.items.iter().map(|user| user.id).collect_vec();
+    ensure!(users[1..].iter().all(|user| page_ids.contains(&user.id)));
+    ensure!(!page_ids.contains(&users[0].id));
+    ensure!(
+        !page_ids.contains(&unconnected_user.id),
+        "only users with an RWGPS connection are public"
+    );
+    ensure!(page_ids.is_sorted());
+
+    Ok(())
+}
+
+pub async fn ride_weather_filters(repos: &Repos) -> anyhow::Result<()> {
+    let user = create_user(repos, None).await?;
+    let ride = create_ride(repos, user.id, datetime(2024, 12, 15, 0)).await?;
+    let unweathered_ride = create_ride(repos, user.id, datetime(2024, 12, 16, 0)).await?;
+
+    let ride_weather = RideWeather {
+        id: ride.id,
+        hours: vec![HourlyWeather {
+            datetime: datetime(2024, 12, 15, 0),
+            point: geo::Point::new(145.7, -37.75),
+            bearing: Some(92.5),
+            temperature: 20.9,
+            precipitation: 0.0,
+            wind_speed: 4.5,
+            wind_direction: 247.0,
+        }],
+    };
+
+    repos.ride_weather_repo.put(ride_weather.clone()).await?;
+
+    ensure!(repos.ride_weather_repo.get(ride.id).await? == ride_weather);
+    ensure!(repos
+        .ride_weather_repo
+        .get(unweathered_ride.id)
+        .await
+        .is_err());
+
+    let found = repos
+        .ride_weather_repo
+        .filter_models(RideWeatherFilter::Ids(vec![ride.id, unweathered_ride.id]))
+        .await?;
+    ensure!(found == vec![ride_weather]);
+
+    repos.ride_repo.delete(ride.id).await?;
+
+    ensure!(repos.ride_weather_repo.get(ride.id).await.is_err());
+
+    Ok(())
+}
+
+pub async fn trip_filters(repos: &Repos) -> anyhow::Result<()> {
+    let user = create_user(repos, None).await?;
+    let other_user = create_user(repos, None).await?;
+
+    let draft = create_trip(repos, user.id, false).await?;
+    let published = create_trip(repos, user.id, true).await?;
+    let other = create_trip(repos, other_user.id, true).await?;
+
+    let trips = repos
+        .trip_repo
+        .filter_models(TripFilter::User(user.id))
+        .await?;
+    ensure!(id_set(trips.iter().map(|trip| trip.id)) == id_set([draft.id, published.id]));
+
+    let trip = repos
+        .trip_repo
+        .find_model(TripFilter::WithUserAndSlug {
+            user_id: user.id,
+            slug: published.slug.clone(),
+        })
+        .await?;
+    ensure!(trip.map(|trip| trip.id) == Some(published.id));
+
+    let trip = repos
+        .trip_repo
+        .find_model(TripFilter::WithUserAndSlug {
+            user_id: other_user.id,
+            slug: published.slug.clone(),
+        })
+        .await?;
+    ensure!(trip.is_none(), "slugs are scoped to a user");
+
+    let trip_ids = id_set(
+        repos
+            .trip_repo
+            .filter_models(TripFilter::Published)
+            .await?
+            .into_iter()
+            .map(|trip| trip.id),
+    );
+    ensure!(trip_ids.contains(&published.id) && trip_ids.contains(&other.id));
+    ensure!(!trip_ids.contains(&draft.id));
+
+    let trip_ids = id_set(
+        repos
+            .trip_repo
+            .filter_models(TripFilter::All)
+            .await?
+            .into_iter()
+            .map(|trip| trip.id),
+    );
+    ensure!(trip_ids.is_superset(&id_set([draft.id, published.id, other.id])));
+
+    let rides = [
+        create_ride(repos, user.id, datetime(2024, 2, 1, 0)).await?,
+        create_ride(repos, user.id, datetime(2024, 2, 2, 0)).await?,
+        create_ride(repos, user.id, datetime(2024, 2, 3, 0)).await?,
+    ];
+    let media = create_media(repos, user.id, vec![]).await?;
+
+    repos
+        .trip_repo
+        .put(Trip {
+            ride_ids: vec![rides[2].id, rides[0].id, rides[1].id],
+            media_ids: vec![media.id],
+            ..draft.clone()
+        })
+        .await?;
+
+    let trip = repos.trip_repo.get(draft.id).await?;
+    ensure!(
+        trip.ride_ids == vec![rides[0].id, rides[1].id, rides[2].id],
+        "ride ids should be in id order"
+    );
+    ensure!(trip.media_ids == vec![media.id]);
+
+    repos
+        .trip_repo
+        .put(Trip {
+            ride_ids: vec![rides[1].id],
+            media_ids: vec![],
+            ..trip
+        })
+        .await?;
+
+    let trip = repos.trip_repo.get(draft.id).await?;
+    ensure!(
+        trip.ride_ids == vec![rides[1].id],
+        "removed rides should be unlinked"
+    );
+    ensure!(
+        trip.media_ids.is_empty(),
+        "removed media should be unlinked"
+    );
+
+    Ok(())
+}
+
+pub async fn media_filters(repos: &Repos) -> anyhow::Result<()> {
+    let user = create_user(repos, None).await?;
+
+    let ride = create_ride(repos, user.id, datetime(2024, 3, 1, 0)).await?;
+    let route = create_route(repos, user.id, HashSet::new()).await?;
+    let point_of_interest = create_point_of_interest(repos, user.id).await?;
+    let trip = create_trip(repos, user.id, false).await?;
+
+    let ride_media = create_media(repos, user.id, vec![MediaRelationId::Ride(ride.id)]).await?;
+    let route_media = create_media(
+        repos,
+        user.id,
+        vec![
+            MediaRelationId::Route(route.id),
+            MediaRelationId::PointOfInterest(point_of_interest.id),
+        ],
+    )
+    .await?;
+    let trip_media = create_media(repos, user.id, vec![]).await?;
+
+    repos
+        .trip_repo
+        .put(Trip {
+            media_ids: vec![trip_media.id],
+            ..trip.clone()
+        })
+        .await?;
+
+    let media = repos
+        .media_repo
+        .filter_models(MediaFilter::ForUser(user.id))
+        .await?;
+    ensure!(
+        id_set(media.iter().map(|media| media.id))
+            == id_set([ride_media.id, route_media.id, trip_media.id])
+    );
+
+    let media = repos
+        .media_repo
+        .filter_models(MediaFilter::ForRide(ride.id))
+        .await?;
+    ensure!(media.iter().map(|media| media.id).collect_vec() == vec![ride_media.id]);
+
+    let media = repos
+        .media_repo
+        .filter_models(MediaFilter::ForRoute(route.id))
+        .await?;
+    ensure!(media.iter().map(|media| media.id).collect_vec() == vec![route_media.id]);
+
+    let media = repos
+        .media_repo
+        .filter_models(MediaFilter::ForPointOfInterest(point_of_interest.id))
+        .await?;
+    ensure!(media.iter().map(|media| media.id).collect_vec() == vec![route_media.id]);
+
+    let media = repos
+        .media_repo
+        .filter_models(MediaFilter::ForTrip(trip.id))
+        .await?;
+    ensure!(
+        media.iter().map(|media| media.id).collect_vec() == vec![trip_media.id],
+        "media linked through the trip should be found"
+    );
+    ensure!(media[0].iter_trip_ids().collect_vec() == vec![trip.id]);
+
+    let media = repos
+        .media_repo
+        .filter_models(MediaFilter::Ids(vec![ride_media.id, trip_media.id]))
+        .await?;
+    ensure!(id_set(media.iter().map(|media| media.id)) == id_set([ride_media.id, trip_media.id]));
+
+    let media = repos.media_repo.get(route_media.id).await?;
+    ensure!(media.iter_route_ids().collect_vec() == vec![route.id]);
+    ensure!(media.iter_point_of_interest_ids().collect_vec() == vec![point_of_interest.id]);
+    ensure!(media.iter_ride_ids().next().is_none());
+
+    repos
+        .media_repo
+        .put(Media {
+            relation_ids: vec![MediaRelationId::Ride(ride.id)],
+            ..media
+        })
+        .await?;
+
+    let media = repos.media_repo.get(route_media.id).await?;
+    ensure!(media.iter_ride_ids().collect_vec() == vec![ride.id]);
+    ensure!(
+        media.iter_route_ids().next().is_none(),
+        "removed relations should be unlinked"
+    );
+
+    let photo_id = unique_number();
+    let external_ref = ExternalRef {
+        id: ExternalId::Rwgps(RwgpsId::Photo(photo_id)),
+        updated_at: datetime(2024, 3, 1, 0),
+        sync_version: Some(2),
+    };
+    repos
+        .media_repo
+        .put(Media {
+            external_ref: Some(external_ref.clone()),
+            ..ride_media.clone()
+        })
+        .await?;
+
+    let media = repos
+        .media_repo
+        .filter_models(MediaFilter::RwgpsPhotoIds(vec![photo_id, unique_number()]))
+        .await?;
+    ensure!(media.iter().map(|media| media.id).collect_vec() == vec![ride_media.id]);
+    ensure!(media[0].external_ref == Some(external_ref));
+
+    Ok(())
+}
+
+pub async fn archived_filters(repos: &Repos) -> anyhow::Result<()> {
+    let user = create_user(repos, None).await?;
+    let archived_at = Some(datetime(2024, 7, 1, 0));
+
+    let trip = create_trip(repos, user.id, true).await?;
+    let ride = create_ride(repos, user.id, datetime(2024, 6, 1, 0)).await?;
+    let ride = Ride {
+        external_ref: Some(ExternalRef {
+            id: ExternalId::Rwgps(RwgpsId::Trip(unique_number())),
+            updated_at: datetime(2024, 6, 2, 0),
+            sync_version: None,
+        }),
+        archived_at,
+        ..ride
+    };
+    repos.ride_repo.put(ride.clone()).await?;
+    repos
+        .trip_repo
+        .put(Trip {
+            ride_ids: vec![ride.id],
+            ..trip.clone()
+        })
+        .await?;
+
+    let route = create_route(repos, user.id, HashSet::new()).await?;
+    let route = Route {
+        archived_at,
+        ..route
+    };
+    repos.route_repo.put(route.clone()).await?;
+
+    let ride_filters = [
+        RideFilter::All,
+        RideFilter::ForUser {
+            user_id: user.id,
+            started_at: None,
+        },
+        RideFilter::ForUserWithDate {
+            user_id: user.id,
+            date: NaiveDate::from_ymd_opt(2024, 6, 1).unwrap(),
+        },
+        RideFilter::ForTrip(trip.id),
+    ];
+    for filter in ride_filters {
+        let rides = repos.ride_repo.filter_models(filter.clone()).await?;
+        ensure!(
+            rides.iter().all(|found| found.id != ride.id),
+            "{filter:?} leaves out archived rides"
+        );
+    }
+
+    let rides = repos
+        .ride_repo
+        .filter_models(RideFilter::SyncedForUser(user.id))
+        .await?;
+    ensure!(id_set(rides.iter().map(|ride| ride.id)) == id_set([ride.id]));
+
+    let rides = repos
+        .ride_repo
+        .filter_models(RideFilter::Ids(vec![ride.id]))
+        .await?;
+    ensure!(rides.len() == 1);
+
+    let route_filters = [
+        RouteFilter::All,
+        RouteFilter::UserId(user.id),
+        RouteFilter::Slug(route.slug.clone()),
+        RouteFilter::Search(route.name.clone()),
+    ];
+    for filter in route_filters {
+        let routes = repos.route_repo.filter_models(filter.clone()).await?;
+        ensure!(
+            routes.iter().all(|found| found.id != route.id),
+            "{filter:?} leaves out archived routes"
+        );
+    }
+
+    let page = repos
+        .route_repo
+        .filter_page(RouteFilter::All, PageRequest::default())
+        .await?;
+    ensure!(page.items.iter().all(|found| found.id != route.id));
+
+    let routes = repos
+        .route_repo
+        .filter_models(RouteFilter::SyncedForUser(user.id))
+        .await?;
+    ensure!(id_set(routes.iter().map(|route| route.id)) == id_set([route.id]));
+
+    let route = repos
+        .route_repo
+        .find_model(RouteFilter::ExternalId(route.external_ref.unwrap().id))
+        .await?;
+    ensure!(route.is_some());
+
+    Ok(())
+}
+
+pub async fn route_filters(repos: &Repos) -> anyhow::Result<()> {
+    let user = create_user(repos, None).await?;
+
+    let starred = create_route(repos, user.id, HashSet::from([Tag::BackcountrySegment])).await?;
+    let plain = create_route(repos, user.id, HashSet::new()).await?;
+
+    let route = repos.route_repo.get(plain.id).await?;
+    ensure!(
+        route.distance == 5000.0,
+        "distance is stored in whole metres"
+    );
+    ensure!(route.tags == HashSet::from([Tag::Custom(String::from("gravel"))]));
+
+    let route = repos
+        .route_repo
+        .find_model(RouteFilter::Slug(starred.slug.clone()))
+        .await?;
+    ensure!(route.map(|route| route.id) == Some(starred.id));
+
+    let routes = repos
+        .route_repo
+        .filter_models(RouteFilter::UserId(user.id))
+        .await?;
+    ensure!(id_set(routes.iter().map(|route| route.id)) == id_set([starred.id, plain.id]));
+
+    let Some(ExternalRef {
+        id: ExternalId::Rwgps(RwgpsId::Route(rwgps_id)),
+        ..
+    }) = plain.external_ref
+    else {
+        unreachable!()
+    };
+    let route = repos
+        .route_repo
+        .find_model(RouteFilter::RwgpsId(rwgps_id))
+        .await?;
+    ensure!(route.map(|route| route.id) == Some(plain.id));
+
+    let route = repos
+        .route_repo
+        .find_model(RouteFilter::ExternalId(ExternalId::Rwgps(RwgpsId::Route(
+            rwgps_id,
+        ))))
+        .await?;
+    ensure!(route.map(|route| route.id) == Some(plain.id));
+
+    let route_ids = id_set(
+        repos
+            .route_repo
+            .filter_models(RouteFilter::Starred)
+            .await?
+            .into_iter()
+            .map(|route| route.id),
+    );
+    ensure!(
+        route_ids.contains(&starred.id),
+        "backcountry segments are starred"
+    );
+    ensure!(!route_ids.contains(&plain.id));
+
+    Ok(())
+}
+
+pub async fn route_points_filters(repos: &Repos) -> anyhow::Result<()> {
+    let user = create_user(repos, None).await?;
+    let route = create_route(repos, user.id, HashSet::new()).await?;
+    let other_route = create_route(repos, user.id, HashSet::new()).await?;
+
+    let route_points = RoutePoints {
+        id: route.id,
+        points: vec![
+            ElevationPoint {
+                point: geo::Point::new(146.0, -37.0),
+                elevation: 100.0,
+            },
+            ElevationPoint {
+                point: geo::Point::new(146.1, -37.1),
+                elevation: 200.0,
+            },
+        ],
+    };
+
+    repos.route_points_repo.put(route_points.clone()).await?;
+    repos
+        .route_points_repo
+        .put(RoutePoints {
+            id: other_route.id,
+            points: vec![],
+        })
+        .await?;
+
+    ensure!(repos.route_points_repo.get(route.id).await? == route_points);
+
+    let points = repos
+        .route_points_repo
+        .filter_models(RoutePointsFilter::Ids(vec![route.id]))
+        .await?;
+    ensure!(points == vec![route_points]);
+
+    Ok(())
+}
+
+pub async fn route_course_points_filters(repos: &Repos) -> anyhow::Result<()> {
+    let user = create_user(repos, None).await?;
+    let route = create_route(repos, user.id, HashSet::new()).await?;
+    let other_route = create_route(repos, user.id, HashSet::new()).await?;
+
+    let route_course_points = RouteCoursePoints {
+        id: route.id,
+        course_points: vec![
+            CoursePoint {
+                point: geo::Point::new(146.9397, -36.8917),
+                distance_m: Some(1250.5),
+                kind: String::from("Left"),
+                note: Some(String::from("Turn left onto Bon Accord Track")),
+            },
+            CoursePoint {
+                point: geo::Point::new(147.1, -36.95),
+                distance_m: None,
+                kind: String::from("Water"),
+                note: None,
+            },
+        ],
+    };
+
+    repos
+        .route_course_points_repo
+        .put(route_course_points.clone())
+        .await?;
+
+    ensure!(repos.route_course_points_repo.get(route.id).await? == route_course_points);
+
+    let found = repos
+        .route_course_points_repo
+        .filter_models(RouteCoursePointsFilter::Ids(vec![route.id, other_route.id]))
+        .await?;
+    ensure!(found == vec![route_course_points.clone()]);
+
+    // Syncing again replaces what was there
+    let route_course_points = RouteCoursePoints {
+        course_points: vec![],
+        ..route_course_points
+    };
+    repos
+        .route_course_points_repo
+        .put(route_course_points.clone())
+        .await?;
+    ensure!(repos.route_course_points_repo.get(route.id).await? == route_course_points);
+
+    repos.route_repo.delete(route.id).await?;
+
+    ensure!(repos.route_course_points_repo.get(route.id).await.is_err());
+
+    Ok(())
+}
+
+pub async fn locality_filters(repos: &Repos) -> anyhow::Result<()> {
+    let user = create_user(repos, None).await?;
+    let route = create_route(repos, user.id, HashSet::new()).await?;
+    let other_route = create_route(repos, user.id, HashSet::new()).await?;
+    let ride = create_ride(repos, user.id, datetime(2024, 12, 15, 0)).await?;
+
+    let harrietville = Locality {
+        name: Some(String::from("Harrietville")),
+        region: Some(String::from("Victoria")),
+    };
+
+    let route_localities = RouteLocalities {
+        id: route.id,
+        start: Some(harrietville.clone()),
+        end: None,
+    };
+    let ride_localities = RideLocalities {
+        id: ride.id,
+        start: Some(harrietville.clone()),
+        finish: Some(Locality {
+            name: None,
+            region: Some(String::from("Victoria")),
+        }),
+    };
+
+    repos
+        .route_localities_repo
+        .put(route_localities.clone())
+        .await?;
+    repos
+        .ride_localities_repo
+        .put(ride_localities.clone())
+        .await?;
+
+    ensure!(repos.route_localities_repo.get(route.id).await? == route_localities);
+    ensure!(repos.ride_localities_repo.get(ride.id).await? == ride_localities);
+
+    let found = repos
+        .route_localities_repo
+        .filter_models(RouteLocalitiesFilter::Ids(vec![route.id, other_route.id]))
+        .await?;
+    ensure!(found == vec![route_localities.clone()]);
+
+    let found = repos
+        .ride_localities_repo
+        .filter_models(RideLocalitiesFilter::Ids(vec![ride.id]))
+        .await?;
+    ensure!(found == vec![ride_localities]);
+
+    // Geocoding again replaces what was there
+    let route_localities = RouteLocalities {
+        end: Some(harrietville),
+        ..route_localities
+    };
+    repos
+        .route_localities_repo
+        .put(route_localities.clone())
+        .await?;
+    ensure!(repos.route_localities_repo.get(route.id).await? == route_localities);
+
+    repos.route_repo.delete(route.id).await?;
+    repos.ride_repo.delete(ride.id).await?;
+
+    ensure!(repos.route_localities_repo.get(route.id).await.is_err());
+    ensure!(repos.ride_localities_repo.get(ride.id).await.is_err());
+
+    Ok(())
+}
+
+pub async fn point_of_interest_filters(repos: &Repos) -> anyhow::Result<()> {
+    let user = create_user(repos, None).await?;
+
+    let inside = create_point_of_interest(repos, user.id).await?;
+    let outside = PointOfInterest {
+        id: PointOfInterestId::new(),
+        slug: unique_name("hut"),
+        point: geo::Point::new(146.7, -37.0),
+        ..inside.clone()
+    };
+    repos.point_of_interest_repo.put(outside.clone()).await?;
+
+    let points_of_interest = repos
+        .point_of_interest_repo
+        .filter_models(PointOfInterestFilter::BoundingBox(geo::Rect::new(
+            geo::coord! { x: 146.55, y: -37.05 },
+            geo::coord! { x: 146.65, y: -36.95 },
+        )))
+        .await?;
+    let ids = id_set(points_of_interest.iter().map(|poi| poi.id));
+    ensure!(ids.contains(&inside.id));
+    ensure!(!ids.contains(&outside.id));
+
+    // About 3.6km from the inside POI and 5.3km from the outside one
+    let near = geo::Point::new(146.64, -37.0);
+    let points_of_interest = repos
+        .point_of_interest_repo
+        .filter_models(PointOfInterestFilter::Near {
+            point: near,
+            distance_m: 6_000.0,
+        })
+        .await?;
+    let ids = points_of_interest.iter().map(|poi| poi.id).collect_vec();
+    ensure!(ids.contains(&inside.id));
+    ensure!(ids.contains(&outside.id));
+    ensure!(
+        points_of_interest
+            .iter()
+            .map(|poi| Haversine::distance(near, poi.point))
+            .tuple_windows()
+            .all(|(a, b)| a <= b),
+        "closest first"
+    );
+
+    let points_of_interest = repos
+        .point_of_interest_repo
+        .filter_models(PointOfInterestFilter::Near {
+            point: geo::Point::new(146.7, -37.0),
+            distance_m: 5_000.0,
+        })
+        .await?;
+    let ids = id_set(points_of_interest.iter().map(|poi| poi.id));
+    ensure!(!ids.contains(&inside.id));
+    ensure!(ids.contains(&outside.id));
+
+    Ok(())
+}
+
+pub async fn search_filters(repos: &Repos) -> anyhow::Result<()> {
+    let user = create_user(repos, None).await?;
+    // A word nothing else in the database contains
+    let token = uuid::Uuid::now_v7().simple().to_string();
+
+    let route = Route {
+        name: format!("Buffalo {token} Loop"),
+        ..create_route(repos, user.id, HashSet::new()).await?
+    };
+    repos.route_repo.put(route.clone()).await?;
+
+    let trip = Trip {
+        name: format!("Buffalo {token} Tour"),
+        ..create_trip(repos, user.id, true).await?
+    };
+    repos.trip_repo.put(trip.clone()).await?;
+
+    let point_of_interest = PointOfInterest {
+        name: format!("Buffalo {token} Hut"),
+        ..create_point_of_interest(repos, user.id).await?
+    };
+    repos
+        .point_of_interest_repo
+        .put(point_of_interest.clone())
+        .await?;
+
+    let query = format!("buff {token}");
+
+    let routes = repos
+        .route_repo
+        .filter_models(RouteFilter::Search(query.clone()))
+        .await?;
+    ensure!(id_set(routes.iter().map(|route| route.id)) == id_set([route.id]));
+
+    let trips = repos
+        .trip_repo
+        .filter_models(TripFilter::Search(query.clone()))
+        .await?;
+    ensure!(id_set(trips.iter().map(|trip| trip.id)) == id_set([trip.id]));
+
+    let points_of_interest = repos
+        .point_of_interest_repo
+        .filter_models(PointOfInterestFilter::Search(query))
+        .await?;
+    ensure!(id_set(points_of_interest.iter().map(|poi| poi.id)) == id_set([point_of_interest.id]));
+
+    let routes = repos
+        .route_repo
+        .filter_models(RouteFilter::Search(format!("{token} hut")))
+        .await?;
+    ensure!(routes.is_empty(), "every term has to match");
+
+    let routes = repos
+        .route_repo
+        .filter_models(RouteFilter::Search(String::from(" - ")))
+        .await?;
+    ensure!(routes.is_empty(), "a query without terms matches nothing");
+
+    Ok(())
+}
+
+pub async fn user_filters(repos: &Repos) -> anyhow::Result<()> {
+    let rwgps_user_id = unique_number() as i32;
+
+    let connected = create_user(repos, Some(rwgps_user_id)).await?;
+    let unconnected = create_user(repos, None).await?;
+
+    let user = repos
+        .user_repo
+        .find_model(UserFilter::Username(connected.username.clone()))
+        .await?;
+    ensure!(user.map(|user| user.id) == Some(connected.id));
+
+    let user = repos
+        .user_repo
+        .find_model(UserFilter::Email(unconnected.email.clone()))
+        .await?;
+    ensure!(user.map(|user| user.id) == Some(unconnected.id));
+
+    let user = repos
+        .user_repo
+        .find_model(UserFilter::RwgpsId(rwgps_user_id as usize))
+        .await?;
+    ensure!(user.map(|user| user.id) == Some(connected.id));
+
+    let users = repos
+        .user_repo
+        .filter_models(UserFilter::Ids(vec![connected.id, unconnected.id]))
+        .await?;
+    ensure!(id_set(users.iter().map(|user| user.id)) == id_set([connected.id, unconnected.id]));
+
+    let user_ids = id_set(repos.user_repo.all().await?.into_iter().map(|user| user.id));
+    ensure!(user_ids.contains(&connected.id));
+    ensure!(
+        !user_ids.contains(&unconnected.id),
+        "all() only returns users with an RWGPS connection"
+    );
+
+    // Saving a user without a connection leaves the stored one alone
+    repos
+        .user_repo
+        .put(User {
+            rwgps_connection: None,
+            ..connected.clone()
+        })
+        .await?;
+
+    let user = repos.user_repo.get(connected.id).await?;
+    ensure!(
+        user.rwgps_connection
+            .map(|connection| connection.rwgps_user_id)
+            == Some(rwgps_user_id)
+    );
+
+    let updated_at = datetime(2024, 6, 1, 0);
+    let sync_cursor = SyncCursor {
+        updated_at: datetime(2024, 5, 1, 0),
+        sync_version: 2,
+    };
+    repos
+        .user_repo
+        .put(User {
+            rwgps_connection: connected.rwgps_connection.clone().map(|connection| {
+                UserRwgpsConnection {
+                    status: RwgpsConnectionStatus::RateLimited,
+                    updated_at,
+                    sync_cursor: Some(sync_cursor),
+                    ..connection
+                }
+            }),
+            ..connected.clone()
+        })
+        .await?;
+
+    let connection = repos.user_repo.get(connected.id).await?.rwgps_connection;
+    ensure!(
+        connection
+            .as_ref()
+            .map(|connection| (connection.status, connection.updated_at))
+            == Some((RwgpsConnectionStatus::RateLimited, updated_at)),
+        "connection status is stored"
+    );
+    ensure!(
+        connection.and_then(|connection| connection.sync_cursor) == Some(sync_cursor),
+        "sync cursor is stored"
+    );
+
+    // Targeted updates only touch their own column
+    let recovered_at = datetime(2024, 6, 2, 0);
+    repos
+        .user_repo
+        .set_rwgps_status(connected.id, RwgpsConnectionStatus::Ok, recovered_at)
+        .await?;
+
+    let next_cursor = SyncCursor {
+        updated_at: datetime(2024, 6, 1, 0),
+        sync_version: 2,
+    };
+    repos
+        .user_repo
+        .set_rwgps_sync_cursor(connected.id, next_cursor)
+        .await?;
+
+    let connection = repos.user_repo.get(connected.id).await?.rwgps_connection;
+    ensure!(
+        connection.as_ref().map(|connection| (
+            connection.status,
+            connection.updated_at,
+            connection.sync_cursor,
+            connection.rwgps_user_id
+        )) == Some((
+            RwgpsConnectionStatus::Ok,
+            recovered_at,
+            Some(next_cursor),
+            rwgps_user_id
+        ))
+    );
+
+    // Users without a connection are left alone
+    repos
+        .user_repo
+        .set_rwgps_sync_cursor(unconnected.id, next_cursor)
+        .await?;
+    ensure!(repos
+        .user_repo
+        .get(unconnected.id)
+        .await?
+        .rwgps_connection
+        .is_none());
+
+    Ok(())
+}
+
+pub async fn point_of_interest_visit_filters(repos: &Repos) -> anyhow::Result<()> {
+    let owner = create_user(repos, None).await?;
+    let visitor = create_user(repos, None).await?;
+
+    let point_of_interest = create_point_of_interest(repos, owner.id).await?;
+    let other_point_of_interest = create_point_of_interest(repos, visitor.id).await?;
+
+    let visit = |point_of_interest_id: PointOfInterestId,
+                 visited_at: DateTime<Utc>,
+                 confirmation: VisitConfirmation| PointOfInterestVisit {
+        id: PointOfInterestVisitId::new(),
+        created_at: visited_at,
+        user_id: visitor.id,
+        point_of_interest_id,
+        visited_at,
+        confirmation,
+        condition: None,
+        comment: None,
+        media_ids: vec![],
+    };
+
+    let early = visit(
+        point_of_interest.id,
+        datetime(2024, 4, 1, 0),
+        VisitConfirmation::Pending,
+    );
+    let late = visit(
+        point_of_interest.id,
+        datetime(2024, 4, 3, 0),
+        VisitConfirmation::Confirmed,
+    );
+    let elsewhere = visit(
+        other_point_of_interest.id,
+        datetime(2024, 4, 2, 0),
+        VisitConfirmation::Pending,
+    );
+
+    for visit in [&early, &late, &elsewhere] {
+        repos
+            .point_of_interest_visit_repo
+            .put(visit.clone())
+            .await?;
+    }
+
+    let visits = repos
+        .point_of_interest_visit_repo
+        .filter_models(PointOfInterestVisitFilter::ForUser(visitor.id))
+        .await?;
+    ensure!(
+        visits.iter().map(|visit| visit.id).collect_vec() == vec![late.id, elsewhere.id, early.id],
+        "visits should be newest first"
+    );
+
+    let visits = repos
+        .point_of_interest_visit_repo
+        .filter_models(PointOfInterestVisitFilter::ForPointOfInterest(
+            point_of_interest.id,
+        ))
+        .await?;
+    ensure!(visits.iter().map(|visit| visit.id).collect_vec() == vec![late.id, early.id]);
+
+    let visits = repos
+        .point_of_interest_visit_repo
+        .filter_models(PointOfInterestVisitFilter::VisitedBetween {
+            point_of_interest_id: None,
+            user_id: Some(visitor.id),
+            start: early.visited_at,
+            end: late.visited_at,
+        })
+        .await?;
+    ensure!(
+        visits.iter().map(|visit| visit.id).collect_vec() == vec![elsewhere.id, early.id],
+        "start is inclusive and end exclusive"
+    );
+
+    let visits = repos
+        .point_of_interest_visit_repo
+        .filter_models(PointOfInterestVisitFilter::VisitedBetween {
+            point_of_interest_id: Some(point_of_interest.id),
+            user_id: None,
+            start: early.visited_at,
+            end: late.visited_at + Duration::seconds(1),
+        })
+        .await?;
+    ensure!(visits.iter().map(|visit| visit.id).collect_vec() == vec![late.id, early.id]);
+
+    let visits = repos
+        .point_of_interest_visit_repo
+        .filter_models(PointOfInterestVisitFilter::PendingForOwner(owner.id))
+        .await?;
+    ensure!(visits.iter().map(|visit| visit.id).collect_vec() == vec![early.id]);
+
+    let media = create_media(repos, visitor.id, vec![]).await?;
+    repos
+        .point_of_interest_visit_repo
+        .put(PointOfInterestVisit {
+            confirmation: VisitConfirmation::Rejected,
+            media_ids: vec![media.id],
+            ..early.clone()
+        })
+        .await?;
+
+    let visit = repos.point_of_interest_visit_repo.get(early.id).await?;
+    ensure!(visit.confirmation == VisitConfirmation::Rejected);
+    ensure!(visit.media_ids == vec![media.id]);
+
+    Ok(())
+}
+
+pub async fn water_beta_filters(repos: &Repos) -> anyhow::Result<()> {
+    let user = create_user(repos, None).await?;
+    let other_user = create_user(repos, None).await?;
+
+    let spring = create_osm_feature(repos, geo::Point::new(146.5, -37.2)).await?;
+    let tank = create_osm_feature(repos, geo::Point::new(146.6, -37.3)).await?;
+
+    let report =
+        |user_id: UserId, osm_feature_id: OsmFeatureId, observed_at: DateTime<Utc>| WaterBeta {
+            id: WaterBetaId::new(),
+            created_at: observed_at,
+            user_id,
+            osm_feature_id,
+            observed_at,
+            flow_status: WaterFlowStatus::Flowing,
+            treatment_needed: false,
+            comment: None,
+            media_ids: vec![],
+        };
+
+    let early = report(user.id, spring.id, datetime(2024, 5, 1, 0));
+    let late = report(other_user.id, spring.id, datetime(2024, 5, 3, 0));
+    let tank_report = report(user.id, tank.id, datetime(2024, 5, 2, 0));
+
+    for report in [&early, &late, &tank_report] {
+        repos.water_beta_repo.put(report.clone()).await?;
+    }
+
+    let reports = repos
+        .water_beta_repo
+        .filter_models(WaterBetaFilter::ForOsmFeatures(vec![spring.id, tank.id]))
+        .await?;
+    ensure!(
+        reports.iter().map(|report| report.id).collect_vec()
+            == vec![late.id, tank_report.id, early.id],
+        "reports should be newest first"
+    );
+
+    let reports = repos
+        .water_beta_repo
+        .filter_models(WaterBetaFilter::ForUser(user.id))
+        .await?;
+    ensure!(reports.iter().map(|report| report.id).collect_vec() == vec![tank_report.id, early.id]);
+
+    let reports = repos
+        .water_beta_repo
+        .filter_models(WaterBetaFilter::Ids(vec![early.id]))
+        .await?;
+    ensure!(reports.iter().map(|report| report.id).collect_vec() == vec![early.id]);
+
+    let media = create_media(repos, user.id, vec![]).await?;
+    repos
+        .water_beta_repo
+        .put(WaterBeta {
+            flow_status: WaterFlowStatus::Dry,
+            media_ids: vec![media.id],
+            ..early.clone()
+        })
+        .await?;
+
+    let report = repos.water_beta_repo.get(early.id).await?;
+    ensure!(report.flow_status == WaterFlowStatus::Dry);
+    ensure!(report.media_ids == vec![media.id]);
+
+    Ok(())
+}
+
+pub async fn osm_feature_filters(repos: &Repos) -> anyhow::Result<()> {
+    // Somewhere quiet in the Southern Ocean so existing features don't matter
+    let near = create_osm_feature(repos, geo::Point::new(100.0005, -60.001)).await?;
+    let far = create_osm_feature(repos, geo::Point::new(100.5, -60.5)).await?;
+
+    let features = repos
+        .osm_feature_repo
+        .filter_models(OsmFeatureFilter::Ids(vec![near.id, far.id]))
+        .await?;
+    ensure!(id_set(features.iter().map(|feature| feature.id)) == id_set([near.id, far.id]));
+
+    let features = repos
+        .osm_feature_repo
+        .filter_models(OsmFeatureFilter::BoundingBox(geo::Rect::new(
+            geo::coord! { x: 99.9, y: -60.1 },
+            geo::coord! { x: 100.1, y: -59.9 },
+        )))
+        .await?;
+    ensure!(features.iter().map(|feature| feature.id).collect_vec() == vec![near.id]);
+
+    let points = vec![
+        geo::Point::new(100.0, -60.0),
+        geo::Point::new(100.001, -60.0),
+    ];
+
+    // near is ~110m south of the line
+    let features = repos
+        .osm_feature_repo
+        .filter_models(OsmFeatureFilter::RouteCorridor {
+            points: points.clone(),
+            distance_m: 200.0,
+        })
+        .await?;
+    ensure!(features.iter().map(|feature| feature.id).collect_vec() == vec![near.id]);
+
+    let features = repos
+        .osm_feature_repo
+        .filter_models(OsmFeatureFilter::RouteCorridor {
+            points,
+            distance_m: 50.0,
+        })
+        .await?;
+    ensure!(features.is_empty());
+
+    let features = repos
+        .osm_feature_repo
+        .filter_models(OsmFeatureFilter::RouteCorridor {
+            points: vec![],
+            distance_m: 200.0,
+        })
+        .await?;
+    ensure!(features.is_empty());
+
+    Ok(())
+}
+
+pub async fn sync_audit_entry_filters(repos: &Repos) -> anyhow::Result<()> {
+    let user = create_user(repos, Some(unique_number() as i32)).await?;
+    let other_user = create_user(repos, None).await?;
+
+    let ride = create_ride(repos, user.id, datetime(2024, 6, 1, 0)).await?;
+    let archived_at = datetime(2024, 7, 1, 0);
+    repos
+        .ride_repo
+        .put(Ride {
+            archived_at: Some(archived_at),
+            ..ride.clone()
+        })
+        .await?;
+
+    ensure!(repos.ride_repo.get(ride.id).await?.archived_at == Some(archived_at));
+
+    let entry = |user_id: UserId, created_at: DateTime<Utc>| SyncAuditEntry {
+        id: SyncAuditEntryId::new(),
+        created_at,
+        user_id,
+        external_id: ExternalId::Rwgps(RwgpsId::Trip(unique_number())),
+        item_id: ride.id.into(),
+        action: SyncAuditAction::Archived,
+        detached_trip_ids: vec![TripId::new()],
+    };
+
+    let older = entry(user.id, datetime(2024, 7, 1, 0));
+    let newer = entry(user.id, datetime(2024, 7, 2, 0));
+    let other = entry(other_user.id, datetime(2024, 7, 3, 0));
+
+    for entry in [&older, &newer, &other] {
+        repos.sync_audit_entry_repo.put(entry.clone()).await?;
+    }
+
+    // Entries are never rewritten
+    repos
+        .sync_audit_entry_repo
+        .put(SyncAuditEntry {
+            action: SyncAuditAction::Deleted,
+            ..older.clone()
+        })
+        .await?;
+
+    let entries = repos
+        .sync_audit_entry_repo
+        .filter_models(SyncAuditEntryFilter::ForUser(user.id))
+        .await?;
+    ensure!(entries == vec![newer, older]);
+
+    // Users still owning content are not deleted
+    ensure!(repos.user_repo.delete(user.id).await.is_err());
+    ensure!(repos.user_repo.get(user.id).await.is_ok());
+
+    repos.ride_repo.delete(ride.id).await?;
+    repos.user_repo.delete(user.id).await?;
+
+    let entries = repos
+        .sync_audit_entry_repo
+        .filter_models(SyncAuditEntryFilter::ForUser(user.id))
+        .await?;
+    ensure!(entries.is_empty());
+
+    Ok(())
+}
+
+pub async fn sync_run_filters(repos: &Repos) -> anyhow::Result<()> {
+    let user = create_user(repos, Some(unique_number() as i32)).await?;
+    let other_user = create_user(repos, None).await?;
+
+    let cursor = SyncCursor {
+        updated_at: datetime(2024, 6, 1, 0),
+        sync_version: 2,
+    };
+
+    let older = SyncRun::new(
+        user.id,
+        ExternalProvider::Rwgps,
+        None,
+        Some(cursor),
+        2,
+        datetime(2024, 7, 1, 0),
+    );
+    let newer = SyncRun::new(
+        user.id,
+        ExternalProvider::Rwgps,
+        Some(cursor),
+        Some(cursor),
+        0,
+        datetime(2024, 7, 2, 0),
+    );
+    let other = SyncRun::new(
+        other_user.id,
+        ExternalProvider::Strava,
+        None,
+        None,
+        1,
+        datetime(2024, 7, 3, 0),
+    );
+
+    for run in [&older, &newer, &other] {
+        repos.sync_run_repo.put(run.clone()).await?;
+    }
+
+    // Runs are rewritten as their items sync
+    let mut older = older;
+    older.record(true, datetime(2024, 7, 1, 1));
+    older.record(false, datetime(2024, 7, 1, 2));
+    repos.sync_run_repo.put(older.clone()).await?;
+
+    ensure!(repos.sync_run_repo.get(older.id).await? == older);
+
+    let runs = repos
+        .sync_run_repo
+        .filter_models(SyncRunFilter::ForUser {
+            user_id: user.id,
+            limit: None,
+        })
+        .await?;
+    ensure!(runs == vec![newer.clone(), older.clone()]);
+
+    let runs = repos
+        .sync_run_repo
+        .filter_models(SyncRunFilter::ForUser {
+            user_id: user.id,
+            limit: Some(1),
+        })
+        .await?;
+    ensure!(runs == vec![newer]);
+
+    // Results are counted in place and the last one finishes the run
+    let recorded = repos
+        .sync_run_repo
+        .record_result(other.id, true, datetime(2024, 7, 3, 1))
+        .await?;
+
+    let mut expected = other.clone();
+    expected.record(true, datetime(2024, 7, 3, 1));
+    ensure!(recorded == expected);
+    ensure!(recorded.finished_at == Some(datetime(2024, 7, 3, 1)));
+    ensure!(repos.sync_run_repo.get(other.id).await? == expected);
+
+    repos.user_repo.delete(user.id).await?;
+
+    let runs = repos
+        .sync_run_repo
+        .filter_models(SyncRunFilter::ForUser {
+            user_id: user.id,
+            limit: None,
+        })
+        .await?;
+    ensure!(runs.is_empty());
+
+    Ok(())
+}
+
+pub async fn delete_cascades(repos: &Repos) -> anyhow::Result<()> {
+    let user = create_user(repos, Some(unique_number() as i32)).await?;
+
+    let ride = create_ride(repos, user.id, datetime(2024, 6, 1, 0)).await?;
+    let kept_ride = create_ride(repos, user.id, datetime(2024, 6, 2, 0)).await?;
+    let media = create_media(repos, user.id, vec![MediaRelationId::Ride(ride.id)]).await?;
+
+    let trip = create_trip(repos, user.id, false).await?;
+    repos
+        .trip_repo
+        .put(Trip {
+            ride_ids: vec![ride.id, kept_ride.id],
+            media_ids: vec![media.id],
+            ..trip.clone()
+        })
+        .await?;
+
+    repos.ride_repo.delete(ride.id).await?;
+
+    ensure!(repos.ride_repo.get(ride.id).await.is_err());
+    ensure!(repos.trip_repo.get(trip.id).await?.ride_ids == vec![kept_ride.id]);
+    ensure!(repos
+        .media_repo
+        .get(media.id)
+        .await?
+        .iter_ride_ids()
+        .next()
+        .is_none());
+
+    repos.media_repo.delete(media.id).await?;
+
+    ensure!(repos.media_repo.get(media.id).await.is_err());
+    ensure!(repos.trip_repo.get(trip.id).await?.media_ids.is_empty());
+
+    let point_of_interest = create_point_of_interest(repos, user.id).await?;
+    let visit = PointOfInterestVisit {
+        id: PointOfInterestVisitId::new(),
+        created_at: datetime(2024, 6, 1, 0),
+        user_id: user.id,
+        point_of_interest_id: point_of_interest.id,
+        visited_at: datetime(2024, 6, 1, 0),
+        confirmation: VisitConfirmation::Confirmed,
+        condition: None,
+        comment: None,
+        media_ids: vec![],
+    };
+    repos
+        .point_of_interest_visit_repo
+        .put(visit.clone())
+        .await?;
+
+    repos
+        .point_of_interest_repo
+        .delete(point_of_interest.id)
+        .await?;
+
+    ensure!(repos
+        .point_of_interest_visit_repo
+        .get(visit.id)
+        .await
+        .is_err());
+
+    repos.trip_repo.delete(trip.id).await?;
+
+    ensure!(repos.trip_repo.get(trip.id).await.is_err());
+    ensure!(repos.ride_repo.get(kept_ride.id).await.is_ok());
+
+    repos.ride_repo.delete(kept_ride.id).await?;
+    repos.user_repo.delete(user.id).await?;
+
+    ensure!(repos.user_repo.get(user.id).await.is_err());
+
+    Ok(())
+}
@@ -0,0 +1,55 @@
+use std::sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+use howitt::models::user::UserId;
+use howitt::models::ModelId;
+
+mod clients;
+pub mod conformance;
+mod repos;
+mod tables;
+
+pub use clients::*;
+pub use repos::*;
+
+use tables::Tables;
+
+/// Shared backing store for the in-memory repos. Every repo built from the
+/// same store sees the same rows, so join tables written by one repo (e.g.
+/// `trip_rides` via the trip repo) are visible to filters on another.
+#[derive(Debug, Clone, Default)]
+pub struct MemoryStore {
+    tables: Arc<RwLock<Tables>>,
+}
+
+impl MemoryStore {
+    pub fn new() -> MemoryStore {
+        MemoryStore::default()
+    }
+
+    fn read(&self) -> RwLockReadGuard<'_, Tables> {
+        self.tables.read().unwrap()
+    }
+
+    fn write(&self) -> RwLockWriteGuard<'_, Tables> {
+        self.tables.write().unwrap()
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum MemoryRepoError {
+    #[error("{model} {id} not found")]
+    NotFound { model: &'static str, id: String },
+    /// The user still owns rides, routes, trips, media, points of interest,
+    /// visits or water beta, which have to be deleted first.
+    #[error("user {0} still owns content")]
+    UserHasContent(UserId),
+}
+
+impl MemoryRepoError {
+    fn not_found<Id: ModelId>(id: Id) -> MemoryRepoError {
+        MemoryRepoError::NotFound {
+            model: Id::model_name(),
+            id: id.to_string(),
+        }
+    }
+}
@@ -0,0 +1,164 @@
+use std::iter;
+
+use howitt::models::external_ref::{ExternalId, RwgpsId};
+use howitt::models::media::{Media, MediaFilter, MediaId, MediaRelationId};
+use howitt::repos::Repo;
+use itertools::Itertools;
+
+use crate::tables::{sync_join_rev, Tables};
+use crate::{MemoryRepoError, MemoryStore};
+
+/// Fills in `relation_ids` the way the `media_relations` view does: grouped by
+/// relation type, each group ordered by id.
+fn hydrate(tables: &Tables, media: &Media) -> Media {
+    let relation_ids = iter::empty()
+        .chain(
+            tables
+                .ride_media
+                .iter()
+                .filter(|(_, media_id)| *media_id == media.id)
+                .map(|(ride_id, _)| *ride_id)
+                .sorted_by_key(|id| *id.as_uuid())
+                .map(MediaRelationId::from),
+        )
+        .chain(
+            tables
+                .route_media
+                .iter()
+                .filter(|(_, media_id)| *media_id == media.id)
+                .map(|(route_id, _)| *route_id)
+                .sorted_by_key(|id| *id.as_uuid())
+                .map(MediaRelationId::from),
+        )
+        .chain(
+            tables
+                .trip_media
+                .iter()
+                .filter(|(_, media_id)| *media_id == media.id)
+                .map(|(trip_id, _)| *trip_id)
+                .sorted_by_key(|id| *id.as_uuid())
+                .map(MediaRelationId::from),
+        )
+        .chain(
+            tables
+                .poi_media
+                .iter()
+                .filter(|(_, media_id)| *media_id == media.id)
+                .map(|(poi_id, _)| *poi_id)
+                .sorted_by_key(|id| *id.as_uuid())
+                .map(MediaRelationId::from),
+        )
+        .collect();
+
+    Media {
+        relation_ids,
+        ..media.clone()
+    }
+}
+
+#[derive(Debug, Clone, derive_more::Constructor)]
+pub struct MemoryMediaRepo {
+    store: MemoryStore,
+}
+
+#[async_trait::async_trait]
+impl Repo for MemoryMediaRepo {
+    type Model = Media;
+    type Error = MemoryRepoError;
+
+    async fn filter_models(&self, filter: MediaFilter) -> Result<Vec<Media>, MemoryRepoError> {
+        let tables = self.store.read();
+
+        let is_related = |media: &Media| match &filter {
+            MediaFilter::All => true,
+            MediaFilter::Ids(ids) => ids.contains(&media.id),
+            MediaFilter::ForUser(user_id) => media.user_id == *user_id,
+            MediaFilter::ForRide(ride_id) => tables.ride_media.contains(&(*ride_id, media.id)),
+            MediaFilter::ForRoute(route_id) => tables.route_media.contains(&(*route_id, media.id)),
+            MediaFilter::ForTrip(trip_id) => tables.trip_media.contains(&(*trip_id, media.id)),
+            MediaFilter::ForPointOfInterest(poi_id) => {
+                tables.poi_media.contains(&(*poi_id, media.id))
+            }
+            MediaFilter::RwgpsPhotoIds(photo_ids) => {
+                media.external_ref.as_ref().is_some_and(|external_ref| {
+                    photo_ids.iter().any(|photo_id| {
+                        external_ref.id == ExternalId::Rwgps(RwgpsId::Photo(*photo_id))
+                    })
+                })
+            }
+        };
+
+        Ok(tables
+            .media
+            .values()
+            .filter(|media| is_related(media))
+            .sorted_by_key(|media| std::cmp::Reverse(media.created_at))
+            .map(|media| hydrate(&tables, media))
+            .collect())
+    }
+
+    async fn all(&self) -> Result<Vec<Media>, MemoryRepoError> {
+        self.filter_models(MediaFilter::All).await
+    }
+
+    async fn get(&self, id: MediaId) -> Result<Media, MemoryRepoError> {
+        let tables = self.store.read();
+
+        tables
+            .media
+            .get(&id)
+            .map(|media| hydrate(&tables, media))
+            .ok_or_else(|| MemoryRepoError::not_found(id))
+    }
+
+    async fn put(&self, media: Media) -> Result<(), MemoryRepoError> {
+        let mut tables = self.store.write();
+
+        sync_join_rev(&mut tables.ride_media, media.id, media.iter_ride_ids());
+        sync_join_rev(&mut tables.route_media, media.id, media.iter_route_ids());
+        sync_join_rev(&mut tables.trip_media, media.id, media.iter_trip_ids());
+        sync_join_rev(
+            &mut tables.poi_media,
+            media.id,
+            media.iter_point_of_interest_ids(),
+        );
+
+        let media = Media {
+            relation_ids: vec![],
+            ..media
+        };
+
+        // The owner and creation time are fixed on insert
+        tables
+            .media
+            .entry(media.id)
+            .and_modify(|existing| {
+                *existing = Media {
+                    created_at: existing.created_at,
+                    user_id: existing.user_id,
+                    ..media.clone()
+                }
+            })
+            .or_insert(media);
+
+        Ok(())
+    }
+
+    async fn delete(&self, id: MediaId) -> Result<(), MemoryRepoError> {
+        let mut tables = self.store.write();
+
+        tables.ride_media.retain(|(_, media_id)| *media_id != id);
+        tables.route_media.retain(|(_, media_id)| *media_id != id);
+        tables.trip_media.retain(|(_, media_id)| *media_id != id);
+        tables.poi_media.retain(|(_, media_id)| *media_id != id);
+        tables
+            .point_of_interest_visit_media
+            .retain(|(_, media_id)| *media_id != id);
+        tables
+            .water_beta_media
+            .retain(|(_, media_id)| *media_id != id);
+        tables.media.remove(&id);
+
+        Ok(())
+    }
+}
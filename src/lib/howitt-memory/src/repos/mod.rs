@@ -0,0 +1,120 @@
+use std::sync::Arc;
+
+use crate::MemoryStore;
+use howitt::repos::Repos;
+
+mod media_repo;
+mod osm_feature_repo;
+mod poi_repo;
+mod poi_visit_repo;
+mod ride_localities_repo;
+mod ride_points_repo;
+mod ride_repo;
+mod ride_sensors_repo;
+mod ride_weather_repo;
+mod route_course_points_repo;
+mod route_localities_repo;
+mod route_points_repo;
+mod route_repo;
+mod sync_audit_entry_repo;
+mod sync_run_repo;
+mod trip_repo;
+mod user_repo;
+mod water_beta_repo;
+
+pub use media_repo::MemoryMediaRepo;
+pub use osm_feature_repo::MemoryOsmFeatureRepo;
+pub use poi_repo::MemoryPointOfInterestRepo;
+pub use poi_visit_repo::MemoryPointOfInterestVisitRepo;
+pub use ride_localities_repo::MemoryRideLocalitiesRepo;
+pub use ride_points_repo::MemoryRidePointsRepo;
+pub use ride_repo::MemoryRideRepo;
+pub use ride_sensors_repo::MemoryRideSensorsRepo;
+pub use ride_weather_repo::MemoryRideWeatherRepo;
+pub use route_course_points_repo::MemoryRouteCoursePointsRepo;
+pub use route_localities_repo::MemoryRouteLocalitiesRepo;
+pub use route_points_repo::MemoryRoutePointsRepo;
+pub use route_repo::MemoryRouteRepo;
+pub use sync_audit_entry_repo::MemorySyncAuditEntryRepo;
+pub use sync_run_repo::MemorySyncRunRepo;
+pub use trip_repo::MemoryTripRepo;
+pub use user_repo::MemoryUserRepo;
+pub use water_beta_repo::MemoryWaterBetaRepo;
+
+#[derive(Clone)]
+pub struct MemoryRepos {
+    pub media_repo: MemoryMediaRepo,
+    pub osm_feature_repo: MemoryOsmFeatureRepo,
+    pub point_of_interest_repo: MemoryPointOfInterestRepo,
+    pub point_of_interest_visit_repo: MemoryPointOfInterestVisitRepo,
+    pub ride_localities_repo: MemoryRideLocalitiesRepo,
+    pub ride_points_repo: MemoryRidePointsRepo,
+    pub ride_repo: MemoryRideRepo,
+    pub ride_sensors_repo: MemoryRideSensorsRepo,
+    pub ride_weather_repo: MemoryRideWeatherRepo,
+    pub route_repo: MemoryRouteRepo,
+    pub route_course_points_repo: MemoryRouteCoursePointsRepo,
+    pub route_localities_repo: MemoryRouteLocalitiesRepo,
+    pub route_points_repo: MemoryRoutePointsRepo,
+    pub sync_audit_entry_repo: MemorySyncAuditEntryRepo,
+    pub sync_run_repo: MemorySyncRunRepo,
+    pub trip_repo: MemoryTripRepo,
+    pub user_repo: MemoryUserRepo,
+    pub water_beta_repo: MemoryWaterBetaRepo,
+}
+
+impl MemoryRepos {
+    pub fn new(store: MemoryStore) -> MemoryRepos {
+        MemoryRepos {
+            media_repo: MemoryMediaRepo::new(store.clone()),
+            osm_feature_repo: MemoryOsmFeatureRepo::new(store.clone()),
+            point_of_interest_repo: MemoryPointOfInterestRepo::new(store.clone()),
+            point_of_interest_visit_repo: MemoryPointOfInterestVisitRepo::new(store.clone()),
+            ride_localities_repo: MemoryRideLocalitiesRepo::new(store.clone()),
+            ride_points_repo: MemoryRidePointsRepo::new(store.clone()),
+            ride_repo: MemoryRideRepo::new(store.clone()),
+            ride_sensors_repo: MemoryRideSensorsRepo::new(store.clone()),
+            ride_weather_repo: MemoryRideWeatherRepo::new(store.clone()),
+            route_repo: MemoryRouteRepo::new(store.clone()),
+            route_course_points_repo: MemoryRouteCoursePointsRepo::new(store.clone()),
+            route_localities_repo: MemoryRouteLocalitiesRepo::new(store.clone()),
+            route_points_repo: MemoryRoutePointsRepo::new(store.clone()),
+            sync_audit_entry_repo: MemorySyncAuditEntryRepo::new(store.clone()),
+            sync_run_repo: MemorySyncRunRepo::new(store.clone()),
+            trip_repo: MemoryTripRepo::new(store.clone()),
+            user_repo: MemoryUserRepo::new(store.clone()),
+            water_beta_repo: MemoryWaterBetaRepo::new(store.clone()),
+        }
+    }
+}
+
+impl Default for MemoryRepos {
+    fn default() -> Self {
+        MemoryRepos::new(MemoryStore::new())
+    }
+}
+
+impl From<MemoryRepos> for Repos {
+    fn from(memory_repos: MemoryRepos) -> Self {
+        Repos {
+            media_repo: Arc::new(memory_repos.media_repo),
+            osm_feature_repo: Arc::new(memory_repos.osm_feature_repo),
+            point_of_interest_repo: Arc::new(memory_repos.point_of_interest_repo),
+            point_of_interest_visit_repo: Arc::new(memory_repos.point_of_interest_visit_repo),
+            ride_localities_repo: Arc::new(memory_repos.ride_localities_repo),
+            ride_points_repo: Arc::new(memory_repos.ride_points_repo),
+            ride_repo: Arc::new(memory_repos.ride_repo),
+            ride_sensors_repo: Arc::new(memory_repos.ride_sensors_repo),
+            ride_weather_repo: Arc::new(memory_repos.ride_weather_repo),
+            route_repo: Arc::new(memory_repos.route_repo),
+            route_course_points_repo: Arc::new(memory_repos.route_course_points_repo),
+            route_localities_repo: Arc::new(memory_repos.route_localities_repo),
+            route_points_repo: Arc::new(memory_repos.route_points_repo),
+            sync_audit_entry_repo: Arc::new(memory_repos.sync_audit_entry_repo),
+            sync_run_repo: Arc::new(memory_repos.sync_run_repo),
+            trip_repo: Arc::new(memory_repos.trip_repo),
+            user_repo: Arc::new(memory_repos.user_repo),
+            water_beta_repo: Arc::new(memory_repos.water_beta_repo),
+        }
+    }
+}
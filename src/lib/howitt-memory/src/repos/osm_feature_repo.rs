@@ -0,0 +1,116 @@
+use geo::algorithm::line_measures::metric_spaces::Haversine;
+use geo::{BoundingRect, Closest, ClosestPoint, CoordsIter, Distance, Intersects};
+use howitt::models::osm_feature::{OsmFeature, OsmFeatureFilter, OsmFeatureId};
+use howitt::repos::Repo;
+
+use crate::{MemoryRepoError, MemoryStore};
+
+fn route_geometry(points: &[geo::Point<f64>]) -> Option<geo::Geometry<f64>> {
+    match points {
+        [] => None,
+        [point] => Some(geo::Geometry::Point(*point)),
+        points => Some(geo::Geometry::LineString(geo::LineString::from_iter(
+            points.iter().copied(),
+        ))),
+    }
+}
+
+/// Stands in for `ST_DWithin` on geographies. The closest point on the route
+/// is found in degrees and the gap measured with haversine, which is close
+/// enough at corridor scale.
+fn is_within(geometry: &geo::Geometry<f64>, route: &geo::Geometry<f64>, distance_m: f64) -> bool {
+    geometry.coords_iter().any(|coord| {
+        let point = geo::Point::from(coord);
+
+        match route.closest_point(&point) {
+            Closest::Intersection(closest) | Closest::SinglePoint(closest) => {
+                Haversine::distance(closest, point) <= distance_m
+            }
+            Closest::Indeterminate => false,
+        }
+    })
+}
+
+#[derive(Debug, Clone, derive_more::Constructor)]
+pub struct MemoryOsmFeatureRepo {
+    store: MemoryStore,
+}
+
+#[async_trait::async_trait]
+impl Repo for MemoryOsmFeatureRepo {
+    type Model = OsmFeature;
+    type Error = MemoryRepoError;
+
+    async fn filter_models(
+        &self,
+        filter: OsmFeatureFilter,
+    ) -> Result<Vec<OsmFeature>, MemoryRepoError> {
+        let tables = self.store.read();
+        let features = tables.osm_features.values();
+
+        let features: Vec<_> = match filter {
+            OsmFeatureFilter::Ids(ids) => features
+                .filter(|feature| ids.contains(&feature.id))
+                .collect(),
+            OsmFeatureFilter::BoundingBox(rect) => features
+                .filter(|feature| {
+                    feature
+                        .geometry
+                        .bounding_rect()
+                        .is_some_and(|bounds| bounds.intersects(&rect))
+                })
+                .collect(),
+            OsmFeatureFilter::RouteCorridor { points, distance_m } => {
+                let Some(route) = route_geometry(&points) else {
+                    return Ok(vec![]);
+                };
+
+                features
+                    .filter(|feature| is_within(&feature.geometry, &route, distance_m))
+                    .collect()
+            }
+        };
+
+        Ok(features.into_iter().cloned().collect())
+    }
+
+    async fn all(&self) -> Result<Vec<OsmFeature>, MemoryRepoError> {
+        Ok(self.store.read().osm_features.values().cloned().collect())
+    }
+
+    async fn get(&self, id: OsmFeatureId) -> Result<OsmFeature, MemoryRepoError> {
+        self.store
+            .read()
+            .osm_features
+            .get(&id)
+            .cloned()
+            .ok_or_else(|| MemoryRepoError::not_found(id))
+    }
+
+    async fn put(&self, model: OsmFeature) -> Result<(), MemoryRepoError> {
+        self.store.write().osm_features.insert(model.id, model);
+
+        Ok(())
+    }
+
+    async fn delete(&self, id: OsmFeatureId) -> Result<(), MemoryRepoError> {
+        let mut tables = self.store.write();
+
+        let water_beta_ids: Vec<_> = tables
+            .water_beta
+            .values()
+            .filter(|report| report.osm_feature_id == id)
+            .map(|report| report.id)
+            .collect();
+
+        tables
+            .water_beta_media
+            .retain(|(water_beta_id, _)| !water_beta_ids.contains(water_beta_id));
+        tables
+            .water_beta
+            .retain(|_, report| report.osm_feature_id != id);
+        tables.osm_features.remove(&id);
+
+        Ok(())
+    }
+}
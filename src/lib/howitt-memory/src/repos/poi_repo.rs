@@ -0,0 +1,104 @@
+use geo::{Distance, Haversine, Intersects};
+use howitt::models::point_of_interest::{
+    PointOfInterest, PointOfInterestFilter, PointOfInterestId,
+};
+use howitt::repos::Repo;
+use howitt::services::search::{matches_terms, search_terms};
+use itertools::Itertools;
+
+use crate::{MemoryRepoError, MemoryStore};
+
+#[derive(Debug, Clone, derive_more::Constructor)]
+pub struct MemoryPointOfInterestRepo {
+    store: MemoryStore,
+}
+
+#[async_trait::async_trait]
+impl Repo for MemoryPointOfInterestRepo {
+    type Model = PointOfInterest;
+    type Error = MemoryRepoError;
+
+    async fn filter_models(
+        &self,
+        filter: PointOfInterestFilter,
+    ) -> Result<Vec<PointOfInterest>, MemoryRepoError> {
+        match filter {
+            PointOfInterestFilter::All => self.all().await,
+            PointOfInterestFilter::BoundingBox(rect) => Ok(self
+                .all()
+                .await?
+                .into_iter()
+                .filter(|poi| rect.intersects(&poi.point))
+                .collect()),
+            PointOfInterestFilter::Near { point, distance_m } => Ok(self
+                .all()
+                .await?
+                .into_iter()
+                .map(|poi| (Haversine::distance(point, poi.point), poi))
+                .filter(|(distance, _)| *distance <= distance_m)
+                .sorted_by(|(a, _), (b, _)| a.total_cmp(b))
+                .map(|(_, poi)| poi)
+                .collect()),
+            PointOfInterestFilter::Search(query) => {
+                let terms = search_terms(&query);
+
+                Ok(self
+                    .all()
+                    .await?
+                    .into_iter()
+                    .filter(|poi| matches_terms(&poi.name, &terms))
+                    .collect())
+            }
+        }
+    }
+
+    async fn all(&self) -> Result<Vec<PointOfInterest>, MemoryRepoError> {
+        Ok(self
+            .store
+            .read()
+            .points_of_interest
+            .values()
+            .cloned()
+            .collect())
+    }
+
+    async fn get(&self, id: PointOfInterestId) -> Result<PointOfInterest, MemoryRepoError> {
+        self.store
+            .read()
+            .points_of_interest
+            .get(&id)
+            .cloned()
+            .ok_or_else(|| MemoryRepoError::not_found(id))
+    }
+
+    async fn put(&self, model: PointOfInterest) -> Result<(), MemoryRepoError> {
+        self.store
+            .write()
+            .points_of_interest
+            .insert(model.id, model);
+
+        Ok(())
+    }
+
+    async fn delete(&self, id: PointOfInterestId) -> Result<(), MemoryRepoError> {
+        let mut tables = self.store.write();
+
+        let visit_ids: Vec<_> = tables
+            .point_of_interest_visits
+            .values()
+            .filter(|visit| visit.point_of_interest_id == id)
+            .map(|visit| visit.id)
+            .collect();
+
+        tables.poi_media.retain(|(poi_id, _)| *poi_id != id);
+        tables
+            .point_of_interest_visit_media
+            .retain(|(visit_id, _)| !visit_ids.contains(visit_id));
+        tables
+            .point_of_interest_visits
+            .retain(|_, visit| visit.point_of_interest_id != id);
+        tables.points_of_interest.remove(&id);
+
+        Ok(())
+    }
+}
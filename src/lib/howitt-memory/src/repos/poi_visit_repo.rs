@@ -0,0 +1,131 @@
+use howitt::models::point_of_interest_visit::{
+    PointOfInterestVisit, PointOfInterestVisitFilter, PointOfInterestVisitId, VisitConfirmation,
+};
+use howitt::repos::Repo;
+use itertools::Itertools;
+
+use crate::tables::{sync_join, Tables};
+use crate::{MemoryRepoError, MemoryStore};
+
+fn hydrate(tables: &Tables, visit: &PointOfInterestVisit) -> PointOfInterestVisit {
+    PointOfInterestVisit {
+        media_ids: tables
+            .point_of_interest_visit_media
+            .iter()
+            .filter(|(visit_id, _)| *visit_id == visit.id)
+            .map(|(_, media_id)| *media_id)
+            .sorted_by_key(|id| *id.as_uuid())
+            .collect(),
+        ..visit.clone()
+    }
+}
+
+#[derive(Debug, Clone, derive_more::Constructor)]
+pub struct MemoryPointOfInterestVisitRepo {
+    store: MemoryStore,
+}
+
+#[async_trait::async_trait]
+impl Repo for MemoryPointOfInterestVisitRepo {
+    type Model = PointOfInterestVisit;
+    type Error = MemoryRepoError;
+
+    async fn filter_models(
+        &self,
+        filter: PointOfInterestVisitFilter,
+    ) -> Result<Vec<PointOfInterestVisit>, MemoryRepoError> {
+        let tables = self.store.read();
+
+        let is_match = |visit: &PointOfInterestVisit| match &filter {
+            PointOfInterestVisitFilter::All => true,
+            PointOfInterestVisitFilter::Ids(ids) => ids.contains(&visit.id),
+            PointOfInterestVisitFilter::ForPointOfInterest(point_of_interest_id) => {
+                visit.point_of_interest_id == *point_of_interest_id
+            }
+            PointOfInterestVisitFilter::ForUser(user_id) => visit.user_id == *user_id,
+            PointOfInterestVisitFilter::VisitedBetween {
+                point_of_interest_id,
+                user_id,
+                start,
+                end,
+            } => {
+                point_of_interest_id.is_none_or(|id| visit.point_of_interest_id == id)
+                    && user_id.is_none_or(|id| visit.user_id == id)
+                    && visit.visited_at >= *start
+                    && visit.visited_at < *end
+            }
+            PointOfInterestVisitFilter::PendingForOwner(owner_id) => {
+                visit.confirmation == VisitConfirmation::Pending
+                    && tables
+                        .points_of_interest
+                        .get(&visit.point_of_interest_id)
+                        .is_some_and(|poi| poi.user_id == *owner_id)
+            }
+        };
+
+        Ok(tables
+            .point_of_interest_visits
+            .values()
+            .filter(|visit| is_match(visit))
+            .sorted_by_key(|visit| std::cmp::Reverse(visit.visited_at))
+            .map(|visit| hydrate(&tables, visit))
+            .collect())
+    }
+
+    async fn all(&self) -> Result<Vec<PointOfInterestVisit>, MemoryRepoError> {
+        self.filter_models(PointOfInterestVisitFilter::All).await
+    }
+
+    async fn get(
+        &self,
+        id: PointOfInterestVisitId,
+    ) -> Result<PointOfInterestVisit, MemoryRepoError> {
+        let tables = self.store.read();
+
+        tables
+            .point_of_interest_visits
+            .get(&id)
+            .map(|visit| hydrate(&tables, visit))
+            .ok_or_else(|| MemoryRepoError::not_found(id))
+    }
+
+    async fn put(&self, visit: PointOfInterestVisit) -> Result<(), MemoryRepoError> {
+        let mut tables = self.store.write();
+
+        sync_join(
+            &mut tables.point_of_interest_visit_media,
+            visit.id,
+            visit.media_ids.iter().copied(),
+        );
+
+        let visit = PointOfInterestVisit {
+            media_ids: vec![],
+            ..visit
+        };
+
+        // Only the visit details can change after it's recorded
+        tables
+            .point_of_interest_visits
+            .entry(visit.id)
+            .and_modify(|existing| {
+                existing.visited_at = visit.visited_at;
+                existing.confirmation = visit.confirmation.clone();
+                existing.comment = visit.comment.clone();
+                existing.condition = visit.condition.clone();
+            })
+            .or_insert(visit);
+
+        Ok(())
+    }
+
+    async fn delete(&self, id: PointOfInterestVisitId) -> Result<(), MemoryRepoError> {
+        let mut tables = self.store.write();
+
+        tables
+            .point_of_interest_visit_media
+            .retain(|(visit_id, _)| *visit_id != id);
+        tables.point_of_interest_visits.remove(&id);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,65 @@
+use howitt::models::locality::{RideLocalities, RideLocalitiesFilter};
+use howitt::models::ride::RideId;
+use howitt::repos::Repo;
+
+use crate::{MemoryRepoError, MemoryStore};
+
+#[derive(Debug, Clone, derive_more::Constructor)]
+pub struct MemoryRideLocalitiesRepo {
+    store: MemoryStore,
+}
+
+#[async_trait::async_trait]
+impl Repo for MemoryRideLocalitiesRepo {
+    type Model = RideLocalities;
+    type Error = MemoryRepoError;
+
+    async fn filter_models(
+        &self,
+        filter: RideLocalitiesFilter,
+    ) -> Result<Vec<RideLocalities>, MemoryRepoError> {
+        let tables = self.store.read();
+
+        Ok(match filter {
+            RideLocalitiesFilter::Ids(ids) => ids
+                .iter()
+                .filter_map(|id| tables.ride_localities.get(id))
+                .cloned()
+                .collect(),
+        })
+    }
+
+    async fn all(&self) -> Result<Vec<RideLocalities>, MemoryRepoError> {
+        Ok(self
+            .store
+            .read()
+            .ride_localities
+            .values()
+            .cloned()
+            .collect())
+    }
+
+    async fn get(&self, id: RideId) -> Result<RideLocalities, MemoryRepoError> {
+        self.store
+            .read()
+            .ride_localities
+            .get(&id)
+            .cloned()
+            .ok_or_else(|| MemoryRepoError::not_found(id))
+    }
+
+    async fn put(&self, ride_localities: RideLocalities) -> Result<(), MemoryRepoError> {
+        self.store
+            .write()
+            .ride_localities
+            .insert(ride_localities.id, ride_localities);
+
+        Ok(())
+    }
+
+    async fn delete(&self, id: RideId) -> Result<(), MemoryRepoError> {
+        self.store.write().ride_localities.remove(&id);
+
+        Ok(())
+    }
+}
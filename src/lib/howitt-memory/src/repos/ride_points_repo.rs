@@ -0,0 +1,47 @@
+use howitt::models::ride::{RideId, RidePoints};
+use howitt::repos::Repo;
+
+use crate::{MemoryRepoError, MemoryStore};
+
+#[derive(Debug, Clone, derive_more::Constructor)]
+pub struct MemoryRidePointsRepo {
+    store: MemoryStore,
+}
+
+#[async_trait::async_trait]
+impl Repo for MemoryRidePointsRepo {
+    type Model = RidePoints;
+    type Error = MemoryRepoError;
+
+    async fn filter_models(&self, _: ()) -> Result<Vec<RidePoints>, MemoryRepoError> {
+        self.all().await
+    }
+
+    async fn all(&self) -> Result<Vec<RidePoints>, MemoryRepoError> {
+        Ok(self.store.read().ride_points.values().cloned().collect())
+    }
+
+    async fn get(&self, id: RideId) -> Result<RidePoints, MemoryRepoError> {
+        self.store
+            .read()
+            .ride_points
+            .get(&id)
+            .cloned()
+            .ok_or_else(|| MemoryRepoError::not_found(id))
+    }
+
+    async fn put(&self, ride_points: RidePoints) -> Result<(), MemoryRepoError> {
+        self.store
+            .write()
+            .ride_points
+            .insert(ride_points.id, ride_points);
+
+        Ok(())
+    }
+
+    async fn delete(&self, id: RideId) -> Result<(), MemoryRepoError> {
+        self.store.write().ride_points.remove(&id);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,185 @@
+use chrono::Utc;
+use howitt::models::external_ref::{ExternalId, RwgpsId};
+use howitt::models::filters::{Page, PageRequest, TemporalFilter};
+use howitt::models::ride::{Ride, RideFilter, RideId};
+use howitt::repos::Repo;
+use itertools::Itertools;
+
+use crate::{MemoryRepoError, MemoryStore};
+
+#[derive(Debug, Clone, derive_more::Constructor)]
+pub struct MemoryRideRepo {
+    store: MemoryStore,
+}
+
+#[async_trait::async_trait]
+impl Repo for MemoryRideRepo {
+    type Model = Ride;
+    type Error = MemoryRepoError;
+
+    async fn filter_models(&self, filter: RideFilter) -> Result<Vec<Ride>, MemoryRepoError> {
+        let tables = self.store.read();
+
+        let includes_archived = matches!(
+            filter,
+            RideFilter::Ids(_)
+                | RideFilter::RwgpsId(_)
+                | RideFilter::ExternalId(_)
+                | RideFilter::SyncedForUser(_)
+        );
+        let rides = tables
+            .rides
+            .values()
+            .filter(|ride| includes_archived || ride.archived_at.is_none());
+
+        let rides = match filter {
+            RideFilter::Ids(ids) => rides.filter(|ride| ids.contains(&ride.id)).collect_vec(),
+            RideFilter::ForUser {
+                user_id,
+                started_at: Some(TemporalFilter::Before { before, last }),
+            } => rides
+                .filter(|ride| ride.user_id == user_id && ride.started_at < before)
+                .sorted_by_key(|ride| std::cmp::Reverse(ride.started_at))
+                .take(last.unwrap_or(100_000))
+                .collect_vec(),
+            RideFilter::ForUser {
+                user_id,
+                started_at: Some(TemporalFilter::After { after, first }),
+            } => rides
+                .filter(|ride| ride.user_id == user_id && ride.started_at > after)
+                .sorted_by_key(|ride| ride.started_at)
+                .take(first.unwrap_or(100_000))
+                .collect_vec(),
+            RideFilter::ForUser {
+                user_id,
+                started_at: None,
+            } => rides.filter(|ride| ride.user_id == user_id).collect_vec(),
+            RideFilter::ForUserWithDate { user_id, date } => {
+                // Same window as the SQL version, which stops at 23:59:59 Melbourne time
+                let tz = chrono_tz::Australia::Melbourne;
+                let start_of_day = date
+                    .and_hms_opt(0, 0, 0)
+                    .unwrap()
+                    .and_local_timezone(tz)
+                    .unwrap()
+                    .with_timezone(&Utc);
+                let end_of_day = date
+                    .and_hms_opt(23, 59, 59)
+                    .unwrap()
+                    .and_local_timezone(tz)
+                    .unwrap()
+                    .with_timezone(&Utc);
+
+                rides
+                    .filter(|ride| {
+                        ride.user_id == user_id
+                            && ride.started_at >= start_of_day
+                            && ride.started_at < end_of_day
+                    })
+                    .sorted_by_key(|ride| ride.started_at)
+                    .collect_vec()
+            }
+            RideFilter::ForTrip(trip_id) => rides
+                .filter(|ride| tables.trip_rides.contains(&(trip_id, ride.id)))
+                .sorted_by_key(|ride| ride.started_at)
+                .collect_vec(),
+            RideFilter::RwgpsId(rwgps_id) => rides
+                .filter(|ride| {
+                    ride.external_ref.as_ref().is_some_and(|external_ref| {
+                        external_ref.id == ExternalId::Rwgps(RwgpsId::Trip(rwgps_id))
+                    })
+                })
+                .collect_vec(),
+            RideFilter::ExternalId(external_id) => rides
+                .filter(|ride| {
+                    ride.external_ref
+                        .as_ref()
+                        .is_some_and(|external_ref| external_ref.id == external_id)
+                })
+                .collect_vec(),
+            RideFilter::SyncedForUser(user_id) => rides
+                .filter(|ride| ride.user_id == user_id && ride.external_ref.is_some())
+                .collect_vec(),
+            RideFilter::All => rides.collect_vec(),
+        };
+
+        Ok(rides.into_iter().cloned().collect())
+    }
+
+    async fn all(&self) -> Result<Vec<Ride>, MemoryRepoError> {
+        Ok(self.store.read().rides.values().cloned().collect())
+    }
+
+    async fn filter_page(
+        &self,
+        filter: RideFilter,
+        page: PageRequest<RideId>,
+    ) -> Result<Page<Ride>, MemoryRepoError> {
+        // Cursors are looked up among every ride, like Postgres does, so
+        // paging on from an archived ride works but a deleted one doesn't
+        let cursor_key = |cursor: Option<RideId>| {
+            cursor
+                .map(|id| {
+                    self.store
+                        .read()
+                        .rides
+                        .get(&id)
+                        .map(|ride| (ride.started_at, ride.id))
+                        .ok_or_else(|| MemoryRepoError::not_found(id))
+                })
+                .transpose()
+        };
+
+        let after = cursor_key(page.after)?;
+        let before = cursor_key(page.before)?;
+
+        let rides = self.filter_models(filter).await?;
+
+        Ok(page.paginate_by(rides, |ride| (ride.started_at, ride.id), after, before))
+    }
+
+    async fn get(&self, id: RideId) -> Result<Ride, MemoryRepoError> {
+        self.store
+            .read()
+            .rides
+            .get(&id)
+            .cloned()
+            .ok_or_else(|| MemoryRepoError::not_found(id))
+    }
+
+    async fn put(&self, ride: Ride) -> Result<(), MemoryRepoError> {
+        // distance_m is an integer column
+        let ride = Ride {
+            distance: ride.distance as i32 as f64,
+            ..ride
+        };
+
+        self.store
+            .write()
+            .rides
+            .entry(ride.id)
+            .and_modify(|existing| {
+                *existing = Ride {
+                    user_id: existing.user_id,
+                    ..ride.clone()
+                }
+            })
+            .or_insert(ride);
+
+        Ok(())
+    }
+
+    async fn delete(&self, id: RideId) -> Result<(), MemoryRepoError> {
+        let mut tables = self.store.write();
+
+        tables.trip_rides.retain(|(_, ride_id)| *ride_id != id);
+        tables.ride_media.retain(|(ride_id, _)| *ride_id != id);
+        tables.ride_localities.remove(&id);
+        tables.ride_points.remove(&id);
+        tables.ride_sensors.remove(&id);
+        tables.ride_weather.remove(&id);
+        tables.rides.remove(&id);
+
+        Ok(())
+    }
+}
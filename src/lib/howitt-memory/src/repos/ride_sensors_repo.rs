@@ -0,0 +1,47 @@
+use howitt::models::ride::{RideId, RideSensors};
+use howitt::repos::Repo;
+
+use crate::{MemoryRepoError, MemoryStore};
+
+#[derive(Debug, Clone, derive_more::Constructor)]
+pub struct MemoryRideSensorsRepo {
+    store: MemoryStore,
+}
+
+#[async_trait::async_trait]
+impl Repo for MemoryRideSensorsRepo {
+    type Model = RideSensors;
+    type Error = MemoryRepoError;
+
+    async fn filter_models(&self, _: ()) -> Result<Vec<RideSensors>, MemoryRepoError> {
+        self.all().await
+    }
+
+    async fn all(&self) -> Result<Vec<RideSensors>, MemoryRepoError> {
+        Ok(self.store.read().ride_sensors.values().cloned().collect())
+    }
+
+    async fn get(&self, id: RideId) -> Result<RideSensors, MemoryRepoError> {
+        self.store
+            .read()
+            .ride_sensors
+            .get(&id)
+            .cloned()
+            .ok_or_else(|| MemoryRepoError::not_found(id))
+    }
+
+    async fn put(&self, ride_sensors: RideSensors) -> Result<(), MemoryRepoError> {
+        self.store
+            .write()
+            .ride_sensors
+            .insert(ride_sensors.id, ride_sensors);
+
+        Ok(())
+    }
+
+    async fn delete(&self, id: RideId) -> Result<(), MemoryRepoError> {
+        self.store.write().ride_sensors.remove(&id);
+
+        Ok(())
+    }
+}
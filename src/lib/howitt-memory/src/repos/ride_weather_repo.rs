@@ -0,0 +1,59 @@
+use howitt::models::ride::RideId;
+use howitt::models::ride_weather::{RideWeather, RideWeatherFilter};
+use howitt::repos::Repo;
+
+use crate::{MemoryRepoError, MemoryStore};
+
+#[derive(Debug, Clone, derive_more::Constructor)]
+pub struct MemoryRideWeatherRepo {
+    store: MemoryStore,
+}
+
+#[async_trait::async_trait]
+impl Repo for MemoryRideWeatherRepo {
+    type Model = RideWeather;
+    type Error = MemoryRepoError;
+
+    async fn filter_models(
+        &self,
+        filter: RideWeatherFilter,
+    ) -> Result<Vec<RideWeather>, MemoryRepoError> {
+        let tables = self.store.read();
+
+        Ok(match filter {
+            RideWeatherFilter::Ids(ids) => ids
+                .iter()
+                .filter_map(|id| tables.ride_weather.get(id))
+                .cloned()
+                .collect(),
+        })
+    }
+
+    async fn all(&self) -> Result<Vec<RideWeather>, MemoryRepoError> {
+        Ok(self.store.read().ride_weather.values().cloned().collect())
+    }
+
+    async fn get(&self, id: RideId) -> Result<RideWeather, MemoryRepoError> {
+        self.store
+            .read()
+            .ride_weather
+            .get(&id)
+            .cloned()
+            .ok_or_else(|| MemoryRepoError::not_found(id))
+    }
+
+    async fn put(&self, ride_weather: RideWeather) -> Result<(), MemoryRepoError> {
+        self.store
+            .write()
+            .ride_weather
+            .insert(ride_weather.id, ride_weather);
+
+        Ok(())
+    }
+
+    async fn delete(&self, id: RideId) -> Result<(), MemoryRepoError> {
+        self.store.write().ride_weather.remove(&id);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,65 @@
+use howitt::models::course_point::{RouteCoursePoints, RouteCoursePointsFilter};
+use howitt::models::route::RouteId;
+use howitt::repos::Repo;
+
+use crate::{MemoryRepoError, MemoryStore};
+
+#[derive(Debug, Clone, derive_more::Constructor)]
+pub struct MemoryRouteCoursePointsRepo {
+    store: MemoryStore,
+}
+
+#[async_trait::async_trait]
+impl Repo for MemoryRouteCoursePointsRepo {
+    type Model = RouteCoursePoints;
+    type Error = MemoryRepoError;
+
+    async fn filter_models(
+        &self,
+        filter: RouteCoursePointsFilter,
+    ) -> Result<Vec<RouteCoursePoints>, MemoryRepoError> {
+        let tables = self.store.read();
+
+        Ok(match filter {
+            RouteCoursePointsFilter::Ids(ids) => ids
+                .iter()
+                .filter_map(|id| tables.route_course_points.get(id))
+                .cloned()
+                .collect(),
+        })
+    }
+
+    async fn all(&self) -> Result<Vec<RouteCoursePoints>, MemoryRepoError> {
+        Ok(self
+            .store
+            .read()
+            .route_course_points
+            .values()
+            .cloned()
+            .collect())
+    }
+
+    async fn get(&self, id: RouteId) -> Result<RouteCoursePoints, MemoryRepoError> {
+        self.store
+            .read()
+            .route_course_points
+            .get(&id)
+            .cloned()
+            .ok_or_else(|| MemoryRepoError::not_found(id))
+    }
+
+    async fn put(&self, route_course_points: RouteCoursePoints) -> Result<(), MemoryRepoError> {
+        self.store
+            .write()
+            .route_course_points
+            .insert(route_course_points.id, route_course_points);
+
+        Ok(())
+    }
+
+    async fn delete(&self, id: RouteId) -> Result<(), MemoryRepoError> {
+        self.store.write().route_course_points.remove(&id);
+
+        Ok(())
+    }
+}
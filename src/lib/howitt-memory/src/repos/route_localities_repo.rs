@@ -0,0 +1,65 @@
+use howitt::models::locality::{RouteLocalities, RouteLocalitiesFilter};
+use howitt::models::route::RouteId;
+use howitt::repos::Repo;
+
+use crate::{MemoryRepoError, MemoryStore};
+
+#[derive(Debug, Clone, derive_more::Constructor)]
+pub struct MemoryRouteLocalitiesRepo {
+    store: MemoryStore,
+}
+
+#[async_trait::async_trait]
+impl Repo for MemoryRouteLocalitiesRepo {
+    type Model = RouteLocalities;
+    type Error = MemoryRepoError;
+
+    async fn filter_models(
+        &self,
+        filter: RouteLocalitiesFilter,
+    ) -> Result<Vec<RouteLocalities>, MemoryRepoError> {
+        let tables = self.store.read();
+
+        Ok(match filter {
+            RouteLocalitiesFilter::Ids(ids) => ids
+                .iter()
+                .filter_map(|id| tables.route_localities.get(id))
+                .cloned()
+                .collect(),
+        })
+    }
+
+    async fn all(&self) -> Result<Vec<RouteLocalities>, MemoryRepoError> {
+        Ok(self
+            .store
+            .read()
+            .route_localities
+            .values()
+            .cloned()
+            .collect())
+    }
+
+    async fn get(&self, id: RouteId) -> Result<RouteLocalities, MemoryRepoError> {
+        self.store
+            .read()
+            .route_localities
+            .get(&id)
+            .cloned()
+            .ok_or_else(|| MemoryRepoError::not_found(id))
+    }
+
+    async fn put(&self, route_localities: RouteLocalities) -> Result<(), MemoryRepoError> {
+        self.store
+            .write()
+            .route_localities
+            .insert(route_localities.id, route_localities);
+
+        Ok(())
+    }
+
+    async fn delete(&self, id: RouteId) -> Result<(), MemoryRepoError> {
+        self.store.write().route_localities.remove(&id);
+
+        Ok(())
+    }
+}
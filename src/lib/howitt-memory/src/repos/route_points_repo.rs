@@ -0,0 +1,61 @@
+use howitt::models::route::{RouteId, RoutePoints, RoutePointsFilter};
+use howitt::repos::Repo;
+
+use crate::{MemoryRepoError, MemoryStore};
+
+#[derive(Debug, Clone, derive_more::Constructor)]
+pub struct MemoryRoutePointsRepo {
+    store: MemoryStore,
+}
+
+#[async_trait::async_trait]
+impl Repo for MemoryRoutePointsRepo {
+    type Model = RoutePoints;
+    type Error = MemoryRepoError;
+
+    async fn filter_models(
+        &self,
+        filter: RoutePointsFilter,
+    ) -> Result<Vec<RoutePoints>, MemoryRepoError> {
+        let tables = self.store.read();
+
+        let route_points = match filter {
+            RoutePointsFilter::Ids(ids) => tables
+                .route_points
+                .values()
+                .filter(|route_points| ids.contains(&route_points.id))
+                .cloned()
+                .collect(),
+        };
+
+        Ok(route_points)
+    }
+
+    async fn all(&self) -> Result<Vec<RoutePoints>, MemoryRepoError> {
+        Ok(self.store.read().route_points.values().cloned().collect())
+    }
+
+    async fn get(&self, id: RouteId) -> Result<RoutePoints, MemoryRepoError> {
+        self.store
+            .read()
+            .route_points
+            .get(&id)
+            .cloned()
+            .ok_or_else(|| MemoryRepoError::not_found(id))
+    }
+
+    async fn put(&self, route_points: RoutePoints) -> Result<(), MemoryRepoError> {
+        self.store
+            .write()
+            .route_points
+            .insert(route_points.id, route_points);
+
+        Ok(())
+    }
+
+    async fn delete(&self, id: RouteId) -> Result<(), MemoryRepoError> {
+        self.store.write().route_points.remove(&id);
+
+        Ok(())
+    }
+}
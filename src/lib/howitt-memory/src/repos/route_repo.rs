@@ -0,0 +1,120 @@
+use howitt::models::external_ref::{ExternalId, RwgpsId};
+use howitt::models::route::{Route, RouteFilter, RouteId};
+use howitt::models::tag::Tag;
+use howitt::repos::Repo;
+use howitt::services::search::{matches_terms, search_terms};
+
+use crate::{MemoryRepoError, MemoryStore};
+
+/// Applies the same mapping as a round trip through the routes table: the
+/// description is split into columns and always read back, custom tags come
+/// from the description, and a backcountry segment is stored as starred.
+fn stored_route(route: Route) -> Route {
+    let description = route.description.clone().unwrap_or_default();
+    let is_starred = route.tags.contains(&Tag::BackcountrySegment);
+
+    Route {
+        distance: route.distance as i32 as f64,
+        sample_points: Some(route.sample_points.unwrap_or_default()),
+        tags: description
+            .tags
+            .iter()
+            .cloned()
+            .map(Tag::Custom)
+            .chain(is_starred.then_some(Tag::Starred))
+            .collect(),
+        description: Some(description),
+        ..route
+    }
+}
+
+#[derive(Debug, Clone, derive_more::Constructor)]
+pub struct MemoryRouteRepo {
+    store: MemoryStore,
+}
+
+#[async_trait::async_trait]
+impl Repo for MemoryRouteRepo {
+    type Model = Route;
+    type Error = MemoryRepoError;
+
+    async fn filter_models(&self, filter: RouteFilter) -> Result<Vec<Route>, MemoryRepoError> {
+        let tables = self.store.read();
+
+        let is_match = |route: &Route| match &filter {
+            RouteFilter::Starred => route.tags.contains(&Tag::Starred),
+            RouteFilter::All => true,
+            RouteFilter::Slug(slug) => route.slug == *slug,
+            RouteFilter::RwgpsId(rwgps_id) => {
+                route.external_ref.as_ref().is_some_and(|external_ref| {
+                    external_ref.id == ExternalId::Rwgps(RwgpsId::Route(*rwgps_id))
+                })
+            }
+            RouteFilter::ExternalId(external_id) => route
+                .external_ref
+                .as_ref()
+                .is_some_and(|external_ref| external_ref.id == *external_id),
+            RouteFilter::UserId(user_id) => route.user_id == *user_id,
+            RouteFilter::Search(query) => matches_terms(&route.name, &search_terms(query)),
+            RouteFilter::SyncedForUser(user_id) => {
+                route.user_id == *user_id && route.external_ref.is_some()
+            }
+        };
+
+        let includes_archived = matches!(
+            filter,
+            RouteFilter::RwgpsId(_) | RouteFilter::ExternalId(_) | RouteFilter::SyncedForUser(_)
+        );
+
+        Ok(tables
+            .routes
+            .values()
+            .filter(|route| includes_archived || route.archived_at.is_none())
+            .filter(|route| is_match(route))
+            .cloned()
+            .collect())
+    }
+
+    async fn all(&self) -> Result<Vec<Route>, MemoryRepoError> {
+        Ok(self.store.read().routes.values().cloned().collect())
+    }
+
+    async fn get(&self, id: RouteId) -> Result<Route, MemoryRepoError> {
+        self.store
+            .read()
+            .routes
+            .get(&id)
+            .cloned()
+            .ok_or_else(|| MemoryRepoError::not_found(id))
+    }
+
+    async fn put(&self, route: Route) -> Result<(), MemoryRepoError> {
+        let route = stored_route(route);
+
+        self.store
+            .write()
+            .routes
+            .entry(route.id)
+            .and_modify(|existing| {
+                *existing = Route {
+                    user_id: existing.user_id,
+                    ..route.clone()
+                }
+            })
+            .or_insert(route);
+
+        Ok(())
+    }
+
+    async fn delete(&self, id: RouteId) -> Result<(), MemoryRepoError> {
+        let mut tables = self.store.write();
+
+        tables.route_media.retain(|(route_id, _)| *route_id != id);
+        tables.route_course_points.remove(&id);
+        tables.route_localities.remove(&id);
+        tables.route_points.remove(&id);
+        tables.routes.remove(&id);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,69 @@
+use howitt::models::sync_audit_entry::{SyncAuditEntry, SyncAuditEntryFilter, SyncAuditEntryId};
+use howitt::repos::Repo;
+use itertools::Itertools;
+
+use crate::{MemoryRepoError, MemoryStore};
+
+#[derive(Debug, Clone, derive_more::Constructor)]
+pub struct MemorySyncAuditEntryRepo {
+    store: MemoryStore,
+}
+
+#[async_trait::async_trait]
+impl Repo for MemorySyncAuditEntryRepo {
+    type Model = SyncAuditEntry;
+    type Error = MemoryRepoError;
+
+    async fn filter_models(
+        &self,
+        filter: SyncAuditEntryFilter,
+    ) -> Result<Vec<SyncAuditEntry>, MemoryRepoError> {
+        let is_match = |entry: &SyncAuditEntry| match &filter {
+            SyncAuditEntryFilter::ForUser(user_id) => entry.user_id == *user_id,
+        };
+
+        Ok(self
+            .all()
+            .await?
+            .into_iter()
+            .filter(|entry| is_match(entry))
+            .collect())
+    }
+
+    async fn all(&self) -> Result<Vec<SyncAuditEntry>, MemoryRepoError> {
+        Ok(self
+            .store
+            .read()
+            .sync_audit_entries
+            .values()
+            .sorted_by_key(|entry| std::cmp::Reverse(entry.created_at))
+            .cloned()
+            .collect())
+    }
+
+    async fn get(&self, id: SyncAuditEntryId) -> Result<SyncAuditEntry, MemoryRepoError> {
+        self.store
+            .read()
+            .sync_audit_entries
+            .get(&id)
+            .cloned()
+            .ok_or_else(|| MemoryRepoError::not_found(id))
+    }
+
+    async fn put(&self, entry: SyncAuditEntry) -> Result<(), MemoryRepoError> {
+        // Entries are a log, so they're never rewritten
+        self.store
+            .write()
+            .sync_audit_entries
+            .entry(entry.id)
+            .or_insert(entry);
+
+        Ok(())
+    }
+
+    async fn delete(&self, id: SyncAuditEntryId) -> Result<(), MemoryRepoError> {
+        self.store.write().sync_audit_entries.remove(&id);
+
+        Ok(())
+    }
+}
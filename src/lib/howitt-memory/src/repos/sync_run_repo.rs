@@ -0,0 +1,82 @@
+use chrono::{DateTime, Utc};
+use howitt::models::sync_run::{SyncRun, SyncRunFilter, SyncRunId};
+use howitt::repos::{AnyhowSyncRunRepo, Repo};
+use itertools::Itertools;
+
+use crate::{MemoryRepoError, MemoryStore};
+
+#[derive(Debug, Clone, derive_more::Constructor)]
+pub struct MemorySyncRunRepo {
+    store: MemoryStore,
+}
+
+#[async_trait::async_trait]
+impl Repo for MemorySyncRunRepo {
+    type Model = SyncRun;
+    type Error = MemoryRepoError;
+
+    async fn filter_models(&self, filter: SyncRunFilter) -> Result<Vec<SyncRun>, MemoryRepoError> {
+        let SyncRunFilter::ForUser { user_id, limit } = filter;
+
+        Ok(self
+            .all()
+            .await?
+            .into_iter()
+            .filter(|run| run.user_id == user_id)
+            .take(limit.unwrap_or(usize::MAX))
+            .collect())
+    }
+
+    async fn all(&self) -> Result<Vec<SyncRun>, MemoryRepoError> {
+        Ok(self
+            .store
+            .read()
+            .sync_runs
+            .values()
+            .sorted_by_key(|run| std::cmp::Reverse(run.created_at))
+            .cloned()
+            .collect())
+    }
+
+    async fn get(&self, id: SyncRunId) -> Result<SyncRun, MemoryRepoError> {
+        self.store
+            .read()
+            .sync_runs
+            .get(&id)
+            .cloned()
+            .ok_or_else(|| MemoryRepoError::not_found(id))
+    }
+
+    async fn put(&self, run: SyncRun) -> Result<(), MemoryRepoError> {
+        self.store.write().sync_runs.insert(run.id, run);
+
+        Ok(())
+    }
+
+    async fn delete(&self, id: SyncRunId) -> Result<(), MemoryRepoError> {
+        self.store.write().sync_runs.remove(&id);
+
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl AnyhowSyncRunRepo for MemorySyncRunRepo {
+    async fn record_result(
+        &self,
+        id: SyncRunId,
+        succeeded: bool,
+        now: DateTime<Utc>,
+    ) -> Result<SyncRun, anyhow::Error> {
+        let mut tables = self.store.write();
+
+        let run = tables
+            .sync_runs
+            .get_mut(&id)
+            .ok_or_else(|| MemoryRepoError::not_found(id))?;
+
+        run.record(succeeded, now);
+
+        Ok(run.clone())
+    }
+}
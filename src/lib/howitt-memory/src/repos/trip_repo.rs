@@ -0,0 +1,121 @@
+use howitt::models::trip::{Trip, TripFilter, TripId};
+use howitt::repos::Repo;
+use howitt::services::search::{matches_terms, search_terms};
+use itertools::Itertools;
+
+use crate::tables::{sync_join, Tables};
+use crate::{MemoryRepoError, MemoryStore};
+
+/// Fills in `ride_ids` and `media_ids` the way the `trip_relations` view does,
+/// each ordered by id.
+fn hydrate(tables: &Tables, trip: &Trip) -> Trip {
+    Trip {
+        ride_ids: tables
+            .trip_rides
+            .iter()
+            .filter(|(trip_id, _)| *trip_id == trip.id)
+            .map(|(_, ride_id)| *ride_id)
+            .sorted_by_key(|id| *id.as_uuid())
+            .collect(),
+        media_ids: tables
+            .trip_media
+            .iter()
+            .filter(|(trip_id, _)| *trip_id == trip.id)
+            .map(|(_, media_id)| *media_id)
+            .sorted_by_key(|id| *id.as_uuid())
+            .collect(),
+        ..trip.clone()
+    }
+}
+
+#[derive(Debug, Clone, derive_more::Constructor)]
+pub struct MemoryTripRepo {
+    store: MemoryStore,
+}
+
+#[async_trait::async_trait]
+impl Repo for MemoryTripRepo {
+    type Model = Trip;
+    type Error = MemoryRepoError;
+
+    async fn filter_models(&self, filter: TripFilter) -> Result<Vec<Trip>, MemoryRepoError> {
+        let tables = self.store.read();
+
+        let is_match = |trip: &Trip| match &filter {
+            TripFilter::User(user_id) => trip.user_id == *user_id,
+            TripFilter::WithUserAndSlug { user_id, slug } => {
+                trip.user_id == *user_id && trip.slug == *slug
+            }
+            TripFilter::All => true,
+            TripFilter::Published => trip.is_published,
+            TripFilter::Search(query) => matches_terms(&trip.name, &search_terms(query)),
+        };
+
+        Ok(tables
+            .trips
+            .values()
+            .filter(|trip| is_match(trip))
+            .map(|trip| hydrate(&tables, trip))
+            .collect())
+    }
+
+    async fn all(&self) -> Result<Vec<Trip>, MemoryRepoError> {
+        self.filter_models(TripFilter::All).await
+    }
+
+    async fn get(&self, id: TripId) -> Result<Trip, MemoryRepoError> {
+        let tables = self.store.read();
+
+        tables
+            .trips
+            .get(&id)
+            .map(|trip| hydrate(&tables, trip))
+            .ok_or_else(|| MemoryRepoError::not_found(id))
+    }
+
+    async fn put(&self, trip: Trip) -> Result<(), MemoryRepoError> {
+        let mut tables = self.store.write();
+
+        sync_join(
+            &mut tables.trip_rides,
+            trip.id,
+            trip.ride_ids.iter().copied(),
+        );
+        sync_join(
+            &mut tables.trip_media,
+            trip.id,
+            trip.media_ids.iter().copied(),
+        );
+
+        let trip = Trip {
+            ride_ids: vec![],
+            media_ids: vec![],
+            ..trip
+        };
+
+        // The owner and creation time are fixed on insert
+        tables
+            .trips
+            .entry(trip.id)
+            .and_modify(|existing| {
+                *existing = Trip {
+                    created_at: existing.created_at,
+                    user_id: existing.user_id,
+                    ..trip.clone()
+                }
+            })
+            .or_insert(trip);
+
+        Ok(())
+    }
+
+    async fn delete(&self, id: TripId) -> Result<(), MemoryRepoError> {
+        let mut tables = self.store.write();
+
+        tables.trip_rides.retain(|(trip_id, _)| *trip_id != id);
+        tables.trip_media.retain(|(trip_id, _)| *trip_id != id);
+        tables.trips.remove(&id);
+
+        Ok(())
+    }
+}
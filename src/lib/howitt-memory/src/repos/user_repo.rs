@@ -0,0 +1,157 @@
+use chrono::{DateTime, Utc};
+use howitt::models::sync_run::SyncCursor;
+use howitt::models::user::{RwgpsConnectionStatus, User, UserFilter, UserId};
+use howitt::repos::{AnyhowUserRepo, Repo};
+
+use crate::tables::Tables;
+use crate::{MemoryRepoError, MemoryStore};
+
+fn hydrate(tables: &Tables, user: &User) -> User {
+    User {
+        rwgps_connection: tables.user_rwgps_connections.get(&user.id).cloned(),
+        ..user.clone()
+    }
+}
+
+fn has_content(tables: &Tables, id: UserId) -> bool {
+    tables.rides.values().any(|x| x.user_id == id)
+        || tables.routes.values().any(|x| x.user_id == id)
+        || tables.trips.values().any(|x| x.user_id == id)
+        || tables.media.values().any(|x| x.user_id == id)
+        || tables.points_of_interest.values().any(|x| x.user_id == id)
+        || tables
+            .point_of_interest_visits
+            .values()
+            .any(|x| x.user_id == id)
+        || tables.water_beta.values().any(|x| x.user_id == id)
+}
+
+#[derive(Debug, Clone, derive_more::Constructor)]
+pub struct MemoryUserRepo {
+    store: MemoryStore,
+}
+
+#[async_trait::async_trait]
+impl Repo for MemoryUserRepo {
+    type Model = User;
+    type Error = MemoryRepoError;
+
+    async fn filter_models(&self, filter: UserFilter) -> Result<Vec<User>, MemoryRepoError> {
+        let tables = self.store.read();
+
+        let is_match = |user: &User| match &filter {
+            UserFilter::Public => user.rwgps_connection.is_some(),
+            UserFilter::Ids(ids) => ids.contains(&user.id),
+            UserFilter::Username(username) => user.username == *username,
+            UserFilter::RwgpsId(rwgps_user_id) => user
+                .rwgps_connection
+                .as_ref()
+                .is_some_and(|connection| connection.rwgps_user_id == *rwgps_user_id as i32),
+            UserFilter::Email(email) => user.email == *email,
+        };
+
+        Ok(tables
+            .users
+            .values()
+            .map(|user| hydrate(&tables, user))
+            .filter(is_match)
+            .collect())
+    }
+
+    /// Like the SQL version, only returns users with an RWGPS connection.
+    async fn all(&self) -> Result<Vec<User>, MemoryRepoError> {
+        let tables = self.store.read();
+
+        Ok(tables
+            .users
+            .values()
+            .map(|user| hydrate(&tables, user))
+            .filter(|user| user.rwgps_connection.is_some())
+            .collect())
+    }
+
+    async fn get(&self, id: UserId) -> Result<User, MemoryRepoError> {
+        let tables = self.store.read();
+
+        tables
+            .users
+            .get(&id)
+            .map(|user| hydrate(&tables, user))
+            .ok_or_else(|| MemoryRepoError::not_found(id))
+    }
+
+    async fn put(&self, model: User) -> Result<(), MemoryRepoError> {
+        let mut tables = self.store.write();
+
+        // A missing connection leaves any stored one in place
+        if let Some(rwgps) = model.rwgps_connection.clone() {
+            tables
+                .user_rwgps_connections
+                .entry(rwgps.user_id)
+                .and_modify(|existing| {
+                    existing.rwgps_user_id = rwgps.rwgps_user_id;
+                    existing.access_token = rwgps.access_token.clone();
+                    existing.updated_at = rwgps.updated_at;
+                    existing.status = rwgps.status;
+                    existing.sync_cursor = rwgps.sync_cursor;
+                })
+                .or_insert(rwgps);
+        }
+
+        tables.users.insert(
+            model.id,
+            User {
+                rwgps_connection: None,
+                ..model
+            },
+        );
+
+        Ok(())
+    }
+
+    async fn delete(&self, id: UserId) -> Result<(), MemoryRepoError> {
+        let mut tables = self.store.write();
+
+        if has_content(&tables, id) {
+            return Err(MemoryRepoError::UserHasContent(id));
+        }
+
+        tables.user_rwgps_connections.remove(&id);
+        tables
+            .sync_audit_entries
+            .retain(|_, entry| entry.user_id != id);
+        tables.sync_runs.retain(|_, run| run.user_id != id);
+        tables.users.remove(&id);
+
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl AnyhowUserRepo for MemoryUserRepo {
+    async fn set_rwgps_status(
+        &self,
+        user_id: UserId,
+        status: RwgpsConnectionStatus,
+        now: DateTime<Utc>,
+    ) -> Result<(), anyhow::Error> {
+        if let Some(connection) = self.store.write().user_rwgps_connections.get_mut(&user_id) {
+            connection.status = status;
+            connection.updated_at = now;
+        }
+
+        Ok(())
+    }
+
+    async fn set_rwgps_sync_cursor(
+        &self,
+        user_id: UserId,
+        cursor: SyncCursor,
+    ) -> Result<(), anyhow::Error> {
+        if let Some(connection) = self.store.write().user_rwgps_connections.get_mut(&user_id) {
+            connection.sync_cursor = Some(cursor);
+        }
+
+        Ok(())
+    }
+}
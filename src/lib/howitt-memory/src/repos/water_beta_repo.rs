@@ -0,0 +1,114 @@
+use howitt::models::water_beta::{WaterBeta, WaterBetaFilter, WaterBetaId};
+use howitt::repos::Repo;
+use itertools::Itertools;
+
+use crate::tables::{sync_join, Tables};
+use crate::{MemoryRepoError, MemoryStore};
+
+fn hydrate(tables: &Tables, report: &WaterBeta) -> WaterBeta {
+    WaterBeta {
+        media_ids: tables
+            .water_beta_media
+            .iter()
+            .filter(|(water_beta_id, _)| *water_beta_id == report.id)
+            .map(|(_, media_id)| *media_id)
+            .sorted_by_key(|id| *id.as_uuid())
+            .collect(),
+        ..report.clone()
+    }
+}
+
+#[derive(Debug, Clone, derive_more::Constructor)]
+pub struct MemoryWaterBetaRepo {
+    store: MemoryStore,
+}
+
+#[async_trait::async_trait]
+impl Repo for MemoryWaterBetaRepo {
+    type Model = WaterBeta;
+    type Error = MemoryRepoError;
+
+    async fn filter_models(
+        &self,
+        filter: WaterBetaFilter,
+    ) -> Result<Vec<WaterBeta>, MemoryRepoError> {
+        let tables = self.store.read();
+
+        let is_match = |report: &WaterBeta| match &filter {
+            WaterBetaFilter::Ids(ids) => ids.contains(&report.id),
+            WaterBetaFilter::ForOsmFeatures(osm_feature_ids) => {
+                osm_feature_ids.contains(&report.osm_feature_id)
+            }
+            WaterBetaFilter::ForUser(user_id) => report.user_id == *user_id,
+        };
+
+        Ok(tables
+            .water_beta
+            .values()
+            .filter(|report| is_match(report))
+            .sorted_by_key(|report| std::cmp::Reverse(report.observed_at))
+            .map(|report| hydrate(&tables, report))
+            .collect())
+    }
+
+    async fn all(&self) -> Result<Vec<WaterBeta>, MemoryRepoError> {
+        let tables = self.store.read();
+
+        Ok(tables
+            .water_beta
+            .values()
+            .sorted_by_key(|report| std::cmp::Reverse(report.observed_at))
+            .map(|report| hydrate(&tables, report))
+            .collect())
+    }
+
+    async fn get(&self, id: WaterBetaId) -> Result<WaterBeta, MemoryRepoError> {
+        let tables = self.store.read();
+
+        tables
+            .water_beta
+            .get(&id)
+            .map(|report| hydrate(&tables, report))
+            .ok_or_else(|| MemoryRepoError::not_found(id))
+    }
+
+    async fn put(&self, report: WaterBeta) -> Result<(), MemoryRepoError> {
+        let mut tables = self.store.write();
+
+        sync_join(
+            &mut tables.water_beta_media,
+            report.id,
+            report.media_ids.iter().copied(),
+        );
+
+        let report = WaterBeta {
+            media_ids: vec![],
+            ..report
+        };
+
+        // Only the observation can change after it's reported
+        tables
+            .water_beta
+            .entry(report.id)
+            .and_modify(|existing| {
+                existing.observed_at = report.observed_at;
+                existing.flow_status = report.flow_status;
+                existing.treatment_needed = report.treatment_needed;
+                existing.comment = report.comment.clone();
+            })
+            .or_insert(report);
+
+        Ok(())
+    }
+
+    async fn delete(&self, id: WaterBetaId) -> Result<(), MemoryRepoError> {
+        let mut tables = self.store.write();
+
+        tables
+            .water_beta_media
+            .retain(|(water_beta_id, _)| *water_beta_id != id);
+        tables.water_beta.remove(&id);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,81 @@
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+
+use howitt::models::{
+    course_point::RouteCoursePoints,
+    locality::{RideLocalities, RouteLocalities},
+    media::{Media, MediaId},
+    osm_feature::{OsmFeature, OsmFeatureId},
+    point_of_interest::{PointOfInterest, PointOfInterestId},
+    point_of_interest_visit::{PointOfInterestVisit, PointOfInterestVisitId},
+    ride::{Ride, RideId, RidePoints, RideSensors},
+    ride_weather::RideWeather,
+    route::{Route, RouteId, RoutePoints},
+    sync_audit_entry::{SyncAuditEntry, SyncAuditEntryId},
+    sync_run::{SyncRun, SyncRunId},
+    trip::{Trip, TripId},
+    user::{User, UserId, UserRwgpsConnection},
+    water_beta::{WaterBeta, WaterBetaId},
+};
+
+/// Mirrors the Postgres schema. Models are stored with their join table
+/// fields (`Trip::ride_ids`, `Media::relation_ids`, ...) emptied out; those
+/// are rebuilt from the join sets on read, the same way the SQL views do.
+#[derive(Debug, Default)]
+pub(crate) struct Tables {
+    pub media: HashMap<MediaId, Media>,
+    pub osm_features: HashMap<OsmFeatureId, OsmFeature>,
+    pub points_of_interest: HashMap<PointOfInterestId, PointOfInterest>,
+    pub point_of_interest_visits: HashMap<PointOfInterestVisitId, PointOfInterestVisit>,
+    pub ride_localities: HashMap<RideId, RideLocalities>,
+    pub ride_points: HashMap<RideId, RidePoints>,
+    pub ride_sensors: HashMap<RideId, RideSensors>,
+    pub ride_weather: HashMap<RideId, RideWeather>,
+    pub rides: HashMap<RideId, Ride>,
+    pub route_course_points: HashMap<RouteId, RouteCoursePoints>,
+    pub route_localities: HashMap<RouteId, RouteLocalities>,
+    pub route_points: HashMap<RouteId, RoutePoints>,
+    pub routes: HashMap<RouteId, Route>,
+    pub sync_audit_entries: HashMap<SyncAuditEntryId, SyncAuditEntry>,
+    pub sync_runs: HashMap<SyncRunId, SyncRun>,
+    pub trips: HashMap<TripId, Trip>,
+    pub user_rwgps_connections: HashMap<UserId, UserRwgpsConnection>,
+    pub users: HashMap<UserId, User>,
+    pub water_beta: HashMap<WaterBetaId, WaterBeta>,
+
+    pub trip_rides: HashSet<(TripId, RideId)>,
+    pub ride_media: HashSet<(RideId, MediaId)>,
+    pub route_media: HashSet<(RouteId, MediaId)>,
+    pub trip_media: HashSet<(TripId, MediaId)>,
+    pub poi_media: HashSet<(PointOfInterestId, MediaId)>,
+    pub point_of_interest_visit_media: HashSet<(PointOfInterestVisitId, MediaId)>,
+    pub water_beta_media: HashSet<(WaterBetaId, MediaId)>,
+}
+
+/// Replaces every row owned by `owner` with one per item in `items`, matching
+/// the DELETE ... NOT IN / INSERT ... ON CONFLICT DO NOTHING pattern used to
+/// sync join tables in the Postgres repos.
+pub(crate) fn sync_join<A, B>(
+    table: &mut HashSet<(A, B)>,
+    owner: A,
+    items: impl IntoIterator<Item = B>,
+) where
+    A: Copy + Eq + Hash,
+    B: Copy + Eq + Hash,
+{
+    table.retain(|(a, _)| *a != owner);
+    table.extend(items.into_iter().map(|b| (owner, b)));
+}
+
+/// As [`sync_join`], for tables where the owner is on the right hand side.
+pub(crate) fn sync_join_rev<A, B>(
+    table: &mut HashSet<(A, B)>,
+    owner: B,
+    items: impl IntoIterator<Item = A>,
+) where
+    A: Copy + Eq + Hash,
+    B: Copy + Eq + Hash,
+{
+    table.retain(|(_, b)| *b != owner);
+    table.extend(items.into_iter().map(|a| (a, owner)));
+}
@@ -0,0 +1,4 @@
+use howitt::repos::Repos;
+use howitt_memory::MemoryRepos;
+
+howitt_memory::conformance_tests!(Repos::from(MemoryRepos::default()));
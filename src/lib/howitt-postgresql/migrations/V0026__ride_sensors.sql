@@ -0,0 +1,4 @@
+create table ride_sensors (
+    ride_id UUID references rides unique not null,
+    samples JSONB NOT NULL
+);
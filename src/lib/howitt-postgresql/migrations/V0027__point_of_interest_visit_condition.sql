@@ -0,0 +1 @@
+ALTER TABLE point_of_interest_visits ADD COLUMN condition VARCHAR(255);
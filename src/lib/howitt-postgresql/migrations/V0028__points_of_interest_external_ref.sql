@@ -0,0 +1,5 @@
+ALTER TABLE points_of_interest ADD COLUMN external_ref JSONB;
+
+CREATE UNIQUE INDEX points_of_interest_external_ref_unique
+ON points_of_interest ((external_ref->'id'))
+WHERE external_ref->'id' IS NOT NULL;
@@ -0,0 +1,21 @@
+-- Nothing has written to water_beta yet, so replace the untyped attributes
+-- with report columns
+ALTER TABLE water_beta DROP COLUMN attributes;
+ALTER TABLE water_beta ALTER COLUMN osm_feature_id SET NOT NULL;
+ALTER TABLE water_beta ADD COLUMN created_at TIMESTAMPTZ NOT NULL DEFAULT NOW();
+ALTER TABLE water_beta ADD COLUMN user_id UUID NOT NULL REFERENCES users(id);
+ALTER TABLE water_beta ADD COLUMN observed_at TIMESTAMPTZ NOT NULL;
+ALTER TABLE water_beta ADD COLUMN flow_status VARCHAR(255) NOT NULL;
+ALTER TABLE water_beta ADD COLUMN treatment_needed BOOLEAN NOT NULL;
+ALTER TABLE water_beta ADD COLUMN comment TEXT;
+
+CREATE INDEX ON water_beta (osm_feature_id, observed_at DESC);
+CREATE INDEX ON water_beta (user_id, observed_at DESC);
+
+CREATE TABLE water_beta_media (
+    water_beta_id UUID NOT NULL REFERENCES water_beta(id),
+    media_id UUID NOT NULL REFERENCES media(id),
+    PRIMARY KEY (water_beta_id, media_id)
+);
+
+CREATE INDEX ON water_beta_media (media_id);
@@ -0,0 +1,4 @@
+create table ride_weather (
+    ride_id UUID references rides unique not null,
+    hours JSONB NOT NULL
+);
@@ -0,0 +1,11 @@
+create table ride_localities (
+    ride_id UUID references rides unique not null,
+    start_locality JSONB,
+    finish_locality JSONB
+);
+
+create table route_localities (
+    route_id UUID references routes unique not null,
+    start_locality JSONB,
+    end_locality JSONB
+);
@@ -0,0 +1,3 @@
+create index routes_name_search_idx on routes using gin (to_tsvector('simple', name));
+create index trips_name_search_idx on trips using gin (to_tsvector('simple', name));
+create index points_of_interest_name_search_idx on points_of_interest using gin (to_tsvector('simple', coalesce(name, '')));
@@ -0,0 +1,16 @@
+-- Rides and routes removed from RWGPS are archived rather than deleted,
+-- depending on the removal policy
+ALTER TABLE rides ADD COLUMN archived_at TIMESTAMPTZ;
+ALTER TABLE routes ADD COLUMN archived_at TIMESTAMPTZ;
+
+CREATE TABLE sync_audit_entries (
+    id UUID PRIMARY KEY,
+    created_at TIMESTAMPTZ NOT NULL,
+    user_id UUID NOT NULL REFERENCES users(id),
+    external_id JSONB NOT NULL,
+    item JSONB NOT NULL,
+    action VARCHAR(255) NOT NULL,
+    detached_trip_ids UUID[] NOT NULL
+);
+
+CREATE INDEX ON sync_audit_entries (user_id, created_at DESC);
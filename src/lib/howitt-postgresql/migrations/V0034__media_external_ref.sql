@@ -0,0 +1,5 @@
+ALTER TABLE media ADD COLUMN external_ref JSONB;
+
+CREATE UNIQUE INDEX media_external_ref_unique
+ON media ((external_ref->'id'))
+WHERE external_ref->'id' IS NOT NULL;
@@ -0,0 +1,4 @@
+create table route_course_points (
+    route_id UUID references routes unique not null,
+    course_points JSONB not null
+);
@@ -0,0 +1,3 @@
+-- How RWGPS last answered requests made with the connection's token, so sync
+-- jobs can be paused and the user asked to reconnect
+ALTER TABLE user_rwgps_connections ADD COLUMN status VARCHAR(255) NOT NULL DEFAULT 'Ok';
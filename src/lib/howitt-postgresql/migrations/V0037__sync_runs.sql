@@ -0,0 +1,17 @@
+-- Where each connection's next history sync picks up from
+ALTER TABLE user_rwgps_connections ADD COLUMN sync_cursor JSONB;
+
+CREATE TABLE sync_runs (
+    id UUID PRIMARY KEY,
+    created_at TIMESTAMPTZ NOT NULL,
+    user_id UUID NOT NULL REFERENCES users(id),
+    provider VARCHAR(255) NOT NULL,
+    since JSONB,
+    cursor JSONB,
+    queued INTEGER NOT NULL,
+    succeeded INTEGER NOT NULL,
+    failed INTEGER NOT NULL,
+    finished_at TIMESTAMPTZ
+);
+
+CREATE INDEX ON sync_runs (user_id, created_at DESC);
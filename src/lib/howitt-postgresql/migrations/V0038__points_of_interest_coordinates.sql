@@ -0,0 +1,4 @@
+-- POIs store their point as JSONB, so bounding box lookups go through an
+-- expression index on the coordinates
+CREATE INDEX points_of_interest_coordinates_idx
+    ON points_of_interest ((((point->>'x')::float8)), (((point->>'y')::float8)));
@@ -0,0 +1,11 @@
+-- Great circle distance in metres, matching geo's Haversine. Used to filter
+-- POIs by distance, which store their point as JSONB rather than a geometry.
+CREATE FUNCTION haversine_distance_m(x1 FLOAT8, y1 FLOAT8, x2 FLOAT8, y2 FLOAT8)
+RETURNS FLOAT8
+LANGUAGE SQL IMMUTABLE STRICT PARALLEL SAFE
+AS $$
+    SELECT 2 * 6371008.8 * asin(sqrt(
+        power(sin(radians(y2 - y1) / 2), 2)
+        + cos(radians(y1)) * cos(radians(y2)) * power(sin(radians(x2 - x1) / 2), 2)
+    ))
+$$;
@@ -1,3 +1,5 @@
+use howitt::models::user::UserId;
+use howitt::services::search::search_terms;
 use sqlx::pool::PoolConnection;
 use sqlx::postgres::PgPoolOptions;
 use sqlx::{PgPool, Postgres, Transaction};
@@ -27,9 +29,32 @@ impl PostgresClient {
     }
 }
 
+/// Builds a `to_tsquery` expression matching names where every search term
+/// starts a word, the same as [`howitt::services::search::matches_terms`].
+/// `None` if the query has no terms to search for.
+pub(crate) fn prefix_tsquery(query: &str) -> Option<String> {
+    let terms = search_terms(query);
+
+    if terms.is_empty() {
+        return None;
+    }
+
+    Some(
+        terms
+            .into_iter()
+            .map(|term| format!("{term}:*"))
+            .collect::<Vec<_>>()
+            .join(" & "),
+    )
+}
+
 #[derive(Debug, thiserror::Error)]
 #[error("Postgres Repo Error {:?}", _0)]
 pub enum PostgresRepoError {
     Sqlx(#[from] sqlx::Error),
     SerdeJson(#[from] serde_json::Error),
+    Wkt(String),
+    /// The user still owns rides, routes, trips, media, points of interest,
+    /// visits or water beta, which have to be deleted first.
+    UserHasContent(UserId),
 }
@@ -24,6 +24,7 @@ struct MediaRow {
     poi_ids: Option<Vec<Uuid>>,
     point: Option<serde_json::Value>,
     captured_at: Option<DateTime<Utc>>,
+    external_ref: Option<serde_json::Value>,
 }
 
 impl TryFrom<MediaRow> for Media {
@@ -72,6 +73,7 @@ impl TryFrom<MediaRow> for Media {
                 None => None,
             },
             captured_at: row.captured_at,
+            external_ref: row.external_ref.map(serde_json::from_value).transpose()?,
         })
     }
 }
@@ -234,6 +236,28 @@ impl Repo for PostgresMediaRepo {
                 .fetch_all(conn.as_mut())
                 .await?
             }
+            MediaFilter::RwgpsPhotoIds(photo_ids) => {
+                let photo_ids: Vec<_> = photo_ids.into_iter().map(|id| id as i32).collect();
+
+                sqlx::query_as!(
+                    MediaRow,
+                    r#"
+                    SELECT
+                        m.*,
+                        mr.ride_ids,
+                        mr.route_ids,
+                        mr.trip_ids,
+                        mr.poi_ids
+                    FROM media m
+                    INNER JOIN media_relations mr ON mr.id = m.id
+                    WHERE (m.external_ref->'id'->'Rwgps'->'Photo')::int = ANY($1)
+                    ORDER BY m.created_at DESC
+                    "#,
+                    &photo_ids
+                )
+                .fetch_all(conn.as_mut())
+                .await?
+            }
         };
 
         Ok(media
@@ -280,12 +304,14 @@ impl Repo for PostgresMediaRepo {
                 user_id,
                 path,
                 point,
-                captured_at
-            ) VALUES ($1, $2, $3, $4, $5, $6)
+                captured_at,
+                external_ref
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7)
             ON CONFLICT (id) DO UPDATE 
             SET path = EXCLUDED.path,
                 point = EXCLUDED.point,
-                captured_at = EXCLUDED.captured_at
+                captured_at = EXCLUDED.captured_at,
+                external_ref = EXCLUDED.external_ref
             "#,
             media.id.as_uuid(),
             media.created_at,
@@ -293,6 +319,11 @@ impl Repo for PostgresMediaRepo {
             media.path,
             media.point.map(|p| serde_json::to_value(p).unwrap()),
             media.captured_at,
+            media
+                .external_ref
+                .as_ref()
+                .map(serde_json::to_value)
+                .transpose()?,
         );
         query.execute(tx.as_mut()).await?;
 
@@ -418,4 +449,55 @@ impl Repo for PostgresMediaRepo {
 
         Ok(())
     }
+
+    async fn delete(&self, id: MediaId) -> Result<(), PostgresRepoError> {
+        let mut tx = self.client.begin().await?;
+
+        sqlx::query!(
+            r#"DELETE FROM ride_media WHERE media_id = $1"#,
+            id.as_uuid()
+        )
+        .execute(tx.as_mut())
+        .await?;
+
+        sqlx::query!(
+            r#"DELETE FROM route_media WHERE media_id = $1"#,
+            id.as_uuid()
+        )
+        .execute(tx.as_mut())
+        .await?;
+
+        sqlx::query!(
+            r#"DELETE FROM trip_media WHERE media_id = $1"#,
+            id.as_uuid()
+        )
+        .execute(tx.as_mut())
+        .await?;
+
+        sqlx::query!(r#"DELETE FROM poi_media WHERE media_id = $1"#, id.as_uuid())
+            .execute(tx.as_mut())
+            .await?;
+
+        sqlx::query!(
+            r#"DELETE FROM point_of_interest_visit_media WHERE media_id = $1"#,
+            id.as_uuid()
+        )
+        .execute(tx.as_mut())
+        .await?;
+
+        sqlx::query!(
+            r#"DELETE FROM water_beta_media WHERE media_id = $1"#,
+            id.as_uuid()
+        )
+        .execute(tx.as_mut())
+        .await?;
+
+        sqlx::query!(r#"DELETE FROM media WHERE id = $1"#, id.as_uuid())
+            .execute(tx.as_mut())
+            .await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
 }
@@ -4,46 +4,86 @@ use crate::PostgresClient;
 use howitt::repos::Repos;
 
 mod media_repo;
+mod osm_feature_repo;
 mod poi_repo;
+mod poi_visit_repo;
+mod ride_localities_repo;
 mod ride_points_repo;
 mod ride_repo;
+mod ride_sensors_repo;
+mod ride_weather_repo;
+mod route_course_points_repo;
+mod route_localities_repo;
 mod route_points_repo;
 mod route_repo;
+mod sync_audit_entry_repo;
+mod sync_run_repo;
 mod trip_repo;
 mod user_repo;
+mod water_beta_repo;
 
 pub use media_repo::PostgresMediaRepo;
+pub use osm_feature_repo::PostgresOsmFeatureRepo;
 pub use poi_repo::PostgresPointOfInterestRepo;
+pub use poi_visit_repo::PostgresPointOfInterestVisitRepo;
+pub use ride_localities_repo::PostgresRideLocalitiesRepo;
 pub use ride_points_repo::PostgresRidePointsRepo;
 pub use ride_repo::PostgresRideRepo;
+pub use ride_sensors_repo::PostgresRideSensorsRepo;
+pub use ride_weather_repo::PostgresRideWeatherRepo;
+pub use route_course_points_repo::PostgresRouteCoursePointsRepo;
+pub use route_localities_repo::PostgresRouteLocalitiesRepo;
 pub use route_points_repo::PostgresRoutePointsRepo;
 pub use route_repo::PostgresRouteRepo;
+pub use sync_audit_entry_repo::PostgresSyncAuditEntryRepo;
+pub use sync_run_repo::PostgresSyncRunRepo;
 pub use trip_repo::PostgresTripRepo;
 pub use user_repo::PostgresUserRepo;
+pub use water_beta_repo::PostgresWaterBetaRepo;
 
 #[derive(Clone)]
 pub struct PostgresRepos {
     pub media_repo: PostgresMediaRepo,
+    pub osm_feature_repo: PostgresOsmFeatureRepo,
     pub point_of_interest_repo: PostgresPointOfInterestRepo,
+    pub point_of_interest_visit_repo: PostgresPointOfInterestVisitRepo,
+    pub ride_localities_repo: PostgresRideLocalitiesRepo,
     pub ride_points_repo: PostgresRidePointsRepo,
     pub ride_repo: PostgresRideRepo,
+    pub ride_sensors_repo: PostgresRideSensorsRepo,
+    pub ride_weather_repo: PostgresRideWeatherRepo,
     pub route_repo: PostgresRouteRepo,
+    pub route_course_points_repo: PostgresRouteCoursePointsRepo,
+    pub route_localities_repo: PostgresRouteLocalitiesRepo,
     pub route_points_repo: PostgresRoutePointsRepo,
+    pub sync_audit_entry_repo: PostgresSyncAuditEntryRepo,
+    pub sync_run_repo: PostgresSyncRunRepo,
     pub trip_repo: PostgresTripRepo,
     pub user_repo: PostgresUserRepo,
+    pub water_beta_repo: PostgresWaterBetaRepo,
 }
 
 impl PostgresRepos {
     pub fn new(client: PostgresClient) -> PostgresRepos {
         PostgresRepos {
             media_repo: PostgresMediaRepo::new(client.clone()),
+            osm_feature_repo: PostgresOsmFeatureRepo::new(client.clone()),
             point_of_interest_repo: PostgresPointOfInterestRepo::new(client.clone()),
+            point_of_interest_visit_repo: PostgresPointOfInterestVisitRepo::new(client.clone()),
+            ride_localities_repo: PostgresRideLocalitiesRepo::new(client.clone()),
             ride_points_repo: PostgresRidePointsRepo::new(client.clone()),
             ride_repo: PostgresRideRepo::new(client.clone()),
+            ride_sensors_repo: PostgresRideSensorsRepo::new(client.clone()),
+            ride_weather_repo: PostgresRideWeatherRepo::new(client.clone()),
             route_repo: PostgresRouteRepo::new(client.clone()),
+            route_course_points_repo: PostgresRouteCoursePointsRepo::new(client.clone()),
+            route_localities_repo: PostgresRouteLocalitiesRepo::new(client.clone()),
             route_points_repo: PostgresRoutePointsRepo::new(client.clone()),
+            sync_audit_entry_repo: PostgresSyncAuditEntryRepo::new(client.clone()),
+            sync_run_repo: PostgresSyncRunRepo::new(client.clone()),
             trip_repo: PostgresTripRepo::new(client.clone()),
             user_repo: PostgresUserRepo::new(client.clone()),
+            water_beta_repo: PostgresWaterBetaRepo::new(client.clone()),
         }
     }
 }
@@ -52,13 +92,23 @@ impl From<PostgresRepos> for Repos {
     fn from(postgres_context: PostgresRepos) -> Self {
         Repos {
             media_repo: Arc::new(postgres_context.media_repo),
+            osm_feature_repo: Arc::new(postgres_context.osm_feature_repo),
             point_of_interest_repo: Arc::new(postgres_context.point_of_interest_repo),
+            point_of_interest_visit_repo: Arc::new(postgres_context.point_of_interest_visit_repo),
+            ride_localities_repo: Arc::new(postgres_context.ride_localities_repo),
             ride_points_repo: Arc::new(postgres_context.ride_points_repo),
             ride_repo: Arc::new(postgres_context.ride_repo),
+            ride_sensors_repo: Arc::new(postgres_context.ride_sensors_repo),
+            ride_weather_repo: Arc::new(postgres_context.ride_weather_repo),
             route_repo: Arc::new(postgres_context.route_repo),
+            route_course_points_repo: Arc::new(postgres_context.route_course_points_repo),
+            route_localities_repo: Arc::new(postgres_context.route_localities_repo),
             route_points_repo: Arc::new(postgres_context.route_points_repo),
+            sync_audit_entry_repo: Arc::new(postgres_context.sync_audit_entry_repo),
+            sync_run_repo: Arc::new(postgres_context.sync_run_repo),
             trip_repo: Arc::new(postgres_context.trip_repo),
             user_repo: Arc::new(postgres_context.user_repo),
+            water_beta_repo: Arc::new(postgres_context.water_beta_repo),
         }
     }
 }
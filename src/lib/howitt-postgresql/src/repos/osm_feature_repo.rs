@@ -0,0 +1,223 @@
+use std::collections::HashMap;
+
+use howitt::ext::iter::ResultIterExt;
+use howitt::models::osm_feature::{OsmFeature, OsmFeatureFilter, OsmFeatureId};
+use howitt::models::Model;
+use howitt::repos::Repo;
+use wkt::{ToWkt, TryFromWkt};
+
+use crate::{PostgresClient, PostgresRepoError};
+
+/// Metres per degree of latitude, used to size the bounding box prefilter for
+/// corridor queries so they can use the GiST index before the exact distance check.
+const METRES_PER_DEGREE: f64 = 111_320.0;
+
+struct OsmFeatureRow {
+    id: i64,
+    geometry: String,
+    name: Option<String>,
+    attributes: Option<serde_json::Value>,
+}
+
+impl TryFrom<OsmFeatureRow> for OsmFeature {
+    type Error = PostgresRepoError;
+
+    fn try_from(row: OsmFeatureRow) -> Result<Self, Self::Error> {
+        Ok(OsmFeature {
+            id: OsmFeatureId::from_i64(row.id),
+            geometry: geo::Geometry::try_from_wkt_str(&row.geometry)
+                .map_err(|e| PostgresRepoError::Wkt(e.to_string()))?,
+            name: row.name,
+            tags: row
+                .attributes
+                .map(serde_json::from_value::<HashMap<String, String>>)
+                .transpose()?
+                .unwrap_or_default(),
+        })
+    }
+}
+
+fn route_geometry(points: &[geo::Point<f64>]) -> Option<geo::Geometry<f64>> {
+    match points {
+        [] => None,
+        [point] => Some(geo::Geometry::Point(*point)),
+        points => Some(geo::Geometry::LineString(geo::LineString::from_iter(
+            points.iter().copied(),
+        ))),
+    }
+}
+
+#[derive(Debug, Clone, derive_more::Constructor)]
+pub struct PostgresOsmFeatureRepo {
+    client: PostgresClient,
+}
+
+#[async_trait::async_trait]
+impl Repo for PostgresOsmFeatureRepo {
+    type Model = OsmFeature;
+    type Error = PostgresRepoError;
+
+    async fn filter_models(
+        &self,
+        filter: OsmFeatureFilter,
+    ) -> Result<Vec<OsmFeature>, PostgresRepoError> {
+        let mut conn = self.client.acquire().await.unwrap();
+
+        let rows = match filter {
+            OsmFeatureFilter::Ids(ids) => {
+                let ids: Vec<i64> = ids.into_iter().map(|id| id.as_i64()).collect();
+
+                sqlx::query_as!(
+                    OsmFeatureRow,
+                    r#"select id, ST_AsText(geometry) as "geometry!", name, attributes
+                    from osm_features where id = ANY($1)"#,
+                    &ids
+                )
+                .fetch_all(conn.as_mut())
+                .await?
+            }
+            OsmFeatureFilter::BoundingBox(rect) => {
+                sqlx::query_as!(
+                    OsmFeatureRow,
+                    r#"select id, ST_AsText(geometry) as "geometry!", name, attributes
+                    from osm_features
+                    where geometry && ST_MakeEnvelope($1, $2, $3, $4, 4326)"#,
+                    rect.min().x,
+                    rect.min().y,
+                    rect.max().x,
+                    rect.max().y
+                )
+                .fetch_all(conn.as_mut())
+                .await?
+            }
+            OsmFeatureFilter::RouteCorridor { points, distance_m } => {
+                let Some(route) = route_geometry(&points) else {
+                    return Ok(vec![]);
+                };
+
+                // The && prefilter hits the GiST index; longitude degrees shrink
+                // towards the poles so the box is widened by the route's max latitude.
+                sqlx::query_as!(
+                    OsmFeatureRow,
+                    r#"with route as (select ST_GeomFromText($1, 4326) as line)
+                    select id, ST_AsText(geometry) as "geometry!", name, attributes
+                    from osm_features, route
+                    where geometry && ST_Expand(
+                        route.line,
+                        $2 / ($3 * cos(radians(greatest(abs(ST_YMin(route.line)), abs(ST_YMax(route.line)))))),
+                        $2 / $3
+                    )
+                    and ST_DWithin(geometry::geography, route.line::geography, $2)"#,
+                    route.wkt_string(),
+                    distance_m,
+                    METRES_PER_DEGREE
+                )
+                .fetch_all(conn.as_mut())
+                .await?
+            }
+        };
+
+        Ok(rows
+            .into_iter()
+            .map(OsmFeature::try_from)
+            .collect_result_vec()?)
+    }
+
+    async fn all(&self) -> Result<Vec<OsmFeature>, PostgresRepoError> {
+        let mut conn = self.client.acquire().await.unwrap();
+
+        let query = sqlx::query_as!(
+            OsmFeatureRow,
+            r#"select id, ST_AsText(geometry) as "geometry!", name, attributes from osm_features"#
+        );
+
+        Ok(query
+            .fetch_all(conn.as_mut())
+            .await?
+            .into_iter()
+            .map(OsmFeature::try_from)
+            .collect_result_vec()?)
+    }
+
+    async fn get(&self, id: <OsmFeature as Model>::Id) -> Result<OsmFeature, PostgresRepoError> {
+        let mut conn = self.client.acquire().await.unwrap();
+
+        let query = sqlx::query_as!(
+            OsmFeatureRow,
+            r#"select id, ST_AsText(geometry) as "geometry!", name, attributes
+            from osm_features where id = $1"#,
+            id.as_i64()
+        );
+
+        Ok(OsmFeature::try_from(query.fetch_one(conn.as_mut()).await?)?)
+    }
+
+    async fn put(&self, model: OsmFeature) -> Result<(), PostgresRepoError> {
+        self.put_batch(vec![model]).await
+    }
+
+    async fn put_batch(&self, models: Vec<OsmFeature>) -> Result<(), PostgresRepoError> {
+        let mut conn = self.client.acquire().await.unwrap();
+
+        let mut ids = Vec::with_capacity(models.len());
+        let mut geometries = Vec::with_capacity(models.len());
+        let mut names = Vec::with_capacity(models.len());
+        let mut attributes = Vec::with_capacity(models.len());
+
+        for model in models {
+            ids.push(model.id.as_i64());
+            geometries.push(model.geometry.wkt_string());
+            names.push(model.name);
+            attributes.push(serde_json::to_value(model.tags)?);
+        }
+
+        let query = sqlx::query!(
+            r#"insert into osm_features (id, geometry, name, attributes)
+            select id, ST_GeomFromText(geometry, 4326), name, attributes
+            from unnest($1::bigint[], $2::text[], $3::text[], $4::jsonb[])
+                as t(id, geometry, name, attributes)
+            on conflict (id) do update set
+                geometry = excluded.geometry,
+                name = excluded.name,
+                attributes = excluded.attributes
+            "#,
+            &ids,
+            &geometries,
+            &names as &[Option<String>],
+            &attributes
+        );
+
+        query.execute(conn.as_mut()).await?;
+
+        Ok(())
+    }
+
+    async fn delete(&self, id: <OsmFeature as Model>::Id) -> Result<(), PostgresRepoError> {
+        let mut tx = self.client.begin().await?;
+
+        sqlx::query!(
+            r#"
+            DELETE FROM water_beta_media
+            WHERE water_beta_id IN (SELECT id FROM water_beta WHERE osm_feature_id = $1)
+            "#,
+            id.as_i64()
+        )
+        .execute(tx.as_mut())
+        .await?;
+
+        sqlx::query!(
+            r#"DELETE FROM water_beta WHERE osm_feature_id = $1"#,
+            id.as_i64()
+        )
+        .execute(tx.as_mut())
+        .await?;
+
+        sqlx::query!(r#"DELETE FROM osm_features WHERE id = $1"#, id.as_i64())
+            .execute(tx.as_mut())
+            .await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+}
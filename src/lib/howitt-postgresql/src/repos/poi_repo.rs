@@ -1,14 +1,16 @@
 use chrono::{DateTime, Utc};
 use howitt::ext::iter::ResultIterExt;
 use howitt::ext::serde::json::unwrap_string_value;
+use howitt::models::filters::{Page, PageRequest};
 
-use howitt::models::point_of_interest::PointOfInterestId;
+use howitt::models::point_of_interest::{PointOfInterestFilter, PointOfInterestId};
 use howitt::models::user::UserId;
 use howitt::models::{point_of_interest::PointOfInterest, Model};
 use howitt::repos::Repo;
+use howitt::services::nearby::padded_bounds;
 use uuid::Uuid;
 
-use crate::{PostgresClient, PostgresRepoError};
+use crate::{prefix_tsquery, PostgresClient, PostgresRepoError};
 
 #[allow(dead_code)]
 struct PointOfInterestRow {
@@ -20,6 +22,7 @@ struct PointOfInterestRow {
     slug: String,
     user_id: Uuid,
     description: Option<String>,
+    external_ref: Option<serde_json::Value>,
 }
 
 impl TryFrom<PointOfInterestRow> for PointOfInterest {
@@ -34,6 +37,7 @@ impl TryFrom<PointOfInterestRow> for PointOfInterest {
             point: serde_json::from_value(row.point)?,
             point_of_interest_type: serde_json::from_value(serde_json::Value::String(row.r#type))?,
             description: row.description,
+            external_ref: row.external_ref.map(serde_json::from_value).transpose()?,
         })
     }
 }
@@ -48,14 +52,73 @@ impl Repo for PostgresPointOfInterestRepo {
     type Model = PointOfInterest;
     type Error = PostgresRepoError;
 
-    async fn filter_models(&self, _filter: ()) -> Result<Vec<PointOfInterest>, PostgresRepoError> {
+    async fn filter_models(
+        &self,
+        filter: PointOfInterestFilter,
+    ) -> Result<Vec<PointOfInterest>, PostgresRepoError> {
         let mut conn = self.client.acquire().await.unwrap();
 
-        let query = sqlx::query_as!(PointOfInterestRow, r#"select * from points_of_interest"#);
+        let rows = match filter {
+            PointOfInterestFilter::All => {
+                sqlx::query_as!(PointOfInterestRow, r#"select * from points_of_interest"#)
+                    .fetch_all(conn.as_mut())
+                    .await?
+            }
+            PointOfInterestFilter::BoundingBox(rect) => {
+                sqlx::query_as!(
+                    PointOfInterestRow,
+                    r#"select * from points_of_interest
+                    where (point->>'x')::float8 between $1 and $3
+                    and (point->>'y')::float8 between $2 and $4"#,
+                    rect.min().x,
+                    rect.min().y,
+                    rect.max().x,
+                    rect.max().y
+                )
+                .fetch_all(conn.as_mut())
+                .await?
+            }
+            PointOfInterestFilter::Near { point, distance_m } => {
+                let Some(bounds) = padded_bounds(&[point], distance_m) else {
+                    return Ok(vec![]);
+                };
 
-        Ok(query
-            .fetch_all(conn.as_mut())
-            .await?
+                // The bounding box narrows the search down using the coordinates index
+                sqlx::query_as!(
+                    PointOfInterestRow,
+                    r#"select * from points_of_interest
+                    where (point->>'x')::float8 between $3 and $5
+                    and (point->>'y')::float8 between $4 and $6
+                    and haversine_distance_m($1, $2, (point->>'x')::float8, (point->>'y')::float8) <= $7
+                    order by haversine_distance_m($1, $2, (point->>'x')::float8, (point->>'y')::float8)"#,
+                    point.x(),
+                    point.y(),
+                    bounds.min().x,
+                    bounds.min().y,
+                    bounds.max().x,
+                    bounds.max().y,
+                    distance_m
+                )
+                .fetch_all(conn.as_mut())
+                .await?
+            }
+            PointOfInterestFilter::Search(query) => match prefix_tsquery(&query) {
+                Some(tsquery) => {
+                    sqlx::query_as!(
+                        PointOfInterestRow,
+                        r#"select * from points_of_interest
+                        where to_tsvector('simple', coalesce(name, '')) @@ to_tsquery('simple', $1)
+                        order by ts_rank(to_tsvector('simple', coalesce(name, '')), to_tsquery('simple', $1)) desc"#,
+                        tsquery
+                    )
+                    .fetch_all(conn.as_mut())
+                    .await?
+                }
+                None => vec![],
+            },
+        };
+
+        Ok(rows
             .into_iter()
             .map(PointOfInterest::try_from)
             .collect_result_vec()?)
@@ -73,6 +136,64 @@ impl Repo for PostgresPointOfInterestRepo {
             .map(PointOfInterest::try_from)
             .collect_result_vec()?)
     }
+
+    async fn filter_page(
+        &self,
+        filter: PointOfInterestFilter,
+        page: PageRequest<PointOfInterestId>,
+    ) -> Result<Page<PointOfInterest>, PostgresRepoError> {
+        let PointOfInterestFilter::All = filter else {
+            let pois = self.filter_models(filter).await?;
+
+            return Ok(page.paginate(pois, |poi| poi.id));
+        };
+
+        let mut conn = self.client.acquire().await.unwrap();
+
+        let after = page.after.map(Uuid::from);
+        let before = page.before.map(Uuid::from);
+        let limit = page.fetch_limit().map(|limit| limit as i64);
+
+        // Walk from whichever end the page is anchored to, fetching one extra
+        // row so paginate can tell if there's more
+        let pois = if page.is_backward() {
+            sqlx::query_as!(
+                PointOfInterestRow,
+                r#"select * from points_of_interest
+                where ($1::uuid is null or id > $1)
+                and ($2::uuid is null or id < $2)
+                order by id desc
+                limit $3"#,
+                after,
+                before,
+                limit
+            )
+            .fetch_all(conn.as_mut())
+            .await
+        } else {
+            sqlx::query_as!(
+                PointOfInterestRow,
+                r#"select * from points_of_interest
+                where ($1::uuid is null or id > $1)
+                and ($2::uuid is null or id < $2)
+                order by id asc
+                limit $3"#,
+                after,
+                before,
+                limit
+            )
+            .fetch_all(conn.as_mut())
+            .await
+        }?;
+
+        let pois = pois
+            .into_iter()
+            .map(PointOfInterest::try_from)
+            .collect_result_vec()?;
+
+        Ok(page.paginate(pois, |poi| poi.id))
+    }
+
     async fn get(
         &self,
         id: <PointOfInterest as Model>::Id,
@@ -102,15 +223,17 @@ impl Repo for PostgresPointOfInterestRepo {
                 point,
                 user_id,
                 slug,
-                description
-            ) values ($1, $2, $3, $4, $5, $6, $7, $8)
+                description,
+                external_ref
+            ) values ($1, $2, $3, $4, $5, $6, $7, $8, $9)
              on conflict (id) do update set 
                 name = $3, 
                 type = $4, 
                 point = $5,
                 user_id = $6,
                 slug = $7,
-                description = $8
+                description = $8,
+                external_ref = $9
              "#,
             model.id.as_uuid(),
             Utc::now(),
@@ -120,10 +243,49 @@ impl Repo for PostgresPointOfInterestRepo {
             model.user_id.as_uuid(),
             model.slug,
             model.description,
+            model.external_ref.map(serde_json::to_value).transpose()?,
         );
 
         query.execute(conn.as_mut()).await?;
 
         Ok(())
     }
+
+    async fn delete(&self, id: <PointOfInterest as Model>::Id) -> Result<(), PostgresRepoError> {
+        let mut tx = self.client.begin().await?;
+
+        sqlx::query!(r#"DELETE FROM poi_media WHERE poi_id = $1"#, id.as_uuid())
+            .execute(tx.as_mut())
+            .await?;
+
+        sqlx::query!(
+            r#"
+            DELETE FROM point_of_interest_visit_media
+            WHERE visit_id IN (
+                SELECT id FROM point_of_interest_visits WHERE point_of_interest_id = $1
+            )
+            "#,
+            id.as_uuid()
+        )
+        .execute(tx.as_mut())
+        .await?;
+
+        sqlx::query!(
+            r#"DELETE FROM point_of_interest_visits WHERE point_of_interest_id = $1"#,
+            id.as_uuid()
+        )
+        .execute(tx.as_mut())
+        .await?;
+
+        sqlx::query!(
+            r#"DELETE FROM points_of_interest WHERE id = $1"#,
+            id.as_uuid()
+        )
+        .execute(tx.as_mut())
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
 }
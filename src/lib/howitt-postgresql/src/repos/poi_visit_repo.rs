@@ -0,0 +1,364 @@
+use chrono::{DateTime, Utc};
+use howitt::ext::iter::ResultIterExt;
+use howitt::ext::serde::json::unwrap_string_value;
+use howitt::models::media::MediaId;
+use howitt::models::point_of_interest::PointOfInterestId;
+use howitt::models::point_of_interest_visit::{
+    PointOfInterestVisit, PointOfInterestVisitFilter, PointOfInterestVisitId,
+};
+use howitt::models::user::UserId;
+use howitt::repos::Repo;
+use uuid::Uuid;
+
+use crate::{PostgresClient, PostgresRepoError};
+
+struct PointOfInterestVisitRow {
+    id: Uuid,
+    created_at: DateTime<Utc>,
+    user_id: Uuid,
+    point_of_interest_id: Uuid,
+    visited_at: DateTime<Utc>,
+    status: String,
+    comment: Option<String>,
+    condition: Option<String>,
+    media_ids: Vec<Uuid>,
+}
+
+impl TryFrom<PointOfInterestVisitRow> for PointOfInterestVisit {
+    type Error = PostgresRepoError;
+
+    fn try_from(row: PointOfInterestVisitRow) -> Result<Self, Self::Error> {
+        Ok(PointOfInterestVisit {
+            id: PointOfInterestVisitId::from(row.id),
+            created_at: row.created_at,
+            user_id: UserId::from(row.user_id),
+            point_of_interest_id: PointOfInterestId::from(row.point_of_interest_id),
+            visited_at: row.visited_at,
+            confirmation: serde_json::from_value(serde_json::Value::String(row.status))?,
+            condition: match row.condition {
+                Some(condition) => Some(serde_json::from_value(serde_json::Value::String(
+                    condition,
+                ))?),
+                None => None,
+            },
+            comment: row.comment,
+            media_ids: row.media_ids.into_iter().map(MediaId::from).collect(),
+        })
+    }
+}
+
+#[derive(Debug, Clone, derive_more::Constructor)]
+pub struct PostgresPointOfInterestVisitRepo {
+    client: PostgresClient,
+}
+
+#[async_trait::async_trait]
+impl Repo for PostgresPointOfInterestVisitRepo {
+    type Model = PointOfInterestVisit;
+    type Error = PostgresRepoError;
+
+    async fn filter_models(
+        &self,
+        filter: PointOfInterestVisitFilter,
+    ) -> Result<Vec<PointOfInterestVisit>, PostgresRepoError> {
+        let mut conn = self.client.acquire().await.unwrap();
+
+        let visits = match filter {
+            PointOfInterestVisitFilter::All => {
+                sqlx::query_as!(
+                    PointOfInterestVisitRow,
+                    r#"
+                    SELECT
+                        v.id,
+                        v.created_at,
+                        v.user_id,
+                        v.point_of_interest_id,
+                        v.visited_at,
+                        v.status,
+                        v.comment,
+                        v.condition,
+                        ARRAY(
+                            SELECT vm.media_id FROM point_of_interest_visit_media vm WHERE vm.visit_id = v.id
+                        ) as "media_ids!"
+                    FROM point_of_interest_visits v
+                    ORDER BY v.visited_at DESC
+                    "#
+                )
+                .fetch_all(conn.as_mut())
+                .await?
+            }
+            PointOfInterestVisitFilter::Ids(ids) => {
+                let uuids: Vec<_> = ids.into_iter().map(Uuid::from).collect();
+
+                sqlx::query_as!(
+                    PointOfInterestVisitRow,
+                    r#"
+                    SELECT
+                        v.id,
+                        v.created_at,
+                        v.user_id,
+                        v.point_of_interest_id,
+                        v.visited_at,
+                        v.status,
+                        v.comment,
+                        v.condition,
+                        ARRAY(
+                            SELECT vm.media_id FROM point_of_interest_visit_media vm WHERE vm.visit_id = v.id
+                        ) as "media_ids!"
+                    FROM point_of_interest_visits v
+                    WHERE v.id = ANY($1)
+                    ORDER BY v.visited_at DESC
+                    "#,
+                    &uuids
+                )
+                .fetch_all(conn.as_mut())
+                .await?
+            }
+            PointOfInterestVisitFilter::ForPointOfInterest(point_of_interest_id) => {
+                sqlx::query_as!(
+                    PointOfInterestVisitRow,
+                    r#"
+                    SELECT
+                        v.id,
+                        v.created_at,
+                        v.user_id,
+                        v.point_of_interest_id,
+                        v.visited_at,
+                        v.status,
+                        v.comment,
+                        v.condition,
+                        ARRAY(
+                            SELECT vm.media_id FROM point_of_interest_visit_media vm WHERE vm.visit_id = v.id
+                        ) as "media_ids!"
+                    FROM point_of_interest_visits v
+                    WHERE v.point_of_interest_id = $1
+                    ORDER BY v.visited_at DESC
+                    "#,
+                    point_of_interest_id.as_uuid()
+                )
+                .fetch_all(conn.as_mut())
+                .await?
+            }
+            PointOfInterestVisitFilter::ForUser(user_id) => {
+                sqlx::query_as!(
+                    PointOfInterestVisitRow,
+                    r#"
+                    SELECT
+                        v.id,
+                        v.created_at,
+                        v.user_id,
+                        v.point_of_interest_id,
+                        v.visited_at,
+                        v.status,
+                        v.comment,
+                        v.condition,
+                        ARRAY(
+                            SELECT vm.media_id FROM point_of_interest_visit_media vm WHERE vm.visit_id = v.id
+                        ) as "media_ids!"
+                    FROM point_of_interest_visits v
+                    WHERE v.user_id = $1
+                    ORDER BY v.visited_at DESC
+                    "#,
+                    user_id.as_uuid()
+                )
+                .fetch_all(conn.as_mut())
+                .await?
+            }
+            PointOfInterestVisitFilter::VisitedBetween {
+                point_of_interest_id,
+                user_id,
+                start,
+                end,
+            } => {
+                sqlx::query_as!(
+                    PointOfInterestVisitRow,
+                    r#"
+                    SELECT
+                        v.id,
+                        v.created_at,
+                        v.user_id,
+                        v.point_of_interest_id,
+                        v.visited_at,
+                        v.status,
+                        v.comment,
+                        v.condition,
+                        ARRAY(
+                            SELECT vm.media_id FROM point_of_interest_visit_media vm WHERE vm.visit_id = v.id
+                        ) as "media_ids!"
+                    FROM point_of_interest_visits v
+                    WHERE ($1::uuid IS NULL OR v.point_of_interest_id = $1)
+                    AND ($2::uuid IS NULL OR v.user_id = $2)
+                    AND v.visited_at >= $3
+                    AND v.visited_at < $4
+                    ORDER BY v.visited_at DESC
+                    "#,
+                    point_of_interest_id.map(Uuid::from),
+                    user_id.map(Uuid::from),
+                    start,
+                    end
+                )
+                .fetch_all(conn.as_mut())
+                .await?
+            }
+            PointOfInterestVisitFilter::PendingForOwner(owner_id) => {
+                sqlx::query_as!(
+                    PointOfInterestVisitRow,
+                    r#"
+                    SELECT
+                        v.id,
+                        v.created_at,
+                        v.user_id,
+                        v.point_of_interest_id,
+                        v.visited_at,
+                        v.status,
+                        v.comment,
+                        v.condition,
+                        ARRAY(
+                            SELECT vm.media_id FROM point_of_interest_visit_media vm WHERE vm.visit_id = v.id
+                        ) as "media_ids!"
+                    FROM point_of_interest_visits v
+                    INNER JOIN points_of_interest p ON p.id = v.point_of_interest_id
+                    WHERE p.user_id = $1
+                    AND v.status = 'Pending'
+                    ORDER BY v.visited_at DESC
+                    "#,
+                    owner_id.as_uuid()
+                )
+                .fetch_all(conn.as_mut())
+                .await?
+            }
+        };
+
+        Ok(visits
+            .into_iter()
+            .map(PointOfInterestVisit::try_from)
+            .collect_result_vec()?)
+    }
+
+    async fn all(&self) -> Result<Vec<PointOfInterestVisit>, PostgresRepoError> {
+        self.filter_models(PointOfInterestVisitFilter::All).await
+    }
+
+    async fn get(
+        &self,
+        id: PointOfInterestVisitId,
+    ) -> Result<PointOfInterestVisit, PostgresRepoError> {
+        let mut conn = self.client.acquire().await.unwrap();
+
+        let query = sqlx::query_as!(
+            PointOfInterestVisitRow,
+            r#"
+            SELECT
+                v.id,
+                v.created_at,
+                v.user_id,
+                v.point_of_interest_id,
+                v.visited_at,
+                v.status,
+                v.comment,
+                v.condition,
+                ARRAY(
+                    SELECT vm.media_id FROM point_of_interest_visit_media vm WHERE vm.visit_id = v.id
+                ) as "media_ids!"
+            FROM point_of_interest_visits v
+            WHERE v.id = $1
+            "#,
+            id.as_uuid()
+        );
+
+        Ok(PointOfInterestVisit::try_from(
+            query.fetch_one(conn.as_mut()).await?,
+        )?)
+    }
+
+    async fn put(&self, visit: PointOfInterestVisit) -> Result<(), PostgresRepoError> {
+        let mut tx = self.client.begin().await?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO point_of_interest_visits (
+                id,
+                created_at,
+                user_id,
+                point_of_interest_id,
+                visited_at,
+                status,
+                comment,
+                condition
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            ON CONFLICT (id) DO UPDATE
+            SET visited_at = EXCLUDED.visited_at,
+                status = EXCLUDED.status,
+                comment = EXCLUDED.comment,
+                condition = EXCLUDED.condition
+            "#,
+            visit.id.as_uuid(),
+            visit.created_at,
+            visit.user_id.as_uuid(),
+            visit.point_of_interest_id.as_uuid(),
+            visit.visited_at,
+            unwrap_string_value(serde_json::to_value(&visit.confirmation)?),
+            visit.comment,
+            match &visit.condition {
+                Some(condition) => Some(unwrap_string_value(serde_json::to_value(condition)?)),
+                None => None,
+            },
+        )
+        .execute(tx.as_mut())
+        .await?;
+
+        let media_ids: Vec<_> = visit.media_ids.iter().map(|id| *id.as_uuid()).collect();
+
+        sqlx::query!(
+            r#"
+            DELETE FROM point_of_interest_visit_media
+            WHERE visit_id = $1
+            AND media_id NOT IN (SELECT * FROM UNNEST($2::uuid[]))
+            "#,
+            visit.id.as_uuid(),
+            &media_ids,
+        )
+        .execute(tx.as_mut())
+        .await?;
+
+        for media_id in media_ids {
+            sqlx::query!(
+                r#"
+                INSERT INTO point_of_interest_visit_media (visit_id, media_id)
+                VALUES ($1, $2)
+                ON CONFLICT (visit_id, media_id) DO NOTHING
+                "#,
+                visit.id.as_uuid(),
+                media_id,
+            )
+            .execute(tx.as_mut())
+            .await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    async fn delete(&self, id: PointOfInterestVisitId) -> Result<(), PostgresRepoError> {
+        let mut tx = self.client.begin().await?;
+
+        sqlx::query!(
+            r#"DELETE FROM point_of_interest_visit_media WHERE visit_id = $1"#,
+            id.as_uuid()
+        )
+        .execute(tx.as_mut())
+        .await?;
+
+        sqlx::query!(
+            r#"DELETE FROM point_of_interest_visits WHERE id = $1"#,
+            id.as_uuid()
+        )
+        .execute(tx.as_mut())
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+}
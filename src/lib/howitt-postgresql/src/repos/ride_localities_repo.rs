@@ -0,0 +1,137 @@
+use howitt::{
+    ext::iter::ResultIterExt,
+    models::{
+        locality::{RideLocalities, RideLocalitiesFilter},
+        ride::RideId,
+    },
+    repos::Repo,
+};
+use uuid::Uuid;
+
+use crate::{PostgresClient, PostgresRepoError};
+
+struct RideLocalitiesRow {
+    ride_id: Uuid,
+    start_locality: Option<serde_json::Value>,
+    finish_locality: Option<serde_json::Value>,
+}
+
+impl TryFrom<RideLocalitiesRow> for RideLocalities {
+    type Error = PostgresRepoError;
+
+    fn try_from(row: RideLocalitiesRow) -> Result<Self, Self::Error> {
+        Ok(RideLocalities {
+            id: RideId::from(row.ride_id),
+            start: row.start_locality.map(serde_json::from_value).transpose()?,
+            finish: row
+                .finish_locality
+                .map(serde_json::from_value)
+                .transpose()?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, derive_more::Constructor)]
+pub struct PostgresRideLocalitiesRepo {
+    client: PostgresClient,
+}
+
+#[async_trait::async_trait]
+impl Repo for PostgresRideLocalitiesRepo {
+    type Model = RideLocalities;
+    type Error = PostgresRepoError;
+
+    async fn filter_models(
+        &self,
+        filter: RideLocalitiesFilter,
+    ) -> Result<Vec<RideLocalities>, PostgresRepoError> {
+        let mut conn = self.client.acquire().await.unwrap();
+
+        let ride_localities = match filter {
+            RideLocalitiesFilter::Ids(ids) => {
+                let uuids: Vec<_> = ids.into_iter().map(|id| *id.as_uuid()).collect();
+
+                sqlx::query_as!(
+                    RideLocalitiesRow,
+                    r#"select * from ride_localities where ride_id = ANY($1)"#,
+                    &uuids
+                )
+                .fetch_all(conn.as_mut())
+                .await?
+            }
+        };
+
+        Ok(ride_localities
+            .into_iter()
+            .map(RideLocalities::try_from)
+            .collect_result_vec()?)
+    }
+
+    async fn all(&self) -> Result<Vec<RideLocalities>, PostgresRepoError> {
+        let mut conn = self.client.acquire().await.unwrap();
+
+        let query = sqlx::query_as!(RideLocalitiesRow, r#"select * from ride_localities"#);
+
+        Ok(query
+            .fetch_all(conn.as_mut())
+            .await?
+            .into_iter()
+            .map(RideLocalities::try_from)
+            .collect_result_vec()?)
+    }
+
+    async fn get(&self, id: RideId) -> Result<RideLocalities, PostgresRepoError> {
+        let mut conn = self.client.acquire().await.unwrap();
+
+        let query = sqlx::query_as!(
+            RideLocalitiesRow,
+            r#"select * from ride_localities where ride_id = $1"#,
+            id.as_uuid()
+        );
+
+        Ok(RideLocalities::try_from(
+            query.fetch_one(conn.as_mut()).await?,
+        )?)
+    }
+
+    async fn put(&self, ride_localities: RideLocalities) -> Result<(), PostgresRepoError> {
+        let mut conn = self.client.acquire().await.unwrap();
+
+        let query = sqlx::query!(
+            r#"insert into ride_localities (
+                ride_id,
+                start_locality,
+                finish_locality
+            ) values ($1, $2, $3)
+            ON CONFLICT (ride_id) DO UPDATE SET
+                start_locality = EXCLUDED.start_locality,
+                finish_locality = EXCLUDED.finish_locality"#,
+            ride_localities.id.as_uuid(),
+            ride_localities
+                .start
+                .map(serde_json::to_value)
+                .transpose()?,
+            ride_localities
+                .finish
+                .map(serde_json::to_value)
+                .transpose()?
+        );
+
+        query.execute(conn.as_mut()).await?;
+
+        Ok(())
+    }
+
+    async fn delete(&self, id: RideId) -> Result<(), PostgresRepoError> {
+        let mut conn = self.client.acquire().await.unwrap();
+
+        let query = sqlx::query!(
+            r#"DELETE FROM ride_localities WHERE ride_id = $1"#,
+            id.as_uuid()
+        );
+
+        query.execute(conn.as_mut()).await?;
+
+        Ok(())
+    }
+}
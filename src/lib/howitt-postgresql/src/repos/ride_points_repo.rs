@@ -79,4 +79,17 @@ impl Repo for PostgresRidePointsRepo {
 
         Ok(())
     }
+
+    async fn delete(&self, id: RideId) -> Result<(), PostgresRepoError> {
+        let mut conn = self.client.acquire().await.unwrap();
+
+        let query = sqlx::query!(
+            r#"DELETE FROM ride_points WHERE ride_id = $1"#,
+            id.as_uuid()
+        );
+
+        query.execute(conn.as_mut()).await?;
+
+        Ok(())
+    }
 }
@@ -1,6 +1,6 @@
 use chrono::{DateTime, Utc};
 use howitt::ext::iter::ResultIterExt;
-use howitt::models::filters::TemporalFilter;
+use howitt::models::filters::{Page, PageRequest, TemporalFilter};
 use howitt::models::ride::{RideFilter, RideId};
 
 use howitt::models::user::UserId;
@@ -20,6 +20,7 @@ struct RideRow {
     started_at: DateTime<Utc>,
     finished_at: DateTime<Utc>,
     user_id: Uuid,
+    archived_at: Option<DateTime<Utc>>,
 }
 
 impl TryFrom<RideRow> for Ride {
@@ -34,6 +35,7 @@ impl TryFrom<RideRow> for Ride {
             external_ref: row.external_ref.map(serde_json::from_value).transpose()?,
             started_at: row.started_at,
             finished_at: row.finished_at,
+            archived_at: row.archived_at,
         })
     }
 }
@@ -43,6 +45,30 @@ pub struct PostgresRideRepo {
     client: PostgresClient,
 }
 
+impl PostgresRideRepo {
+    /// Where a page cursor falls in start order. A cursor whose ride has
+    /// since been deleted is an error, rather than an empty page.
+    async fn cursor_key(
+        &self,
+        cursor: Option<RideId>,
+    ) -> Result<Option<(DateTime<Utc>, RideId)>, PostgresRepoError> {
+        let Some(id) = cursor else {
+            return Ok(None);
+        };
+
+        let mut conn = self.client.acquire().await.unwrap();
+
+        let started_at = sqlx::query_scalar!(
+            r#"select started_at from rides where id = $1"#,
+            id.as_uuid()
+        )
+        .fetch_one(conn.as_mut())
+        .await?;
+
+        Ok(Some((started_at, id)))
+    }
+}
+
 #[async_trait::async_trait]
 impl Repo for PostgresRideRepo {
     type Model = Ride;
@@ -69,7 +95,7 @@ impl Repo for PostgresRideRepo {
             } => {
                 sqlx::query_as!(
                     RideRow,
-                    r#"select * from rides where user_id = $1 and started_at < $2 order by started_at desc limit $3"#,
+                    r#"select * from rides where user_id = $1 and archived_at is null and started_at < $2 order by started_at desc limit $3"#,
                     user_id.as_uuid(),
                     before,
                     last.unwrap_or(100_000) as i32
@@ -83,7 +109,7 @@ impl Repo for PostgresRideRepo {
             } => {
                 sqlx::query_as!(
                     RideRow,
-                    r#"select * from rides where user_id = $1 and started_at > $2 order by started_at asc limit $3"#,
+                    r#"select * from rides where user_id = $1 and archived_at is null and started_at > $2 order by started_at asc limit $3"#,
                     user_id.as_uuid(),
                     after,
                     first.unwrap_or(100_000) as i32
@@ -97,7 +123,7 @@ impl Repo for PostgresRideRepo {
             } => {
                 sqlx::query_as!(
                     RideRow,
-                    r#"select * from rides where user_id = $1"#,
+                    r#"select * from rides where user_id = $1 and archived_at is null"#,
                     user_id.as_uuid()
                 )
                 .fetch_all(conn.as_mut())
@@ -119,6 +145,7 @@ impl Repo for PostgresRideRepo {
                     RideRow,
                     r#"select * from rides 
                     where user_id = $1 
+                    and archived_at is null
                     and started_at >= $2
                     and started_at < $3
                     order by started_at asc"#,
@@ -137,6 +164,7 @@ impl Repo for PostgresRideRepo {
                     FROM rides r
                     INNER JOIN trip_rides tr ON tr.ride_id = r.id
                     WHERE tr.trip_id = $1
+                    AND r.archived_at IS NULL
                     ORDER BY r.started_at ASC
                     "#,
                     trip_id.as_uuid()
@@ -153,8 +181,26 @@ impl Repo for PostgresRideRepo {
                 .fetch_all(conn.as_mut())
                 .await
             }
+            RideFilter::ExternalId(external_id) => {
+                sqlx::query_as!(
+                    RideRow,
+                    r#"select * from rides where external_ref->'id' = $1"#,
+                    serde_json::to_value(external_id)?
+                )
+                .fetch_all(conn.as_mut())
+                .await
+            }
+            RideFilter::SyncedForUser(user_id) => {
+                sqlx::query_as!(
+                    RideRow,
+                    r#"select * from rides where user_id = $1 and external_ref is not null"#,
+                    user_id.as_uuid()
+                )
+                .fetch_all(conn.as_mut())
+                .await
+            }
             RideFilter::All => {
-                sqlx::query_as!(RideRow, r#"select * from rides"#)
+                sqlx::query_as!(RideRow, r#"select * from rides where archived_at is null"#)
                     .fetch_all(conn.as_mut())
                     .await
             }
@@ -199,14 +245,16 @@ impl Repo for PostgresRideRepo {
                 distance_m,
                 started_at,
                 finished_at,
-                user_id
-            ) values ($1, $2, $3, $4, $5, $6, $7, $8)
+                user_id,
+                archived_at
+            ) values ($1, $2, $3, $4, $5, $6, $7, $8, $9)
             ON CONFLICT (id) DO UPDATE SET
                 name = EXCLUDED.name,
                 external_ref = EXCLUDED.external_ref,
                 distance_m = EXCLUDED.distance_m,
                 started_at = EXCLUDED.started_at,
-                finished_at = EXCLUDED.finished_at"#,
+                finished_at = EXCLUDED.finished_at,
+                archived_at = EXCLUDED.archived_at"#,
             ride.id.as_uuid(),
             ride.name,
             Utc::now(),
@@ -215,10 +263,127 @@ impl Repo for PostgresRideRepo {
             ride.started_at,
             ride.finished_at,
             ride.user_id.as_uuid(),
+            ride.archived_at,
         );
 
         query.execute(conn.as_mut()).await?;
 
         Ok(())
     }
+
+    async fn filter_page(
+        &self,
+        filter: RideFilter,
+        page: PageRequest<RideId>,
+    ) -> Result<Page<Ride>, PostgresRepoError> {
+        let after = self.cursor_key(page.after).await?;
+        let before = self.cursor_key(page.before).await?;
+
+        let RideFilter::ForUser {
+            user_id,
+            started_at: None,
+        } = filter
+        else {
+            let rides = self.filter_models(filter).await?;
+
+            return Ok(page.paginate_by(rides, |ride| (ride.started_at, ride.id), after, before));
+        };
+
+        let mut conn = self.client.acquire().await.unwrap();
+
+        let limit = page.fetch_limit().map(|limit| limit as i64);
+
+        // Walk from whichever end the page is anchored to, fetching one extra
+        // row so paginate can tell if there's more
+        let rides = if page.is_backward() {
+            sqlx::query_as!(
+                RideRow,
+                r#"select * from rides
+                where user_id = $1
+                and archived_at is null
+                and ($2::timestamptz is null or (started_at, id) > ($2, $3::uuid))
+                and ($4::timestamptz is null or (started_at, id) < ($4, $5::uuid))
+                order by started_at desc, id desc
+                limit $6"#,
+                user_id.as_uuid(),
+                after.map(|(started_at, _)| started_at),
+                after.map(|(_, id)| Uuid::from(id)),
+                before.map(|(started_at, _)| started_at),
+                before.map(|(_, id)| Uuid::from(id)),
+                limit
+            )
+            .fetch_all(conn.as_mut())
+            .await
+        } else {
+            sqlx::query_as!(
+                RideRow,
+                r#"select * from rides
+                where user_id = $1
+                and archived_at is null
+                and ($2::timestamptz is null or (started_at, id) > ($2, $3::uuid))
+                and ($4::timestamptz is null or (started_at, id) < ($4, $5::uuid))
+                order by started_at asc, id asc
+                limit $6"#,
+                user_id.as_uuid(),
+                after.map(|(started_at, _)| started_at),
+                after.map(|(_, id)| Uuid::from(id)),
+                before.map(|(started_at, _)| started_at),
+                before.map(|(_, id)| Uuid::from(id)),
+                limit
+            )
+            .fetch_all(conn.as_mut())
+            .await
+        }?;
+
+        let rides = rides.into_iter().map(Ride::try_from).collect_result_vec()?;
+
+        Ok(page.paginate_by(rides, |ride| (ride.started_at, ride.id), after, before))
+    }
+
+    async fn delete(&self, id: <Ride as Model>::Id) -> Result<(), PostgresRepoError> {
+        let mut tx = self.client.begin().await?;
+
+        sqlx::query!(r#"DELETE FROM trip_rides WHERE ride_id = $1"#, id.as_uuid())
+            .execute(tx.as_mut())
+            .await?;
+
+        sqlx::query!(r#"DELETE FROM ride_media WHERE ride_id = $1"#, id.as_uuid())
+            .execute(tx.as_mut())
+            .await?;
+
+        sqlx::query!(
+            r#"DELETE FROM ride_localities WHERE ride_id = $1"#,
+            id.as_uuid()
+        )
+        .execute(tx.as_mut())
+        .await?;
+
+        sqlx::query!(
+            r#"DELETE FROM ride_points WHERE ride_id = $1"#,
+            id.as_uuid()
+        )
+        .execute(tx.as_mut())
+        .await?;
+
+        sqlx::query!(
+            r#"DELETE FROM ride_sensors WHERE ride_id = $1"#,
+            id.as_uuid()
+        )
+        .execute(tx.as_mut())
+        .await?;
+        sqlx::query!(
+            r#"DELETE FROM ride_weather WHERE ride_id = $1"#,
+            id.as_uuid()
+        )
+        .execute(tx.as_mut())
+        .await?;
+
+        sqlx::query!(r#"DELETE FROM rides WHERE id = $1"#, id.as_uuid())
+            .execute(tx.as_mut())
+            .await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
 }
@@ -0,0 +1,98 @@
+use howitt::{
+    ext::iter::ResultIterExt,
+    models::ride::{RideId, RideSensors},
+    repos::Repo,
+};
+use uuid::Uuid;
+
+use crate::{PostgresClient, PostgresRepoError};
+
+struct RideSensorsRow {
+    ride_id: Uuid,
+    samples: serde_json::Value,
+}
+
+impl TryFrom<RideSensorsRow> for RideSensors {
+    type Error = PostgresRepoError;
+
+    fn try_from(row: RideSensorsRow) -> Result<Self, Self::Error> {
+        Ok(RideSensors {
+            id: RideId::from(row.ride_id),
+            samples: serde_json::from_value(row.samples)?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, derive_more::Constructor)]
+pub struct PostgresRideSensorsRepo {
+    client: PostgresClient,
+}
+
+#[async_trait::async_trait]
+impl Repo for PostgresRideSensorsRepo {
+    type Model = RideSensors;
+    type Error = PostgresRepoError;
+
+    async fn filter_models(&self, _: ()) -> Result<Vec<RideSensors>, PostgresRepoError> {
+        self.all().await
+    }
+
+    async fn all(&self) -> Result<Vec<RideSensors>, PostgresRepoError> {
+        let mut conn = self.client.acquire().await.unwrap();
+
+        let query = sqlx::query_as!(RideSensorsRow, r#"select * from ride_sensors"#);
+
+        Ok(query
+            .fetch_all(conn.as_mut())
+            .await?
+            .into_iter()
+            .map(RideSensors::try_from)
+            .collect_result_vec()?)
+    }
+
+    async fn get(&self, id: RideId) -> Result<RideSensors, PostgresRepoError> {
+        let mut conn = self.client.acquire().await.unwrap();
+
+        let query = sqlx::query_as!(
+            RideSensorsRow,
+            r#"select * from ride_sensors where ride_id = $1"#,
+            id.as_uuid()
+        );
+
+        Ok(RideSensors::try_from(
+            query.fetch_one(conn.as_mut()).await?,
+        )?)
+    }
+
+    async fn put(&self, ride_sensors: RideSensors) -> Result<(), PostgresRepoError> {
+        let mut conn = self.client.acquire().await.unwrap();
+
+        let query = sqlx::query!(
+            r#"insert into ride_sensors (
+                ride_id,
+                samples
+            ) values ($1, $2)
+            ON CONFLICT (ride_id) DO UPDATE SET
+                samples = EXCLUDED.samples"#,
+            ride_sensors.id.as_uuid(),
+            serde_json::to_value(ride_sensors.samples)?
+        );
+
+        query.execute(conn.as_mut()).await?;
+
+        Ok(())
+    }
+
+    async fn delete(&self, id: RideId) -> Result<(), PostgresRepoError> {
+        let mut conn = self.client.acquire().await.unwrap();
+
+        let query = sqlx::query!(
+            r#"DELETE FROM ride_sensors WHERE ride_id = $1"#,
+            id.as_uuid()
+        );
+
+        query.execute(conn.as_mut()).await?;
+
+        Ok(())
+    }
+}
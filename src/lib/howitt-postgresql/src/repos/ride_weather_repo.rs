@@ -0,0 +1,123 @@
+use howitt::{
+    ext::iter::ResultIterExt,
+    models::{
+        ride::RideId,
+        ride_weather::{RideWeather, RideWeatherFilter},
+    },
+    repos::Repo,
+};
+use uuid::Uuid;
+
+use crate::{PostgresClient, PostgresRepoError};
+
+struct RideWeatherRow {
+    ride_id: Uuid,
+    hours: serde_json::Value,
+}
+
+impl TryFrom<RideWeatherRow> for RideWeather {
+    type Error = PostgresRepoError;
+
+    fn try_from(row: RideWeatherRow) -> Result<Self, Self::Error> {
+        Ok(RideWeather {
+            id: RideId::from(row.ride_id),
+            hours: serde_json::from_value(row.hours)?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, derive_more::Constructor)]
+pub struct PostgresRideWeatherRepo {
+    client: PostgresClient,
+}
+
+#[async_trait::async_trait]
+impl Repo for PostgresRideWeatherRepo {
+    type Model = RideWeather;
+    type Error = PostgresRepoError;
+
+    async fn filter_models(
+        &self,
+        filter: RideWeatherFilter,
+    ) -> Result<Vec<RideWeather>, PostgresRepoError> {
+        let mut conn = self.client.acquire().await.unwrap();
+
+        let ride_weather = match filter {
+            RideWeatherFilter::Ids(ids) => {
+                let uuids: Vec<_> = ids.into_iter().map(|id| *id.as_uuid()).collect();
+
+                sqlx::query_as!(
+                    RideWeatherRow,
+                    r#"select * from ride_weather where ride_id = ANY($1)"#,
+                    &uuids
+                )
+                .fetch_all(conn.as_mut())
+                .await?
+            }
+        };
+
+        Ok(ride_weather
+            .into_iter()
+            .map(RideWeather::try_from)
+            .collect_result_vec()?)
+    }
+
+    async fn all(&self) -> Result<Vec<RideWeather>, PostgresRepoError> {
+        let mut conn = self.client.acquire().await.unwrap();
+
+        let query = sqlx::query_as!(RideWeatherRow, r#"select * from ride_weather"#);
+
+        Ok(query
+            .fetch_all(conn.as_mut())
+            .await?
+            .into_iter()
+            .map(RideWeather::try_from)
+            .collect_result_vec()?)
+    }
+
+    async fn get(&self, id: RideId) -> Result<RideWeather, PostgresRepoError> {
+        let mut conn = self.client.acquire().await.unwrap();
+
+        let query = sqlx::query_as!(
+            RideWeatherRow,
+            r#"select * from ride_weather where ride_id = $1"#,
+            id.as_uuid()
+        );
+
+        Ok(RideWeather::try_from(
+            query.fetch_one(conn.as_mut()).await?,
+        )?)
+    }
+
+    async fn put(&self, ride_weather: RideWeather) -> Result<(), PostgresRepoError> {
+        let mut conn = self.client.acquire().await.unwrap();
+
+        let query = sqlx::query!(
+            r#"insert into ride_weather (
+                ride_id,
+                hours
+            ) values ($1, $2)
+            ON CONFLICT (ride_id) DO UPDATE SET
+                hours = EXCLUDED.hours"#,
+            ride_weather.id.as_uuid(),
+            serde_json::to_value(ride_weather.hours)?
+        );
+
+        query.execute(conn.as_mut()).await?;
+
+        Ok(())
+    }
+
+    async fn delete(&self, id: RideId) -> Result<(), PostgresRepoError> {
+        let mut conn = self.client.acquire().await.unwrap();
+
+        let query = sqlx::query!(
+            r#"DELETE FROM ride_weather WHERE ride_id = $1"#,
+            id.as_uuid()
+        );
+
+        query.execute(conn.as_mut()).await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,123 @@
+use howitt::{
+    ext::iter::ResultIterExt,
+    models::{
+        course_point::{RouteCoursePoints, RouteCoursePointsFilter},
+        route::RouteId,
+    },
+    repos::Repo,
+};
+use uuid::Uuid;
+
+use crate::{PostgresClient, PostgresRepoError};
+
+struct RouteCoursePointsRow {
+    route_id: Uuid,
+    course_points: serde_json::Value,
+}
+
+impl TryFrom<RouteCoursePointsRow> for RouteCoursePoints {
+    type Error = PostgresRepoError;
+
+    fn try_from(row: RouteCoursePointsRow) -> Result<Self, Self::Error> {
+        Ok(RouteCoursePoints {
+            id: RouteId::from(row.route_id),
+            course_points: serde_json::from_value(row.course_points)?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, derive_more::Constructor)]
+pub struct PostgresRouteCoursePointsRepo {
+    client: PostgresClient,
+}
+
+#[async_trait::async_trait]
+impl Repo for PostgresRouteCoursePointsRepo {
+    type Model = RouteCoursePoints;
+    type Error = PostgresRepoError;
+
+    async fn filter_models(
+        &self,
+        filter: RouteCoursePointsFilter,
+    ) -> Result<Vec<RouteCoursePoints>, PostgresRepoError> {
+        let mut conn = self.client.acquire().await.unwrap();
+
+        let route_course_points = match filter {
+            RouteCoursePointsFilter::Ids(ids) => {
+                let uuids: Vec<_> = ids.into_iter().map(|id| *id.as_uuid()).collect();
+
+                sqlx::query_as!(
+                    RouteCoursePointsRow,
+                    r#"select * from route_course_points where route_id = ANY($1)"#,
+                    &uuids
+                )
+                .fetch_all(conn.as_mut())
+                .await?
+            }
+        };
+
+        Ok(route_course_points
+            .into_iter()
+            .map(RouteCoursePoints::try_from)
+            .collect_result_vec()?)
+    }
+
+    async fn all(&self) -> Result<Vec<RouteCoursePoints>, PostgresRepoError> {
+        let mut conn = self.client.acquire().await.unwrap();
+
+        let query = sqlx::query_as!(RouteCoursePointsRow, r#"select * from route_course_points"#);
+
+        Ok(query
+            .fetch_all(conn.as_mut())
+            .await?
+            .into_iter()
+            .map(RouteCoursePoints::try_from)
+            .collect_result_vec()?)
+    }
+
+    async fn get(&self, id: RouteId) -> Result<RouteCoursePoints, PostgresRepoError> {
+        let mut conn = self.client.acquire().await.unwrap();
+
+        let query = sqlx::query_as!(
+            RouteCoursePointsRow,
+            r#"select * from route_course_points where route_id = $1"#,
+            id.as_uuid()
+        );
+
+        Ok(RouteCoursePoints::try_from(
+            query.fetch_one(conn.as_mut()).await?,
+        )?)
+    }
+
+    async fn put(&self, route_course_points: RouteCoursePoints) -> Result<(), PostgresRepoError> {
+        let mut conn = self.client.acquire().await.unwrap();
+
+        let query = sqlx::query!(
+            r#"insert into route_course_points (
+                route_id,
+                course_points
+            ) values ($1, $2)
+            ON CONFLICT (route_id) DO UPDATE SET
+                course_points = EXCLUDED.course_points"#,
+            route_course_points.id.as_uuid(),
+            serde_json::to_value(route_course_points.course_points)?
+        );
+
+        query.execute(conn.as_mut()).await?;
+
+        Ok(())
+    }
+
+    async fn delete(&self, id: RouteId) -> Result<(), PostgresRepoError> {
+        let mut conn = self.client.acquire().await.unwrap();
+
+        let query = sqlx::query!(
+            r#"DELETE FROM route_course_points WHERE route_id = $1"#,
+            id.as_uuid()
+        );
+
+        query.execute(conn.as_mut()).await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,131 @@
+use howitt::{
+    ext::iter::ResultIterExt,
+    models::{
+        locality::{RouteLocalities, RouteLocalitiesFilter},
+        route::RouteId,
+    },
+    repos::Repo,
+};
+use uuid::Uuid;
+
+use crate::{PostgresClient, PostgresRepoError};
+
+struct RouteLocalitiesRow {
+    route_id: Uuid,
+    start_locality: Option<serde_json::Value>,
+    end_locality: Option<serde_json::Value>,
+}
+
+impl TryFrom<RouteLocalitiesRow> for RouteLocalities {
+    type Error = PostgresRepoError;
+
+    fn try_from(row: RouteLocalitiesRow) -> Result<Self, Self::Error> {
+        Ok(RouteLocalities {
+            id: RouteId::from(row.route_id),
+            start: row.start_locality.map(serde_json::from_value).transpose()?,
+            end: row.end_locality.map(serde_json::from_value).transpose()?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, derive_more::Constructor)]
+pub struct PostgresRouteLocalitiesRepo {
+    client: PostgresClient,
+}
+
+#[async_trait::async_trait]
+impl Repo for PostgresRouteLocalitiesRepo {
+    type Model = RouteLocalities;
+    type Error = PostgresRepoError;
+
+    async fn filter_models(
+        &self,
+        filter: RouteLocalitiesFilter,
+    ) -> Result<Vec<RouteLocalities>, PostgresRepoError> {
+        let mut conn = self.client.acquire().await.unwrap();
+
+        let route_localities = match filter {
+            RouteLocalitiesFilter::Ids(ids) => {
+                let uuids: Vec<_> = ids.into_iter().map(|id| *id.as_uuid()).collect();
+
+                sqlx::query_as!(
+                    RouteLocalitiesRow,
+                    r#"select * from route_localities where route_id = ANY($1)"#,
+                    &uuids
+                )
+                .fetch_all(conn.as_mut())
+                .await?
+            }
+        };
+
+        Ok(route_localities
+            .into_iter()
+            .map(RouteLocalities::try_from)
+            .collect_result_vec()?)
+    }
+
+    async fn all(&self) -> Result<Vec<RouteLocalities>, PostgresRepoError> {
+        let mut conn = self.client.acquire().await.unwrap();
+
+        let query = sqlx::query_as!(RouteLocalitiesRow, r#"select * from route_localities"#);
+
+        Ok(query
+            .fetch_all(conn.as_mut())
+            .await?
+            .into_iter()
+            .map(RouteLocalities::try_from)
+            .collect_result_vec()?)
+    }
+
+    async fn get(&self, id: RouteId) -> Result<RouteLocalities, PostgresRepoError> {
+        let mut conn = self.client.acquire().await.unwrap();
+
+        let query = sqlx::query_as!(
+            RouteLocalitiesRow,
+            r#"select * from route_localities where route_id = $1"#,
+            id.as_uuid()
+        );
+
+        Ok(RouteLocalities::try_from(
+            query.fetch_one(conn.as_mut()).await?,
+        )?)
+    }
+
+    async fn put(&self, route_localities: RouteLocalities) -> Result<(), PostgresRepoError> {
+        let mut conn = self.client.acquire().await.unwrap();
+
+        let query = sqlx::query!(
+            r#"insert into route_localities (
+                route_id,
+                start_locality,
+                end_locality
+            ) values ($1, $2, $3)
+            ON CONFLICT (route_id) DO UPDATE SET
+                start_locality = EXCLUDED.start_locality,
+                end_locality = EXCLUDED.end_locality"#,
+            route_localities.id.as_uuid(),
+            route_localities
+                .start
+                .map(serde_json::to_value)
+                .transpose()?,
+            route_localities.end.map(serde_json::to_value).transpose()?
+        );
+
+        query.execute(conn.as_mut()).await?;
+
+        Ok(())
+    }
+
+    async fn delete(&self, id: RouteId) -> Result<(), PostgresRepoError> {
+        let mut conn = self.client.acquire().await.unwrap();
+
+        let query = sqlx::query!(
+            r#"DELETE FROM route_localities WHERE route_id = $1"#,
+            id.as_uuid()
+        );
+
+        query.execute(conn.as_mut()).await?;
+
+        Ok(())
+    }
+}
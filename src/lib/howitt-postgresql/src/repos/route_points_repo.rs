@@ -105,4 +105,17 @@ impl Repo for PostgresRoutePointsRepo {
 
         Ok(())
     }
+
+    async fn delete(&self, id: RouteId) -> Result<(), PostgresRepoError> {
+        let mut conn = self.client.acquire().await.unwrap();
+
+        let query = sqlx::query!(
+            r#"DELETE FROM route_points WHERE route_id = $1"#,
+            id.as_uuid()
+        );
+
+        query.execute(conn.as_mut()).await?;
+
+        Ok(())
+    }
 }
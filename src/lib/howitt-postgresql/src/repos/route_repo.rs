@@ -1,6 +1,7 @@
 use chrono::{DateTime, Utc};
 use howitt::ext::iter::ResultIterExt;
 use howitt::ext::serde::json::unwrap_string_value;
+use howitt::models::filters::{Page, PageRequest};
 use howitt::models::route::{Route, RouteFilter, RouteId};
 use howitt::models::route_description::RouteDescription;
 use howitt::models::tag::Tag;
@@ -10,7 +11,7 @@ use howitt::models::Model;
 use howitt::repos::Repo;
 use uuid::Uuid;
 
-use crate::{PostgresClient, PostgresRepoError};
+use crate::{prefix_tsquery, PostgresClient, PostgresRepoError};
 
 #[allow(dead_code)]
 struct RouteIndexRow {
@@ -32,6 +33,7 @@ struct RouteIndexRow {
     tags: Vec<String>,
     is_starred: bool,
     user_id: Uuid,
+    archived_at: Option<DateTime<Utc>>,
 }
 
 impl TryFrom<RouteIndexRow> for Route {
@@ -81,6 +83,7 @@ impl TryFrom<RouteIndexRow> for Route {
                     vec![]
                 })
                 .collect(),
+            archived_at: row.archived_at,
         })
     }
 }
@@ -105,6 +108,7 @@ struct RouteRow {
     tags: Vec<String>,
     is_starred: bool,
     user_id: Uuid,
+    archived_at: Option<DateTime<Utc>>,
 }
 
 impl TryFrom<RouteRow> for Route {
@@ -154,6 +158,7 @@ impl TryFrom<RouteRow> for Route {
                     vec![]
                 })
                 .collect(),
+            archived_at: row.archived_at,
         })
     }
 }
@@ -173,17 +178,21 @@ impl Repo for PostgresRouteRepo {
 
         let rows = match filter {
             RouteFilter::Starred => {
-                sqlx::query_as!(RouteRow, r#"select * from routes where is_starred = true"#)
+                sqlx::query_as!(RouteRow, r#"select * from routes where is_starred = true and archived_at is null"#)
                     .fetch_all(conn.as_mut())
                     .await?
             }
             RouteFilter::All => {
-                sqlx::query_as!(RouteRow, r#"select * from routes"#)
+                sqlx::query_as!(RouteRow, r#"select * from routes where archived_at is null"#)
                     .fetch_all(conn.as_mut())
                     .await?
             }
             RouteFilter::Slug(slug) => {
-                sqlx::query_as!(RouteRow, r#"select * from routes where slug = $1"#, slug)
+                sqlx::query_as!(
+                    RouteRow,
+                    r#"select * from routes where slug = $1 and archived_at is null"#,
+                    slug
+                )
                     .fetch_all(conn.as_mut())
                     .await?
             }
@@ -194,15 +203,46 @@ impl Repo for PostgresRouteRepo {
             )
             .fetch_all(conn.as_mut())
             .await?,
+            RouteFilter::ExternalId(external_id) => sqlx::query_as!(
+                RouteRow,
+                r#"select * from routes where external_ref->'id' = $1"#,
+                serde_json::to_value(external_id)?
+            )
+            .fetch_all(conn.as_mut())
+            .await?,
             RouteFilter::UserId(user_id) => {
                 sqlx::query_as!(
                     RouteRow,
-                    r#"select * from routes where user_id = $1"#,
+                    r#"select * from routes where user_id = $1 and archived_at is null"#,
                     user_id.as_uuid()
                 )
                 .fetch_all(conn.as_mut())
                 .await?
             }
+            RouteFilter::SyncedForUser(user_id) => {
+                sqlx::query_as!(
+                    RouteRow,
+                    r#"select * from routes where user_id = $1 and external_ref is not null"#,
+                    user_id.as_uuid()
+                )
+                .fetch_all(conn.as_mut())
+                .await?
+            }
+            RouteFilter::Search(query) => match prefix_tsquery(&query) {
+                Some(tsquery) => {
+                    sqlx::query_as!(
+                        RouteRow,
+                        r#"select * from routes
+                        where to_tsvector('simple', name) @@ to_tsquery('simple', $1)
+                        and archived_at is null
+                        order by ts_rank(to_tsvector('simple', name), to_tsquery('simple', $1)) desc"#,
+                        tsquery
+                    )
+                    .fetch_all(conn.as_mut())
+                    .await?
+                }
+                None => vec![],
+            },
         };
 
         Ok(rows.into_iter().map(Route::try_from).collect_result_vec()?)
@@ -230,7 +270,8 @@ impl Repo for PostgresRouteRepo {
                 direction,
                 tags,
                 is_starred,
-                user_id
+                user_id,
+                archived_at
             from routes"#
         );
 
@@ -241,6 +282,66 @@ impl Repo for PostgresRouteRepo {
             .map(Route::try_from)
             .collect_result_vec()?)
     }
+
+    async fn filter_page(
+        &self,
+        filter: RouteFilter,
+        page: PageRequest<RouteId>,
+    ) -> Result<Page<Route>, PostgresRepoError> {
+        let RouteFilter::All = filter else {
+            let routes = self.filter_models(filter).await?;
+
+            return Ok(page.paginate(routes, |route| route.id));
+        };
+
+        let mut conn = self.client.acquire().await.unwrap();
+
+        let after = page.after.map(Uuid::from);
+        let before = page.before.map(Uuid::from);
+        let limit = page.fetch_limit().map(|limit| limit as i64);
+
+        // Walk from whichever end the page is anchored to, fetching one extra
+        // row so paginate can tell if there's more
+        let routes = if page.is_backward() {
+            sqlx::query_as!(
+                RouteRow,
+                r#"select * from routes
+                where archived_at is null
+                and ($1::uuid is null or id > $1)
+                and ($2::uuid is null or id < $2)
+                order by id desc
+                limit $3"#,
+                after,
+                before,
+                limit
+            )
+            .fetch_all(conn.as_mut())
+            .await
+        } else {
+            sqlx::query_as!(
+                RouteRow,
+                r#"select * from routes
+                where archived_at is null
+                and ($1::uuid is null or id > $1)
+                and ($2::uuid is null or id < $2)
+                order by id asc
+                limit $3"#,
+                after,
+                before,
+                limit
+            )
+            .fetch_all(conn.as_mut())
+            .await
+        }?;
+
+        let routes = routes
+            .into_iter()
+            .map(Route::try_from)
+            .collect_result_vec()?;
+
+        Ok(page.paginate(routes, |route| route.id))
+    }
+
     async fn get(&self, id: <Route as Model>::Id) -> Result<Route, PostgresRepoError> {
         let mut conn = self.client.acquire().await.unwrap();
 
@@ -275,8 +376,9 @@ impl Repo for PostgresRouteRepo {
                 direction,
                 tags,
                 is_starred,
-                user_id
-            ) values ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18)
+                user_id,
+                archived_at
+            ) values ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19)
             ON CONFLICT (id) DO UPDATE SET
                 name = EXCLUDED.name,
                 slug = EXCLUDED.slug,
@@ -292,7 +394,8 @@ impl Repo for PostgresRouteRepo {
                 scouted = EXCLUDED.scouted,
                 direction = EXCLUDED.direction,
                 tags = EXCLUDED.tags,
-                is_starred = EXCLUDED.is_starred"#,
+                is_starred = EXCLUDED.is_starred,
+                archived_at = EXCLUDED.archived_at"#,
             route.id.as_uuid(),
             Utc::now(),
             route.name,
@@ -350,11 +453,52 @@ impl Repo for PostgresRouteRepo {
                 .map(unwrap_string_value),
             route.description.as_ref().map(|x| &*x.tags).unwrap_or(&[]),
             route.tags.contains(&Tag::BackcountrySegment),
-            route.user_id.as_uuid()
+            route.user_id.as_uuid(),
+            route.archived_at
         );
 
         query.execute(conn.as_mut()).await?;
 
         Ok(())
     }
+
+    async fn delete(&self, id: <Route as Model>::Id) -> Result<(), PostgresRepoError> {
+        let mut tx = self.client.begin().await?;
+
+        sqlx::query!(
+            r#"DELETE FROM route_media WHERE route_id = $1"#,
+            id.as_uuid()
+        )
+        .execute(tx.as_mut())
+        .await?;
+
+        sqlx::query!(
+            r#"DELETE FROM route_course_points WHERE route_id = $1"#,
+            id.as_uuid()
+        )
+        .execute(tx.as_mut())
+        .await?;
+
+        sqlx::query!(
+            r#"DELETE FROM route_localities WHERE route_id = $1"#,
+            id.as_uuid()
+        )
+        .execute(tx.as_mut())
+        .await?;
+
+        sqlx::query!(
+            r#"DELETE FROM route_points WHERE route_id = $1"#,
+            id.as_uuid()
+        )
+        .execute(tx.as_mut())
+        .await?;
+
+        sqlx::query!(r#"DELETE FROM routes WHERE id = $1"#, id.as_uuid())
+            .execute(tx.as_mut())
+            .await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
 }
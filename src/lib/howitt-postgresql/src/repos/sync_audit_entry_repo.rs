@@ -0,0 +1,151 @@
+use chrono::{DateTime, Utc};
+use howitt::ext::iter::ResultIterExt;
+use howitt::ext::serde::json::unwrap_string_value;
+use howitt::models::sync_audit_entry::{SyncAuditEntry, SyncAuditEntryFilter, SyncAuditEntryId};
+use howitt::models::trip::TripId;
+use howitt::models::user::UserId;
+use howitt::repos::Repo;
+use uuid::Uuid;
+
+use crate::{PostgresClient, PostgresRepoError};
+
+struct SyncAuditEntryRow {
+    id: Uuid,
+    created_at: DateTime<Utc>,
+    user_id: Uuid,
+    external_id: serde_json::Value,
+    item: serde_json::Value,
+    action: String,
+    detached_trip_ids: Vec<Uuid>,
+}
+
+impl TryFrom<SyncAuditEntryRow> for SyncAuditEntry {
+    type Error = PostgresRepoError;
+
+    fn try_from(row: SyncAuditEntryRow) -> Result<Self, Self::Error> {
+        Ok(SyncAuditEntry {
+            id: SyncAuditEntryId::from(row.id),
+            created_at: row.created_at,
+            user_id: UserId::from(row.user_id),
+            external_id: serde_json::from_value(row.external_id)?,
+            item_id: serde_json::from_value(row.item)?,
+            action: serde_json::from_value(serde_json::Value::String(row.action))?,
+            detached_trip_ids: row
+                .detached_trip_ids
+                .into_iter()
+                .map(TripId::from)
+                .collect(),
+        })
+    }
+}
+
+#[derive(Debug, Clone, derive_more::Constructor)]
+pub struct PostgresSyncAuditEntryRepo {
+    client: PostgresClient,
+}
+
+#[async_trait::async_trait]
+impl Repo for PostgresSyncAuditEntryRepo {
+    type Model = SyncAuditEntry;
+    type Error = PostgresRepoError;
+
+    async fn filter_models(
+        &self,
+        filter: SyncAuditEntryFilter,
+    ) -> Result<Vec<SyncAuditEntry>, PostgresRepoError> {
+        let mut conn = self.client.acquire().await.unwrap();
+
+        let entries = match filter {
+            SyncAuditEntryFilter::ForUser(user_id) => sqlx::query_as!(
+                SyncAuditEntryRow,
+                r#"select * from sync_audit_entries where user_id = $1 order by created_at desc"#,
+                user_id.as_uuid()
+            )
+            .fetch_all(conn.as_mut())
+            .await?,
+        };
+
+        Ok(entries
+            .into_iter()
+            .map(SyncAuditEntry::try_from)
+            .collect_result_vec()?)
+    }
+
+    async fn all(&self) -> Result<Vec<SyncAuditEntry>, PostgresRepoError> {
+        let mut conn = self.client.acquire().await.unwrap();
+
+        let query = sqlx::query_as!(
+            SyncAuditEntryRow,
+            r#"select * from sync_audit_entries order by created_at desc"#
+        );
+
+        Ok(query
+            .fetch_all(conn.as_mut())
+            .await?
+            .into_iter()
+            .map(SyncAuditEntry::try_from)
+            .collect_result_vec()?)
+    }
+
+    async fn get(&self, id: SyncAuditEntryId) -> Result<SyncAuditEntry, PostgresRepoError> {
+        let mut conn = self.client.acquire().await.unwrap();
+
+        let query = sqlx::query_as!(
+            SyncAuditEntryRow,
+            r#"select * from sync_audit_entries where id = $1"#,
+            id.as_uuid()
+        );
+
+        Ok(SyncAuditEntry::try_from(
+            query.fetch_one(conn.as_mut()).await?,
+        )?)
+    }
+
+    async fn put(&self, entry: SyncAuditEntry) -> Result<(), PostgresRepoError> {
+        let mut conn = self.client.acquire().await.unwrap();
+
+        let detached_trip_ids: Vec<_> = entry
+            .detached_trip_ids
+            .iter()
+            .map(|id| *id.as_uuid())
+            .collect();
+
+        // Entries are a log, so they're never rewritten
+        sqlx::query!(
+            r#"insert into sync_audit_entries (
+                id,
+                created_at,
+                user_id,
+                external_id,
+                item,
+                action,
+                detached_trip_ids
+            ) values ($1, $2, $3, $4, $5, $6, $7)
+            ON CONFLICT (id) DO NOTHING"#,
+            entry.id.as_uuid(),
+            entry.created_at,
+            entry.user_id.as_uuid(),
+            serde_json::to_value(&entry.external_id)?,
+            serde_json::to_value(entry.item_id)?,
+            unwrap_string_value(serde_json::to_value(entry.action)?),
+            &detached_trip_ids,
+        )
+        .execute(conn.as_mut())
+        .await?;
+
+        Ok(())
+    }
+
+    async fn delete(&self, id: SyncAuditEntryId) -> Result<(), PostgresRepoError> {
+        let mut conn = self.client.acquire().await.unwrap();
+
+        sqlx::query!(
+            r#"delete from sync_audit_entries where id = $1"#,
+            id.as_uuid()
+        )
+        .execute(conn.as_mut())
+        .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,184 @@
+use chrono::{DateTime, Utc};
+use howitt::ext::iter::ResultIterExt;
+use howitt::ext::serde::json::unwrap_string_value;
+use howitt::models::sync_run::{SyncRun, SyncRunFilter, SyncRunId};
+use howitt::models::user::UserId;
+use howitt::repos::{AnyhowSyncRunRepo, Repo};
+use uuid::Uuid;
+
+use crate::{PostgresClient, PostgresRepoError};
+
+struct SyncRunRow {
+    id: Uuid,
+    created_at: DateTime<Utc>,
+    user_id: Uuid,
+    provider: String,
+    since: Option<serde_json::Value>,
+    cursor: Option<serde_json::Value>,
+    queued: i32,
+    succeeded: i32,
+    failed: i32,
+    finished_at: Option<DateTime<Utc>>,
+}
+
+impl TryFrom<SyncRunRow> for SyncRun {
+    type Error = PostgresRepoError;
+
+    fn try_from(row: SyncRunRow) -> Result<Self, Self::Error> {
+        Ok(SyncRun {
+            id: SyncRunId::from(row.id),
+            created_at: row.created_at,
+            user_id: UserId::from(row.user_id),
+            provider: serde_json::from_value(serde_json::Value::String(row.provider))?,
+            since: row.since.map(serde_json::from_value).transpose()?,
+            cursor: row.cursor.map(serde_json::from_value).transpose()?,
+            queued: row.queued as usize,
+            succeeded: row.succeeded as usize,
+            failed: row.failed as usize,
+            finished_at: row.finished_at,
+        })
+    }
+}
+
+#[derive(Debug, Clone, derive_more::Constructor)]
+pub struct PostgresSyncRunRepo {
+    client: PostgresClient,
+}
+
+#[async_trait::async_trait]
+impl Repo for PostgresSyncRunRepo {
+    type Model = SyncRun;
+    type Error = PostgresRepoError;
+
+    async fn filter_models(
+        &self,
+        filter: SyncRunFilter,
+    ) -> Result<Vec<SyncRun>, PostgresRepoError> {
+        let mut conn = self.client.acquire().await.unwrap();
+
+        let runs = match filter {
+            SyncRunFilter::ForUser { user_id, limit } => sqlx::query_as!(
+                SyncRunRow,
+                r#"select * from sync_runs where user_id = $1 order by created_at desc limit $2"#,
+                user_id.as_uuid(),
+                limit.map(|limit| limit as i64)
+            )
+            .fetch_all(conn.as_mut())
+            .await?,
+        };
+
+        Ok(runs
+            .into_iter()
+            .map(SyncRun::try_from)
+            .collect_result_vec()?)
+    }
+
+    async fn all(&self) -> Result<Vec<SyncRun>, PostgresRepoError> {
+        let mut conn = self.client.acquire().await.unwrap();
+
+        let query = sqlx::query_as!(
+            SyncRunRow,
+            r#"select * from sync_runs order by created_at desc"#
+        );
+
+        Ok(query
+            .fetch_all(conn.as_mut())
+            .await?
+            .into_iter()
+            .map(SyncRun::try_from)
+            .collect_result_vec()?)
+    }
+
+    async fn get(&self, id: SyncRunId) -> Result<SyncRun, PostgresRepoError> {
+        let mut conn = self.client.acquire().await.unwrap();
+
+        let query = sqlx::query_as!(
+            SyncRunRow,
+            r#"select * from sync_runs where id = $1"#,
+            id.as_uuid()
+        );
+
+        Ok(SyncRun::try_from(query.fetch_one(conn.as_mut()).await?)?)
+    }
+
+    async fn put(&self, run: SyncRun) -> Result<(), PostgresRepoError> {
+        let mut conn = self.client.acquire().await.unwrap();
+
+        sqlx::query!(
+            r#"insert into sync_runs (
+                id,
+                created_at,
+                user_id,
+                provider,
+                since,
+                cursor,
+                queued,
+                succeeded,
+                failed,
+                finished_at
+            ) values ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+            ON CONFLICT (id) DO UPDATE SET
+                queued = EXCLUDED.queued,
+                succeeded = EXCLUDED.succeeded,
+                failed = EXCLUDED.failed,
+                finished_at = EXCLUDED.finished_at"#,
+            run.id.as_uuid(),
+            run.created_at,
+            run.user_id.as_uuid(),
+            unwrap_string_value(serde_json::to_value(run.provider)?),
+            run.since.map(serde_json::to_value).transpose()?,
+            run.cursor.map(serde_json::to_value).transpose()?,
+            run.queued as i32,
+            run.succeeded as i32,
+            run.failed as i32,
+            run.finished_at,
+        )
+        .execute(conn.as_mut())
+        .await?;
+
+        Ok(())
+    }
+
+    async fn delete(&self, id: SyncRunId) -> Result<(), PostgresRepoError> {
+        let mut conn = self.client.acquire().await.unwrap();
+
+        sqlx::query!(r#"delete from sync_runs where id = $1"#, id.as_uuid())
+            .execute(conn.as_mut())
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl AnyhowSyncRunRepo for PostgresSyncRunRepo {
+    async fn record_result(
+        &self,
+        id: SyncRunId,
+        succeeded: bool,
+        now: DateTime<Utc>,
+    ) -> Result<SyncRun, anyhow::Error> {
+        let mut conn = self.client.acquire().await?;
+
+        // The right hand sides all see the row as it was before the update
+        let row = sqlx::query_as!(
+            SyncRunRow,
+            r#"update sync_runs set
+                succeeded = succeeded + case when $2 then 1 else 0 end,
+                failed = failed + case when $2 then 0 else 1 end,
+                finished_at = coalesce(
+                    finished_at,
+                    case when succeeded + failed + 1 >= queued then $3::timestamptz end
+                )
+            where id = $1
+            returning *"#,
+            id.as_uuid(),
+            succeeded,
+            now
+        )
+        .fetch_one(conn.as_mut())
+        .await?;
+
+        Ok(SyncRun::try_from(row)?)
+    }
+}
@@ -1,5 +1,6 @@
 use chrono::{DateTime, Utc};
 use howitt::ext::iter::ResultIterExt;
+use howitt::models::filters::{Page, PageRequest};
 use howitt::models::trip::{Trip, TripFilter, TripId};
 use howitt::models::user::UserId;
 use howitt::models::{media::MediaId, ride::RideId};
@@ -7,7 +8,7 @@ use howitt::repos::Repo;
 use itertools::Itertools;
 use uuid::Uuid;
 
-use crate::{PostgresClient, PostgresRepoError};
+use crate::{prefix_tsquery, PostgresClient, PostgresRepoError};
 
 struct TripRow {
     id: Uuid,
@@ -137,11 +138,108 @@ impl Repo for PostgresTripRepo {
                 .fetch_all(conn.as_mut())
                 .await
             }
+            TripFilter::Search(query) => match prefix_tsquery(&query) {
+                Some(tsquery) => {
+                    sqlx::query_as!(
+                        TripRow,
+                        r#"
+                            SELECT 
+                                t.*,
+                                tr.ride_ids,
+                                tr.media_ids
+                            FROM trips t
+                            INNER JOIN trip_relations tr ON tr.id = t.id
+                            WHERE to_tsvector('simple', t.name) @@ to_tsquery('simple', $1)
+                            ORDER BY ts_rank(to_tsvector('simple', t.name), to_tsquery('simple', $1)) DESC
+                        "#,
+                        tsquery
+                    )
+                    .fetch_all(conn.as_mut())
+                    .await
+                }
+                None => Ok(vec![]),
+            },
         }?;
 
         Ok(trips.into_iter().map(Trip::try_from).collect_result_vec()?)
     }
 
+    async fn filter_page(
+        &self,
+        filter: TripFilter,
+        page: PageRequest<TripId>,
+    ) -> Result<Page<Trip>, PostgresRepoError> {
+        let user_id = match filter {
+            TripFilter::All => None,
+            TripFilter::User(user_id) => Some(Uuid::from(user_id)),
+            filter => {
+                let trips = self.filter_models(filter).await?;
+
+                return Ok(page.paginate(trips, |trip| trip.id));
+            }
+        };
+
+        let mut conn = self.client.acquire().await.unwrap();
+
+        let after = page.after.map(Uuid::from);
+        let before = page.before.map(Uuid::from);
+        let limit = page.fetch_limit().map(|limit| limit as i64);
+
+        // Walk from whichever end the page is anchored to, fetching one extra
+        // row so paginate can tell if there's more
+        let trips = if page.is_backward() {
+            sqlx::query_as!(
+                TripRow,
+                r#"
+                    SELECT 
+                        t.*,
+                        tr.ride_ids,
+                        tr.media_ids
+                    FROM trips t
+                    INNER JOIN trip_relations tr ON tr.id = t.id
+                    WHERE ($1::uuid is null or t.user_id = $1)
+                    AND ($2::uuid is null or t.id > $2)
+                    AND ($3::uuid is null or t.id < $3)
+                    ORDER BY t.id DESC
+                    LIMIT $4
+                "#,
+                user_id,
+                after,
+                before,
+                limit
+            )
+            .fetch_all(conn.as_mut())
+            .await
+        } else {
+            sqlx::query_as!(
+                TripRow,
+                r#"
+                    SELECT 
+                        t.*,
+                        tr.ride_ids,
+                        tr.media_ids
+                    FROM trips t
+                    INNER JOIN trip_relations tr ON tr.id = t.id
+                    WHERE ($1::uuid is null or t.user_id = $1)
+                    AND ($2::uuid is null or t.id > $2)
+                    AND ($3::uuid is null or t.id < $3)
+                    ORDER BY t.id ASC
+                    LIMIT $4
+                "#,
+                user_id,
+                after,
+                before,
+                limit
+            )
+            .fetch_all(conn.as_mut())
+            .await
+        }?;
+
+        let trips = trips.into_iter().map(Trip::try_from).collect_result_vec()?;
+
+        Ok(page.paginate(trips, |trip| trip.id))
+    }
+
     async fn all(&self) -> Result<Vec<Trip>, PostgresRepoError> {
         self.filter_models(TripFilter::All).await
     }
@@ -266,4 +364,24 @@ impl Repo for PostgresTripRepo {
 
         Ok(())
     }
+
+    async fn delete(&self, id: TripId) -> Result<(), PostgresRepoError> {
+        let mut tx = self.client.begin().await?;
+
+        sqlx::query!(r#"DELETE FROM trip_rides WHERE trip_id = $1"#, id.as_uuid())
+            .execute(tx.as_mut())
+            .await?;
+
+        sqlx::query!(r#"DELETE FROM trip_media WHERE trip_id = $1"#, id.as_uuid())
+            .execute(tx.as_mut())
+            .await?;
+
+        sqlx::query!(r#"DELETE FROM trips WHERE id = $1"#, id.as_uuid())
+            .execute(tx.as_mut())
+            .await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
 }
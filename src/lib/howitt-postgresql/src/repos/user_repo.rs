@@ -2,10 +2,13 @@ use argon2::password_hash::Encoding;
 use argon2::PasswordHash;
 use chrono::{DateTime, Utc};
 use howitt::ext::iter::ResultIterExt;
+use howitt::ext::serde::json::unwrap_string_value;
+use howitt::models::filters::{Page, PageRequest};
 
-use howitt::models::user::{UserFilter, UserId, UserRwgpsConnection};
+use howitt::models::sync_run::SyncCursor;
+use howitt::models::user::{RwgpsConnectionStatus, UserFilter, UserId, UserRwgpsConnection};
 use howitt::models::{user::User, Model};
-use howitt::repos::Repo;
+use howitt::repos::{AnyhowUserRepo, Repo};
 use uuid::Uuid;
 
 use crate::{PostgresClient, PostgresRepoError};
@@ -23,6 +26,8 @@ struct UserRow {
     rwgps_access_token: Option<String>,
     rwgps_created_at: Option<DateTime<Utc>>,
     rwgps_updated_at: Option<DateTime<Utc>>,
+    rwgps_status: Option<String>,
+    rwgps_sync_cursor: Option<serde_json::Value>,
 }
 
 impl TryFrom<UserRow> for User {
@@ -37,6 +42,13 @@ impl TryFrom<UserRow> for User {
                 access_token,
                 created_at: row.rwgps_created_at.unwrap(),
                 updated_at: row.rwgps_updated_at.unwrap(),
+                status: serde_json::from_value(serde_json::Value::String(
+                    row.rwgps_status.unwrap(),
+                ))?,
+                sync_cursor: row
+                    .rwgps_sync_cursor
+                    .map(serde_json::from_value)
+                    .transpose()?,
             }),
             _ => None,
         };
@@ -68,6 +80,7 @@ impl Repo for PostgresUserRepo {
         let mut conn = self.client.acquire().await.unwrap();
 
         let users = match filter {
+            UserFilter::Public => return self.all().await,
             UserFilter::Ids(ids) => {
                 let uuids: Vec<_> = ids.into_iter().map(Uuid::from).collect();
 
@@ -80,7 +93,9 @@ impl Repo for PostgresUserRepo {
                         rc.rwgps_user_id as "rwgps_user_id?",
                         rc.access_token as "rwgps_access_token?",
                         rc.created_at as "rwgps_created_at?",
-                        rc.updated_at as "rwgps_updated_at?"
+                        rc.updated_at as "rwgps_updated_at?",
+                        rc.status as "rwgps_status?",
+                        rc.sync_cursor as "rwgps_sync_cursor?"
                     FROM users u
                     LEFT JOIN user_rwgps_connections rc ON rc.user_id = u.id
                     WHERE u.id = ANY($1)
@@ -100,7 +115,9 @@ impl Repo for PostgresUserRepo {
                         rc.rwgps_user_id as "rwgps_user_id?",
                         rc.access_token as "rwgps_access_token?",
                         rc.created_at as "rwgps_created_at?",
-                        rc.updated_at as "rwgps_updated_at?"
+                        rc.updated_at as "rwgps_updated_at?",
+                        rc.status as "rwgps_status?",
+                        rc.sync_cursor as "rwgps_sync_cursor?"
                     FROM users u
                     LEFT JOIN user_rwgps_connections rc ON rc.user_id = u.id
                     WHERE u.username = $1
@@ -120,7 +137,9 @@ impl Repo for PostgresUserRepo {
                     rc.rwgps_user_id as "rwgps_user_id?",
                     rc.access_token as "rwgps_access_token?",
                     rc.created_at as "rwgps_created_at?",
-                    rc.updated_at as "rwgps_updated_at?"
+                    rc.updated_at as "rwgps_updated_at?",
+                    rc.status as "rwgps_status?",
+                    rc.sync_cursor as "rwgps_sync_cursor?"
                 FROM users u
                 INNER JOIN user_rwgps_connections rc ON rc.user_id = u.id
                 WHERE rc.rwgps_user_id = $1
@@ -140,7 +159,9 @@ impl Repo for PostgresUserRepo {
                         rc.rwgps_user_id as "rwgps_user_id?",
                         rc.access_token as "rwgps_access_token?",
                         rc.created_at as "rwgps_created_at?",
-                        rc.updated_at as "rwgps_updated_at?"
+                        rc.updated_at as "rwgps_updated_at?",
+                        rc.status as "rwgps_status?",
+                        rc.sync_cursor as "rwgps_sync_cursor?"
                     FROM users u
                     LEFT JOIN user_rwgps_connections rc ON rc.user_id = u.id
                     WHERE u.email = $1
@@ -167,7 +188,9 @@ impl Repo for PostgresUserRepo {
                 rc.rwgps_user_id as "rwgps_user_id?",
                 rc.access_token as "rwgps_access_token?",
                 rc.created_at as "rwgps_created_at?",
-                rc.updated_at as "rwgps_updated_at?"
+                rc.updated_at as "rwgps_updated_at?",
+                rc.status as "rwgps_status?",
+                rc.sync_cursor as "rwgps_sync_cursor?"
             FROM users u
             INNER JOIN user_rwgps_connections rc ON rc.user_id = u.id
             "#,
@@ -181,6 +204,84 @@ impl Repo for PostgresUserRepo {
             .collect_result_vec()?)
     }
 
+    async fn filter_page(
+        &self,
+        filter: UserFilter,
+        page: PageRequest<UserId>,
+    ) -> Result<Page<User>, PostgresRepoError> {
+        let UserFilter::Public = filter else {
+            let users = self.filter_models(filter).await?;
+
+            return Ok(page.paginate(users, |user| user.id));
+        };
+
+        let mut conn = self.client.acquire().await.unwrap();
+
+        let after = page.after.map(Uuid::from);
+        let before = page.before.map(Uuid::from);
+        let limit = page.fetch_limit().map(|limit| limit as i64);
+
+        // Walk from whichever end the page is anchored to, fetching one extra
+        // row so paginate can tell if there's more
+        let users = if page.is_backward() {
+            sqlx::query_as!(
+                UserRow,
+                r#"
+                SELECT 
+                    u.*,
+                    rc.id as "rwgps_id?",
+                    rc.rwgps_user_id as "rwgps_user_id?",
+                    rc.access_token as "rwgps_access_token?",
+                    rc.created_at as "rwgps_created_at?",
+                    rc.updated_at as "rwgps_updated_at?",
+                    rc.status as "rwgps_status?",
+                    rc.sync_cursor as "rwgps_sync_cursor?"
+                FROM users u
+                INNER JOIN user_rwgps_connections rc ON rc.user_id = u.id
+                WHERE ($1::uuid is null or u.id > $1)
+                AND ($2::uuid is null or u.id < $2)
+                ORDER BY u.id DESC
+                LIMIT $3
+                "#,
+                after,
+                before,
+                limit
+            )
+            .fetch_all(conn.as_mut())
+            .await
+        } else {
+            sqlx::query_as!(
+                UserRow,
+                r#"
+                SELECT 
+                    u.*,
+                    rc.id as "rwgps_id?",
+                    rc.rwgps_user_id as "rwgps_user_id?",
+                    rc.access_token as "rwgps_access_token?",
+                    rc.created_at as "rwgps_created_at?",
+                    rc.updated_at as "rwgps_updated_at?",
+                    rc.status as "rwgps_status?",
+                    rc.sync_cursor as "rwgps_sync_cursor?"
+                FROM users u
+                INNER JOIN user_rwgps_connections rc ON rc.user_id = u.id
+                WHERE ($1::uuid is null or u.id > $1)
+                AND ($2::uuid is null or u.id < $2)
+                ORDER BY u.id ASC
+                LIMIT $3
+                "#,
+                after,
+                before,
+                limit
+            )
+            .fetch_all(conn.as_mut())
+            .await
+        }?;
+
+        let users = users.into_iter().map(User::try_from).collect_result_vec()?;
+
+        Ok(page.paginate(users, |user| user.id))
+    }
+
     async fn get(&self, id: <User as Model>::Id) -> Result<User, PostgresRepoError> {
         let mut conn = self.client.acquire().await.unwrap();
 
@@ -193,7 +294,9 @@ impl Repo for PostgresUserRepo {
                 rc.rwgps_user_id as "rwgps_user_id?",
                 rc.access_token as "rwgps_access_token?",
                 rc.created_at as "rwgps_created_at?",
-                rc.updated_at as "rwgps_updated_at?"
+                rc.updated_at as "rwgps_updated_at?",
+                rc.status as "rwgps_status?",
+                rc.sync_cursor as "rwgps_sync_cursor?"
             FROM users u
             LEFT JOIN user_rwgps_connections rc ON rc.user_id = u.id
             WHERE u.id = $1
@@ -242,12 +345,16 @@ impl Repo for PostgresUserRepo {
                     rwgps_user_id,
                     access_token,
                     created_at,
-                    updated_at
-                ) VALUES ($1, $2, $3, $4, $5, $6)
+                    updated_at,
+                    status,
+                    sync_cursor
+                ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
                 ON CONFLICT (user_id) DO UPDATE SET
                     rwgps_user_id = EXCLUDED.rwgps_user_id,
                     access_token = EXCLUDED.access_token,
-                    updated_at = EXCLUDED.updated_at
+                    updated_at = EXCLUDED.updated_at,
+                    status = EXCLUDED.status,
+                    sync_cursor = EXCLUDED.sync_cursor
                 "#,
                 rwgps.id,
                 rwgps.user_id.as_uuid(),
@@ -255,6 +362,8 @@ impl Repo for PostgresUserRepo {
                 rwgps.access_token,
                 rwgps.created_at,
                 rwgps.updated_at,
+                unwrap_string_value(serde_json::to_value(rwgps.status)?),
+                rwgps.sync_cursor.map(serde_json::to_value).transpose()?,
             )
             .execute(tx.as_mut())
             .await?;
@@ -264,4 +373,94 @@ impl Repo for PostgresUserRepo {
 
         Ok(())
     }
+
+    async fn delete(&self, id: <User as Model>::Id) -> Result<(), PostgresRepoError> {
+        let mut tx = self.client.begin().await?;
+
+        let has_content = sqlx::query_scalar!(
+            r#"SELECT
+                EXISTS (SELECT 1 FROM rides WHERE user_id = $1)
+                OR EXISTS (SELECT 1 FROM routes WHERE user_id = $1)
+                OR EXISTS (SELECT 1 FROM trips WHERE user_id = $1)
+                OR EXISTS (SELECT 1 FROM media WHERE user_id = $1)
+                OR EXISTS (SELECT 1 FROM points_of_interest WHERE user_id = $1)
+                OR EXISTS (SELECT 1 FROM point_of_interest_visits WHERE user_id = $1)
+                OR EXISTS (SELECT 1 FROM water_beta WHERE user_id = $1)
+                AS "has_content!""#,
+            id.as_uuid()
+        )
+        .fetch_one(tx.as_mut())
+        .await?;
+
+        if has_content {
+            return Err(PostgresRepoError::UserHasContent(id));
+        }
+
+        sqlx::query!(
+            r#"DELETE FROM user_rwgps_connections WHERE user_id = $1"#,
+            id.as_uuid()
+        )
+        .execute(tx.as_mut())
+        .await?;
+
+        sqlx::query!(
+            r#"DELETE FROM sync_audit_entries WHERE user_id = $1"#,
+            id.as_uuid()
+        )
+        .execute(tx.as_mut())
+        .await?;
+
+        sqlx::query!(r#"DELETE FROM sync_runs WHERE user_id = $1"#, id.as_uuid())
+            .execute(tx.as_mut())
+            .await?;
+
+        sqlx::query!(r#"DELETE FROM users WHERE id = $1"#, id.as_uuid())
+            .execute(tx.as_mut())
+            .await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl AnyhowUserRepo for PostgresUserRepo {
+    async fn set_rwgps_status(
+        &self,
+        user_id: UserId,
+        status: RwgpsConnectionStatus,
+        now: DateTime<Utc>,
+    ) -> Result<(), anyhow::Error> {
+        let mut conn = self.client.acquire().await?;
+
+        sqlx::query!(
+            r#"UPDATE user_rwgps_connections SET status = $2, updated_at = $3 WHERE user_id = $1"#,
+            user_id.as_uuid(),
+            unwrap_string_value(serde_json::to_value(status)?),
+            now
+        )
+        .execute(conn.as_mut())
+        .await?;
+
+        Ok(())
+    }
+
+    async fn set_rwgps_sync_cursor(
+        &self,
+        user_id: UserId,
+        cursor: SyncCursor,
+    ) -> Result<(), anyhow::Error> {
+        let mut conn = self.client.acquire().await?;
+
+        sqlx::query!(
+            r#"UPDATE user_rwgps_connections SET sync_cursor = $2 WHERE user_id = $1"#,
+            user_id.as_uuid(),
+            serde_json::to_value(cursor)?
+        )
+        .execute(conn.as_mut())
+        .await?;
+
+        Ok(())
+    }
 }
@@ -0,0 +1,289 @@
+use chrono::{DateTime, Utc};
+use howitt::ext::iter::ResultIterExt;
+use howitt::ext::serde::json::unwrap_string_value;
+use howitt::models::media::MediaId;
+use howitt::models::osm_feature::OsmFeatureId;
+use howitt::models::user::UserId;
+use howitt::models::water_beta::{WaterBeta, WaterBetaFilter, WaterBetaId};
+use howitt::repos::Repo;
+use uuid::Uuid;
+
+use crate::{PostgresClient, PostgresRepoError};
+
+struct WaterBetaRow {
+    id: Uuid,
+    created_at: DateTime<Utc>,
+    user_id: Uuid,
+    osm_feature_id: i64,
+    observed_at: DateTime<Utc>,
+    flow_status: String,
+    treatment_needed: bool,
+    comment: Option<String>,
+    media_ids: Vec<Uuid>,
+}
+
+impl TryFrom<WaterBetaRow> for WaterBeta {
+    type Error = PostgresRepoError;
+
+    fn try_from(row: WaterBetaRow) -> Result<Self, Self::Error> {
+        Ok(WaterBeta {
+            id: WaterBetaId::from(row.id),
+            created_at: row.created_at,
+            user_id: UserId::from(row.user_id),
+            osm_feature_id: OsmFeatureId::from_i64(row.osm_feature_id),
+            observed_at: row.observed_at,
+            flow_status: serde_json::from_value(serde_json::Value::String(row.flow_status))?,
+            treatment_needed: row.treatment_needed,
+            comment: row.comment,
+            media_ids: row.media_ids.into_iter().map(MediaId::from).collect(),
+        })
+    }
+}
+
+#[derive(Debug, Clone, derive_more::Constructor)]
+pub struct PostgresWaterBetaRepo {
+    client: PostgresClient,
+}
+
+#[async_trait::async_trait]
+impl Repo for PostgresWaterBetaRepo {
+    type Model = WaterBeta;
+    type Error = PostgresRepoError;
+
+    async fn filter_models(
+        &self,
+        filter: WaterBetaFilter,
+    ) -> Result<Vec<WaterBeta>, PostgresRepoError> {
+        let mut conn = self.client.acquire().await.unwrap();
+
+        let reports = match filter {
+            WaterBetaFilter::Ids(ids) => {
+                let uuids: Vec<_> = ids.into_iter().map(Uuid::from).collect();
+
+                sqlx::query_as!(
+                    WaterBetaRow,
+                    r#"
+                    SELECT
+                        w.id,
+                        w.created_at,
+                        w.user_id,
+                        w.osm_feature_id,
+                        w.observed_at,
+                        w.flow_status,
+                        w.treatment_needed,
+                        w.comment,
+                        ARRAY(
+                            SELECT wm.media_id FROM water_beta_media wm WHERE wm.water_beta_id = w.id
+                        ) as "media_ids!"
+                    FROM water_beta w
+                    WHERE w.id = ANY($1)
+                    ORDER BY w.observed_at DESC
+                    "#,
+                    &uuids
+                )
+                .fetch_all(conn.as_mut())
+                .await?
+            }
+            WaterBetaFilter::ForOsmFeatures(osm_feature_ids) => {
+                let ids: Vec<i64> = osm_feature_ids.into_iter().map(|id| id.as_i64()).collect();
+
+                sqlx::query_as!(
+                    WaterBetaRow,
+                    r#"
+                    SELECT
+                        w.id,
+                        w.created_at,
+                        w.user_id,
+                        w.osm_feature_id,
+                        w.observed_at,
+                        w.flow_status,
+                        w.treatment_needed,
+                        w.comment,
+                        ARRAY(
+                            SELECT wm.media_id FROM water_beta_media wm WHERE wm.water_beta_id = w.id
+                        ) as "media_ids!"
+                    FROM water_beta w
+                    WHERE w.osm_feature_id = ANY($1)
+                    ORDER BY w.observed_at DESC
+                    "#,
+                    &ids
+                )
+                .fetch_all(conn.as_mut())
+                .await?
+            }
+            WaterBetaFilter::ForUser(user_id) => {
+                sqlx::query_as!(
+                    WaterBetaRow,
+                    r#"
+                    SELECT
+                        w.id,
+                        w.created_at,
+                        w.user_id,
+                        w.osm_feature_id,
+                        w.observed_at,
+                        w.flow_status,
+                        w.treatment_needed,
+                        w.comment,
+                        ARRAY(
+                            SELECT wm.media_id FROM water_beta_media wm WHERE wm.water_beta_id = w.id
+                        ) as "media_ids!"
+                    FROM water_beta w
+                    WHERE w.user_id = $1
+                    ORDER BY w.observed_at DESC
+                    "#,
+                    user_id.as_uuid()
+                )
+                .fetch_all(conn.as_mut())
+                .await?
+            }
+        };
+
+        Ok(reports
+            .into_iter()
+            .map(WaterBeta::try_from)
+            .collect_result_vec()?)
+    }
+
+    async fn all(&self) -> Result<Vec<WaterBeta>, PostgresRepoError> {
+        let mut conn = self.client.acquire().await.unwrap();
+
+        let query = sqlx::query_as!(
+            WaterBetaRow,
+            r#"
+            SELECT
+                w.id,
+                w.created_at,
+                w.user_id,
+                w.osm_feature_id,
+                w.observed_at,
+                w.flow_status,
+                w.treatment_needed,
+                w.comment,
+                ARRAY(
+                    SELECT wm.media_id FROM water_beta_media wm WHERE wm.water_beta_id = w.id
+                ) as "media_ids!"
+            FROM water_beta w
+            ORDER BY w.observed_at DESC
+            "#
+        );
+
+        Ok(query
+            .fetch_all(conn.as_mut())
+            .await?
+            .into_iter()
+            .map(WaterBeta::try_from)
+            .collect_result_vec()?)
+    }
+
+    async fn get(&self, id: WaterBetaId) -> Result<WaterBeta, PostgresRepoError> {
+        let mut conn = self.client.acquire().await.unwrap();
+
+        let query = sqlx::query_as!(
+            WaterBetaRow,
+            r#"
+            SELECT
+                w.id,
+                w.created_at,
+                w.user_id,
+                w.osm_feature_id,
+                w.observed_at,
+                w.flow_status,
+                w.treatment_needed,
+                w.comment,
+                ARRAY(
+                    SELECT wm.media_id FROM water_beta_media wm WHERE wm.water_beta_id = w.id
+                ) as "media_ids!"
+            FROM water_beta w
+            WHERE w.id = $1
+            "#,
+            id.as_uuid()
+        );
+
+        Ok(WaterBeta::try_from(query.fetch_one(conn.as_mut()).await?)?)
+    }
+
+    async fn put(&self, report: WaterBeta) -> Result<(), PostgresRepoError> {
+        let mut tx = self.client.begin().await?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO water_beta (
+                id,
+                created_at,
+                user_id,
+                osm_feature_id,
+                observed_at,
+                flow_status,
+                treatment_needed,
+                comment
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            ON CONFLICT (id) DO UPDATE
+            SET observed_at = EXCLUDED.observed_at,
+                flow_status = EXCLUDED.flow_status,
+                treatment_needed = EXCLUDED.treatment_needed,
+                comment = EXCLUDED.comment
+            "#,
+            report.id.as_uuid(),
+            report.created_at,
+            report.user_id.as_uuid(),
+            report.osm_feature_id.as_i64(),
+            report.observed_at,
+            unwrap_string_value(serde_json::to_value(report.flow_status)?),
+            report.treatment_needed,
+            report.comment,
+        )
+        .execute(tx.as_mut())
+        .await?;
+
+        let media_ids: Vec<_> = report.media_ids.iter().map(|id| *id.as_uuid()).collect();
+
+        sqlx::query!(
+            r#"
+            DELETE FROM water_beta_media
+            WHERE water_beta_id = $1
+            AND media_id NOT IN (SELECT * FROM UNNEST($2::uuid[]))
+            "#,
+            report.id.as_uuid(),
+            &media_ids,
+        )
+        .execute(tx.as_mut())
+        .await?;
+
+        for media_id in media_ids {
+            sqlx::query!(
+                r#"
+                INSERT INTO water_beta_media (water_beta_id, media_id)
+                VALUES ($1, $2)
+                ON CONFLICT (water_beta_id, media_id) DO NOTHING
+                "#,
+                report.id.as_uuid(),
+                media_id,
+            )
+            .execute(tx.as_mut())
+            .await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    async fn delete(&self, id: WaterBetaId) -> Result<(), PostgresRepoError> {
+        let mut tx = self.client.begin().await?;
+
+        sqlx::query!(
+            r#"DELETE FROM water_beta_media WHERE water_beta_id = $1"#,
+            id.as_uuid()
+        )
+        .execute(tx.as_mut())
+        .await?;
+
+        sqlx::query!(r#"DELETE FROM water_beta WHERE id = $1"#, id.as_uuid())
+            .execute(tx.as_mut())
+            .await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,18 @@
+//! Runs the shared repo conformance suite against a real database. Ignored by
+//! default; run with `DATABASE_URL=... cargo test -p howitt-postgresql -- --ignored`
+//! after applying the migrations.
+
+use howitt::repos::Repos;
+use howitt_postgresql::{PostgresClient, PostgresRepos};
+
+async fn repos() -> Repos {
+    let url = std::env::var("DATABASE_URL").expect("DATABASE_URL must be set");
+    let client = PostgresClient::connect(&url).await.unwrap();
+
+    Repos::from(PostgresRepos::new(client))
+}
+
+howitt_memory::conformance_tests!(
+    #[ignore]
+    repos().await
+);
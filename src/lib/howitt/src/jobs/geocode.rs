@@ -0,0 +1,11 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::{ride::RideId, route::RouteId};
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub enum GeocodeJob {
+    /// Names the localities at either terminus of a route
+    Route(RouteId),
+    /// Names the localities where a ride started and finished
+    Ride(RideId),
+}
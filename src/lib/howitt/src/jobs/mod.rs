@@ -1,11 +1,19 @@
 use derive_more::derive::From;
 use serde::{Deserialize, Serialize};
 
+use crate::models::ride::RideId;
+
+pub mod geocode;
 pub mod media;
+pub mod poi;
 pub mod rwgps;
 
 #[derive(Debug, Deserialize, Serialize, From, Clone)]
 pub enum Job {
+    Geocode(geocode::GeocodeJob),
     Media(media::MediaJob),
+    Poi(poi::PoiJob),
     Rwgps(rwgps::RwgpsJob),
+    /// Looks up the weather through each hour of a ride
+    Weather(RideId),
 }
@@ -0,0 +1,8 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::ride::RideId;
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub enum PoiJob {
+    DetectVisits(RideId),
+}
@@ -1,20 +1,63 @@
-use rwgps_types::webhook::RwgpsWebhookNotification;
 use serde::{Deserialize, Serialize};
 
-use crate::models::user::UserRwgpsConnection;
+use crate::models::{sync_run::SyncRunId, user::UserRwgpsConnection};
+use crate::services::sync::provider::WebhookEvent;
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub enum RwgpsJob {
-    Webhook(RwgpsWebhookNotification),
+    Webhook(WebhookEvent),
     SyncTrip {
         rwgps_trip_id: usize,
         connection: UserRwgpsConnection,
+        /// The history sync that queued the job, if any
+        #[serde(default)]
+        sync_run_id: Option<SyncRunId>,
     },
     SyncRoute {
         rwgps_route_id: usize,
         connection: UserRwgpsConnection,
+        /// The history sync that queued the job, if any
+        #[serde(default)]
+        sync_run_id: Option<SyncRunId>,
+    },
+    /// The trip is gone from RWGPS, so archive or delete its ride
+    RemoveTrip {
+        rwgps_trip_id: usize,
+        connection: UserRwgpsConnection,
+    },
+    /// The route is gone from RWGPS, so archive or delete it
+    RemoveRoute {
+        rwgps_route_id: usize,
+        connection: UserRwgpsConnection,
     },
     SyncHistory {
         connection: UserRwgpsConnection,
     },
 }
+
+impl RwgpsJob {
+    /// The connection the job makes RWGPS requests with. Webhooks and
+    /// removals only touch what's stored here, so they don't make any.
+    pub fn rwgps_connection_mut(&mut self) -> Option<&mut UserRwgpsConnection> {
+        match self {
+            RwgpsJob::SyncTrip { connection, .. }
+            | RwgpsJob::SyncRoute { connection, .. }
+            | RwgpsJob::SyncHistory { connection } => Some(connection),
+            RwgpsJob::Webhook(_) | RwgpsJob::RemoveTrip { .. } | RwgpsJob::RemoveRoute { .. } => {
+                None
+            }
+        }
+    }
+
+    pub fn sync_run_id(&self) -> Option<SyncRunId> {
+        match self {
+            RwgpsJob::SyncTrip { sync_run_id, .. } | RwgpsJob::SyncRoute { sync_run_id, .. } => {
+                *sync_run_id
+            }
+            RwgpsJob::Webhook(_)
+            | RwgpsJob::RemoveTrip { .. }
+            | RwgpsJob::RemoveRoute { .. }
+            | RwgpsJob::SyncHistory { .. } => None,
+        }
+    }
+}
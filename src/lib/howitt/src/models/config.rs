@@ -2,7 +2,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::models::{route::RouteId, Model, ModelId};
 
-#[derive(PartialEq, Eq, Copy, Clone, Hash, Debug)]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Copy, Clone, Hash, Debug)]
 pub struct ConfigId;
 
 impl std::fmt::Display for ConfigId {
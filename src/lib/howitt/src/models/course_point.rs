@@ -0,0 +1,44 @@
+use serde::{Deserialize, Serialize};
+
+use super::{route::RouteId, Model};
+
+/// A cue planned on the route where it was drawn, e.g. a turn instruction or a
+/// POI placed on an RWGPS route.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct CoursePoint {
+    pub point: geo::Point,
+    /// Distance along the route, if the source measured it
+    pub distance_m: Option<f64>,
+    /// e.g. "Left", "Summit" or "Water"
+    pub kind: String,
+    pub note: Option<String>,
+}
+
+impl std::fmt::Display for CoursePoint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.note {
+            Some(note) if !note.is_empty() => write!(f, "{note}"),
+            _ => write!(f, "{}", self.kind),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct RouteCoursePoints {
+    pub id: RouteId,
+    pub course_points: Vec<CoursePoint>,
+}
+
+#[derive(Debug, Clone)]
+pub enum RouteCoursePointsFilter {
+    Ids(Vec<RouteId>),
+}
+
+impl Model for RouteCoursePoints {
+    type Id = RouteId;
+    type Filter = RouteCoursePointsFilter;
+
+    fn id(&self) -> RouteId {
+        self.id
+    }
+}
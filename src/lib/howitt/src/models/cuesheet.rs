@@ -1,6 +1,7 @@
 use serde::{Deserialize, Serialize};
 
 use super::{
+    course_point::CoursePoint,
     point::{elevation_point, progress::DistanceElevationProgress},
     point_of_interest::PointOfInterest,
 };
@@ -10,7 +11,7 @@ pub struct Cuesheet {
     pub cues: Vec<Cue>,
 }
 
-#[derive(Debug, Serialize, Deserialize, derive_more::Display)]
+#[derive(Debug, Clone, Serialize, Deserialize, derive_more::Display)]
 pub enum CueStop {
     #[display("Start")]
     Start,
@@ -18,6 +19,12 @@ pub enum CueStop {
     End,
     #[display("{} ({})", "_0.name", "_0.point_of_interest_type")]
     POI(PointOfInterest),
+    #[display("{_0}")]
+    CoursePoint(CoursePoint),
+    /// The closest public transport stop to the start or end of the route, and
+    /// how far away from it the stop is
+    #[display("{} ({:.1} km away)", _0.name, _1 / 1000.0)]
+    Station(PointOfInterest, f64),
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -10,11 +10,43 @@ pub trait ExternallySourced {
 #[derive(Debug, PartialEq, Eq, Hash, Clone, Serialize, Deserialize)]
 pub enum ExternalId {
     Rwgps(RwgpsId),
+    Gtfs(GtfsId),
+    Strava(StravaId),
 }
 impl ExternalId {
-    pub fn canonical_url(&self) -> url::Url {
+    pub fn canonical_url(&self) -> Option<url::Url> {
+        match self {
+            ExternalId::Rwgps(rwgps_id) => Some(rwgps_id.canonical_url()),
+            ExternalId::Gtfs(_) => None,
+            ExternalId::Strava(strava_id) => Some(strava_id.canonical_url()),
+        }
+    }
+
+    pub fn provider(&self) -> ExternalProvider {
         match self {
-            ExternalId::Rwgps(rwgps_id) => rwgps_id.canonical_url(),
+            ExternalId::Rwgps(_) => ExternalProvider::Rwgps,
+            ExternalId::Gtfs(_) => ExternalProvider::Gtfs,
+            ExternalId::Strava(_) => ExternalProvider::Strava,
+        }
+    }
+}
+
+/// Where an external item comes from.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Serialize, Deserialize)]
+pub enum ExternalProvider {
+    Rwgps,
+    Gtfs,
+    Strava,
+}
+
+impl ExternalProvider {
+    /// Whether the provider reports when an item was last changed. Strava
+    /// doesn't, so its refs carry the activity's start time and only the sync
+    /// version says whether a copy is stale. Edits arrive by webhook instead.
+    pub fn tracks_updates(&self) -> bool {
+        match self {
+            ExternalProvider::Rwgps | ExternalProvider::Gtfs => true,
+            ExternalProvider::Strava => false,
         }
     }
 }
@@ -43,6 +75,28 @@ impl RwgpsId {
     }
 }
 
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Serialize, Deserialize)]
+pub enum StravaId {
+    Activity(usize),
+}
+
+impl StravaId {
+    pub fn canonical_url(&self) -> url::Url {
+        match self {
+            StravaId::Activity(activity_id) => {
+                url::Url::parse(&format!("https://www.strava.com/activities/{activity_id}"))
+                    .unwrap()
+            }
+        }
+    }
+}
+
+/// GTFS ids are only unique within a feed, so they're namespaced by a feed name.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Serialize, Deserialize)]
+pub enum GtfsId {
+    Stop { feed: String, stop_id: String },
+}
+
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 pub struct ExternalRef {
     pub id: ExternalId,
@@ -70,6 +124,9 @@ impl<T> ExternalRefItemMap<T> {
                 .map(|external_ref| (external_ref, item))
         }))
     }
+    pub fn get(&self, id: &ExternalId) -> Option<&T> {
+        self.0.get(id).map(|(_, item)| item)
+    }
     pub fn match_ref(
         &self,
         ExternalRef {
@@ -78,10 +135,12 @@ impl<T> ExternalRefItemMap<T> {
             sync_version,
         }: ExternalRef,
     ) -> ExternalRefMatch<'_, T> {
+        let tracks_updates = id.provider().tracks_updates();
+
         match self.0.get(&id) {
             Some((external_ref, item)) => {
                 if external_ref.sync_version == sync_version
-                    && external_ref.updated_at == updated_at
+                    && (!tracks_updates || external_ref.updated_at == updated_at)
                 {
                     ExternalRefMatch::Fresh(item)
                 } else {
@@ -98,3 +157,58 @@ pub enum ExternalRefMatch<'a, T> {
     Fresh(&'a T),
     Stale(&'a T),
 }
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+
+    use super::*;
+
+    fn external_ref(id: ExternalId, day: u32) -> ExternalRef {
+        ExternalRef {
+            id,
+            updated_at: Utc.with_ymd_and_hms(2025, 3, day, 0, 0, 0).unwrap(),
+            sync_version: Some(2),
+        }
+    }
+
+    #[test]
+    fn test_match_ref_per_provider() {
+        let trip_id = ExternalId::Rwgps(RwgpsId::Trip(1));
+        let activity_id = ExternalId::Strava(StravaId::Activity(1));
+
+        let items = ExternalRefItemMap::new([
+            (external_ref(trip_id.clone(), 1), "trip"),
+            (external_ref(activity_id.clone(), 1), "activity"),
+        ]);
+
+        assert!(matches!(
+            items.match_ref(external_ref(trip_id.clone(), 1)),
+            ExternalRefMatch::Fresh(&"trip")
+        ));
+        assert!(matches!(
+            items.match_ref(external_ref(trip_id, 2)),
+            ExternalRefMatch::Stale(&"trip")
+        ));
+
+        // Strava doesn't say when an activity changed, so only the sync
+        // version counts
+        assert!(matches!(
+            items.match_ref(external_ref(activity_id.clone(), 2)),
+            ExternalRefMatch::Fresh(&"activity")
+        ));
+        assert!(matches!(
+            items.match_ref(ExternalRef {
+                sync_version: Some(1),
+                ..external_ref(activity_id, 1)
+            }),
+            ExternalRefMatch::Stale(&"activity")
+        ));
+
+        // The same number on another provider is a different item
+        assert!(matches!(
+            items.match_ref(external_ref(ExternalId::Rwgps(RwgpsId::Route(1)), 1)),
+            ExternalRefMatch::NotFound
+        ));
+    }
+}
@@ -11,3 +11,205 @@ pub enum TemporalFilter {
         first: Option<usize>,
     },
 }
+
+/// Relay-style page arguments over model ids. Ids are v7 uuids (or otherwise
+/// ordered) so ascending id order is creation order, and `after`/`before`
+/// are exclusive keyset bounds on it.
+#[derive(Debug, Clone)]
+pub struct PageRequest<Id> {
+    pub after: Option<Id>,
+    pub before: Option<Id>,
+    pub first: Option<usize>,
+    pub last: Option<usize>,
+}
+
+impl<Id> Default for PageRequest<Id> {
+    fn default() -> Self {
+        PageRequest {
+            after: None,
+            before: None,
+            first: None,
+            last: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub has_previous_page: bool,
+    pub has_next_page: bool,
+}
+
+impl<Id: Ord + Copy> PageRequest<Id> {
+    /// Whether a keyset query should walk backwards from `before`, i.e. only
+    /// `last` is limiting the page.
+    pub fn is_backward(&self) -> bool {
+        self.first.is_none() && self.last.is_some()
+    }
+
+    /// How many rows a keyset query needs to fetch in its direction: one more
+    /// than the page so `paginate` can tell whether there's another page.
+    pub fn fetch_limit(&self) -> Option<usize> {
+        if self.is_backward() {
+            self.last.map(|last| last + 1)
+        } else {
+            self.first.map(|first| first + 1)
+        }
+    }
+
+    /// Applies the page to `items`, which may be every model matching a
+    /// filter or just the rows a keyset query fetched. Items come back in
+    /// ascending id order.
+    pub fn paginate<T>(&self, items: Vec<T>, id: impl Fn(&T) -> Id) -> Page<T> {
+        self.paginate_by(items, id, self.after, self.before)
+    }
+
+    /// Like `paginate`, but orders items by `key` rather than by id, e.g.
+    /// rides by when they started. `after` and `before` are the cursors'
+    /// keys, which the repo looks up itself since a keyset query doesn't
+    /// fetch the cursor rows.
+    pub fn paginate_by<T, K: Ord>(
+        &self,
+        items: Vec<T>,
+        key: impl Fn(&T) -> K,
+        after: Option<K>,
+        before: Option<K>,
+    ) -> Page<T> {
+        let mut items = items;
+        items.sort_by_key(|item| key(item));
+
+        let len = items.len();
+        let start = match after {
+            Some(after) => items.partition_point(|item| key(item) <= after),
+            None => 0,
+        };
+        let end = match before {
+            Some(before) => items.partition_point(|item| key(item) < before),
+            None => len,
+        }
+        .max(start);
+
+        let mut has_previous_page = start > 0;
+        let mut has_next_page = end < len;
+
+        let mut items: Vec<T> = items.into_iter().take(end).skip(start).collect();
+
+        if let Some(first) = self.first {
+            if items.len() > first {
+                items.truncate(first);
+                has_next_page = true;
+            }
+        }
+
+        if let Some(last) = self.last {
+            if items.len() > last {
+                items.drain(..items.len() - last);
+                has_previous_page = true;
+            }
+        }
+
+        Page {
+            items,
+            has_previous_page,
+            has_next_page,
+        }
+    }
+}
+
+impl<T> Page<T> {
+    pub fn map<U>(self, f: impl FnMut(T) -> U) -> Page<U> {
+        Page {
+            items: self.items.into_iter().map(f).collect(),
+            has_previous_page: self.has_previous_page,
+            has_next_page: self.has_next_page,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn page(
+        after: Option<u32>,
+        before: Option<u32>,
+        first: Option<usize>,
+        last: Option<usize>,
+    ) -> PageRequest<u32> {
+        PageRequest {
+            after,
+            before,
+            first,
+            last,
+        }
+    }
+
+    #[test]
+    fn test_paginate_first_after() {
+        let result = page(Some(2), None, Some(2), None).paginate(vec![5, 1, 4, 2, 3], |n| *n);
+
+        assert_eq!(result.items, vec![3, 4]);
+        assert!(result.has_previous_page);
+        assert!(result.has_next_page);
+    }
+
+    #[test]
+    fn test_paginate_last_before() {
+        let result = page(None, Some(4), None, Some(2)).paginate(vec![1, 2, 3, 4, 5], |n| *n);
+
+        assert_eq!(result.items, vec![2, 3]);
+        assert!(result.has_previous_page);
+        assert!(result.has_next_page);
+    }
+
+    #[test]
+    fn test_paginate_by_key() {
+        // (id, key) pairs, paged in key order
+        let items = vec![(1, 'c'), (2, 'a'), (3, 'e'), (4, 'b'), (5, 'd')];
+
+        let result =
+            page(Some(4), None, Some(2), None).paginate_by(items.clone(), |n| n.1, Some('b'), None);
+
+        assert_eq!(result.items, vec![(1, 'c'), (5, 'd')]);
+        assert!(result.has_previous_page);
+        assert!(result.has_next_page);
+
+        // The cursor's key bounds the page without the cursor among the
+        // items, as when a keyset query has already left it out
+        let keyset_rows = vec![(1, 'c'), (5, 'd'), (3, 'e')];
+        let result =
+            page(Some(4), None, Some(2), None).paginate_by(keyset_rows, |n| n.1, Some('b'), None);
+
+        assert_eq!(result.items, vec![(1, 'c'), (5, 'd')]);
+        assert!(result.has_next_page);
+    }
+
+    #[test]
+    fn test_paginate_whole_range() {
+        let result = PageRequest::default().paginate(vec![3, 1, 2], |n: &u32| *n);
+
+        assert_eq!(result.items, vec![1, 2, 3]);
+        assert!(!result.has_previous_page);
+        assert!(!result.has_next_page);
+    }
+
+    #[test]
+    fn test_paginate_keyset_rows() {
+        // A backward keyset query fetched last + 1 rows before the cursor
+        let request = page(None, Some(10), None, Some(2));
+        assert_eq!(request.fetch_limit(), Some(3));
+
+        let result = request.paginate(vec![9, 8, 7], |n| *n);
+
+        assert_eq!(result.items, vec![8, 9]);
+        assert!(result.has_previous_page);
+    }
+
+    #[test]
+    fn test_paginate_crossed_bounds() {
+        let result = page(Some(4), Some(2), None, None).paginate(vec![1, 2, 3, 4, 5], |n| *n);
+
+        assert!(result.items.is_empty());
+    }
+}
@@ -0,0 +1,89 @@
+use serde::{Deserialize, Serialize};
+
+use super::{ride::RideId, route::RouteId, Model};
+
+/// The town and state a point falls in, as reverse geocoded.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct Locality {
+    /// Town, suburb or locality
+    pub name: Option<String>,
+    /// State or province
+    pub region: Option<String>,
+}
+
+impl Locality {
+    /// "Harrietville, Victoria", or whichever half is known. `None` if neither is.
+    pub fn label(&self) -> Option<String> {
+        match (&self.name, &self.region) {
+            (Some(name), Some(region)) => Some(format!("{name}, {region}")),
+            (Some(name), None) => Some(name.clone()),
+            (None, Some(region)) => Some(region.clone()),
+            (None, None) => None,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct RouteLocalities {
+    pub id: RouteId,
+    pub start: Option<Locality>,
+    pub end: Option<Locality>,
+}
+
+#[derive(Debug, Clone)]
+pub enum RouteLocalitiesFilter {
+    Ids(Vec<RouteId>),
+}
+
+impl Model for RouteLocalities {
+    type Id = RouteId;
+    type Filter = RouteLocalitiesFilter;
+
+    fn id(&self) -> RouteId {
+        self.id
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct RideLocalities {
+    pub id: RideId,
+    pub start: Option<Locality>,
+    pub finish: Option<Locality>,
+}
+
+#[derive(Debug, Clone)]
+pub enum RideLocalitiesFilter {
+    Ids(Vec<RideId>),
+}
+
+impl Model for RideLocalities {
+    type Id = RideId;
+    type Filter = RideLocalitiesFilter;
+
+    fn id(&self) -> RideId {
+        self.id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_label() {
+        let locality = |name: Option<&str>, region: Option<&str>| Locality {
+            name: name.map(String::from),
+            region: region.map(String::from),
+        };
+
+        assert_eq!(
+            locality(Some("Harrietville"), Some("Victoria")).label(),
+            Some(String::from("Harrietville, Victoria"))
+        );
+        assert_eq!(
+            locality(None, Some("Victoria")).label(),
+            Some(String::from("Victoria"))
+        );
+        assert_eq!(locality(None, None).label(), None);
+    }
+}
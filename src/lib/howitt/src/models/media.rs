@@ -3,7 +3,12 @@ use derive_more::derive::{Display, From};
 use serde::{Deserialize, Serialize};
 
 use super::{
-    point_of_interest::PointOfInterestId, ride::RideId, route::RouteId, trip::TripId, user::UserId,
+    external_ref::{ExternalRef, ExternallySourced},
+    point_of_interest::PointOfInterestId,
+    ride::RideId,
+    route::RouteId,
+    trip::TripId,
+    user::UserId,
     Model, ModelName, ModelUuid,
 };
 
@@ -56,6 +61,13 @@ pub struct Media {
     pub relation_ids: Vec<MediaRelationId>,
     pub point: Option<geo::Point<f64>>,
     pub captured_at: Option<DateTime<Utc>>,
+    pub external_ref: Option<ExternalRef>,
+}
+
+impl ExternallySourced for Media {
+    fn external_ref(&self) -> Option<&ExternalRef> {
+        self.external_ref.as_ref()
+    }
 }
 
 impl Media {
@@ -93,6 +105,7 @@ pub enum MediaFilter {
     ForRoute(RouteId),
     ForTrip(TripId),
     ForPointOfInterest(PointOfInterestId),
+    RwgpsPhotoIds(Vec<usize>),
 }
 
 impl Model for Media {
@@ -5,24 +5,31 @@ use uuid::Timestamp;
 
 pub mod cardinal_direction;
 pub mod config;
+pub mod course_point;
 pub mod cuesheet;
 pub mod external_ref;
 pub mod filters;
+pub mod locality;
 pub mod maybe_pair;
 pub mod media;
 pub mod note;
+pub mod osm_feature;
 pub mod point;
 pub mod point_of_interest;
 pub mod point_of_interest_visit;
 pub mod ride;
+pub mod ride_weather;
 pub mod route;
 pub mod route_description;
 pub mod segment_summary;
 pub mod slope_end;
+pub mod sync_audit_entry;
+pub mod sync_run;
 pub mod tag;
 pub mod terminus;
 pub mod trip;
 pub mod user;
+pub mod water_beta;
 
 pub trait Model: Send + Sync + Sized + Clone + 'static {
     type Id: ModelId;
@@ -32,7 +39,17 @@ pub trait Model: Send + Sync + Sized + Clone + 'static {
 }
 
 pub trait ModelId:
-    Send + Sync + std::fmt::Debug + std::fmt::Display + PartialEq + Copy + Clone + Hash + Eq + 'static
+    Send
+    + Sync
+    + std::fmt::Debug
+    + std::fmt::Display
+    + PartialEq
+    + Copy
+    + Clone
+    + Hash
+    + Eq
+    + Ord
+    + 'static
 {
     fn model_name() -> &'static str;
 }
@@ -47,6 +64,9 @@ pub enum ModelName {
     User,
     Trip,
     Note,
+    WaterBeta,
+    SyncAuditEntry,
+    SyncRun,
 }
 impl ModelName {
     const fn to_str(self) -> &'static str {
@@ -59,11 +79,16 @@ impl ModelName {
             ModelName::User => "USER",
             ModelName::Trip => "TRIP",
             ModelName::Note => "NOTE",
+            ModelName::WaterBeta => "WATER_BETA",
+            ModelName::SyncAuditEntry => "SYNC_AUDIT_ENTRY",
+            ModelName::SyncRun => "SYNC_RUN",
         }
     }
 }
 
-#[derive(derive_more::From, derive_more::Into, PartialEq, Eq, Hash, Clone, Copy)]
+#[derive(
+    derive_more::From, derive_more::Into, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy,
+)]
 pub struct ModelUuid<const NAME: ModelName>(uuid::Uuid);
 
 impl<const NAME: ModelName> ModelUuid<NAME> {
@@ -0,0 +1,129 @@
+use std::collections::HashMap;
+
+use geo::Centroid;
+use serde::{Deserialize, Serialize};
+
+use super::{Model, ModelId};
+
+/// OSM node and way ids are separate sequences, so way ids are stored negated
+/// to share the `osm_features` primary key.
+#[derive(
+    Debug,
+    PartialEq,
+    Eq,
+    Hash,
+    Clone,
+    Copy,
+    PartialOrd,
+    Ord,
+    Serialize,
+    Deserialize,
+    derive_more::Display,
+)]
+#[display("OSM_FEATURE#{}", _0)]
+pub struct OsmFeatureId(i64);
+
+impl OsmFeatureId {
+    pub fn node(id: i64) -> OsmFeatureId {
+        OsmFeatureId(id)
+    }
+
+    pub fn way(id: i64) -> OsmFeatureId {
+        OsmFeatureId(-id)
+    }
+
+    pub fn from_i64(id: i64) -> OsmFeatureId {
+        OsmFeatureId(id)
+    }
+
+    pub fn as_i64(&self) -> i64 {
+        self.0
+    }
+
+    pub fn osm_url(&self) -> String {
+        if self.0 < 0 {
+            format!("https://www.openstreetmap.org/way/{}", -self.0)
+        } else {
+            format!("https://www.openstreetmap.org/node/{}", self.0)
+        }
+    }
+}
+
+impl ModelId for OsmFeatureId {
+    fn model_name() -> &'static str {
+        "OSM_FEATURE"
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Serialize, Deserialize)]
+pub enum OsmFeatureType {
+    DrinkingWater,
+    Shelter,
+    CampSite,
+    Toilets,
+    Spring,
+}
+
+impl OsmFeatureType {
+    pub fn is_water_source(&self) -> bool {
+        matches!(self, OsmFeatureType::DrinkingWater | OsmFeatureType::Spring)
+    }
+
+    /// The tag whitelist for features imported from OSM extracts.
+    pub fn from_tags(tags: &HashMap<String, String>) -> Option<OsmFeatureType> {
+        let tag = |key: &str, value: &str| tags.get(key).map(String::as_str) == Some(value);
+
+        if tag("amenity", "drinking_water") {
+            Some(OsmFeatureType::DrinkingWater)
+        } else if tag("natural", "spring") {
+            Some(OsmFeatureType::Spring)
+        } else if tag("amenity", "shelter") {
+            Some(OsmFeatureType::Shelter)
+        } else if tag("tourism", "camp_site") {
+            Some(OsmFeatureType::CampSite)
+        } else if tag("amenity", "toilets") {
+            Some(OsmFeatureType::Toilets)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct OsmFeature {
+    pub id: OsmFeatureId,
+    pub geometry: geo::Geometry<f64>,
+    pub name: Option<String>,
+    pub tags: HashMap<String, String>,
+}
+
+impl OsmFeature {
+    pub fn feature_type(&self) -> Option<OsmFeatureType> {
+        OsmFeatureType::from_tags(&self.tags)
+    }
+
+    /// A single representative point, e.g. the centroid of a shelter mapped as an area.
+    pub fn point(&self) -> Option<geo::Point<f64>> {
+        self.geometry.centroid()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum OsmFeatureFilter {
+    Ids(Vec<OsmFeatureId>),
+    BoundingBox(geo::Rect<f64>),
+    /// Features within `distance_m` metres of a route line.
+    RouteCorridor {
+        points: Vec<geo::Point<f64>>,
+        distance_m: f64,
+    },
+}
+
+impl Model for OsmFeature {
+    type Id = OsmFeatureId;
+    type Filter = OsmFeatureFilter;
+
+    fn id(&self) -> OsmFeatureId {
+        self.id
+    }
+}
@@ -2,7 +2,9 @@ use crate::ext::iter::ScanAllExt;
 use geo::{Bearing, Distance, Haversine};
 use itertools::Itertools;
 
-use super::{Point, WithDatetime, WithElevation};
+use crate::models::ride_weather::WindRelation;
+
+use super::{Point, WithDatetime, WithElevation, WithWind};
 
 pub trait Delta<P> {
     fn delta(value1: &P, value2: &P) -> Self;
@@ -193,6 +195,65 @@ impl<P: Point + WithDatetime> AccumulatingDelta<P> for MovingDelta {
     }
 }
 
+#[derive(Debug)]
+pub struct WindComponentDelta {
+    pub headwind_kmh: f64, // Negative for a tailwind
+    pub crosswind_kmh: f64,
+}
+
+impl<P: WithWind> Delta<P> for WindComponentDelta {
+    fn delta(value1: &P, value2: &P) -> Self {
+        let BearingDelta(bearing) = BearingDelta::delta(value1, value2);
+
+        // Use the wind where the rider set off from
+        match value1.wind() {
+            Some(wind) => WindComponentDelta {
+                headwind_kmh: wind.headwind_kmh(bearing),
+                crosswind_kmh: wind.crosswind_kmh(bearing),
+            },
+            None => WindComponentDelta {
+                headwind_kmh: 0.0,
+                crosswind_kmh: 0.0,
+            },
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct HeadwindDelta(pub chrono::Duration); // Track moving time spent riding into a headwind
+
+impl<P: WithDatetime + WithWind> Delta<P> for HeadwindDelta {
+    fn delta(value1: &P, value2: &P) -> Self {
+        let MovingDelta(moving) = MovingDelta::delta(value1, value2);
+        let BearingDelta(bearing) = BearingDelta::delta(value1, value2);
+
+        let relation = value1.wind().and_then(|wind| wind.relation(bearing));
+
+        if relation == Some(WindRelation::Headwind) {
+            HeadwindDelta(moving)
+        } else {
+            HeadwindDelta(chrono::Duration::zero())
+        }
+    }
+}
+
+impl<P: WithDatetime + WithWind> AccumulatingDelta<P> for HeadwindDelta {
+    fn running_totals(values: &[P]) -> Vec<Self> {
+        std::iter::once(HeadwindDelta(chrono::Duration::zero()))
+            .chain(
+                values
+                    .iter()
+                    .tuple_windows()
+                    .map(|(a, b)| Self::delta(a, b)),
+            )
+            .scan_all(chrono::Duration::zero(), |acc, HeadwindDelta(d)| {
+                *acc = *acc + d;
+                HeadwindDelta(*acc)
+            })
+            .collect()
+    }
+}
+
 // ----------
 
 impl<P, T1> Delta<P> for (T1,)
@@ -363,7 +424,9 @@ mod tests {
     use super::*;
     use crate::models::point::{
         elevation_point::ElevationPoint, temporal_elevation_point::TemporalElevationPoint,
+        wind_point::WindPoint,
     };
+    use crate::models::ride_weather::Wind;
 
     #[test]
     fn test_distance_delta() {
@@ -578,4 +641,48 @@ mod tests {
         let deltas = <(SpeedDelta, MovingDelta)>::delta(&p1, &p2);
         insta::assert_debug_snapshot!(deltas);
     }
+
+    #[test]
+    fn test_headwind_delta() {
+        let easterly = Some(Wind {
+            speed_kmh: 20.0,
+            direction: 90.0,
+        });
+
+        let point = |lng: f64, minutes: i64, wind: Option<Wind>| WindPoint {
+            point: TemporalElevationPoint {
+                point: GeoPoint::new(lng, -37.0),
+                elevation: 100.0,
+                datetime: Utc.timestamp_opt(minutes * 60, 0).unwrap(),
+            },
+            wind,
+        };
+
+        let points = vec![
+            // East for half an hour, into the wind
+            point(145.0, 0, easterly),
+            // Back west for 15 minutes with it behind
+            point(145.1, 30, easterly),
+            // East again, but with no weather for the hour
+            point(145.05, 45, None),
+            point(145.1, 60, None),
+        ];
+
+        let totals = <(ElapsedDelta, HeadwindDelta)>::running_totals(&points);
+        let headwind = totals
+            .iter()
+            .map(|(_, HeadwindDelta(d))| d.num_minutes())
+            .collect_vec();
+
+        assert_eq!(headwind, vec![0, 30, 30, 30]);
+        assert_eq!(totals.last().unwrap().0 .0.num_minutes(), 60);
+
+        let WindComponentDelta {
+            headwind_kmh,
+            crosswind_kmh,
+        } = WindComponentDelta::delta(&points[1], &points[2]);
+
+        assert!((headwind_kmh + 20.0).abs() < 0.1);
+        assert!(crosswind_kmh < 0.1);
+    }
 }
@@ -4,8 +4,10 @@ pub mod elevation_point;
 pub mod point;
 pub mod progress;
 pub mod temporal_elevation_point;
+pub mod wind_point;
 
 pub use closest_point::*;
 pub use elevation_point::*;
 pub use point::*;
 pub use temporal_elevation_point::*;
+pub use wind_point::*;
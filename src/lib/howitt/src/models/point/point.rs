@@ -38,6 +38,10 @@ pub trait WithDatetime: Point {
     fn datetime(&self) -> &chrono::DateTime<chrono::Utc>;
 }
 
+pub trait WithWind: Point {
+    fn wind(&self) -> Option<&crate::models::ride_weather::Wind>;
+}
+
 impl Point for geo::Point {
     fn as_geo_point(&self) -> &geo::Point {
         self
@@ -0,0 +1,36 @@
+use chrono::{DateTime, Utc};
+
+use crate::models::ride_weather::Wind;
+
+use super::{point::Point, WithDatetime, WithElevation, WithWind};
+
+/// A point paired with the wind blowing there when the rider passed through.
+#[derive(Debug, PartialEq, Clone)]
+pub struct WindPoint<P> {
+    pub point: P,
+    pub wind: Option<Wind>,
+}
+
+impl<P: Point> Point for WindPoint<P> {
+    fn as_geo_point(&self) -> &geo::Point {
+        self.point.as_geo_point()
+    }
+}
+
+impl<P: WithElevation> WithElevation for WindPoint<P> {
+    fn elevation(&self) -> f64 {
+        self.point.elevation()
+    }
+}
+
+impl<P: WithDatetime> WithDatetime for WindPoint<P> {
+    fn datetime(&self) -> &DateTime<Utc> {
+        self.point.datetime()
+    }
+}
+
+impl<P: Point> WithWind for WindPoint<P> {
+    fn wind(&self) -> Option<&Wind> {
+        self.wind.as_ref()
+    }
+}
@@ -1,6 +1,10 @@
 use serde::{Deserialize, Serialize};
 
-use super::{user::UserId, Model, ModelName, ModelUuid};
+use super::{
+    external_ref::{ExternalRef, ExternallySourced},
+    user::UserId,
+    Model, ModelName, ModelUuid,
+};
 
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 pub enum PointOfInterestType {
@@ -11,6 +15,16 @@ pub enum PointOfInterestType {
     Generic,
 }
 
+impl PointOfInterestType {
+    pub const ALL: [PointOfInterestType; 5] = [
+        PointOfInterestType::PublicTransportStop,
+        PointOfInterestType::Campsite,
+        PointOfInterestType::WaterSource,
+        PointOfInterestType::Hut,
+        PointOfInterestType::Generic,
+    ];
+}
+
 pub type PointOfInterestId = ModelUuid<{ ModelName::PointOfInterest }>;
 
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
@@ -22,11 +36,31 @@ pub struct PointOfInterest {
     pub point: geo::Point<f64>,
     pub point_of_interest_type: PointOfInterestType,
     pub description: Option<String>,
+    pub external_ref: Option<ExternalRef>,
+}
+
+impl ExternallySourced for PointOfInterest {
+    fn external_ref(&self) -> Option<&ExternalRef> {
+        self.external_ref.as_ref()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum PointOfInterestFilter {
+    All,
+    BoundingBox(geo::Rect<f64>),
+    /// Within `distance_m` metres of `point`, closest first
+    Near {
+        point: geo::Point<f64>,
+        distance_m: f64,
+    },
+    /// Names matching the search terms, best match first
+    Search(String),
 }
 
 impl Model for PointOfInterest {
     type Id = PointOfInterestId;
-    type Filter = ();
+    type Filter = PointOfInterestFilter;
 
     fn id(&self) -> PointOfInterestId {
         PointOfInterestId::from(self.id)
@@ -1,7 +1,11 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
-use super::{media::MediaId, point_of_interest::PointOfInterestId, user::UserId};
+use super::{
+    media::MediaId, point_of_interest::PointOfInterestId, user::UserId, Model, ModelName, ModelUuid,
+};
+
+pub type PointOfInterestVisitId = ModelUuid<{ ModelName::PointOfInterestVisit }>;
 
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 pub enum VisitConfirmation {
@@ -18,6 +22,8 @@ pub enum POICondition {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PointOfInterestVisit {
+    pub id: PointOfInterestVisitId,
+    pub created_at: DateTime<Utc>,
     pub user_id: UserId,
     pub point_of_interest_id: PointOfInterestId,
     pub visited_at: DateTime<Utc>,
@@ -26,3 +32,174 @@ pub struct PointOfInterestVisit {
     pub comment: Option<String>,
     pub media_ids: Vec<MediaId>,
 }
+
+/// What a visitor can change about their visit.
+#[derive(Debug, PartialEq, Clone)]
+pub struct VisitEdit {
+    pub visited_at: DateTime<Utc>,
+    pub condition: Option<POICondition>,
+    pub comment: Option<String>,
+    pub media_ids: Vec<MediaId>,
+}
+
+#[derive(Debug, PartialEq, thiserror::Error)]
+pub enum VisitConfirmationError {
+    #[error("Only pending visits can be confirmed or rejected")]
+    NotPending,
+    #[error("Visits can only be moved to confirmed or rejected")]
+    InvalidConfirmation,
+}
+
+impl PointOfInterestVisit {
+    /// Moves a pending visit to confirmed or rejected.
+    pub fn set_confirmation(
+        &mut self,
+        confirmation: VisitConfirmation,
+    ) -> Result<(), VisitConfirmationError> {
+        match (&self.confirmation, &confirmation) {
+            (VisitConfirmation::Pending, VisitConfirmation::Pending) => {
+                Err(VisitConfirmationError::InvalidConfirmation)
+            }
+            (VisitConfirmation::Pending, _) => {
+                self.confirmation = confirmation;
+                Ok(())
+            }
+            _ => Err(VisitConfirmationError::NotPending),
+        }
+    }
+
+    fn current_edit(&self) -> VisitEdit {
+        VisitEdit {
+            visited_at: self.visited_at,
+            condition: self.condition.clone(),
+            comment: self.comment.clone(),
+            media_ids: self.media_ids.clone(),
+        }
+    }
+
+    /// Applies an edit to the visit. Anyone but the POI owner changing it
+    /// sends it back to pending, so the owner never vouches for content they
+    /// haven't seen.
+    pub fn edit(&mut self, edit: VisitEdit, editor_id: UserId, poi_owner_id: UserId) {
+        if edit == self.current_edit() {
+            return;
+        }
+
+        if editor_id != poi_owner_id {
+            self.confirmation = VisitConfirmation::Pending;
+        }
+
+        let VisitEdit {
+            visited_at,
+            condition,
+            comment,
+            media_ids,
+        } = edit;
+
+        self.visited_at = visited_at;
+        self.condition = condition;
+        self.comment = comment;
+        self.media_ids = media_ids;
+    }
+}
+
+impl Model for PointOfInterestVisit {
+    type Id = PointOfInterestVisitId;
+    type Filter = PointOfInterestVisitFilter;
+
+    fn id(&self) -> Self::Id {
+        self.id
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum PointOfInterestVisitFilter {
+    All,
+    Ids(Vec<PointOfInterestVisitId>),
+    ForPointOfInterest(PointOfInterestId),
+    ForUser(UserId),
+    /// Visits between two instants, optionally narrowed to a POI and/or user
+    VisitedBetween {
+        point_of_interest_id: Option<PointOfInterestId>,
+        user_id: Option<UserId>,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    },
+    /// Pending visits to POIs owned by the given user, awaiting their confirmation
+    PendingForOwner(UserId),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_test_visit(confirmation: VisitConfirmation) -> PointOfInterestVisit {
+        PointOfInterestVisit {
+            id: PointOfInterestVisitId::new(),
+            created_at: Utc::now(),
+            user_id: UserId::new(),
+            point_of_interest_id: PointOfInterestId::new(),
+            visited_at: Utc::now(),
+            confirmation,
+            condition: None,
+            comment: None,
+            media_ids: vec![],
+        }
+    }
+
+    #[test]
+    fn test_set_confirmation_from_pending() {
+        let mut visit = create_test_visit(VisitConfirmation::Pending);
+
+        assert_eq!(visit.set_confirmation(VisitConfirmation::Confirmed), Ok(()));
+        assert_eq!(visit.confirmation, VisitConfirmation::Confirmed);
+    }
+
+    #[test]
+    fn test_set_confirmation_rejects_invalid_transitions() {
+        let mut visit = create_test_visit(VisitConfirmation::Pending);
+        assert_eq!(
+            visit.set_confirmation(VisitConfirmation::Pending),
+            Err(VisitConfirmationError::InvalidConfirmation)
+        );
+
+        let mut visit = create_test_visit(VisitConfirmation::Rejected);
+        assert_eq!(
+            visit.set_confirmation(VisitConfirmation::Confirmed),
+            Err(VisitConfirmationError::NotPending)
+        );
+    }
+
+    #[test]
+    fn test_edit_by_visitor_needs_confirming_again() {
+        let mut visit = create_test_visit(VisitConfirmation::Confirmed);
+        let poi_owner_id = UserId::new();
+
+        // Saving the visit as it is keeps the owner's confirmation
+        visit.edit(visit.current_edit(), visit.user_id, poi_owner_id);
+        assert_eq!(visit.confirmation, VisitConfirmation::Confirmed);
+
+        let edit = VisitEdit {
+            comment: Some(String::from("Tank's dry")),
+            ..visit.current_edit()
+        };
+        visit.edit(edit, visit.user_id, poi_owner_id);
+
+        assert_eq!(visit.comment.as_deref(), Some("Tank's dry"));
+        assert_eq!(visit.confirmation, VisitConfirmation::Pending);
+    }
+
+    #[test]
+    fn test_edit_by_poi_owner_keeps_confirmation() {
+        let mut visit = create_test_visit(VisitConfirmation::Confirmed);
+
+        let edit = VisitEdit {
+            condition: Some(POICondition::Issue),
+            ..visit.current_edit()
+        };
+        visit.edit(edit, visit.user_id, visit.user_id);
+
+        assert_eq!(visit.condition, Some(POICondition::Issue));
+        assert_eq!(visit.confirmation, VisitConfirmation::Confirmed);
+    }
+}
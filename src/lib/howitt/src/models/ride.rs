@@ -5,8 +5,11 @@ use serde::{Deserialize, Serialize};
 use crate::models::{external_ref::ExternalRef, point::TemporalElevationPoint};
 
 use super::{
-    external_ref::ExternallySourced, filters::TemporalFilter, trip::TripId, user::UserId, Model,
-    ModelName, ModelUuid,
+    external_ref::{ExternalId, ExternallySourced},
+    filters::TemporalFilter,
+    trip::TripId,
+    user::UserId,
+    Model, ModelName, ModelUuid,
 };
 
 pub type RideId = ModelUuid<{ ModelName::Ride }>;
@@ -20,6 +23,10 @@ pub struct Ride {
     pub started_at: DateTime<Utc>,
     pub finished_at: DateTime<Utc>,
     pub external_ref: Option<ExternalRef>,
+    /// Set when the ride was removed from its source. Archived rides are kept
+    /// but left out of listings.
+    #[serde(default)]
+    pub archived_at: Option<DateTime<Utc>>,
 }
 
 impl Model for Ride {
@@ -37,6 +44,8 @@ impl ExternallySourced for Ride {
     }
 }
 
+/// Archived rides are left out, except when they're looked up by id or
+/// external ref, or with `SyncedForUser`, which sync uses to bring them back.
 #[derive(Debug, Clone)]
 pub enum RideFilter {
     All,
@@ -51,6 +60,9 @@ pub enum RideFilter {
     },
     ForTrip(TripId),
     RwgpsId(usize),
+    ExternalId(ExternalId),
+    /// Every ride synced from a provider for the user, archived ones included
+    SyncedForUser(UserId),
 }
 
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
@@ -67,3 +79,36 @@ impl Model for RidePoints {
         RideId::from(self.id)
     }
 }
+
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct SensorSample {
+    pub datetime: DateTime<Utc>,
+    pub heart_rate: Option<u8>,
+    pub cadence: Option<u8>,
+    pub power: Option<u16>,
+    pub temperature: Option<i8>,
+}
+
+impl SensorSample {
+    pub fn is_empty(&self) -> bool {
+        self.heart_rate.is_none()
+            && self.cadence.is_none()
+            && self.power.is_none()
+            && self.temperature.is_none()
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct RideSensors {
+    pub id: RideId,
+    pub samples: Vec<SensorSample>,
+}
+
+impl Model for RideSensors {
+    type Id = RideId;
+    type Filter = ();
+
+    fn id(&self) -> RideId {
+        self.id
+    }
+}
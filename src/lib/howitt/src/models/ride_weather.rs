@@ -0,0 +1,256 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::{ride::RideId, Model};
+
+/// Below this wind speed (km/h) there's no meaningful headwind or tailwind.
+pub const CALM_WIND_SPEED_KMH: f64 = 5.0;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+pub enum WindRelation {
+    Headwind,
+    Crosswind,
+    Tailwind,
+}
+
+impl WindRelation {
+    /// Classifies wind blowing from `wind_direction` for a rider heading
+    /// along `bearing`, both in degrees clockwise from north.
+    pub fn from_directions(bearing: f64, wind_direction: f64) -> WindRelation {
+        let difference = (wind_direction - bearing).rem_euclid(360.0);
+        let difference = difference.min(360.0 - difference);
+
+        if difference <= 45.0 {
+            WindRelation::Headwind
+        } else if difference >= 135.0 {
+            WindRelation::Tailwind
+        } else {
+            WindRelation::Crosswind
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy, Serialize, Deserialize)]
+pub struct Wind {
+    pub speed_kmh: f64,
+    /// Degrees the wind is blowing from
+    pub direction: f64,
+}
+
+impl Wind {
+    /// How the wind meets a rider heading along `bearing`, or `None` if it's
+    /// too calm to matter.
+    pub fn relation(&self, bearing: f64) -> Option<WindRelation> {
+        if self.speed_kmh < CALM_WIND_SPEED_KMH {
+            return None;
+        }
+
+        Some(WindRelation::from_directions(bearing, self.direction))
+    }
+
+    /// Component of the wind blowing against a rider heading along `bearing`.
+    /// Negative when it's behind them.
+    pub fn headwind_kmh(&self, bearing: f64) -> f64 {
+        self.speed_kmh * (self.direction - bearing).to_radians().cos()
+    }
+
+    /// Component of the wind blowing across a rider heading along `bearing`,
+    /// from either side.
+    pub fn crosswind_kmh(&self, bearing: f64) -> f64 {
+        (self.speed_kmh * (self.direction - bearing).to_radians().sin()).abs()
+    }
+}
+
+/// Observed weather for one hour of a ride, at the rider's position at the
+/// start of that hour.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct HourlyWeather {
+    pub datetime: DateTime<Utc>,
+    pub point: geo::Point,
+    /// Rider's overall heading through the hour in degrees, if they moved far
+    /// enough for it to mean anything
+    pub bearing: Option<f64>,
+    /// Degrees Celsius
+    pub temperature: f64,
+    /// Millimetres over the hour
+    pub precipitation: f64,
+    /// km/h at 10m
+    pub wind_speed: f64,
+    /// Degrees the wind is blowing from
+    pub wind_direction: f64,
+}
+
+impl HourlyWeather {
+    pub fn wind(&self) -> Wind {
+        Wind {
+            speed_kmh: self.wind_speed,
+            direction: self.wind_direction,
+        }
+    }
+
+    pub fn wind_relation(&self) -> Option<WindRelation> {
+        self.bearing
+            .and_then(|bearing| self.wind().relation(bearing))
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct RideWeather {
+    pub id: RideId,
+    pub hours: Vec<HourlyWeather>,
+}
+
+impl RideWeather {
+    pub fn max_temperature(&self) -> Option<f64> {
+        self.hours
+            .iter()
+            .map(|hour| hour.temperature)
+            .max_by(f64::total_cmp)
+    }
+
+    pub fn min_temperature(&self) -> Option<f64> {
+        self.hours
+            .iter()
+            .map(|hour| hour.temperature)
+            .min_by(f64::total_cmp)
+    }
+
+    pub fn total_precipitation(&self) -> f64 {
+        self.hours.iter().map(|hour| hour.precipitation).sum()
+    }
+
+    pub fn max_wind_speed(&self) -> Option<f64> {
+        self.hours
+            .iter()
+            .map(|hour| hour.wind_speed)
+            .max_by(f64::total_cmp)
+    }
+
+    /// Fraction of the hours with a known heading spent riding into a
+    /// headwind. Calm hours count against it.
+    pub fn headwind_share(&self) -> Option<f64> {
+        let hours_with_bearing = self
+            .hours
+            .iter()
+            .filter(|hour| hour.bearing.is_some())
+            .count();
+
+        if hours_with_bearing == 0 {
+            return None;
+        }
+
+        let headwind_hours = self
+            .hours
+            .iter()
+            .filter(|hour| hour.wind_relation() == Some(WindRelation::Headwind))
+            .count();
+
+        Some(headwind_hours as f64 / hours_with_bearing as f64)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum RideWeatherFilter {
+    Ids(Vec<RideId>),
+}
+
+impl Model for RideWeather {
+    type Id = RideId;
+    type Filter = RideWeatherFilter;
+
+    fn id(&self) -> RideId {
+        self.id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    fn hour(
+        bearing: Option<f64>,
+        temperature: f64,
+        wind_speed: f64,
+        wind_direction: f64,
+    ) -> HourlyWeather {
+        HourlyWeather {
+            datetime: Utc.with_ymd_and_hms(2024, 12, 15, 0, 0, 0).unwrap(),
+            point: geo::Point::new(145.7, -37.75),
+            bearing,
+            temperature,
+            precipitation: 0.5,
+            wind_speed,
+            wind_direction,
+        }
+    }
+
+    #[test]
+    fn test_wind_relation() {
+        assert_eq!(
+            WindRelation::from_directions(90.0, 100.0),
+            WindRelation::Headwind
+        );
+        assert_eq!(
+            WindRelation::from_directions(350.0, 20.0),
+            WindRelation::Headwind
+        );
+        assert_eq!(
+            WindRelation::from_directions(90.0, 180.0),
+            WindRelation::Crosswind
+        );
+        assert_eq!(
+            WindRelation::from_directions(90.0, 260.0),
+            WindRelation::Tailwind
+        );
+    }
+
+    #[test]
+    fn test_wind_components() {
+        let wind = Wind {
+            speed_kmh: 20.0,
+            direction: 90.0,
+        };
+
+        assert!((wind.headwind_kmh(90.0) - 20.0).abs() < 1e-9);
+        assert!((wind.headwind_kmh(270.0) + 20.0).abs() < 1e-9);
+        assert!(wind.headwind_kmh(0.0).abs() < 1e-9);
+        assert!((wind.crosswind_kmh(0.0) - 20.0).abs() < 1e-9);
+        assert!((wind.crosswind_kmh(180.0) - 20.0).abs() < 1e-9);
+        assert!(wind.crosswind_kmh(90.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_aggregates() {
+        let weather = RideWeather {
+            id: RideId::new(),
+            hours: vec![
+                // Heading east into an easterly
+                hour(Some(90.0), 20.9, 12.0, 80.0),
+                // Heading east with a westerly behind
+                hour(Some(90.0), 25.8, 12.0, 270.0),
+                // Heading east into a barely-there easterly
+                hour(Some(90.0), 27.9, 2.0, 90.0),
+                // Stopped
+                hour(None, 22.4, 20.0, 90.0),
+            ],
+        };
+
+        assert_eq!(weather.max_temperature(), Some(27.9));
+        assert_eq!(weather.min_temperature(), Some(20.9));
+        assert_eq!(weather.max_wind_speed(), Some(20.0));
+        assert_eq!(weather.total_precipitation(), 2.0);
+        assert_eq!(weather.headwind_share(), Some(1.0 / 3.0));
+    }
+
+    #[test]
+    fn test_headwind_share_without_bearings() {
+        let weather = RideWeather {
+            id: RideId::new(),
+            hours: vec![hour(None, 20.0, 10.0, 90.0)],
+        };
+
+        assert_eq!(weather.headwind_share(), None);
+    }
+}
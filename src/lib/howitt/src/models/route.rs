@@ -4,7 +4,10 @@ use itertools::Itertools;
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    models::{external_ref::ExternalRef, point::ElevationPoint},
+    models::{
+        external_ref::{ExternalId, ExternalRef},
+        point::ElevationPoint,
+    },
     services::nearby::{nearby_routes, NearbyRoute},
 };
 
@@ -31,6 +34,10 @@ pub struct Route {
     pub external_ref: Option<ExternalRef>,
     #[serde(default)]
     pub tags: HashSet<Tag>,
+    /// Set when the route was removed from its source. Archived routes are
+    /// kept but left out of listings.
+    #[serde(default)]
+    pub archived_at: Option<chrono::DateTime<chrono::Utc>>,
 }
 
 impl Route {
@@ -83,13 +90,20 @@ impl ExternallySourced for Route {
     }
 }
 
+/// Archived routes are left out, except when they're looked up by external
+/// ref, or with `SyncedForUser`, which sync uses to bring them back.
 #[derive(Debug, Clone)]
 pub enum RouteFilter {
     Starred,
     All,
     Slug(String),
     RwgpsId(usize),
+    ExternalId(ExternalId),
     UserId(UserId),
+    /// Names matching the search terms, best match first
+    Search(String),
+    /// Every route synced from a provider for the user, archived ones included
+    SyncedForUser(UserId),
 }
 
 impl Model for Route {
@@ -0,0 +1,52 @@
+use chrono::{DateTime, Utc};
+use derive_more::From;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    external_ref::ExternalId, ride::RideId, route::RouteId, trip::TripId, user::UserId, Model,
+    ModelName, ModelUuid,
+};
+
+pub type SyncAuditEntryId = ModelUuid<{ ModelName::SyncAuditEntry }>;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, From, Serialize, Deserialize)]
+pub enum SyncedItemId {
+    Route(RouteId),
+    Ride(RideId),
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+pub enum SyncAuditAction {
+    /// Kept, but left out of listings
+    Archived,
+    Deleted,
+}
+
+/// A record of a synced item being removed because it went away at its
+/// source.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct SyncAuditEntry {
+    pub id: SyncAuditEntryId,
+    pub created_at: DateTime<Utc>,
+    pub user_id: UserId,
+    pub external_id: ExternalId,
+    pub item_id: SyncedItemId,
+    pub action: SyncAuditAction,
+    /// Trips the item was taken out of
+    pub detached_trip_ids: Vec<TripId>,
+}
+
+impl Model for SyncAuditEntry {
+    type Id = SyncAuditEntryId;
+    type Filter = SyncAuditEntryFilter;
+
+    fn id(&self) -> Self::Id {
+        self.id
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum SyncAuditEntryFilter {
+    /// Most recent first
+    ForUser(UserId),
+}
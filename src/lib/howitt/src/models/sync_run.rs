@@ -0,0 +1,156 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::{external_ref::ExternalProvider, user::UserId, Model, ModelName, ModelUuid};
+
+pub type SyncRunId = ModelUuid<{ ModelName::SyncRun }>;
+
+/// How far a connection has synced. Items changed at or before `updated_at`
+/// were synced by `sync_version`, so a later sync only has to fetch what
+/// changed since. A cursor from an older sync version is ignored.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+pub struct SyncCursor {
+    pub updated_at: DateTime<Utc>,
+    pub sync_version: usize,
+}
+
+/// A history sync, and how far through the items it queued the worker is.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct SyncRun {
+    pub id: SyncRunId,
+    pub created_at: DateTime<Utc>,
+    pub user_id: UserId,
+    pub provider: ExternalProvider,
+    /// The cursor the run picked up from, or `None` if it compared everything
+    pub since: Option<SyncCursor>,
+    /// The newest change the run listed. It becomes the connection's cursor
+    /// if every item syncs.
+    pub cursor: Option<SyncCursor>,
+    pub queued: usize,
+    pub succeeded: usize,
+    pub failed: usize,
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+impl SyncRun {
+    pub fn new(
+        user_id: UserId,
+        provider: ExternalProvider,
+        since: Option<SyncCursor>,
+        cursor: Option<SyncCursor>,
+        queued: usize,
+        now: DateTime<Utc>,
+    ) -> SyncRun {
+        SyncRun {
+            id: SyncRunId::new(),
+            created_at: now,
+            user_id,
+            provider,
+            since,
+            cursor,
+            queued,
+            succeeded: 0,
+            failed: 0,
+            finished_at: (queued == 0).then_some(now),
+        }
+    }
+
+    /// Counts an item's result, finishing the run once every queued item is
+    /// accounted for.
+    pub fn record(&mut self, succeeded: bool, now: DateTime<Utc>) {
+        if succeeded {
+            self.succeeded += 1;
+        } else {
+            self.failed += 1;
+        }
+
+        if self.finished_at.is_none() && self.succeeded + self.failed >= self.queued {
+            self.finished_at = Some(now);
+        }
+    }
+
+    /// The cursor to move the connection to, once the run has finished
+    /// without failures. Failed items are picked up again by the next run.
+    pub fn completed_cursor(&self) -> Option<SyncCursor> {
+        match (self.finished_at, self.failed) {
+            (Some(_), 0) => self.cursor,
+            _ => None,
+        }
+    }
+}
+
+impl Model for SyncRun {
+    type Id = SyncRunId;
+    type Filter = SyncRunFilter;
+
+    fn id(&self) -> Self::Id {
+        self.id
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum SyncRunFilter {
+    /// Most recent first, the latest `limit` of them if given
+    ForUser {
+        user_id: UserId,
+        limit: Option<usize>,
+    },
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    #[test]
+    fn test_record() {
+        let now = Utc.with_ymd_and_hms(2025, 3, 1, 0, 0, 0).unwrap();
+        let cursor = SyncCursor {
+            updated_at: now,
+            sync_version: 2,
+        };
+
+        let mut run = SyncRun::new(
+            UserId::new(),
+            ExternalProvider::Rwgps,
+            None,
+            Some(cursor),
+            2,
+            now,
+        );
+        assert_eq!(run.completed_cursor(), None);
+
+        run.record(true, now);
+        assert_eq!(run.finished_at, None);
+
+        run.record(true, now);
+        assert_eq!(run.finished_at, Some(now));
+        assert_eq!(run.completed_cursor(), Some(cursor));
+
+        // A failure holds the cursor back, so the item is retried
+        let mut run = SyncRun::new(
+            UserId::new(),
+            ExternalProvider::Rwgps,
+            None,
+            Some(cursor),
+            2,
+            now,
+        );
+        run.record(true, now);
+        run.record(false, now);
+        assert_eq!(run.finished_at, Some(now));
+        assert_eq!(run.completed_cursor(), None);
+
+        // Nothing to sync finishes straight away
+        let run = SyncRun::new(
+            UserId::new(),
+            ExternalProvider::Rwgps,
+            Some(cursor),
+            Some(cursor),
+            0,
+            now,
+        );
+        assert_eq!(run.completed_cursor(), Some(cursor));
+    }
+}
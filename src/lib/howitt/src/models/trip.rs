@@ -24,7 +24,12 @@ pub enum TripFilter {
     All,
     Published,
     User(UserId),
-    WithUserAndSlug { user_id: UserId, slug: String },
+    WithUserAndSlug {
+        user_id: UserId,
+        slug: String,
+    },
+    /// Names matching the search terms, best match first
+    Search(String),
 }
 
 impl Model for Trip {
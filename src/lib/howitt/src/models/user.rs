@@ -1,9 +1,9 @@
 use argon2::password_hash::PasswordHashString;
-use chrono::{serde::ts_seconds, DateTime, Utc};
+use chrono::{serde::ts_seconds, DateTime, Duration, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use super::{Model, ModelName, ModelUuid};
+use super::{sync_run::SyncCursor, Model, ModelName, ModelUuid};
 
 pub type UserId = ModelUuid<{ ModelName::User }>;
 
@@ -24,11 +24,57 @@ pub struct UserRwgpsConnection {
     pub rwgps_user_id: i32,
     pub access_token: String,
     pub created_at: DateTime<Utc>,
+    /// When the token or status last changed
     pub updated_at: DateTime<Utc>,
+    /// Jobs queued before this was added have no status
+    #[serde(default)]
+    pub status: RwgpsConnectionStatus,
+    /// Where the next history sync picks up from
+    #[serde(default)]
+    pub sync_cursor: Option<SyncCursor>,
+}
+
+/// How RWGPS last answered requests made with the connection's token.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default, Serialize, Deserialize)]
+pub enum RwgpsConnectionStatus {
+    #[default]
+    Ok,
+    /// The token was revoked or has expired, so the user needs to reconnect
+    Unauthorised,
+    RateLimited,
+}
+
+/// How long sync jobs are held off after RWGPS rate limits a connection
+pub fn rwgps_rate_limit_pause() -> Duration {
+    Duration::minutes(15)
+}
+
+impl UserRwgpsConnection {
+    /// Whether the user's sync jobs should be skipped. An unauthorised
+    /// connection stays paused until the user reconnects, which queues a
+    /// history sync to catch up on whatever was skipped.
+    pub fn is_paused(&self, now: DateTime<Utc>) -> bool {
+        match self.status {
+            RwgpsConnectionStatus::Ok => false,
+            RwgpsConnectionStatus::Unauthorised => true,
+            RwgpsConnectionStatus::RateLimited => now - self.updated_at < rwgps_rate_limit_pause(),
+        }
+    }
+
+    /// When a rate limited connection's pause is up. Unauthorised connections
+    /// wait for the user to reconnect instead.
+    pub fn resumes_at(&self) -> Option<DateTime<Utc>> {
+        match self.status {
+            RwgpsConnectionStatus::RateLimited => Some(self.updated_at + rwgps_rate_limit_pause()),
+            RwgpsConnectionStatus::Ok | RwgpsConnectionStatus::Unauthorised => None,
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
 pub enum UserFilter {
+    /// Users with an RWGPS connection, the ones listed publicly.
+    Public,
     Username(String),
     Email(String),
     Ids(Vec<UserId>),
@@ -53,3 +99,40 @@ pub struct UserSession {
     #[serde(rename = "iat", with = "ts_seconds")]
     pub issued_at: DateTime<Utc>,
 }
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    #[test]
+    fn test_is_paused() {
+        let updated_at = Utc.with_ymd_and_hms(2025, 2, 12, 8, 0, 0).unwrap();
+        let connection = |status| UserRwgpsConnection {
+            id: Uuid::nil(),
+            user_id: UserId::new(),
+            rwgps_user_id: 1,
+            access_token: String::from("token"),
+            created_at: updated_at,
+            updated_at,
+            status,
+            sync_cursor: None,
+        };
+        let later = |minutes| updated_at + Duration::minutes(minutes);
+
+        assert!(!connection(RwgpsConnectionStatus::Ok).is_paused(later(0)));
+        assert!(connection(RwgpsConnectionStatus::RateLimited).is_paused(later(5)));
+        assert!(!connection(RwgpsConnectionStatus::RateLimited).is_paused(later(15)));
+        assert!(connection(RwgpsConnectionStatus::Unauthorised).is_paused(later(24 * 60)));
+
+        assert_eq!(
+            connection(RwgpsConnectionStatus::RateLimited).resumes_at(),
+            Some(later(15))
+        );
+        assert_eq!(
+            connection(RwgpsConnectionStatus::Unauthorised).resumes_at(),
+            None
+        );
+    }
+}
@@ -0,0 +1,44 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::{media::MediaId, osm_feature::OsmFeatureId, user::UserId, Model, ModelName, ModelUuid};
+
+pub type WaterBetaId = ModelUuid<{ ModelName::WaterBeta }>;
+
+#[derive(Debug, PartialEq, Clone, Copy, Serialize, Deserialize)]
+pub enum WaterFlowStatus {
+    Flowing,
+    Trickle,
+    Dry,
+}
+
+/// A rider's report on the state of a water source at a point in time.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WaterBeta {
+    pub id: WaterBetaId,
+    pub created_at: DateTime<Utc>,
+    pub user_id: UserId,
+    pub osm_feature_id: OsmFeatureId,
+    pub observed_at: DateTime<Utc>,
+    pub flow_status: WaterFlowStatus,
+    pub treatment_needed: bool,
+    pub comment: Option<String>,
+    pub media_ids: Vec<MediaId>,
+}
+
+impl Model for WaterBeta {
+    type Id = WaterBetaId;
+    type Filter = WaterBetaFilter;
+
+    fn id(&self) -> Self::Id {
+        self.id
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum WaterBetaFilter {
+    Ids(Vec<WaterBetaId>),
+    /// Reports for any of the given features, most recently observed first
+    ForOsmFeatures(Vec<OsmFeatureId>),
+    ForUser(UserId),
+}
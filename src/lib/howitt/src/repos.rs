@@ -1,14 +1,24 @@
 use crate::ext::futures::FuturesIteratorExt;
 use crate::models::{
+    course_point::RouteCoursePoints,
+    filters::{Page, PageRequest},
+    locality::{RideLocalities, RouteLocalities},
     media::Media,
+    osm_feature::OsmFeature,
     point_of_interest::PointOfInterest,
-    ride::{Ride, RidePoints},
+    point_of_interest_visit::PointOfInterestVisit,
+    ride::{Ride, RidePoints, RideSensors},
+    ride_weather::RideWeather,
     route::{Route, RoutePoints},
+    sync_audit_entry::SyncAuditEntry,
+    sync_run::{SyncCursor, SyncRun, SyncRunId},
     trip::Trip,
-    user::User,
+    user::{RwgpsConnectionStatus, User, UserId},
+    water_beta::WaterBeta,
     Model,
 };
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use std::sync::Arc;
 
 use crate::ext::iter::ResultIterExt;
@@ -27,6 +37,9 @@ pub trait Repo: Send + Sync {
 
     async fn put(&self, model: <Self as Repo>::Model) -> Result<(), Self::Error>;
 
+    /// Removes the model along with any join table rows referencing it.
+    async fn delete(&self, id: <<Self as Repo>::Model as Model>::Id) -> Result<(), Self::Error>;
+
     async fn get_batch(
         &self,
         ids: Vec<<<Self as Repo>::Model as Model>::Id>,
@@ -67,6 +80,19 @@ pub trait Repo: Send + Sync {
 
         Ok(models.into_iter().nth(0))
     }
+
+    /// A page of the models matching `filter`, keyed on their ids. The default
+    /// loads every match and pages in memory; repos can override it with a
+    /// keyset query.
+    async fn filter_page(
+        &self,
+        filter: <Self::Model as Model>::Filter,
+        page: PageRequest<<Self::Model as Model>::Id>,
+    ) -> Result<Page<Self::Model>, Self::Error> {
+        let models = self.filter_models(filter).await?;
+
+        Ok(page.paginate(models, |model| model.id()))
+    }
 }
 
 #[async_trait]
@@ -95,7 +121,18 @@ pub trait AnyhowRepo: Send + Sync + std::fmt::Debug {
         filter: <Self::Model as Model>::Filter,
     ) -> Result<Option<Self::Model>, anyhow::Error>;
 
+    async fn filter_page(
+        &self,
+        filter: <Self::Model as Model>::Filter,
+        page: PageRequest<<Self::Model as Model>::Id>,
+    ) -> Result<Page<Self::Model>, anyhow::Error>;
+
     async fn put(&self, model: <Self as AnyhowRepo>::Model) -> Result<(), anyhow::Error>;
+
+    async fn delete(
+        &self,
+        id: <<Self as AnyhowRepo>::Model as Model>::Id,
+    ) -> Result<(), anyhow::Error>;
 }
 
 #[async_trait]
@@ -128,28 +165,93 @@ where
     ) -> Result<Option<Self::Model>, anyhow::Error> {
         Ok(Repo::find_model(self, filter).await?)
     }
+    async fn filter_page(
+        &self,
+        filter: <Self::Model as Model>::Filter,
+        page: PageRequest<T::Id>,
+    ) -> Result<Page<Self::Model>, anyhow::Error> {
+        Ok(Repo::filter_page(self, filter, page).await?)
+    }
     async fn put(&self, model: T) -> Result<(), anyhow::Error> {
         Ok(Repo::put(self, model).await?)
     }
+    async fn delete(&self, id: T::Id) -> Result<(), anyhow::Error> {
+        Ok(Repo::delete(self, id).await?)
+    }
+}
+
+/// Writes to a user's RWGPS connection that leave the rest of it alone, so
+/// jobs finishing at the same time don't undo each other's changes. Users
+/// without a connection are left as they are.
+#[async_trait]
+pub trait AnyhowUserRepo: AnyhowRepo<Model = User> {
+    /// Also moves the connection's `updated_at` to `now`, which is what a
+    /// rate limit pause counts from.
+    async fn set_rwgps_status(
+        &self,
+        user_id: UserId,
+        status: RwgpsConnectionStatus,
+        now: DateTime<Utc>,
+    ) -> Result<(), anyhow::Error>;
+
+    async fn set_rwgps_sync_cursor(
+        &self,
+        user_id: UserId,
+        cursor: SyncCursor,
+    ) -> Result<(), anyhow::Error>;
+}
+
+/// Counts results on a sync run in place, since the jobs for its items finish
+/// concurrently.
+#[async_trait]
+pub trait AnyhowSyncRunRepo: AnyhowRepo<Model = SyncRun> {
+    /// Counts an item's result like [`SyncRun::record`] and returns the run
+    /// as it stands afterwards.
+    async fn record_result(
+        &self,
+        id: SyncRunId,
+        succeeded: bool,
+        now: DateTime<Utc>,
+    ) -> Result<SyncRun, anyhow::Error>;
 }
 
 pub type MediaRepo = Arc<dyn AnyhowRepo<Model = Media>>;
+pub type OsmFeatureRepo = Arc<dyn AnyhowRepo<Model = OsmFeature>>;
 pub type PointOfInterestRepo = Arc<dyn AnyhowRepo<Model = PointOfInterest>>;
+pub type PointOfInterestVisitRepo = Arc<dyn AnyhowRepo<Model = PointOfInterestVisit>>;
+pub type RideLocalitiesRepo = Arc<dyn AnyhowRepo<Model = RideLocalities>>;
 pub type RidePointsRepo = Arc<dyn AnyhowRepo<Model = RidePoints>>;
 pub type RideRepo = Arc<dyn AnyhowRepo<Model = Ride>>;
+pub type RideSensorsRepo = Arc<dyn AnyhowRepo<Model = RideSensors>>;
+pub type RideWeatherRepo = Arc<dyn AnyhowRepo<Model = RideWeather>>;
 pub type RouteRepo = Arc<dyn AnyhowRepo<Model = Route>>;
+pub type RouteCoursePointsRepo = Arc<dyn AnyhowRepo<Model = RouteCoursePoints>>;
+pub type RouteLocalitiesRepo = Arc<dyn AnyhowRepo<Model = RouteLocalities>>;
 pub type RoutePointsRepo = Arc<dyn AnyhowRepo<Model = RoutePoints>>;
+pub type SyncAuditEntryRepo = Arc<dyn AnyhowRepo<Model = SyncAuditEntry>>;
+pub type SyncRunRepo = Arc<dyn AnyhowSyncRunRepo>;
 pub type TripRepo = Arc<dyn AnyhowRepo<Model = Trip>>;
-pub type UserRepo = Arc<dyn AnyhowRepo<Model = User>>;
+pub type UserRepo = Arc<dyn AnyhowUserRepo>;
+pub type WaterBetaRepo = Arc<dyn AnyhowRepo<Model = WaterBeta>>;
 
 #[derive(Clone)]
 pub struct Repos {
     pub media_repo: MediaRepo,
+    pub osm_feature_repo: OsmFeatureRepo,
     pub point_of_interest_repo: PointOfInterestRepo,
+    pub point_of_interest_visit_repo: PointOfInterestVisitRepo,
+    pub ride_localities_repo: RideLocalitiesRepo,
     pub ride_points_repo: RidePointsRepo,
     pub ride_repo: RideRepo,
+    pub ride_sensors_repo: RideSensorsRepo,
+    pub ride_weather_repo: RideWeatherRepo,
     pub route_repo: RouteRepo,
+    pub route_course_points_repo: RouteCoursePointsRepo,
+    pub route_localities_repo: RouteLocalitiesRepo,
     pub route_points_repo: RoutePointsRepo,
+    pub sync_audit_entry_repo: SyncAuditEntryRepo,
+    pub sync_run_repo: SyncRunRepo,
     pub trip_repo: TripRepo,
     pub user_repo: UserRepo,
+    pub water_beta_repo: WaterBetaRepo,
 }
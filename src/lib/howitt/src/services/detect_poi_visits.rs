@@ -0,0 +1,308 @@
+use chrono::{DateTime, Duration, Utc};
+use geo::{algorithm::line_measures::metric_spaces::Haversine, Distance};
+
+use crate::{
+    models::{
+        media::{Media, MediaFilter},
+        point::TemporalElevationPoint,
+        point_of_interest::{PointOfInterest, PointOfInterestFilter, PointOfInterestType},
+        point_of_interest_visit::{
+            PointOfInterestVisit, PointOfInterestVisitFilter, PointOfInterestVisitId,
+            VisitConfirmation,
+        },
+        ride::{Ride, RideId},
+    },
+    repos::{MediaRepo, PointOfInterestRepo, PointOfInterestVisitRepo, RidePointsRepo, RideRepo},
+};
+
+use super::nearby::{nearby_points_of_interest, padded_bounds};
+
+/// How close a ride needs to pass to a POI of the given type to count as a visit.
+/// Campsites and huts cover more ground than a tap or a platform.
+pub fn visit_radius_m(point_of_interest_type: &PointOfInterestType) -> f64 {
+    match point_of_interest_type {
+        PointOfInterestType::PublicTransportStop => 50.0,
+        PointOfInterestType::Campsite => 150.0,
+        PointOfInterestType::WaterSource => 30.0,
+        PointOfInterestType::Hut => 75.0,
+        PointOfInterestType::Generic => 50.0,
+    }
+}
+
+/// The largest [`visit_radius_m`] of any POI type.
+pub fn max_visit_radius_m() -> f64 {
+    PointOfInterestType::ALL
+        .iter()
+        .map(visit_radius_m)
+        .fold(0.0, f64::max)
+}
+
+/// Media is attached to a visit when it was captured within this window of the
+/// visit, and (if it has a location) within this distance of the POI.
+pub fn media_time_window() -> Duration {
+    Duration::minutes(30)
+}
+
+const MEDIA_MAX_DISTANCE_M: f64 = 250.0;
+
+fn is_media_near_visit(media: &Media, poi: &PointOfInterest, visited_at: DateTime<Utc>) -> bool {
+    let Some(captured_at) = media.captured_at else {
+        return false;
+    };
+
+    if (captured_at - visited_at).abs() > media_time_window() {
+        return false;
+    }
+
+    match media.point {
+        Some(point) => Haversine::distance(point, poi.point) <= MEDIA_MAX_DISTANCE_M,
+        None => true,
+    }
+}
+
+/// Builds pending visits for every POI the ride passed within its type specific
+/// radius. POIs which already have a visit from the rider during the ride are skipped.
+pub fn detect_visits(
+    ride: &Ride,
+    points: &[TemporalElevationPoint],
+    pois: &[PointOfInterest],
+    media: &[Media],
+    existing_visits: &[PointOfInterestVisit],
+) -> Vec<PointOfInterestVisit> {
+    nearby_points_of_interest(points, pois, max_visit_radius_m())
+        .into_iter()
+        .filter(|nearby| {
+            nearby.distance <= visit_radius_m(&nearby.point_of_interest.point_of_interest_type)
+        })
+        .filter(|nearby| {
+            !existing_visits
+                .iter()
+                .any(|visit| visit.point_of_interest_id == nearby.point_of_interest.id)
+        })
+        .map(|nearby| {
+            let poi = &*nearby.point_of_interest;
+            let visited_at = nearby.closest_point.datetime;
+
+            PointOfInterestVisit {
+                id: PointOfInterestVisitId::new(),
+                created_at: Utc::now(),
+                user_id: ride.user_id,
+                point_of_interest_id: poi.id,
+                visited_at,
+                confirmation: VisitConfirmation::Pending,
+                condition: None,
+                comment: None,
+                media_ids: media
+                    .iter()
+                    .filter(|media| media.user_id == ride.user_id)
+                    .filter(|media| is_media_near_visit(media, poi, visited_at))
+                    .map(|media| media.id)
+                    .collect(),
+            }
+        })
+        .collect()
+}
+
+pub struct DetectRideVisitsParams {
+    pub ride_repo: RideRepo,
+    pub ride_points_repo: RidePointsRepo,
+    pub point_of_interest_repo: PointOfInterestRepo,
+    pub point_of_interest_visit_repo: PointOfInterestVisitRepo,
+    pub media_repo: MediaRepo,
+    pub ride_id: RideId,
+}
+
+pub async fn detect_ride_visits(
+    DetectRideVisitsParams {
+        ride_repo,
+        ride_points_repo,
+        point_of_interest_repo,
+        point_of_interest_visit_repo,
+        media_repo,
+        ride_id,
+    }: DetectRideVisitsParams,
+) -> Result<Vec<PointOfInterestVisit>, anyhow::Error> {
+    let ride = ride_repo.get(ride_id).await?;
+
+    // Archived rides were removed upstream, they don't count as visits
+    if ride.archived_at.is_some() {
+        return Ok(vec![]);
+    }
+
+    let ride_points = ride_points_repo.get(ride_id).await?;
+
+    let Some(bounds) = padded_bounds(&ride_points.points, max_visit_radius_m()) else {
+        return Ok(vec![]);
+    };
+    let pois = point_of_interest_repo
+        .filter_models(PointOfInterestFilter::BoundingBox(bounds))
+        .await?;
+
+    let media = media_repo
+        .filter_models(MediaFilter::ForUser(ride.user_id))
+        .await?;
+
+    let existing_visits = point_of_interest_visit_repo
+        .filter_models(PointOfInterestVisitFilter::VisitedBetween {
+            point_of_interest_id: None,
+            user_id: Some(ride.user_id),
+            start: ride.started_at,
+            end: ride.finished_at + Duration::seconds(1),
+        })
+        .await?;
+
+    let visits = detect_visits(&ride, &ride_points.points, &pois, &media, &existing_visits);
+
+    for visit in visits.iter() {
+        point_of_interest_visit_repo.put(visit.clone()).await?;
+    }
+
+    tracing::info!(
+        ride_id = %ride.id,
+        total_visits = visits.len(),
+        "Detected POI visits"
+    );
+
+    Ok(visits)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use geo::Contains;
+
+    use super::*;
+    use crate::models::{media::MediaId, point_of_interest::PointOfInterestId, user::UserId};
+
+    fn create_test_poi(
+        point: geo::Point,
+        point_of_interest_type: PointOfInterestType,
+    ) -> PointOfInterest {
+        PointOfInterest {
+            id: PointOfInterestId::new(),
+            user_id: UserId::new(),
+            name: String::from("Test POI"),
+            slug: String::from("test-poi"),
+            point,
+            point_of_interest_type,
+            description: None,
+            external_ref: None,
+        }
+    }
+
+    fn create_test_ride() -> (Ride, Vec<TemporalElevationPoint>) {
+        let started_at = Utc.with_ymd_and_hms(2024, 1, 1, 8, 0, 0).unwrap();
+
+        // Heading east along -37.0 at roughly 90m per minute
+        let points = (0..10)
+            .map(|i| TemporalElevationPoint {
+                datetime: started_at + Duration::minutes(i),
+                point: geo::Point::new(146.0 + i as f64 * 0.001, -37.0),
+                elevation: 100.0,
+            })
+            .collect::<Vec<_>>();
+
+        (
+            Ride {
+                id: RideId::new(),
+                name: String::from("Test Ride"),
+                user_id: UserId::new(),
+                distance: 0.0,
+                started_at,
+                finished_at: started_at + Duration::minutes(9),
+                external_ref: None,
+                archived_at: None,
+            },
+            points,
+        )
+    }
+
+    #[test]
+    fn test_detect_visits_uses_type_specific_radius() {
+        let (ride, points) = create_test_ride();
+
+        // Both roughly 100m north of the track
+        let campsite = create_test_poi(
+            geo::Point::new(146.003, -36.9991),
+            PointOfInterestType::Campsite,
+        );
+        let water = create_test_poi(
+            geo::Point::new(146.003, -36.9991),
+            PointOfInterestType::WaterSource,
+        );
+
+        let visits = detect_visits(&ride, &points, &[campsite.clone(), water], &[], &[]);
+
+        assert_eq!(visits.len(), 1);
+        assert_eq!(visits[0].point_of_interest_id, campsite.id);
+        assert_eq!(visits[0].confirmation, VisitConfirmation::Pending);
+        assert_eq!(visits[0].visited_at, points[3].datetime);
+        assert_eq!(visits[0].user_id, ride.user_id);
+    }
+
+    #[test]
+    fn test_max_visit_radius_covers_every_type() {
+        assert_eq!(max_visit_radius_m(), 150.0);
+    }
+
+    #[test]
+    fn test_padded_bounds_cover_visit_radius() {
+        let (_, points) = create_test_ride();
+        let bounds = padded_bounds(&points, max_visit_radius_m()).unwrap();
+
+        // 140m south of the start, and 140m east of the end
+        let south = geo::Point::new(146.0, -37.00126);
+        let east = geo::Point::new(146.01058, -37.0);
+        assert!(Haversine::distance(south, points[0].point) < max_visit_radius_m());
+        assert!(Haversine::distance(east, points[9].point) < max_visit_radius_m());
+
+        assert!(bounds.contains(&south));
+        assert!(bounds.contains(&east));
+        assert!(!bounds.contains(&geo::Point::new(146.0, -37.01)));
+    }
+
+    #[test]
+    fn test_detect_visits_skips_existing_visits() {
+        let (ride, points) = create_test_ride();
+        let poi = create_test_poi(geo::Point::new(146.005, -37.0), PointOfInterestType::Hut);
+
+        let existing = detect_visits(&ride, &points, std::slice::from_ref(&poi), &[], &[]);
+        assert_eq!(existing.len(), 1);
+
+        let visits = detect_visits(&ride, &points, &[poi], &[], &existing);
+        assert!(visits.is_empty());
+    }
+
+    #[test]
+    fn test_detect_visits_attaches_nearby_media() {
+        let (ride, points) = create_test_ride();
+        let poi = create_test_poi(geo::Point::new(146.005, -37.0), PointOfInterestType::Hut);
+
+        let create_media = |minutes: i64, point: Option<geo::Point>| Media {
+            id: MediaId::new(),
+            created_at: Utc::now(),
+            user_id: ride.user_id,
+            path: String::from("test.jpg"),
+            relation_ids: vec![],
+            point,
+            captured_at: Some(points[5].datetime + Duration::minutes(minutes)),
+            external_ref: None,
+        };
+
+        let near = create_media(5, Some(geo::Point::new(146.005, -37.0)));
+        let unlocated = create_media(-10, None);
+        let too_late = create_media(45, Some(geo::Point::new(146.005, -37.0)));
+        let too_far = create_media(5, Some(geo::Point::new(146.05, -37.0)));
+
+        let visits = detect_visits(
+            &ride,
+            &points,
+            &[poi],
+            &[near.clone(), unlocated.clone(), too_late, too_far],
+            &[],
+        );
+
+        assert_eq!(visits.len(), 1);
+        assert_eq!(visits[0].media_ids, vec![near.id, unlocated.id]);
+    }
+}
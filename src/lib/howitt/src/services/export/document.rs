@@ -0,0 +1,292 @@
+use chrono::{DateTime, Utc};
+use itertools::Itertools;
+
+use crate::{
+    ext::futures::FuturesIteratorExt,
+    models::{
+        media::{Media, MediaFilter},
+        ride::{Ride, RidePoints},
+        route::{Route, RoutePoints},
+        trip::Trip,
+    },
+    repos::{MediaRepo, RidePointsRepo, RideRepo},
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExportPoint {
+    pub point: geo::Point,
+    pub elevation: Option<f64>,
+    pub datetime: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExportWaypoint {
+    pub point: geo::Point,
+    pub name: String,
+    pub description: Option<String>,
+    pub datetime: Option<DateTime<Utc>>,
+}
+
+/// A format agnostic representation of something being exported. Each segment
+/// is written as a separate track segment (or line string).
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExportDocument {
+    pub name: String,
+    pub description: Option<String>,
+    pub segments: Vec<Vec<ExportPoint>>,
+    pub waypoints: Vec<ExportWaypoint>,
+}
+
+impl ExportDocument {
+    pub fn from_route(route: &Route, route_points: &RoutePoints) -> ExportDocument {
+        ExportDocument {
+            name: route.name.clone(),
+            description: route
+                .description
+                .as_ref()
+                .and_then(|description| description.description.clone()),
+            segments: vec![route_points
+                .points
+                .iter()
+                .map(|point| ExportPoint {
+                    point: point.point,
+                    elevation: Some(point.elevation),
+                    datetime: None,
+                })
+                .collect()],
+            waypoints: vec![],
+        }
+    }
+
+    pub fn from_ride(ride: &Ride, ride_points: &RidePoints) -> ExportDocument {
+        ExportDocument {
+            name: ride.name.clone(),
+            description: None,
+            segments: vec![ride_points_segment(ride_points)],
+            waypoints: vec![],
+        }
+    }
+
+    /// Each ride becomes a segment. Notes are placed at the ride point closest
+    /// in time, and media with a known location become waypoints.
+    pub fn from_trip(trip: &Trip, rides: &[(Ride, RidePoints)], media: &[Media]) -> ExportDocument {
+        let rides = rides
+            .iter()
+            .sorted_by_key(|(ride, _)| ride.started_at)
+            .collect_vec();
+
+        let all_points = rides
+            .iter()
+            .flat_map(|(_, ride_points)| &ride_points.points)
+            .collect_vec();
+
+        let note_waypoints = trip.notes.iter().filter_map(|note| {
+            let closest = all_points
+                .iter()
+                .min_by_key(|point| (point.datetime - note.timestamp).num_seconds().abs())?;
+
+            Some(ExportWaypoint {
+                point: closest.point,
+                name: note.text.clone(),
+                description: None,
+                datetime: Some(note.timestamp),
+            })
+        });
+
+        let media_waypoints = media
+            .iter()
+            .filter_map(|media| {
+                Some(ExportWaypoint {
+                    point: media.point?,
+                    name: media
+                        .path
+                        .rsplit('/')
+                        .next()
+                        .unwrap_or(&media.path)
+                        .to_string(),
+                    description: None,
+                    datetime: media.captured_at,
+                })
+            })
+            .sorted_by_key(|waypoint| waypoint.datetime);
+
+        ExportDocument {
+            name: trip.name.clone(),
+            description: trip.description.clone(),
+            segments: rides
+                .iter()
+                .map(|(_, ride_points)| ride_points_segment(ride_points))
+                .collect(),
+            waypoints: note_waypoints.chain(media_waypoints).collect(),
+        }
+    }
+}
+
+fn ride_points_segment(ride_points: &RidePoints) -> Vec<ExportPoint> {
+    ride_points
+        .points
+        .iter()
+        .map(|point| ExportPoint {
+            point: point.point,
+            elevation: Some(point.elevation),
+            datetime: Some(point.datetime),
+        })
+        .collect()
+}
+
+pub struct BuildTripExportDocumentParams {
+    pub ride_repo: RideRepo,
+    pub ride_points_repo: RidePointsRepo,
+    pub media_repo: MediaRepo,
+}
+
+pub async fn build_trip_export_document(
+    trip: &Trip,
+    BuildTripExportDocumentParams {
+        ride_repo,
+        ride_points_repo,
+        media_repo,
+    }: BuildTripExportDocumentParams,
+) -> Result<ExportDocument, anyhow::Error> {
+    let rides = ride_repo.get_batch(trip.ride_ids.clone()).await?;
+    let media = media_repo
+        .filter_models(MediaFilter::ForTrip(trip.id))
+        .await?;
+
+    // A ride without points is left out rather than failing the whole export
+    let rides = rides
+        .into_iter()
+        .map(|ride| {
+            let ride_points_repo = ride_points_repo.clone();
+            async move {
+                let ride_points = ride_points_repo.get(ride.id).await;
+                (ride, ride_points)
+            }
+        })
+        .collect_futures_ordered()
+        .await
+        .into_iter()
+        .filter_map(|(ride, ride_points)| match ride_points {
+            Ok(ride_points) => Some((ride, ride_points)),
+            Err(error) => {
+                tracing::warn!(ride_id = %ride.id, %error, "Skipping ride without points in trip export");
+                None
+            }
+        })
+        .collect_vec();
+
+    Ok(ExportDocument::from_trip(trip, &rides, &media))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+    use crate::models::{
+        point::{ElevationPoint, TemporalElevationPoint},
+        ride::RideId,
+        route::RouteId,
+        route_description::RouteDescription,
+        trip::{TripId, TripNote},
+        user::UserId,
+    };
+
+    fn create_test_ride(hour: u32) -> (Ride, RidePoints) {
+        let id = RideId::new();
+        let started_at = Utc.with_ymd_and_hms(2024, 1, 1, hour, 0, 0).unwrap();
+        let finished_at = Utc.with_ymd_and_hms(2024, 1, 1, hour, 30, 0).unwrap();
+
+        let points = vec![
+            TemporalElevationPoint {
+                datetime: started_at,
+                point: geo::Point::new(146.0, -37.0 - hour as f64 / 100.0),
+                elevation: 100.0,
+            },
+            TemporalElevationPoint {
+                datetime: finished_at,
+                point: geo::Point::new(146.01, -37.0 - hour as f64 / 100.0),
+                elevation: 200.0,
+            },
+        ];
+
+        (
+            Ride {
+                id,
+                name: format!("Ride {hour}"),
+                user_id: UserId::new(),
+                distance: 0.0,
+                started_at,
+                finished_at,
+                external_ref: None,
+                archived_at: None,
+            },
+            RidePoints { id, points },
+        )
+    }
+
+    #[test]
+    fn test_trip_legs_become_segments_in_order() {
+        let rides = vec![create_test_ride(10), create_test_ride(8)];
+
+        let trip = Trip {
+            id: TripId::new(),
+            created_at: Utc::now(),
+            user_id: UserId::new(),
+            name: String::from("Test Trip"),
+            slug: String::from("test-trip"),
+            year: 2024,
+            description: None,
+            notes: vec![TripNote {
+                timestamp: Utc.with_ymd_and_hms(2024, 1, 1, 10, 25, 0).unwrap(),
+                text: String::from("Lunch"),
+            }],
+            ride_ids: rides.iter().map(|(ride, _)| ride.id).collect(),
+            media_ids: vec![],
+            is_published: true,
+        };
+
+        let document = ExportDocument::from_trip(&trip, &rides, &[]);
+
+        assert_eq!(document.segments.len(), 2);
+        assert_eq!(
+            document.segments[0][0].datetime,
+            Some(Utc.with_ymd_and_hms(2024, 1, 1, 8, 0, 0).unwrap())
+        );
+        assert_eq!(document.waypoints.len(), 1);
+        assert_eq!(document.waypoints[0].name, "Lunch");
+        assert_eq!(document.waypoints[0].point, geo::Point::new(146.01, -37.1));
+    }
+
+    #[test]
+    fn test_route_description_is_exported() {
+        let id = RouteId::new();
+        let route = Route {
+            id,
+            name: String::from("Test Route"),
+            slug: String::from("test-route"),
+            user_id: UserId::new(),
+            distance: 0.0,
+            sample_points: None,
+            description: Some(RouteDescription {
+                description: Some(String::from("Along the river")),
+                ..Default::default()
+            }),
+            external_ref: None,
+            tags: Default::default(),
+            archived_at: None,
+        };
+        let route_points = RoutePoints {
+            id,
+            points: vec![ElevationPoint {
+                point: geo::Point::new(146.0, -37.0),
+                elevation: 100.0,
+            }],
+        };
+
+        let document = ExportDocument::from_route(&route, &route_points);
+
+        assert_eq!(document.description.as_deref(), Some("Along the river"));
+        assert_eq!(document.segments[0].len(), 1);
+    }
+}
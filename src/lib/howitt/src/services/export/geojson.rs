@@ -0,0 +1,122 @@
+use serde_json::{json, Value};
+
+use super::{ExportDocument, ExportError, ExportPoint};
+
+fn to_coordinates(point: &ExportPoint) -> Value {
+    match point.elevation {
+        Some(elevation) => json!([point.point.x(), point.point.y(), elevation]),
+        None => json!([point.point.x(), point.point.y()]),
+    }
+}
+
+/// Serialises the document as a FeatureCollection with one MultiLineString
+/// feature for the segments and a Point feature per waypoint. Timestamps are
+/// written to a `coordTimes` property, matching the convention used by
+/// togeojson.
+pub fn to_geojson(document: &ExportDocument) -> Result<String, ExportError> {
+    let coord_times = document
+        .segments
+        .iter()
+        .map(|segment| {
+            segment
+                .iter()
+                .map(|point| point.datetime.map(|datetime| datetime.to_rfc3339()))
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    let has_times = coord_times
+        .iter()
+        .flatten()
+        .any(|datetime| datetime.is_some());
+
+    let mut properties = json!({
+        "name": document.name,
+        "description": document.description,
+    });
+
+    if has_times {
+        properties["coordTimes"] = json!(coord_times);
+    }
+
+    let track_feature = json!({
+        "type": "Feature",
+        "geometry": {
+            "type": "MultiLineString",
+            "coordinates": document
+                .segments
+                .iter()
+                .map(|segment| segment.iter().map(to_coordinates).collect::<Vec<_>>())
+                .collect::<Vec<_>>(),
+        },
+        "properties": properties,
+    });
+
+    let waypoint_features = document.waypoints.iter().map(|waypoint| {
+        json!({
+            "type": "Feature",
+            "geometry": {
+                "type": "Point",
+                "coordinates": [waypoint.point.x(), waypoint.point.y()],
+            },
+            "properties": {
+                "name": waypoint.name,
+                "description": waypoint.description,
+                "time": waypoint.datetime.map(|datetime| datetime.to_rfc3339()),
+            },
+        })
+    });
+
+    let feature_collection = json!({
+        "type": "FeatureCollection",
+        "features": std::iter::once(track_feature)
+            .chain(waypoint_features)
+            .collect::<Vec<_>>(),
+    });
+
+    Ok(serde_json::to_string(&feature_collection)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::export::ExportWaypoint;
+
+    #[test]
+    fn test_to_geojson() {
+        let document = ExportDocument {
+            name: String::from("Test"),
+            description: None,
+            segments: vec![vec![
+                ExportPoint {
+                    point: geo::Point::new(146.0, -37.0),
+                    elevation: Some(100.0),
+                    datetime: None,
+                },
+                ExportPoint {
+                    point: geo::Point::new(146.1, -37.1),
+                    elevation: None,
+                    datetime: None,
+                },
+            ]],
+            waypoints: vec![ExportWaypoint {
+                point: geo::Point::new(146.0, -37.0),
+                name: String::from("Hut"),
+                description: None,
+                datetime: None,
+            }],
+        };
+
+        let value: Value = serde_json::from_str(&to_geojson(&document).unwrap()).unwrap();
+
+        assert_eq!(value["features"].as_array().unwrap().len(), 2);
+        assert_eq!(
+            value["features"][0]["geometry"]["coordinates"],
+            json!([[[146.0, -37.0, 100.0], [146.1, -37.1]]])
+        );
+        assert!(value["features"][0]["properties"]
+            .get("coordTimes")
+            .is_none());
+        assert_eq!(value["features"][1]["properties"]["name"], "Hut");
+    }
+}
@@ -0,0 +1,57 @@
+use chrono::{DateTime, Utc};
+
+use super::{ExportDocument, ExportError, ExportPoint, ExportWaypoint};
+
+fn to_gpx_time(datetime: DateTime<Utc>) -> Option<gpx::Time> {
+    time::OffsetDateTime::from_unix_timestamp(datetime.timestamp())
+        .ok()
+        .map(gpx::Time::from)
+}
+
+fn to_track_waypoint(point: &ExportPoint) -> gpx::Waypoint {
+    let mut waypoint = gpx::Waypoint::new(point.point);
+    waypoint.elevation = point.elevation;
+    waypoint.time = point.datetime.and_then(to_gpx_time);
+    waypoint
+}
+
+fn to_waypoint(waypoint: &ExportWaypoint) -> gpx::Waypoint {
+    let mut gpx_waypoint = gpx::Waypoint::new(waypoint.point);
+    gpx_waypoint.name = Some(waypoint.name.clone());
+    gpx_waypoint.description = waypoint.description.clone();
+    gpx_waypoint.time = waypoint.datetime.and_then(to_gpx_time);
+    gpx_waypoint
+}
+
+pub fn to_gpx(document: &ExportDocument) -> Result<String, ExportError> {
+    let mut track = gpx::Track::new();
+    track.name = Some(document.name.clone());
+    track.description = document.description.clone();
+    track.segments = document
+        .segments
+        .iter()
+        .map(|segment| {
+            let mut track_segment = gpx::TrackSegment::new();
+            track_segment.points = segment.iter().map(to_track_waypoint).collect();
+            track_segment
+        })
+        .collect();
+
+    let gpx = gpx::Gpx {
+        version: gpx::GpxVersion::Gpx11,
+        creator: Some(String::from("howitt")),
+        metadata: Some(gpx::Metadata {
+            name: Some(document.name.clone()),
+            description: document.description.clone(),
+            ..Default::default()
+        }),
+        waypoints: document.waypoints.iter().map(to_waypoint).collect(),
+        tracks: vec![track],
+        routes: vec![],
+    };
+
+    let mut buf = Vec::new();
+    gpx::write(&gpx, &mut buf)?;
+
+    Ok(String::from_utf8(buf)?)
+}
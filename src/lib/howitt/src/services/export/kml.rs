@@ -0,0 +1,93 @@
+use itertools::Itertools;
+
+use super::{ExportDocument, ExportPoint};
+
+fn escape_xml(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+fn to_coordinates(point: &ExportPoint) -> String {
+    match point.elevation {
+        Some(elevation) => format!("{},{},{}", point.point.x(), point.point.y(), elevation),
+        None => format!("{},{}", point.point.x(), point.point.y()),
+    }
+}
+
+pub fn to_kml(document: &ExportDocument) -> String {
+    let line_strings = document
+        .segments
+        .iter()
+        .map(|segment| {
+            format!(
+                "<LineString><tessellate>1</tessellate><coordinates>{}</coordinates></LineString>",
+                segment.iter().map(to_coordinates).join(" ")
+            )
+        })
+        .join("");
+
+    let description = document
+        .description
+        .as_ref()
+        .map(|description| format!("<description>{}</description>", escape_xml(description)))
+        .unwrap_or_default();
+
+    let waypoints = document
+        .waypoints
+        .iter()
+        .map(|waypoint| {
+            let timestamp = waypoint
+                .datetime
+                .map(|datetime| {
+                    format!("<TimeStamp><when>{}</when></TimeStamp>", datetime.to_rfc3339())
+                })
+                .unwrap_or_default();
+
+            format!(
+                "<Placemark><name>{}</name>{}<Point><coordinates>{},{}</coordinates></Point></Placemark>",
+                escape_xml(&waypoint.name),
+                timestamp,
+                waypoint.point.x(),
+                waypoint.point.y()
+            )
+        })
+        .join("");
+
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?><kml xmlns="http://www.opengis.net/kml/2.2"><Document><name>{name}</name>{description}<Placemark><name>{name}</name><MultiGeometry>{line_strings}</MultiGeometry></Placemark>{waypoints}</Document></kml>"#,
+        name = escape_xml(&document.name),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escape_xml() {
+        assert_eq!(escape_xml("Fish & <Chips>"), "Fish &amp; &lt;Chips&gt;");
+    }
+
+    #[test]
+    fn test_to_kml() {
+        let document = ExportDocument {
+            name: String::from("Up & Over"),
+            description: None,
+            segments: vec![vec![ExportPoint {
+                point: geo::Point::new(146.0, -37.0),
+                elevation: Some(100.0),
+                datetime: None,
+            }]],
+            waypoints: vec![],
+        };
+
+        let kml = to_kml(&document);
+
+        assert!(kml.contains("<name>Up &amp; Over</name>"));
+        assert!(kml.contains("<coordinates>146,-37,100</coordinates>"));
+    }
+}
@@ -0,0 +1,79 @@
+pub mod document;
+pub mod geojson;
+pub mod gpx;
+pub mod kml;
+
+pub use document::*;
+
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ExportError {
+    #[error("Failed to write GPX: {0}")]
+    Gpx(#[from] ::gpx::errors::GpxError),
+    #[error("Failed to write GeoJSON: {0}")]
+    SerdeJson(#[from] serde_json::Error),
+    #[error("Export was not valid UTF-8: {0}")]
+    Utf8(#[from] std::string::FromUtf8Error),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Gpx,
+    GeoJson,
+    Kml,
+}
+
+impl ExportFormat {
+    pub fn from_extension(extension: &str) -> Option<ExportFormat> {
+        match extension.to_lowercase().as_str() {
+            "gpx" => Some(ExportFormat::Gpx),
+            "geojson" | "json" => Some(ExportFormat::GeoJson),
+            "kml" => Some(ExportFormat::Kml),
+            _ => None,
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Gpx => "gpx",
+            ExportFormat::GeoJson => "geojson",
+            ExportFormat::Kml => "kml",
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            ExportFormat::Gpx => "application/gpx+xml",
+            ExportFormat::GeoJson => "application/geo+json",
+            ExportFormat::Kml => "application/vnd.google-earth.kml+xml",
+        }
+    }
+}
+
+pub fn export_document(
+    document: &ExportDocument,
+    format: ExportFormat,
+) -> Result<String, ExportError> {
+    match format {
+        ExportFormat::Gpx => self::gpx::to_gpx(document),
+        ExportFormat::GeoJson => self::geojson::to_geojson(document),
+        ExportFormat::Kml => Ok(self::kml::to_kml(document)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_export_format_from_extension() {
+        assert_eq!(ExportFormat::from_extension("GPX"), Some(ExportFormat::Gpx));
+        assert_eq!(
+            ExportFormat::from_extension("json"),
+            Some(ExportFormat::GeoJson)
+        );
+        assert_eq!(ExportFormat::from_extension("kml"), Some(ExportFormat::Kml));
+        assert_eq!(ExportFormat::from_extension("fit"), None);
+    }
+}
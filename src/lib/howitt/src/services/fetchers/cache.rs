@@ -10,6 +10,13 @@ impl<Redis: RedisClient> CacheFetcher<Redis> {
         Self { redis_client }
     }
 
+    /// Removes every cached entry whose key starts with `prefix`.
+    pub async fn invalidate_prefix(&self, prefix: &str) -> Result<(), anyhow::Error> {
+        self.redis_client.delete_prefix(prefix).await?;
+
+        Ok(())
+    }
+
     pub async fn fetch_or_insert_with<T, F, Fut>(
         &self,
         key: &str,
@@ -0,0 +1,39 @@
+use howitt_client_types::RedisClient;
+
+use super::cache::CacheFetcher;
+
+/// Caches elevation lookups for points that don't carry one, such as public
+/// transport stops. The ground doesn't move, so entries never go stale.
+pub struct ElevationFetcher<Redis: RedisClient> {
+    pub cache_fetcher: CacheFetcher<Redis>,
+}
+
+impl<Redis: RedisClient> ElevationFetcher<Redis> {
+    pub fn new(redis_client: Redis) -> Self {
+        Self {
+            cache_fetcher: CacheFetcher::new(redis_client),
+        }
+    }
+
+    /// Points are rounded to 5 decimal places (~1m).
+    fn key(point: &geo::Point) -> String {
+        [
+            "ELEVATION".to_string(),
+            format!("{:.5}", point.y()),
+            format!("{:.5}", point.x()),
+        ]
+        .join("#")
+    }
+
+    pub async fn fetch<F, Fut>(&self, point: geo::Point, lookup: F) -> Result<f64, anyhow::Error>
+    where
+        F: FnOnce(geo::Point) -> Fut,
+        Fut: std::future::Future<Output = Result<f64, anyhow::Error>>,
+    {
+        let key = Self::key(&point);
+
+        self.cache_fetcher
+            .fetch_or_insert_with(&key, || lookup(point))
+            .await
+    }
+}
@@ -0,0 +1,46 @@
+use howitt_client_types::RedisClient;
+
+use crate::models::locality::Locality;
+
+use super::cache::CacheFetcher;
+
+/// Caches reverse geocoding lookups, so routes and rides setting out from the
+/// same trailhead only cost one request.
+pub struct LocalityFetcher<Redis: RedisClient> {
+    pub cache_fetcher: CacheFetcher<Redis>,
+}
+
+impl<Redis: RedisClient> LocalityFetcher<Redis> {
+    pub fn new(redis_client: Redis) -> Self {
+        Self {
+            cache_fetcher: CacheFetcher::new(redis_client),
+        }
+    }
+
+    /// Points are rounded to 3 decimal places (~100m) so nearby lookups share
+    /// a cache entry.
+    fn key(point: &geo::Point) -> String {
+        [
+            "LOCALITY".to_string(),
+            format!("{:.3}", point.y()),
+            format!("{:.3}", point.x()),
+        ]
+        .join("#")
+    }
+
+    pub async fn fetch<F, Fut>(
+        &self,
+        point: geo::Point,
+        lookup: F,
+    ) -> Result<Option<Locality>, anyhow::Error>
+    where
+        F: FnOnce(geo::Point) -> Fut,
+        Fut: std::future::Future<Output = Result<Option<Locality>, anyhow::Error>>,
+    {
+        let key = Self::key(&point);
+
+        self.cache_fetcher
+            .fetch_or_insert_with(&key, || lookup(point))
+            .await
+    }
+}
@@ -1,8 +1,12 @@
 mod cache;
+mod elevation;
+mod locality;
 mod simplified_ride_points;
 mod simplified_route_points;
 mod simplified_trip_elevation_points;
 
+pub use elevation::*;
+pub use locality::*;
 pub use simplified_ride_points::*;
 pub use simplified_route_points::*;
 pub use simplified_trip_elevation_points::*;
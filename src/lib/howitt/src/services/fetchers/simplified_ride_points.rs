@@ -29,6 +29,13 @@ impl<Redis: RedisClient> SimplifiedRidePointsFetcher<Redis> {
         [id.to_string(), "POINTS".to_string(), detail_level.to_string()].join("#")
     }
 
+    /// Drops the cached points for every detail level.
+    pub async fn invalidate(&self, id: RideId) -> Result<(), anyhow::Error> {
+        self.cache_fetcher
+            .invalidate_prefix(&format!("{}#POINTS#", id))
+            .await
+    }
+
     pub async fn fetch(
         &self,
         id: RideId,
@@ -26,7 +26,12 @@ impl<Redis: RedisClient> SimplifiedRoutePointsFetcher<Redis> {
     }
 
     fn key(id: RouteId, detail_level: &DetailLevel) -> String {
-        [id.to_string(), "POINTS".to_string(), detail_level.to_string()].join("#")
+        [
+            id.to_string(),
+            "POINTS".to_string(),
+            detail_level.to_string(),
+        ]
+        .join("#")
     }
 
     pub async fn fetch(
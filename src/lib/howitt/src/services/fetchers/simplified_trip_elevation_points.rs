@@ -62,6 +62,13 @@ impl<Redis: RedisClient> SimplifiedTripElevationPointsFetcher<Redis> {
         .join("#")
     }
 
+    /// Drops the cached elevation points for every epsilon.
+    pub async fn invalidate(&self, id: TripId) -> Result<(), anyhow::Error> {
+        self.cache_fetcher
+            .invalidate_prefix(&format!("{}#TRIP_ELEVATION_POINTS#", id))
+            .await
+    }
+
     pub async fn fetch(
         &self,
         id: TripId,
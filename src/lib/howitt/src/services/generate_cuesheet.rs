@@ -1,84 +1,264 @@
-use itertools::{Itertools, Position};
+use geo::{Distance, Haversine};
+use itertools::Itertools;
+use ordered_float::OrderedFloat;
 
 use crate::ext::iter::ScanAllExt;
 
 use crate::models::{
+    course_point::CoursePoint,
     cuesheet::{Cue, CueStop, Cuesheet},
     point::{
+        delta::{AccumulatingDelta, DistanceDelta},
         progress::{DistanceElevationProgress, Progress},
-        ElevationPoint,
+        ElevationPoint, Point,
     },
-    point_of_interest::PointOfInterest,
+    point_of_interest::{PointOfInterest, PointOfInterestType},
 };
 
-use super::nearby::{nearby_points_of_interest, NearbyPointOfInterest};
+use super::{
+    nearby::nearby_points_of_interest,
+    transport_access::{nearest_transport_stops, NearbyTransportStop},
+};
+
+/// How far a course point's own distance along the route can be from ours
+/// before we stop trusting it. Distances measured elsewhere drift a little.
+const COURSE_POINT_DISTANCE_TOLERANCE_M: f64 = 1000.0;
+
+/// Index of the route point a course point falls on. Course points measured
+/// along the route are matched near that distance, so a turn on an
+/// out-and-back lands on the right leg; the rest go to the closest point.
+fn course_point_idx(
+    route: &[ElevationPoint],
+    distances: &[DistanceDelta],
+    course_point: &CoursePoint,
+) -> Option<usize> {
+    let distance_from_course_point = |point: &ElevationPoint| {
+        OrderedFloat(Haversine::distance(
+            *point.as_geo_point(),
+            course_point.point,
+        ))
+    };
 
-pub fn generate_cuesheet(route: &[ElevationPoint], pois: &[PointOfInterest]) -> Cuesheet {
-    let nearby_pois = nearby_points_of_interest(route, pois, 500.0);
+    let idx_near_distance = course_point.distance_m.and_then(|distance_m| {
+        route
+            .iter()
+            .zip(distances)
+            .enumerate()
+            .filter(|(_, (_, DistanceDelta(d)))| {
+                (d - distance_m).abs() <= COURSE_POINT_DISTANCE_TOLERANCE_M
+            })
+            .min_by_key(|(_, (point, _))| distance_from_course_point(point))
+            .map(|(idx, _)| idx)
+    });
 
-    let partitioned_points = route
+    idx_near_distance.or_else(|| {
+        route
+            .iter()
+            .position_min_by_key(|point| distance_from_course_point(point))
+    })
+}
+
+/// The nearest public transport stop to a route end, or `otherwise` if there
+/// isn't one in range.
+fn terminus_stop(point: &ElevationPoint, pois: &[PointOfInterest], otherwise: CueStop) -> CueStop {
+    nearest_transport_stops(point, pois)
+        .into_iter()
+        .next()
+        .map(
+            |NearbyTransportStop {
+                 point_of_interest,
+                 distance,
+                 ..
+             }| CueStop::Station(point_of_interest, distance),
+        )
+        .unwrap_or(otherwise)
+}
+
+/// Cues between the POIs and course points along the route. Public transport
+/// stops only appear as the nearest station to the start and the end.
+pub fn generate_cuesheet(
+    route: &[ElevationPoint],
+    pois: &[PointOfInterest],
+    course_points: &[CoursePoint],
+) -> Cuesheet {
+    let Some(last_idx) = route.len().checked_sub(1) else {
+        return Cuesheet { cues: vec![] };
+    };
+
+    let (transport_stops, pois): (Vec<_>, Vec<_>) = pois
         .iter()
-        .with_position()
-        .map(|(position, point)| match position {
-            Position::First | Position::Middle => (point, false),
-            Position::Last | Position::Only => (point, true),
-        })
-        .scan_all(vec![], |state, (point, is_last)| {
-            state.push(point);
-
-            let nearby_poi = nearby_pois
-                .iter()
-                .find(|nearby| nearby.closest_point.as_ref() == point);
-
-            match nearby_poi {
-                Some(nearby_poi) => {
-                    let points = std::mem::take(state);
-                    Some((points, Some(nearby_poi.clone())))
-                }
-                None => {
-                    if is_last {
-                        let points = std::mem::take(state);
-                        Some((points, None))
-                    } else {
-                        None
-                    }
-                }
-            }
-        })
-        .flatten();
-
-    let summarized_partitioned_points =
-        partitioned_points.map(|(points, poi): (Vec<&ElevationPoint>, _)| {
-            // Convert points to progress and get the last one for the summary
-            let progress = DistanceElevationProgress::last_from_points(
-                points.clone().into_iter().cloned().collect(),
-            )
-            .expect("Should have at least one point");
+        .cloned()
+        .partition(|poi| poi.point_of_interest_type == PointOfInterestType::PublicTransportStop);
+
+    let start = terminus_stop(&route[0], &transport_stops, CueStop::Start);
+    let end = terminus_stop(&route[last_idx], &transport_stops, CueStop::End);
 
-            (points, poi, progress)
+    let poi_stops = nearby_points_of_interest(route, &pois, 500.0)
+        .into_iter()
+        .map(|nearby| {
+            (
+                nearby.point_idx,
+                CueStop::POI(nearby.point_of_interest.into_owned()),
+            )
         });
 
-    let cues = summarized_partitioned_points
+    let distances = DistanceDelta::running_totals(route);
+    let course_point_stops = course_points.iter().filter_map(|course_point| {
+        course_point_idx(route, &distances, course_point)
+            .map(|idx| (idx, CueStop::CoursePoint(course_point.clone())))
+    });
+
+    // Ordered by distance along the route
+    let stops = poi_stops
+        .chain(course_point_stops)
+        .sorted_by_key(|(idx, _)| *idx)
+        .collect_vec();
+
+    // A station is always worth a cue, even after a stop on the last point
+    let ends_at_stop = stops.last().is_some_and(|(idx, _)| *idx == last_idx)
+        && !matches!(end, CueStop::Station(..));
+
+    let cues = stops
+        .into_iter()
+        .chain((!ends_at_stop).then_some((last_idx, end)))
         .scan_all(
-            None,
-            |prev_poi: &mut Option<NearbyPointOfInterest<_>>, (_, poi, summary)| {
-                let cue = Cue {
-                    origin: match prev_poi {
-                        Some(poi) => CueStop::POI(poi.point_of_interest.clone().into_owned()),
-                        None => CueStop::Start,
-                    },
-                    destination: match &poi {
-                        Some(poi) => CueStop::POI(poi.point_of_interest.clone().into_owned()),
-                        None => CueStop::End,
-                    },
-                    summary,
-                };
+            (0, start),
+            |(start_idx, origin): &mut (usize, CueStop), (idx, destination)| {
+                // Stops on the same point each get a cue, with no distance
+                // between them
+                let points = route[(*start_idx).min(idx)..=idx].to_vec();
+
+                let summary = DistanceElevationProgress::last_from_points(points)
+                    .expect("Should have at least one point");
+
+                *start_idx = idx + 1;
 
-                *prev_poi = poi;
-                cue
+                Cue {
+                    origin: std::mem::replace(origin, destination.clone()),
+                    destination,
+                    summary,
+                }
             },
         )
         .collect_vec();
 
     Cuesheet { cues }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{
+        point_of_interest::{PointOfInterestId, PointOfInterestType},
+        user::UserId,
+    };
+
+    /// ~8.9km east and back again
+    fn create_test_route() -> Vec<ElevationPoint> {
+        (0..=20)
+            .map(|i| {
+                let lng = if i <= 10 {
+                    146.0 + i as f64 * 0.01
+                } else {
+                    146.1 - (i - 10) as f64 * 0.01
+                };
+
+                ElevationPoint {
+                    point: geo::Point::new(lng, -37.0),
+                    elevation: 200.0,
+                }
+            })
+            .collect()
+    }
+
+    fn course_point(lng: f64, distance_m: Option<f64>, kind: &str) -> CoursePoint {
+        CoursePoint {
+            point: geo::Point::new(lng, -37.0),
+            distance_m,
+            kind: String::from(kind),
+            note: None,
+        }
+    }
+
+    #[test]
+    fn test_generate_cuesheet_merges_course_points() {
+        let route = create_test_route();
+        let turnaround = PointOfInterest {
+            id: PointOfInterestId::new(),
+            user_id: UserId::new(),
+            name: String::from("Turnaround Hut"),
+            slug: String::from("turnaround-hut"),
+            point: geo::Point::new(146.1, -37.0),
+            point_of_interest_type: PointOfInterestType::Hut,
+            description: None,
+            external_ref: None,
+        };
+
+        let course_points = vec![
+            // On the way back, which only its distance gives away
+            course_point(146.05, Some(13_300.0), "Left"),
+            // No distance, so it goes to the first closest point
+            course_point(146.02, None, "Right"),
+        ];
+
+        let cuesheet = generate_cuesheet(&route, &[turnaround], &course_points);
+
+        let stops = cuesheet
+            .cues
+            .iter()
+            .map(|cue| (cue.origin.to_string(), cue.destination.to_string()))
+            .collect_vec();
+
+        assert_eq!(stops.len(), 4);
+        assert_eq!(stops[0], (String::from("Start"), String::from("Right")));
+        assert!(matches!(cuesheet.cues[1].destination, CueStop::POI(_)));
+        assert_eq!(stops[2].1, String::from("Left"));
+        assert_eq!(stops[3], (String::from("Left"), String::from("End")));
+
+        assert!((cuesheet.cues[0].summary.distance_m - 1776.0).abs() < 50.0);
+    }
+
+    #[test]
+    fn test_generate_cuesheet_stations_at_ends() {
+        let route = create_test_route();
+        let station = |name: &str, lng: f64| PointOfInterest {
+            id: PointOfInterestId::new(),
+            user_id: UserId::new(),
+            name: String::from(name),
+            slug: name.to_lowercase().replace(' ', "-"),
+            point: geo::Point::new(lng, -37.003),
+            point_of_interest_type: PointOfInterestType::PublicTransportStop,
+            description: None,
+            external_ref: None,
+        };
+
+        // Both stations are within 500m of the route, but only the nearest to
+        // the start and end gets a cue
+        let near = station("Near Station", 146.0);
+        let far = station("Far Station", 146.05);
+
+        let cuesheet = generate_cuesheet(&route, &[far, near], &[]);
+
+        assert_eq!(cuesheet.cues.len(), 1);
+        assert_eq!(
+            cuesheet.cues[0].origin.to_string(),
+            String::from("Near Station (0.3 km away)")
+        );
+        assert_eq!(
+            cuesheet.cues[0].destination.to_string(),
+            String::from("Near Station (0.3 km away)")
+        );
+    }
+
+    #[test]
+    fn test_generate_cuesheet_without_stops() {
+        let route = create_test_route();
+
+        let cuesheet = generate_cuesheet(&route, &[], &[]);
+
+        assert_eq!(cuesheet.cues.len(), 1);
+        assert!(matches!(cuesheet.cues[0].origin, CueStop::Start));
+        assert!(matches!(cuesheet.cues[0].destination, CueStop::End));
+        assert!(generate_cuesheet(&[], &[], &[]).cues.is_empty());
+    }
+}
@@ -0,0 +1,322 @@
+use std::io::Read;
+
+use chrono::{DateTime, Utc};
+use fitparser::{profile::MesgNum, FitDataRecord, Value};
+use thiserror::Error;
+
+use crate::{
+    models::{
+        point::{
+            delta::{AccumulatingDelta, DistanceDelta},
+            TemporalElevationPoint,
+        },
+        ride::{Ride, RideId, RidePoints, RideSensors, SensorSample},
+        user::UserId,
+    },
+    repos::{RidePointsRepo, RideRepo, RideSensorsRepo},
+    services::sync::dedupe::{find_duplicate_ride, FindDuplicateRideParams},
+};
+
+#[derive(Error, Debug)]
+pub enum FitImportError {
+    #[error("Failed to parse FIT: {0}")]
+    Parse(#[from] fitparser::Error),
+    #[error("FIT contains no record messages with a position, altitude and timestamp")]
+    NoTemporalPoints,
+    #[error("Ride already exists: {0}")]
+    Duplicate(RideId),
+    #[error("Repository error: {0}")]
+    Repo(#[from] anyhow::Error),
+}
+
+/// A single FIT `record` message, with positions converted from semicircles to degrees.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FitRecord {
+    pub datetime: DateTime<Utc>,
+    pub point: Option<geo::Point>,
+    pub elevation: Option<f64>,
+    pub heart_rate: Option<u8>,
+    pub cadence: Option<u8>,
+    pub power: Option<u16>,
+    pub temperature: Option<i8>,
+}
+
+impl FitRecord {
+    pub fn temporal_elevation_point(&self) -> Option<TemporalElevationPoint> {
+        Some(TemporalElevationPoint {
+            datetime: self.datetime,
+            point: self.point?,
+            elevation: self.elevation?,
+        })
+    }
+
+    pub fn sensor_sample(&self) -> SensorSample {
+        SensorSample {
+            datetime: self.datetime,
+            heart_rate: self.heart_rate,
+            cadence: self.cadence,
+            power: self.power,
+            temperature: self.temperature,
+        }
+    }
+}
+
+const SEMICIRCLES_TO_DEGREES: f64 = 180.0 / 2_147_483_648.0;
+
+fn value_as_f64(value: &Value) -> Option<f64> {
+    match value {
+        Value::SInt8(v) => Some(*v as f64),
+        Value::UInt8(v) | Value::UInt8z(v) | Value::Byte(v) => Some(*v as f64),
+        Value::SInt16(v) => Some(*v as f64),
+        Value::UInt16(v) | Value::UInt16z(v) => Some(*v as f64),
+        Value::SInt32(v) => Some(*v as f64),
+        Value::UInt32(v) | Value::UInt32z(v) => Some(*v as f64),
+        Value::SInt64(v) => Some(*v as f64),
+        Value::UInt64(v) | Value::UInt64z(v) => Some(*v as f64),
+        Value::Float32(v) => Some(*v as f64),
+        Value::Float64(v) => Some(*v),
+        _ => None,
+    }
+}
+
+fn parse_record(record: &FitDataRecord) -> Option<FitRecord> {
+    let field = |name: &str| {
+        record
+            .fields()
+            .iter()
+            .find(|field| field.name() == name)
+            .map(|field| field.value())
+    };
+    let number = |name: &str| field(name).and_then(value_as_f64);
+
+    let datetime = match field("timestamp")? {
+        Value::Timestamp(timestamp) => timestamp.with_timezone(&Utc),
+        _ => return None,
+    };
+
+    let point = match (number("position_long"), number("position_lat")) {
+        (Some(lng), Some(lat)) => Some(geo::Point::new(
+            lng * SEMICIRCLES_TO_DEGREES,
+            lat * SEMICIRCLES_TO_DEGREES,
+        )),
+        _ => None,
+    };
+
+    Some(FitRecord {
+        datetime,
+        point,
+        elevation: number("enhanced_altitude").or_else(|| number("altitude")),
+        heart_rate: number("heart_rate").map(|v| v as u8),
+        cadence: number("cadence").map(|v| v as u8),
+        power: number("power").map(|v| v as u16),
+        temperature: number("temperature").map(|v| v as i8),
+    })
+}
+
+pub fn parse_fit_records(mut data: impl Read) -> Result<Vec<FitRecord>, FitImportError> {
+    Ok(fitparser::from_reader(&mut data)?
+        .iter()
+        .filter(|record| record.kind() == MesgNum::Record)
+        .filter_map(parse_record)
+        .collect())
+}
+
+pub struct ImportFitRideParams<R> {
+    pub ride_repo: RideRepo,
+    pub ride_points_repo: RidePointsRepo,
+    pub ride_sensors_repo: RideSensorsRepo,
+    pub user_id: UserId,
+    pub name: Option<String>,
+    pub data: R,
+}
+
+pub async fn import_fit_ride<R: Read>(
+    ImportFitRideParams {
+        ride_repo,
+        ride_points_repo,
+        ride_sensors_repo,
+        user_id,
+        name,
+        data,
+    }: ImportFitRideParams<R>,
+) -> Result<Ride, FitImportError> {
+    let records = parse_fit_records(data)?;
+
+    let points = records
+        .iter()
+        .filter_map(FitRecord::temporal_elevation_point)
+        .collect::<Vec<_>>();
+
+    let samples = records
+        .iter()
+        .map(FitRecord::sensor_sample)
+        .filter(|sample| !sample.is_empty())
+        .collect::<Vec<_>>();
+
+    tracing::info!(
+        total_records = records.len(),
+        total_points = points.len(),
+        total_samples = samples.len(),
+        "Parsed FIT records"
+    );
+
+    let started_at = points
+        .iter()
+        .map(|point| point.datetime)
+        .min()
+        .ok_or(FitImportError::NoTemporalPoints)?;
+
+    let finished_at = points
+        .iter()
+        .map(|point| point.datetime)
+        .max()
+        .ok_or(FitImportError::NoTemporalPoints)?;
+
+    let duplicate_ride = find_duplicate_ride(FindDuplicateRideParams {
+        ride_repo: ride_repo.clone(),
+        user_id,
+        started_at,
+        finished_at,
+    })
+    .await?;
+
+    if let Some(duplicate_ride) = duplicate_ride {
+        return Err(FitImportError::Duplicate(duplicate_ride.id));
+    }
+
+    let distance = DistanceDelta::running_totals(&points)
+        .last()
+        .map(|DistanceDelta(distance)| *distance)
+        .unwrap_or(0.0);
+
+    let id = RideId::new();
+    let ride = Ride {
+        id,
+        name: name.unwrap_or_else(|| format!("Ride {}", started_at.format("%Y-%m-%d"))),
+        user_id,
+        distance,
+        started_at,
+        finished_at,
+        external_ref: None,
+        archived_at: None,
+    };
+
+    ride_repo.put(ride.clone()).await?;
+    ride_points_repo.put(RidePoints { id, points }).await?;
+
+    if !samples.is_empty() {
+        ride_sensors_repo.put(RideSensors { id, samples }).await?;
+    }
+
+    tracing::info!(ride_id = %id, "Imported ride from FIT");
+
+    Ok(ride)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Local, TimeZone};
+    use fitparser::FitDataField;
+
+    use super::*;
+
+    fn datetime() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap()
+    }
+
+    fn record(fields: Vec<(&str, Value)>) -> FitDataRecord {
+        let mut record = FitDataRecord::new(MesgNum::Record);
+
+        for (number, (name, value)) in fields.into_iter().enumerate() {
+            record.push(FitDataField::new(
+                name.to_string(),
+                number as u8,
+                None,
+                value,
+                String::new(),
+            ));
+        }
+
+        record
+    }
+
+    fn timestamp() -> (&'static str, Value) {
+        (
+            "timestamp",
+            Value::Timestamp(datetime().with_timezone(&Local)),
+        )
+    }
+
+    #[test]
+    fn test_value_as_f64() {
+        assert_eq!(value_as_f64(&Value::SInt8(-5)), Some(-5.0));
+        assert_eq!(value_as_f64(&Value::UInt8z(7)), Some(7.0));
+        assert_eq!(value_as_f64(&Value::UInt16(1_000)), Some(1_000.0));
+        assert_eq!(
+            value_as_f64(&Value::SInt32(-1_073_741_824)),
+            Some(-1_073_741_824.0)
+        );
+        assert_eq!(value_as_f64(&Value::Float32(1.5)), Some(1.5));
+        assert_eq!(value_as_f64(&Value::Float64(123.25)), Some(123.25));
+        assert_eq!(value_as_f64(&Value::String("12".to_string())), None);
+        assert_eq!(value_as_f64(&Value::Timestamp(Local::now())), None);
+    }
+
+    #[test]
+    fn test_parse_record() {
+        let parsed = parse_record(&record(vec![
+            timestamp(),
+            ("position_lat", Value::SInt32(-1_073_741_824)),
+            ("position_long", Value::SInt32(536_870_912)),
+            ("altitude", Value::Float64(90.0)),
+            ("enhanced_altitude", Value::Float64(100.5)),
+            ("heart_rate", Value::UInt8(140)),
+            ("cadence", Value::UInt8(85)),
+            ("power", Value::UInt16(250)),
+            ("temperature", Value::SInt8(-3)),
+        ]))
+        .unwrap();
+
+        assert_eq!(
+            parsed,
+            FitRecord {
+                datetime: datetime(),
+                point: Some(geo::Point::new(45.0, -90.0)),
+                elevation: Some(100.5),
+                heart_rate: Some(140),
+                cadence: Some(85),
+                power: Some(250),
+                temperature: Some(-3),
+            }
+        );
+        assert!(parsed.temporal_elevation_point().is_some());
+    }
+
+    #[test]
+    fn test_parse_record_partial() {
+        // Indoor records have sensors but no position, and fall back to
+        // plain altitude
+        let parsed = parse_record(&record(vec![
+            timestamp(),
+            ("altitude", Value::Float64(90.0)),
+            ("heart_rate", Value::UInt8(120)),
+        ]))
+        .unwrap();
+
+        assert_eq!(parsed.point, None);
+        assert_eq!(parsed.elevation, Some(90.0));
+        assert_eq!(parsed.heart_rate, Some(120));
+        assert_eq!(parsed.temporal_elevation_point(), None);
+        assert!(!parsed.sensor_sample().is_empty());
+
+        // A record without a timestamp can't be placed in the ride
+        assert_eq!(
+            parse_record(&record(vec![("heart_rate", Value::UInt8(120))])),
+            None
+        );
+        assert_eq!(
+            parse_record(&record(vec![("timestamp", Value::UInt32(0))])),
+            None
+        );
+    }
+}
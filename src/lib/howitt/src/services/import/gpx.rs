@@ -0,0 +1,307 @@
+use std::{collections::HashSet, io::Read};
+
+use thiserror::Error;
+
+use crate::{
+    ext::gpx::WaypointExt,
+    models::{
+        point::{
+            delta::{AccumulatingDelta, DistanceDelta},
+            ElevationPoint, Point, TemporalElevationPoint,
+        },
+        ride::{Ride, RideId, RidePoints},
+        route::{Route, RouteId, RoutePoints},
+        user::UserId,
+    },
+    repos::{RidePointsRepo, RideRepo, RoutePointsRepo, RouteRepo},
+    services::{
+        simplify_points::{simplify_points_v2, DetailLevel},
+        slug::generate_slug,
+    },
+};
+
+#[derive(Error, Debug)]
+pub enum GpxImportError {
+    #[error("Failed to parse GPX: {0}")]
+    Parse(#[from] gpx::errors::GpxError),
+    #[error("GPX contains no track points with both an elevation and a timestamp")]
+    NoTemporalPoints,
+    #[error("GPX contains no route or track points with an elevation")]
+    NoPoints,
+    #[error("Repository error: {0}")]
+    Repo(#[from] anyhow::Error),
+}
+
+pub fn parse_gpx(data: impl Read) -> Result<gpx::Gpx, GpxImportError> {
+    Ok(gpx::read(data)?)
+}
+
+/// The first name found in the file, checking metadata, then tracks, then routes.
+pub fn gpx_name(gpx: &gpx::Gpx) -> Option<String> {
+    gpx.metadata
+        .as_ref()
+        .and_then(|metadata| metadata.name.clone())
+        .or_else(|| gpx.tracks.iter().find_map(|track| track.name.clone()))
+        .or_else(|| gpx.routes.iter().find_map(|route| route.name.clone()))
+}
+
+/// Collects every track point that has both an elevation and a timestamp.
+pub fn gpx_temporal_elevation_points(gpx: &gpx::Gpx) -> Vec<TemporalElevationPoint> {
+    gpx.tracks
+        .iter()
+        .flat_map(|track| &track.segments)
+        .flat_map(|segment| &segment.points)
+        .filter_map(|waypoint| match (waypoint.elevation, waypoint.time()) {
+            (Some(elevation), Some(datetime)) => Some(TemporalElevationPoint {
+                datetime,
+                point: waypoint.point(),
+                elevation,
+            }),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Collects route points, falling back to track points (ignoring any
+/// timestamps) when the file has no routes.
+pub fn gpx_elevation_points(gpx: &gpx::Gpx) -> Vec<ElevationPoint> {
+    let to_elevation_point = |waypoint: &gpx::Waypoint| {
+        waypoint.elevation.map(|elevation| ElevationPoint {
+            point: waypoint.point(),
+            elevation,
+        })
+    };
+
+    let route_points = gpx
+        .routes
+        .iter()
+        .flat_map(|route| &route.points)
+        .filter_map(to_elevation_point)
+        .collect::<Vec<_>>();
+
+    if !route_points.is_empty() {
+        return route_points;
+    }
+
+    gpx.tracks
+        .iter()
+        .flat_map(|track| &track.segments)
+        .flat_map(|segment| &segment.points)
+        .filter_map(to_elevation_point)
+        .collect()
+}
+
+fn total_distance<P: Point>(points: &[P]) -> f64 {
+    DistanceDelta::running_totals(points)
+        .last()
+        .map(|DistanceDelta(distance)| *distance)
+        .unwrap_or(0.0)
+}
+
+pub struct ImportGpxRideParams<R> {
+    pub ride_repo: RideRepo,
+    pub ride_points_repo: RidePointsRepo,
+    pub user_id: UserId,
+    pub name: Option<String>,
+    pub data: R,
+}
+
+pub async fn import_gpx_ride<R: Read>(
+    ImportGpxRideParams {
+        ride_repo,
+        ride_points_repo,
+        user_id,
+        name,
+        data,
+    }: ImportGpxRideParams<R>,
+) -> Result<Ride, GpxImportError> {
+    let gpx = parse_gpx(data)?;
+    let points = gpx_temporal_elevation_points(&gpx);
+
+    tracing::info!(
+        total_points = points.len(),
+        "Parsed temporal elevation points from GPX"
+    );
+
+    let started_at = points
+        .iter()
+        .map(|point| point.datetime)
+        .min()
+        .ok_or(GpxImportError::NoTemporalPoints)?;
+
+    let finished_at = points
+        .iter()
+        .map(|point| point.datetime)
+        .max()
+        .ok_or(GpxImportError::NoTemporalPoints)?;
+
+    let id = RideId::new();
+    let ride = Ride {
+        id,
+        name: name
+            .or_else(|| gpx_name(&gpx))
+            .unwrap_or_else(|| format!("Ride {}", started_at.format("%Y-%m-%d"))),
+        user_id,
+        distance: total_distance(&points),
+        started_at,
+        finished_at,
+        external_ref: None,
+        archived_at: None,
+    };
+
+    ride_repo.put(ride.clone()).await?;
+    ride_points_repo.put(RidePoints { id, points }).await?;
+
+    tracing::info!(ride_id = %id, "Imported ride from GPX");
+
+    Ok(ride)
+}
+
+/// Picks a slug for a new route which doesn't clash with any existing route,
+/// numbering it when the name is taken, as it is for every untitled import
+/// after the first.
+fn unique_slug(name: &str, taken: &HashSet<String>) -> String {
+    let base = generate_slug(name);
+
+    std::iter::once(base.clone())
+        .chain((2..).map(|n| format!("{base}-{n}")))
+        .find(|slug| !taken.contains(slug))
+        .expect("slug candidates are unbounded")
+}
+
+pub struct ImportGpxRouteParams<R> {
+    pub route_repo: RouteRepo,
+    pub route_points_repo: RoutePointsRepo,
+    pub user_id: UserId,
+    pub name: Option<String>,
+    pub data: R,
+}
+
+pub async fn import_gpx_route<R: Read>(
+    ImportGpxRouteParams {
+        route_repo,
+        route_points_repo,
+        user_id,
+        name,
+        data,
+    }: ImportGpxRouteParams<R>,
+) -> Result<Route, GpxImportError> {
+    let gpx = parse_gpx(data)?;
+    let points = gpx_elevation_points(&gpx);
+
+    tracing::info!(
+        total_points = points.len(),
+        "Parsed elevation points from GPX"
+    );
+
+    if points.is_empty() {
+        return Err(GpxImportError::NoPoints);
+    }
+
+    let name = name
+        .or_else(|| gpx_name(&gpx))
+        .unwrap_or_else(|| String::from("Imported route"));
+
+    // Archived routes hold on to their slugs too
+    let taken_slugs: HashSet<String> = route_repo
+        .all()
+        .await?
+        .into_iter()
+        .map(|route| route.slug)
+        .collect();
+
+    let id = RouteId::new();
+    let route = Route {
+        id,
+        slug: unique_slug(&name, &taken_slugs),
+        name,
+        user_id,
+        distance: total_distance(&points),
+        sample_points: Some(simplify_points_v2(
+            points.clone(),
+            DetailLevel::ExtremelyLow,
+        )),
+        description: None,
+        external_ref: None,
+        tags: Default::default(),
+        archived_at: None,
+    };
+
+    route_repo.put(route.clone()).await?;
+    route_points_repo.put(RoutePoints { id, points }).await?;
+
+    tracing::info!(route_id = %id, "Imported route from GPX");
+
+    Ok(route)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TRACK_GPX: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<gpx version="1.1" creator="test" xmlns="http://www.topografix.com/GPX/1/1">
+  <metadata><name>Morning ride</name></metadata>
+  <trk>
+    <name>Track name</name>
+    <trkseg>
+      <trkpt lat="-37.0" lon="146.0"><ele>100</ele><time>2024-01-01T00:00:00Z</time></trkpt>
+      <trkpt lat="-37.001" lon="146.0"><ele>110</ele><time>2024-01-01T00:01:00Z</time></trkpt>
+      <trkpt lat="-37.002" lon="146.0"><ele>120</ele></trkpt>
+    </trkseg>
+  </trk>
+</gpx>"#;
+
+    const ROUTE_GPX: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<gpx version="1.1" creator="test" xmlns="http://www.topografix.com/GPX/1/1">
+  <rte>
+    <name>Route name</name>
+    <rtept lat="-37.0" lon="146.0"><ele>100</ele></rtept>
+    <rtept lat="-37.001" lon="146.0"><ele>110</ele></rtept>
+  </rte>
+</gpx>"#;
+
+    #[test]
+    fn test_temporal_points_skip_untimed() {
+        let gpx = parse_gpx(TRACK_GPX.as_bytes()).unwrap();
+        let points = gpx_temporal_elevation_points(&gpx);
+
+        assert_eq!(points.len(), 2);
+        assert_eq!(points[1].elevation, 110.0);
+    }
+
+    #[test]
+    fn test_elevation_points_fall_back_to_tracks() {
+        let gpx = parse_gpx(TRACK_GPX.as_bytes()).unwrap();
+        assert_eq!(gpx_elevation_points(&gpx).len(), 3);
+    }
+
+    #[test]
+    fn test_elevation_points_prefer_routes() {
+        let gpx = parse_gpx(ROUTE_GPX.as_bytes()).unwrap();
+        let points = gpx_elevation_points(&gpx);
+
+        assert_eq!(points.len(), 2);
+        assert!((total_distance(&points) - 111.2).abs() < 1.0);
+    }
+
+    #[test]
+    fn test_gpx_name() {
+        let track = parse_gpx(TRACK_GPX.as_bytes()).unwrap();
+        let route = parse_gpx(ROUTE_GPX.as_bytes()).unwrap();
+
+        assert_eq!(gpx_name(&track).as_deref(), Some("Morning ride"));
+        assert_eq!(gpx_name(&route).as_deref(), Some("Route name"));
+    }
+
+    #[test]
+    fn test_unique_slug() {
+        let taken = HashSet::from([
+            String::from("imported-route"),
+            String::from("imported-route-2"),
+        ]);
+
+        assert_eq!(unique_slug("Falls Creek", &taken), "falls-creek");
+        assert_eq!(unique_slug("Imported route", &taken), "imported-route-3");
+    }
+}
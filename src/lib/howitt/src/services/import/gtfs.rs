@@ -0,0 +1,233 @@
+use std::collections::HashSet;
+
+use ::gtfs::GtfsStop;
+use chrono::Utc;
+
+use crate::{
+    models::{
+        external_ref::{ExternalId, ExternalRef, ExternalRefItemMap, ExternalRefMatch, GtfsId},
+        point_of_interest::{PointOfInterest, PointOfInterestId, PointOfInterestType},
+        user::UserId,
+    },
+    repos::PointOfInterestRepo,
+    services::slug::generate_slug,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum GtfsStopImportAction {
+    Create(PointOfInterest),
+    Update(PointOfInterest),
+    Unchanged,
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct GtfsStopImportSummary {
+    pub created: usize,
+    pub updated: usize,
+    pub unchanged: usize,
+}
+
+fn gtfs_stop_external_id(feed: &str, stop: &GtfsStop) -> ExternalId {
+    ExternalId::Gtfs(GtfsId::Stop {
+        feed: feed.to_string(),
+        stop_id: stop.stop_id.clone(),
+    })
+}
+
+/// Picks a slug for a new stop which doesn't clash with any existing POI.
+/// Stops commonly share names (e.g. platforms), so the stop id is used to
+/// disambiguate before falling back to a counter.
+fn unique_slug(stop: &GtfsStop, taken: &HashSet<String>) -> String {
+    let base = generate_slug(&stop.stop_name);
+
+    std::iter::once(base.clone())
+        .chain(std::iter::once(format!(
+            "{base}-{}",
+            generate_slug(&stop.stop_id)
+        )))
+        .chain((2..).map(|n| format!("{base}-{}-{n}", generate_slug(&stop.stop_id))))
+        .find(|slug| !taken.contains(slug))
+        .expect("slug candidates are unbounded")
+}
+
+/// Works out what to do with each stop in a feed. Stops are matched to existing
+/// POIs by their GTFS stop id, so ids (and therefore visits and media) and
+/// slugs are kept stable across imports, while names and positions follow the feed.
+pub fn plan_gtfs_stop_import(
+    existing: Vec<PointOfInterest>,
+    stops: &[GtfsStop],
+    feed: &str,
+    user_id: UserId,
+) -> Vec<GtfsStopImportAction> {
+    let mut taken_slugs: HashSet<String> = existing.iter().map(|poi| poi.slug.clone()).collect();
+    let existing = ExternalRefItemMap::from_externally_reffed(existing);
+
+    stops
+        .iter()
+        .map(|stop| {
+            let point = geo::Point::new(stop.stop_lon, stop.stop_lat);
+            let external_ref = ExternalRef {
+                id: gtfs_stop_external_id(feed, stop),
+                updated_at: Utc::now(),
+                sync_version: None,
+            };
+
+            match existing.match_ref(external_ref.clone()) {
+                ExternalRefMatch::Fresh(poi) | ExternalRefMatch::Stale(poi) => {
+                    if poi.name == stop.stop_name && poi.point == point {
+                        return GtfsStopImportAction::Unchanged;
+                    }
+
+                    GtfsStopImportAction::Update(PointOfInterest {
+                        name: stop.stop_name.clone(),
+                        point,
+                        external_ref: Some(external_ref),
+                        ..poi.clone()
+                    })
+                }
+                ExternalRefMatch::NotFound => {
+                    let slug = unique_slug(stop, &taken_slugs);
+                    taken_slugs.insert(slug.clone());
+
+                    GtfsStopImportAction::Create(PointOfInterest {
+                        id: PointOfInterestId::new(),
+                        user_id,
+                        name: stop.stop_name.clone(),
+                        slug,
+                        point,
+                        point_of_interest_type: PointOfInterestType::PublicTransportStop,
+                        description: None,
+                        external_ref: Some(external_ref),
+                    })
+                }
+            }
+        })
+        .collect()
+}
+
+pub struct ImportGtfsStopsParams {
+    pub point_of_interest_repo: PointOfInterestRepo,
+    pub user_id: UserId,
+    pub feed: String,
+    pub stops: Vec<GtfsStop>,
+}
+
+pub async fn import_gtfs_stops(
+    ImportGtfsStopsParams {
+        point_of_interest_repo,
+        user_id,
+        feed,
+        stops,
+    }: ImportGtfsStopsParams,
+) -> Result<GtfsStopImportSummary, anyhow::Error> {
+    let existing = point_of_interest_repo.all().await?;
+
+    let mut summary = GtfsStopImportSummary::default();
+
+    for action in plan_gtfs_stop_import(existing, &stops, &feed, user_id) {
+        match action {
+            GtfsStopImportAction::Create(poi) => {
+                point_of_interest_repo.put(poi).await?;
+                summary.created += 1;
+            }
+            GtfsStopImportAction::Update(poi) => {
+                point_of_interest_repo.put(poi).await?;
+                summary.updated += 1;
+            }
+            GtfsStopImportAction::Unchanged => {
+                summary.unchanged += 1;
+            }
+        }
+    }
+
+    tracing::info!(
+        feed,
+        created = summary.created,
+        updated = summary.updated,
+        unchanged = summary.unchanged,
+        "Imported GTFS stops"
+    );
+
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_test_stop(stop_id: &str, stop_name: &str, stop_lon: f64) -> GtfsStop {
+        GtfsStop {
+            stop_id: String::from(stop_id),
+            stop_name: String::from(stop_name),
+            stop_lat: -37.0,
+            stop_lon,
+        }
+    }
+
+    fn created(actions: Vec<GtfsStopImportAction>) -> Vec<PointOfInterest> {
+        actions
+            .into_iter()
+            .filter_map(|action| match action {
+                GtfsStopImportAction::Create(poi) => Some(poi),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_new_stops_get_unique_slugs() {
+        let stops = vec![
+            create_test_stop("1001", "Platform 1", 145.0),
+            create_test_stop("1002", "Platform 1", 145.1),
+            create_test_stop("1003", "Seymour Station", 145.2),
+        ];
+
+        let pois = created(plan_gtfs_stop_import(
+            vec![],
+            &stops,
+            "vline",
+            UserId::new(),
+        ));
+
+        assert_eq!(
+            pois.iter().map(|poi| poi.slug.as_str()).collect::<Vec<_>>(),
+            vec!["platform-1", "platform-1-1002", "seymour-station"]
+        );
+        assert!(pois
+            .iter()
+            .all(|poi| poi.point_of_interest_type == PointOfInterestType::PublicTransportStop));
+    }
+
+    #[test]
+    fn test_reimport_updates_matched_stops_in_place() {
+        let user_id = UserId::new();
+        let stops = vec![
+            create_test_stop("1001", "Seymour", 145.0),
+            create_test_stop("1002", "Tallarook", 145.1),
+        ];
+
+        let existing = created(plan_gtfs_stop_import(vec![], &stops, "vline", user_id));
+
+        let stops = vec![
+            create_test_stop("1001", "Seymour Station", 145.001),
+            create_test_stop("1002", "Tallarook", 145.1),
+        ];
+
+        let actions = plan_gtfs_stop_import(existing.clone(), &stops, "vline", user_id);
+
+        match &actions[0] {
+            GtfsStopImportAction::Update(poi) => {
+                assert_eq!(poi.id, existing[0].id);
+                assert_eq!(poi.slug, "seymour");
+                assert_eq!(poi.name, "Seymour Station");
+                assert_eq!(poi.point, geo::Point::new(145.001, -37.0));
+            }
+            action => panic!("Expected update, got {action:?}"),
+        }
+        assert_eq!(actions[1], GtfsStopImportAction::Unchanged);
+
+        // The same stop id from a different feed is a different stop
+        let actions = plan_gtfs_stop_import(existing, &stops[1..], "metro", user_id);
+        assert_eq!(created(actions)[0].slug, "tallarook-1002");
+    }
+}
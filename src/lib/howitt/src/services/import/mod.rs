@@ -0,0 +1,9 @@
+pub mod fit;
+pub mod gpx;
+pub mod gtfs;
+pub mod osm;
+
+pub use self::fit::*;
+pub use self::gpx::*;
+pub use self::gtfs::*;
+pub use self::osm::*;
@@ -0,0 +1,93 @@
+use std::collections::HashMap;
+
+use crate::models::osm_feature::{OsmFeature, OsmFeatureId, OsmFeatureType};
+
+/// Builds a feature from an OSM element, or `None` if its tags aren't on the whitelist.
+pub fn osm_feature_from_element<'a>(
+    id: OsmFeatureId,
+    geometry: geo::Geometry<f64>,
+    tags: impl Iterator<Item = (&'a str, &'a str)>,
+) -> Option<OsmFeature> {
+    let tags: HashMap<String, String> = tags
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect();
+
+    OsmFeatureType::from_tags(&tags)?;
+
+    Some(OsmFeature {
+        id,
+        geometry,
+        name: tags.get("name").cloned(),
+        tags,
+    })
+}
+
+/// Resolves a way's node refs to a geometry. Closed ways (e.g. a shelter
+/// mapped as a building outline) become polygons. Returns `None` if any node
+/// is missing from the extract.
+pub fn osm_way_geometry(
+    refs: &[i64],
+    node_points: &HashMap<i64, geo::Point<f64>>,
+) -> Option<geo::Geometry<f64>> {
+    let points = refs
+        .iter()
+        .map(|id| node_points.get(id).copied())
+        .collect::<Option<Vec<_>>>()?;
+
+    match points.as_slice() {
+        [] => None,
+        [point] => Some(geo::Geometry::Point(*point)),
+        [first, .., last] if first == last && points.len() >= 4 => Some(geo::Geometry::Polygon(
+            geo::Polygon::new(geo::LineString::from_iter(points), vec![]),
+        )),
+        _ => Some(geo::Geometry::LineString(geo::LineString::from_iter(
+            points,
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_osm_feature_from_element_applies_whitelist() {
+        let point = geo::Geometry::Point(geo::Point::new(146.5, -37.1));
+
+        let feature = osm_feature_from_element(
+            OsmFeatureId::node(1),
+            point.clone(),
+            [("amenity", "drinking_water"), ("name", "Tank")].into_iter(),
+        )
+        .unwrap();
+
+        assert_eq!(feature.name.as_deref(), Some("Tank"));
+        assert_eq!(feature.feature_type(), Some(OsmFeatureType::DrinkingWater));
+
+        assert!(osm_feature_from_element(
+            OsmFeatureId::node(2),
+            point,
+            [("amenity", "bench")].into_iter(),
+        )
+        .is_none());
+    }
+
+    #[test]
+    fn test_osm_way_geometry() {
+        let node_points = HashMap::from([
+            (1, geo::Point::new(0.0, 0.0)),
+            (2, geo::Point::new(0.001, 0.0)),
+            (3, geo::Point::new(0.001, 0.001)),
+        ]);
+
+        assert!(matches!(
+            osm_way_geometry(&[1, 2, 3, 1], &node_points),
+            Some(geo::Geometry::Polygon(_))
+        ));
+        assert!(matches!(
+            osm_way_geometry(&[1, 2, 3], &node_points),
+            Some(geo::Geometry::LineString(_))
+        ));
+        assert_eq!(osm_way_geometry(&[1, 4], &node_points), None);
+    }
+}
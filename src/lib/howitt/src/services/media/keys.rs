@@ -1,7 +1,7 @@
 use sanitize_filename::sanitize;
 
 use crate::models::{
-    media::{ImageSpec, ImageContentType, MediaId},
+    media::{ImageContentType, ImageSpec, Media, MediaId, IMAGE_SPECS},
     user::UserId,
 };
 
@@ -50,6 +50,27 @@ pub fn generate_resized_media_key(
     )
 }
 
+/// Every bucket key written for a media item: the original upload and each
+/// resized variant.
+pub fn media_object_keys(media: &Media) -> Vec<String> {
+    let resized_keys = IMAGE_SPECS.iter().flat_map(|image_spec| {
+        [ImageContentType::Jpeg, ImageContentType::Webp]
+            .into_iter()
+            .map(|content_type| {
+                generate_resized_media_key(GenerateResizedMediaKeyParams {
+                    media_id: media.id,
+                    user_id: media.user_id,
+                    content_type,
+                    image_spec: image_spec.clone(),
+                })
+            })
+    });
+
+    std::iter::once(media.path.clone())
+        .chain(resized_keys)
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use crate::models::media::ImageDimensions;
@@ -136,4 +157,28 @@ mod tests {
             "resizes/user/9d25a949-c374-4f22-9ca8-1c17d4982384/media/7f9c24e5-2c44-4a8e-95d3-a515bf484018_fit_800x600.webp"
         );
     }
+
+    #[test]
+    fn test_media_object_keys() {
+        let media = Media {
+            id: MediaId::from(
+                uuid::Uuid::parse_str("550e8400-e29b-41d4-a716-446655440000").unwrap(),
+            ),
+            created_at: chrono::Utc::now(),
+            user_id: UserId::from(
+                uuid::Uuid::parse_str("123e4567-e89b-12d3-a456-426614174000").unwrap(),
+            ),
+            path: "originals/user/123e4567-e89b-12d3-a456-426614174000/media/550e8400-e29b-41d4-a716-446655440000/test-image.jpg".to_string(),
+            relation_ids: vec![],
+            point: None,
+            captured_at: None,
+            external_ref: None,
+        };
+
+        let keys = media_object_keys(&media);
+
+        assert_eq!(keys.len(), 1 + IMAGE_SPECS.len() * 2);
+        assert_eq!(keys[0], media.path);
+        assert!(keys.contains(&"resizes/user/123e4567-e89b-12d3-a456-426614174000/media/550e8400-e29b-41d4-a716-446655440000_fit_2400x2400.webp".to_string()));
+    }
 }
@@ -150,6 +150,7 @@ mod tests {
             started_at,
             finished_at,
             external_ref: None,
+            archived_at: None,
         }
     }
 
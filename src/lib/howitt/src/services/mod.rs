@@ -1,12 +1,22 @@
+pub mod detect_poi_visits;
 pub mod euclidean;
+pub mod export;
 pub mod fetchers;
 pub mod generate_cuesheet;
+pub mod import;
 pub mod lerp;
 pub mod media;
 pub mod nearby;
 pub mod num;
+pub mod ride_weather;
+pub mod route_forecast;
+pub mod search;
 pub mod simplify_points;
 pub mod slug;
 pub mod smoothing;
 pub mod sync;
+pub mod transport_access;
 pub mod user;
+pub mod water_resupply;
+pub mod water_sources;
+pub mod wind;
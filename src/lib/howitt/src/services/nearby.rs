@@ -14,6 +14,33 @@ use itertools::Itertools;
 
 use super::simplify_points::{simplify_points_v2, DetailLevel};
 
+const METRES_PER_DEGREE: f64 = 111_320.0;
+
+/// The bounds of `points` grown by `distance_m` on every side, for narrowing
+/// down which POIs could be within `distance_m` of any of them. Longitude
+/// degrees shrink towards the poles so the box is widened by the max latitude.
+pub fn padded_bounds<P: Point>(points: &[P], distance_m: f64) -> Option<geo::Rect<f64>> {
+    let (min_x, max_x) = points
+        .iter()
+        .map(|point| ordered_float::OrderedFloat(point.as_geo_point().x()))
+        .minmax()
+        .into_option()?;
+    let (min_y, max_y) = points
+        .iter()
+        .map(|point| ordered_float::OrderedFloat(point.as_geo_point().y()))
+        .minmax()
+        .into_option()?;
+
+    let max_latitude = min_y.abs().max(max_y.abs()).min(89.0);
+    let padding_y = distance_m / METRES_PER_DEGREE;
+    let padding_x = padding_y / max_latitude.to_radians().cos();
+
+    Some(geo::Rect::new(
+        geo::coord! { x: *min_x - padding_x, y: *min_y - padding_y },
+        geo::coord! { x: *max_x + padding_x, y: *max_y + padding_y },
+    ))
+}
+
 #[derive(Debug, Clone)]
 pub struct NearbyPointOfInterest<'point, 'poi, P>
 where
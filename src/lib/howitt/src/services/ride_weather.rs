@@ -0,0 +1,174 @@
+use chrono::{DateTime, DurationRound, TimeDelta, Utc};
+use geo::{Bearing, Distance, Haversine};
+use itertools::Itertools;
+pub use open_meteo::schema::HourlyObservation;
+
+use crate::models::{
+    point::{Point, TemporalElevationPoint, WithDatetime},
+    ride::RideId,
+    ride_weather::{HourlyWeather, RideWeather},
+};
+
+/// Below this the rider was stopped or milling about, and the heading is noise.
+const MIN_BEARING_DISTANCE_M: f64 = 200.0;
+
+/// Where the rider was at the start of an hour of the ride.
+#[derive(Debug, PartialEq, Clone)]
+pub struct HourSample {
+    pub hour: DateTime<Utc>,
+    pub point: geo::Point,
+    pub bearing: Option<f64>,
+}
+
+/// Bearing from `first` to `last`, if they're far enough apart for it to be
+/// a heading rather than GPS jitter.
+pub fn heading(first: &geo::Point, last: &geo::Point) -> Option<f64> {
+    if Haversine::distance(*first, *last) >= MIN_BEARING_DISTANCE_M {
+        Some(Haversine::bearing(*first, *last))
+    } else {
+        None
+    }
+}
+
+/// Buckets ride points by the (UTC) hour they were recorded in, sampling the
+/// first point of each. The bearing is taken from the first point to the last.
+pub fn sample_hours(points: &[TemporalElevationPoint]) -> Vec<HourSample> {
+    points
+        .iter()
+        .chunk_by(|point| {
+            point
+                .datetime()
+                .duration_trunc(TimeDelta::hours(1))
+                .unwrap_or(*point.datetime())
+        })
+        .into_iter()
+        .filter_map(|(hour, points)| {
+            let points = points.collect_vec();
+            let first = points.first()?.as_geo_point();
+            let last = points.last()?.as_geo_point();
+
+            Some(HourSample {
+                hour,
+                point: *first,
+                bearing: heading(first, last),
+            })
+        })
+        .collect()
+}
+
+pub fn hourly_weather(sample: HourSample, observation: HourlyObservation) -> HourlyWeather {
+    let HourlyObservation {
+        temperature,
+        precipitation,
+        wind_speed,
+        wind_direction,
+    } = observation;
+
+    HourlyWeather {
+        datetime: sample.hour,
+        point: sample.point,
+        bearing: sample.bearing,
+        temperature,
+        precipitation,
+        wind_speed,
+        wind_direction,
+    }
+}
+
+/// Pairs each sample with its observation. Hours without one are dropped.
+pub fn build_ride_weather(
+    ride_id: RideId,
+    samples: impl IntoIterator<Item = (HourSample, Option<HourlyObservation>)>,
+) -> RideWeather {
+    RideWeather {
+        id: ride_id,
+        hours: samples
+            .into_iter()
+            .filter_map(|(sample, observation)| {
+                observation.map(|observation| hourly_weather(sample, observation))
+            })
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    fn point(hour: u32, minute: u32, lng: f64) -> TemporalElevationPoint {
+        TemporalElevationPoint {
+            datetime: Utc.with_ymd_and_hms(2024, 12, 15, hour, minute, 0).unwrap(),
+            point: geo::Point::new(lng, -37.75),
+            elevation: 200.0,
+        }
+    }
+
+    #[test]
+    fn test_sample_hours() {
+        let points = vec![
+            point(0, 41, 145.70),
+            point(0, 59, 145.72),
+            point(1, 0, 145.73),
+            point(1, 30, 145.73),
+            point(2, 5, 145.74),
+            point(2, 55, 145.76),
+        ];
+
+        let samples = sample_hours(&points);
+
+        assert_eq!(
+            samples
+                .iter()
+                .map(|sample| (sample.hour, sample.point))
+                .collect_vec(),
+            vec![
+                (
+                    Utc.with_ymd_and_hms(2024, 12, 15, 0, 0, 0).unwrap(),
+                    geo::Point::new(145.70, -37.75)
+                ),
+                (
+                    Utc.with_ymd_and_hms(2024, 12, 15, 1, 0, 0).unwrap(),
+                    geo::Point::new(145.73, -37.75)
+                ),
+                (
+                    Utc.with_ymd_and_hms(2024, 12, 15, 2, 0, 0).unwrap(),
+                    geo::Point::new(145.74, -37.75)
+                ),
+            ]
+        );
+
+        // Heading roughly east, then stopped, then east again
+        assert!(samples[0]
+            .bearing
+            .is_some_and(|bearing| (bearing - 90.0).abs() < 1.0));
+        assert_eq!(samples[1].bearing, None);
+        assert!(samples[2]
+            .bearing
+            .is_some_and(|bearing| (bearing - 90.0).abs() < 1.0));
+    }
+
+    #[test]
+    fn test_build_ride_weather_skips_missing_observations() {
+        let ride_id = RideId::new();
+        let samples = sample_hours(&[point(0, 41, 145.70), point(1, 0, 145.73)]);
+        let observation = HourlyObservation {
+            temperature: 20.9,
+            precipitation: 0.0,
+            wind_speed: 4.5,
+            wind_direction: 247.0,
+        };
+
+        let weather =
+            build_ride_weather(ride_id, samples.into_iter().zip([Some(observation), None]));
+
+        assert_eq!(weather.id, ride_id);
+        assert_eq!(weather.hours.len(), 1);
+        assert_eq!(weather.hours[0].temperature, 20.9);
+        assert_eq!(
+            weather.hours[0].datetime,
+            Utc.with_ymd_and_hms(2024, 12, 15, 0, 0, 0).unwrap()
+        );
+    }
+}
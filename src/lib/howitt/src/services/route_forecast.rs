@@ -0,0 +1,169 @@
+use chrono::{DateTime, DurationRound, TimeDelta, Utc};
+use itertools::Itertools;
+
+use crate::models::{
+    point::{
+        progress::{DistanceElevationProgress, Progress},
+        ElevationPoint, Point,
+    },
+    ride_weather::HourlyWeather,
+};
+
+use super::ride_weather::{heading, hourly_weather, HourSample, HourlyObservation};
+
+/// Where a rider is expected to be at the start of an hour of riding a route.
+#[derive(Debug, PartialEq, Clone)]
+pub struct RouteForecastSample {
+    pub distance_m: f64,
+    pub elevation_m: f64,
+    pub sample: HourSample,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct RouteForecastHour {
+    pub distance_m: f64,
+    pub elevation_m: f64,
+    pub weather: HourlyWeather,
+}
+
+/// Estimates when a rider leaving at `departs_at` and holding `speed_kmh`
+/// reaches each point of the route, then samples the first point reached in
+/// each (UTC) hour. The bearing is the rider's heading through the hour.
+pub fn sample_route_hours(
+    route: &[ElevationPoint],
+    departs_at: DateTime<Utc>,
+    speed_kmh: f64,
+) -> Vec<RouteForecastSample> {
+    if speed_kmh <= 0.0 {
+        return vec![];
+    }
+
+    let speed_mps = speed_kmh / 3.6;
+
+    DistanceElevationProgress::from_points(route.to_vec())
+        .map(|progress| {
+            let elapsed =
+                TimeDelta::milliseconds((progress.distance_m / speed_mps * 1000.0) as i64);
+            (departs_at + elapsed, progress)
+        })
+        .chunk_by(|(arrives_at, _)| {
+            arrives_at
+                .duration_trunc(TimeDelta::hours(1))
+                .unwrap_or(*arrives_at)
+        })
+        .into_iter()
+        .filter_map(|(hour, progress)| {
+            let progress = progress.map(|(_, progress)| progress).collect_vec();
+            let first = progress.first()?;
+            let last = progress.last()?;
+
+            Some(RouteForecastSample {
+                distance_m: first.distance_m,
+                elevation_m: first.point.elevation,
+                sample: HourSample {
+                    hour,
+                    point: *first.point.as_geo_point(),
+                    bearing: heading(first.point.as_geo_point(), last.point.as_geo_point()),
+                },
+            })
+        })
+        .collect()
+}
+
+/// Pairs each sample with its forecast. Hours without one, such as those past
+/// the end of the forecast, are dropped.
+pub fn build_route_forecast(
+    samples: impl IntoIterator<Item = (RouteForecastSample, Option<HourlyObservation>)>,
+) -> Vec<RouteForecastHour> {
+    samples
+        .into_iter()
+        .filter_map(|(sample, observation)| {
+            Some(RouteForecastHour {
+                distance_m: sample.distance_m,
+                elevation_m: sample.elevation_m,
+                weather: hourly_weather(sample.sample, observation?),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    fn create_test_route() -> Vec<ElevationPoint> {
+        // ~44km heading east, one point every ~880m
+        (0..=50)
+            .map(|i| ElevationPoint {
+                point: geo::Point::new(146.0 + i as f64 * 0.01, -37.0),
+                elevation: 200.0 + i as f64 * 10.0,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_sample_route_hours() {
+        let departs_at = Utc.with_ymd_and_hms(2025, 2, 12, 8, 30, 0).unwrap();
+
+        let samples = sample_route_hours(&create_test_route(), departs_at, 20.0);
+
+        // 08:30 to roughly 10:43 at 20km/h
+        assert_eq!(
+            samples
+                .iter()
+                .map(|sample| sample.sample.hour)
+                .collect_vec(),
+            vec![
+                Utc.with_ymd_and_hms(2025, 2, 12, 8, 0, 0).unwrap(),
+                Utc.with_ymd_and_hms(2025, 2, 12, 9, 0, 0).unwrap(),
+                Utc.with_ymd_and_hms(2025, 2, 12, 10, 0, 0).unwrap(),
+            ]
+        );
+
+        assert_eq!(samples[0].distance_m, 0.0);
+        assert_eq!(samples[0].elevation_m, 200.0);
+
+        // Half an hour in, and then another hour on
+        assert!((samples[1].distance_m - 10_000.0).abs() < 1000.0);
+        assert!((samples[2].distance_m - 30_000.0).abs() < 1000.0);
+
+        assert!(samples.iter().all(|sample| sample
+            .sample
+            .bearing
+            .is_some_and(|bearing| (bearing - 90.0).abs() < 1.0)));
+    }
+
+    #[test]
+    fn test_sample_route_hours_without_speed() {
+        let departs_at = Utc.with_ymd_and_hms(2025, 2, 12, 8, 30, 0).unwrap();
+
+        assert!(sample_route_hours(&create_test_route(), departs_at, 0.0).is_empty());
+    }
+
+    #[test]
+    fn test_build_route_forecast_drops_missing_hours() {
+        let departs_at = Utc.with_ymd_and_hms(2025, 2, 12, 8, 30, 0).unwrap();
+        let samples = sample_route_hours(&create_test_route(), departs_at, 20.0);
+        let observation = HourlyObservation {
+            temperature: 18.0,
+            precipitation: 0.2,
+            wind_speed: 15.0,
+            wind_direction: 270.0,
+        };
+
+        let forecast = build_route_forecast(samples.into_iter().zip([
+            Some(observation.clone()),
+            Some(observation),
+            None,
+        ]));
+
+        assert_eq!(forecast.len(), 2);
+        assert_eq!(forecast[0].weather.temperature, 18.0);
+        assert_eq!(
+            forecast[1].weather.datetime,
+            Utc.with_ymd_and_hms(2025, 2, 12, 9, 0, 0).unwrap()
+        );
+    }
+}
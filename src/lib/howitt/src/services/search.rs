@@ -0,0 +1,267 @@
+use std::collections::HashSet;
+
+use geo::{Distance, Haversine};
+use itertools::Itertools;
+
+use crate::models::{
+    point_of_interest::{PointOfInterest, PointOfInterestType},
+    route::Route,
+    trip::Trip,
+};
+
+/// How far from `near` a POI can be and still turn up on its type alone.
+pub const NEARBY_POINT_OF_INTEREST_RADIUS_M: f64 = 25_000.0;
+
+/// Lowercased words of a search query, with punctuation dropped. Repos match
+/// a name when every term starts one of its words, so "harr hut" finds
+/// "Harrietville Hut".
+pub fn search_terms(query: &str) -> Vec<String> {
+    query
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|term| !term.is_empty())
+        .map(|term| term.to_lowercase())
+        .collect()
+}
+
+pub fn matches_terms(text: &str, terms: &[String]) -> bool {
+    let words = search_terms(text);
+
+    !terms.is_empty()
+        && terms
+            .iter()
+            .all(|term| words.iter().any(|word| word.starts_with(term)))
+}
+
+fn point_of_interest_type_words(point_of_interest_type: &PointOfInterestType) -> &'static str {
+    match point_of_interest_type {
+        PointOfInterestType::PublicTransportStop => "public transport stop station train bus",
+        PointOfInterestType::Campsite => "campsite camping camp",
+        PointOfInterestType::WaterSource => "water source tank spring creek",
+        PointOfInterestType::Hut => "hut shelter",
+        PointOfInterestType::Generic => "",
+    }
+}
+
+/// POIs within [`NEARBY_POINT_OF_INTEREST_RADIUS_M`] of `near` whose name or
+/// type matches the query, so "water" near a trailhead lists the tanks around
+/// it whatever they're called. `points_of_interest` are usually already
+/// narrowed down with [`PointOfInterestFilter::Near`].
+///
+/// [`PointOfInterestFilter::Near`]: crate::models::point_of_interest::PointOfInterestFilter::Near
+pub fn nearby_points_of_interest(
+    points_of_interest: &[PointOfInterest],
+    terms: &[String],
+    near: &geo::Point,
+) -> Vec<PointOfInterest> {
+    points_of_interest
+        .iter()
+        .filter(|poi| Haversine::distance(poi.point, *near) <= NEARBY_POINT_OF_INTEREST_RADIUS_M)
+        .filter(|poi| {
+            matches_terms(&poi.name, terms)
+                || matches_terms(
+                    point_of_interest_type_words(&poi.point_of_interest_type),
+                    terms,
+                )
+        })
+        .cloned()
+        .collect()
+}
+
+/// A forward geocoding result, for places that aren't in the database.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Place {
+    pub name: String,
+    /// The rest of the address, e.g. "Victoria, Australia"
+    pub address: Option<String>,
+    pub point: geo::Point,
+}
+
+#[derive(Debug, Clone)]
+pub enum SearchResult {
+    Route(Box<Route>),
+    Trip(Trip),
+    PointOfInterest(PointOfInterest),
+    Place(Place),
+}
+
+#[derive(Debug, Default)]
+pub struct SearchSources {
+    /// Full text matches, best first
+    pub routes: Vec<Route>,
+    /// Full text matches, best first
+    pub trips: Vec<Trip>,
+    /// Full text matches, best first
+    pub points_of_interest: Vec<PointOfInterest>,
+    pub nearby_points_of_interest: Vec<PointOfInterest>,
+    /// Geocoder results, best first
+    pub places: Vec<Place>,
+}
+
+fn distance_from(near: Option<&geo::Point>, point: &geo::Point) -> f64 {
+    near.map(|near| Haversine::distance(*near, *point))
+        .unwrap_or(0.0)
+}
+
+/// Merges the results from each source into one list of at most `limit`:
+/// routes, then trips, then POIs, then places. POIs and places are ordered
+/// by distance from `near` when it's given.
+pub fn merge_search_results(
+    SearchSources {
+        routes,
+        trips,
+        points_of_interest,
+        nearby_points_of_interest,
+        places,
+    }: SearchSources,
+    near: Option<&geo::Point>,
+    limit: usize,
+) -> Vec<SearchResult> {
+    let mut seen_point_of_interest_ids = HashSet::new();
+
+    let points_of_interest = points_of_interest
+        .into_iter()
+        .chain(nearby_points_of_interest)
+        .filter(|poi| seen_point_of_interest_ids.insert(poi.id))
+        .enumerate()
+        .sorted_by(|(i1, poi1), (i2, poi2)| {
+            distance_from(near, &poi1.point)
+                .total_cmp(&distance_from(near, &poi2.point))
+                .then(i1.cmp(i2))
+        })
+        .map(|(_, poi)| poi);
+
+    let places = places
+        .into_iter()
+        .enumerate()
+        .sorted_by(|(i1, place1), (i2, place2)| {
+            distance_from(near, &place1.point)
+                .total_cmp(&distance_from(near, &place2.point))
+                .then(i1.cmp(i2))
+        })
+        .map(|(_, place)| place);
+
+    routes
+        .into_iter()
+        .map(|route| SearchResult::Route(Box::new(route)))
+        .chain(trips.into_iter().map(SearchResult::Trip))
+        .chain(points_of_interest.map(SearchResult::PointOfInterest))
+        .chain(places.map(SearchResult::Place))
+        .take(limit)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{point_of_interest::PointOfInterestId, user::UserId};
+
+    fn poi(name: &str, point_of_interest_type: PointOfInterestType, lng: f64) -> PointOfInterest {
+        PointOfInterest {
+            id: PointOfInterestId::new(),
+            user_id: UserId::new(),
+            name: String::from(name),
+            slug: String::from(name),
+            point: geo::Point::new(lng, -37.0),
+            point_of_interest_type,
+            description: None,
+            external_ref: None,
+        }
+    }
+
+    #[test]
+    fn test_matches_terms() {
+        assert!(matches_terms("Harrietville Hut", &search_terms("harr hut")));
+        assert!(matches_terms("Mt. Hotham", &search_terms("hotham")));
+        assert!(!matches_terms("Harrietville Hut", &search_terms("ville")));
+        assert!(!matches_terms(
+            "Harrietville Hut",
+            &search_terms("hut tank")
+        ));
+        assert!(!matches_terms("Harrietville Hut", &search_terms("  ")));
+    }
+
+    #[test]
+    fn test_nearby_points_of_interest() {
+        let near = geo::Point::new(146.0, -37.0);
+        let pois = vec![
+            poi("Pheasant Creek", PointOfInterestType::WaterSource, 146.1),
+            poi("Water Tower", PointOfInterestType::Generic, 146.05),
+            poi("Far Creek", PointOfInterestType::WaterSource, 147.0),
+            poi("Bluff Hut", PointOfInterestType::Hut, 146.02),
+        ];
+
+        let nearby = nearby_points_of_interest(&pois, &search_terms("water"), &near);
+
+        assert_eq!(
+            nearby.iter().map(|poi| poi.name.as_str()).collect_vec(),
+            vec!["Pheasant Creek", "Water Tower"]
+        );
+    }
+
+    #[test]
+    fn test_merge_search_results() {
+        let near = geo::Point::new(146.0, -37.0);
+        let far_hut = poi("Far Hut", PointOfInterestType::Hut, 146.2);
+        let close_hut = poi("Close Hut", PointOfInterestType::Hut, 146.01);
+
+        let results = merge_search_results(
+            SearchSources {
+                points_of_interest: vec![far_hut.clone(), close_hut.clone()],
+                // Matched by both, but only listed once
+                nearby_points_of_interest: vec![close_hut.clone()],
+                places: vec![
+                    Place {
+                        name: String::from("Hut Road"),
+                        address: None,
+                        point: geo::Point::new(146.5, -37.0),
+                    },
+                    Place {
+                        name: String::from("Hut Lane"),
+                        address: None,
+                        point: geo::Point::new(146.1, -37.0),
+                    },
+                ],
+                ..SearchSources::default()
+            },
+            Some(&near),
+            10,
+        );
+
+        let names = results
+            .iter()
+            .map(|result| match result {
+                SearchResult::PointOfInterest(poi) => poi.name.as_str(),
+                SearchResult::Place(place) => place.name.as_str(),
+                _ => unreachable!(),
+            })
+            .collect_vec();
+
+        assert_eq!(names, vec!["Close Hut", "Far Hut", "Hut Lane", "Hut Road"]);
+    }
+
+    #[test]
+    fn test_merge_search_results_limit() {
+        let results = merge_search_results(
+            SearchSources {
+                points_of_interest: vec![
+                    poi("Bluff Hut", PointOfInterestType::Hut, 146.1),
+                    poi("Howitt Hut", PointOfInterestType::Hut, 146.2),
+                ],
+                places: vec![Place {
+                    name: String::from("Hut Road"),
+                    address: None,
+                    point: geo::Point::new(146.5, -37.0),
+                }],
+                ..SearchSources::default()
+            },
+            None,
+            2,
+        );
+
+        // The limit is for the whole list, so the place is cut
+        assert_eq!(results.len(), 2);
+        assert!(results
+            .iter()
+            .all(|result| matches!(result, SearchResult::PointOfInterest(_))));
+    }
+}
@@ -0,0 +1,105 @@
+use chrono::{DateTime, Duration, Utc};
+
+use crate::{
+    models::{
+        filters::TemporalFilter,
+        ride::{Ride, RideFilter},
+        user::UserId,
+    },
+    repos::RideRepo,
+};
+
+/// Rides from different sources are considered the same ride when both their
+/// start and finish times fall within this window of each other.
+pub fn ride_dedupe_window() -> Duration {
+    Duration::minutes(5)
+}
+
+pub fn is_duplicate_ride(
+    ride: &Ride,
+    started_at: DateTime<Utc>,
+    finished_at: DateTime<Utc>,
+) -> bool {
+    let window = ride_dedupe_window();
+
+    (ride.started_at - started_at).abs() <= window
+        && (ride.finished_at - finished_at).abs() <= window
+}
+
+pub struct FindDuplicateRideParams {
+    pub ride_repo: RideRepo,
+    pub user_id: UserId,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: DateTime<Utc>,
+}
+
+pub async fn find_duplicate_ride(
+    FindDuplicateRideParams {
+        ride_repo,
+        user_id,
+        started_at,
+        finished_at,
+    }: FindDuplicateRideParams,
+) -> Result<Option<Ride>, anyhow::Error> {
+    let candidates = ride_repo
+        .filter_models(RideFilter::ForUser {
+            user_id,
+            started_at: Some(TemporalFilter::After {
+                after: started_at - ride_dedupe_window(),
+                first: Some(10),
+            }),
+        })
+        .await?;
+
+    Ok(candidates
+        .into_iter()
+        .find(|ride| is_duplicate_ride(ride, started_at, finished_at)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ride::RideId;
+    use chrono::TimeZone;
+
+    fn create_test_ride(started_at: DateTime<Utc>, finished_at: DateTime<Utc>) -> Ride {
+        Ride {
+            id: RideId::new(),
+            name: String::from("Test Ride"),
+            user_id: UserId::new(),
+            distance: 0.0,
+            started_at,
+            finished_at,
+            external_ref: None,
+            archived_at: None,
+        }
+    }
+
+    #[test]
+    fn test_is_duplicate_ride_within_window() {
+        let ride = create_test_ride(
+            Utc.with_ymd_and_hms(2024, 1, 1, 8, 0, 0).unwrap(),
+            Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap(),
+        );
+
+        assert!(is_duplicate_ride(
+            &ride,
+            Utc.with_ymd_and_hms(2024, 1, 1, 8, 2, 0).unwrap(),
+            Utc.with_ymd_and_hms(2024, 1, 1, 11, 57, 0).unwrap(),
+        ));
+    }
+
+    #[test]
+    fn test_is_duplicate_ride_outside_window() {
+        let ride = create_test_ride(
+            Utc.with_ymd_and_hms(2024, 1, 1, 8, 0, 0).unwrap(),
+            Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap(),
+        );
+
+        assert!(!is_duplicate_ride(
+            &ride,
+            Utc.with_ymd_and_hms(2024, 1, 1, 8, 0, 0).unwrap(),
+            Utc.with_ymd_and_hms(2024, 1, 1, 10, 0, 0).unwrap(),
+        ));
+    }
+}
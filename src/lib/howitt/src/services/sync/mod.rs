@@ -1 +1,6 @@
+pub mod dedupe;
+pub mod provider;
 pub mod rwgps_v2;
+pub mod select_sync_candidates;
+pub mod strava;
+pub mod sync_item;
@@ -0,0 +1,83 @@
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use crate::models::{
+    course_point::CoursePoint,
+    external_ref::{ExternalId, ExternalProvider, ExternalRef},
+    point::{ElevationPoint, TemporalElevationPoint},
+};
+
+/// A route as it comes from a provider, before it's matched against what's
+/// already stored.
+#[derive(Debug, Clone)]
+pub struct ExternalRoute {
+    pub external_ref: ExternalRef,
+    pub name: String,
+    pub distance: f64,
+    pub points: Vec<ElevationPoint>,
+    pub course_points: Vec<CoursePoint>,
+}
+
+/// A recorded ride as it comes from a provider, before it's matched against
+/// what's already stored.
+#[derive(Debug, Clone)]
+pub struct ExternalRide {
+    pub external_ref: ExternalRef,
+    pub name: String,
+    pub distance: f64,
+    pub points: Vec<TemporalElevationPoint>,
+}
+
+#[derive(Debug, Clone)]
+pub enum ExternalItem {
+    Route(ExternalRoute),
+    Ride(ExternalRide),
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+pub enum WebhookAction {
+    /// The item was created or changed, so sync it
+    Upsert,
+    /// The item is gone, so archive or delete it
+    Remove,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct WebhookEvent {
+    /// The provider's id for the account the item belongs to
+    pub owner_id: usize,
+    pub id: ExternalId,
+    pub action: WebhookAction,
+}
+
+/// Somewhere routes and rides are synced from. Providers only talk to their
+/// API and turn what it returns into [`ExternalItem`]s; matching those
+/// against stored models is shared, see [`super::sync_item`] and
+/// [`super::select_sync_candidates`].
+#[async_trait]
+pub trait SyncProvider: Send + Sync {
+    /// A user's account with the provider
+    type Connection: Send + Sync;
+    /// An item as fetched, which may hold more than [`ExternalItem`] does
+    type Item: Send;
+
+    const PROVIDER: ExternalProvider;
+    /// Stored on every ref. Bumping it marks everything synced before as
+    /// stale, so the next history sync converts it again.
+    const SYNC_VERSION: usize;
+
+    /// Refs for everything in the user's account that can be synced
+    async fn list(&self, connection: &Self::Connection) -> Result<Vec<ExternalRef>, anyhow::Error>;
+
+    async fn fetch(
+        &self,
+        connection: &Self::Connection,
+        id: &ExternalId,
+    ) -> Result<Self::Item, anyhow::Error>;
+
+    fn convert(&self, item: &Self::Item) -> Result<ExternalItem, anyhow::Error>;
+
+    /// Events in a webhook request body, once its signature has been checked.
+    /// Events the provider sends that don't concern synced items are dropped.
+    fn parse_webhook(&self, body: &[u8]) -> Result<Vec<WebhookEvent>, anyhow::Error>;
+}
@@ -1,5 +1,6 @@
 pub mod context;
-pub mod select_historical_route_sync_candidates;
-pub mod select_historical_trip_sync_candidates;
+pub mod provider;
+pub mod remove_item;
+pub mod sync_photos;
 pub mod sync_route;
 pub mod sync_trip;
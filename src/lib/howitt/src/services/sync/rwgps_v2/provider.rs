@@ -0,0 +1,218 @@
+use async_trait::async_trait;
+use rwgps_types::{
+    client::{AuthenticatedRwgpsClient, RwgpsClient},
+    credentials::Credentials,
+    webhook::{Action, ItemType, RwgpsWebhookNotification, RwgpsWebhookPayload},
+    RoutePointOfInterest,
+};
+
+use crate::{
+    models::{
+        course_point::CoursePoint,
+        external_ref::{ExternalId, ExternalProvider, ExternalRef, RwgpsId},
+        point::{ElevationPoint, TemporalElevationPoint},
+        user::UserRwgpsConnection,
+    },
+    services::sync::provider::{
+        ExternalItem, ExternalRide, ExternalRoute, SyncProvider, WebhookAction, WebhookEvent,
+    },
+};
+
+/// [`SyncProvider::SYNC_VERSION`] for RWGPS, which trip photos are stored
+/// with too.
+pub const RWGPS_SYNC_VERSION: usize = 2;
+
+pub enum RwgpsItem {
+    Route(rwgps_types::Route),
+    Trip(rwgps_types::Trip),
+}
+
+#[derive(Clone)]
+pub struct RwgpsProvider<Client> {
+    pub client: Client,
+}
+
+impl<Client: RwgpsClient> RwgpsProvider<Client> {
+    fn authenticated(&self, connection: &UserRwgpsConnection) -> Client::AuthenticatedClient {
+        self.client
+            .with_credentials(Credentials::from_token(connection.access_token.clone()))
+    }
+}
+
+/// Turn instructions and POIs planned on the route, so they can go on the
+/// cuesheet. POIs aren't measured along the route, and ones that can't be read
+/// are skipped.
+fn route_course_points(
+    course_points: &[rwgps_types::CoursePoint],
+    points_of_interest: &[serde_json::Value],
+) -> Vec<CoursePoint> {
+    let course_points = course_points.iter().map(|course_point| CoursePoint {
+        point: geo::Point::new(course_point.lng, course_point.lat),
+        distance_m: course_point.distance,
+        kind: course_point.point_type.clone(),
+        note: Some(course_point.note.clone()).filter(|note| !note.is_empty()),
+    });
+
+    let points_of_interest = points_of_interest
+        .iter()
+        .filter_map(|value| serde_json::from_value::<RoutePointOfInterest>(value.clone()).ok())
+        .map(|poi| CoursePoint {
+            point: geo::Point::new(poi.lng, poi.lat),
+            distance_m: None,
+            kind: poi.poi_type_name.unwrap_or_else(|| String::from("POI")),
+            note: poi.name.or(poi.description),
+        });
+
+    course_points.chain(points_of_interest).collect()
+}
+
+fn convert_route(route: &rwgps_types::Route, sync_version: usize) -> ExternalRoute {
+    let points = route
+        .track_points
+        .iter()
+        .filter_map(|track_point| {
+            match (
+                geo::Point::try_from(track_point.clone()),
+                track_point.elevation,
+            ) {
+                (Ok(point), Some(elevation)) => Some(ElevationPoint { point, elevation }),
+                _ => None,
+            }
+        })
+        .collect();
+
+    ExternalRoute {
+        external_ref: ExternalRef {
+            id: ExternalId::Rwgps(RwgpsId::Route(route.id)),
+            updated_at: route.updated_at,
+            sync_version: Some(sync_version),
+        },
+        name: route.name.clone(),
+        distance: route.distance.unwrap_or(0.0),
+        points,
+        course_points: route_course_points(&route.course_points, &route.points_of_interest),
+    }
+}
+
+fn convert_trip(trip: &rwgps_types::Trip, sync_version: usize) -> ExternalRide {
+    let points = trip
+        .track_points
+        .iter()
+        .filter_map(|track_point| {
+            match (
+                geo::Point::try_from(track_point.clone()),
+                track_point.elevation,
+                track_point.datetime,
+            ) {
+                (Ok(point), Some(elevation), Some(datetime)) => Some(TemporalElevationPoint {
+                    point,
+                    elevation,
+                    datetime,
+                }),
+                _ => None,
+            }
+        })
+        .collect();
+
+    ExternalRide {
+        external_ref: ExternalRef {
+            id: ExternalId::Rwgps(RwgpsId::Trip(trip.id)),
+            updated_at: trip.updated_at,
+            sync_version: Some(sync_version),
+        },
+        name: trip.name.clone(),
+        distance: trip.distance,
+        points,
+    }
+}
+
+fn webhook_event(notification: &RwgpsWebhookNotification) -> WebhookEvent {
+    let item_id = notification.item_id as usize;
+
+    WebhookEvent {
+        owner_id: notification.user_id as usize,
+        id: ExternalId::Rwgps(match notification.item_type {
+            ItemType::Route => RwgpsId::Route(item_id),
+            ItemType::Trip => RwgpsId::Trip(item_id),
+        }),
+        // Removed means it was taken out of the user's library, which is as
+        // good as deleted from where we sit
+        action: match notification.action {
+            Action::Deleted | Action::Removed => WebhookAction::Remove,
+            Action::Created | Action::Updated | Action::Added => WebhookAction::Upsert,
+        },
+    }
+}
+
+#[async_trait]
+impl<Client> SyncProvider for RwgpsProvider<Client>
+where
+    Client: RwgpsClient + Send + Sync,
+    Client::AuthenticatedClient: Send + Sync,
+{
+    type Connection = UserRwgpsConnection;
+    type Item = RwgpsItem;
+
+    const PROVIDER: ExternalProvider = ExternalProvider::Rwgps;
+    const SYNC_VERSION: usize = RWGPS_SYNC_VERSION;
+
+    async fn list(
+        &self,
+        connection: &UserRwgpsConnection,
+    ) -> Result<Vec<ExternalRef>, anyhow::Error> {
+        let client = self.authenticated(connection);
+        let rwgps_user_id = connection.rwgps_user_id as usize;
+
+        let (routes, trips) = futures::try_join!(
+            client.user_routes(rwgps_user_id),
+            client.user_trips(rwgps_user_id),
+        )?;
+
+        let routes = routes.into_iter().map(|summary| ExternalRef {
+            id: ExternalId::Rwgps(RwgpsId::Route(summary.id)),
+            updated_at: summary.updated_at,
+            sync_version: Some(Self::SYNC_VERSION),
+        });
+
+        let trips = trips.into_iter().map(|summary| ExternalRef {
+            id: ExternalId::Rwgps(RwgpsId::Trip(summary.id)),
+            updated_at: summary.updated_at,
+            sync_version: Some(Self::SYNC_VERSION),
+        });
+
+        Ok(routes.chain(trips).collect())
+    }
+
+    async fn fetch(
+        &self,
+        connection: &UserRwgpsConnection,
+        id: &ExternalId,
+    ) -> Result<RwgpsItem, anyhow::Error> {
+        let client = self.authenticated(connection);
+
+        match id {
+            ExternalId::Rwgps(RwgpsId::Route(route_id)) => {
+                Ok(RwgpsItem::Route(client.route(*route_id).await?))
+            }
+            ExternalId::Rwgps(RwgpsId::Trip(trip_id)) => {
+                Ok(RwgpsItem::Trip(client.trip(*trip_id).await?))
+            }
+            _ => Err(anyhow::anyhow!("{id:?} can't be fetched from RWGPS")),
+        }
+    }
+
+    fn convert(&self, item: &RwgpsItem) -> Result<ExternalItem, anyhow::Error> {
+        Ok(match item {
+            RwgpsItem::Route(route) => {
+                ExternalItem::Route(convert_route(route, Self::SYNC_VERSION))
+            }
+            RwgpsItem::Trip(trip) => ExternalItem::Ride(convert_trip(trip, Self::SYNC_VERSION)),
+        })
+    }
+
+    fn parse_webhook(&self, body: &[u8]) -> Result<Vec<WebhookEvent>, anyhow::Error> {
+        let payload: RwgpsWebhookPayload = serde_json::from_slice(body)?;
+
+        Ok(payload.notifications.iter().map(webhook_event).collect())
+    }
+}
@@ -0,0 +1,210 @@
+use std::{str::FromStr, sync::Arc};
+
+use chrono::Utc;
+use howitt_client_types::RedisClient;
+
+use crate::{
+    models::{
+        external_ref::{ExternalId, ExternalRefItemMap, RwgpsId},
+        ride::{Ride, RideFilter, RideId},
+        route::{Route, RouteFilter},
+        sync_audit_entry::{SyncAuditAction, SyncAuditEntry, SyncAuditEntryId, SyncedItemId},
+        trip::{TripFilter, TripId},
+        user::{UserId, UserRwgpsConnection},
+    },
+    repos::{RideRepo, RouteRepo, SyncAuditEntryRepo, TripRepo},
+    services::fetchers::{SimplifiedRidePointsFetcher, SimplifiedTripElevationPointsFetcher},
+};
+
+/// What happens to a synced route or ride once it's gone from RWGPS.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum RemovalPolicy {
+    /// Keep it, but leave it out of listings
+    #[default]
+    Archive,
+    Delete,
+}
+
+impl FromStr for RemovalPolicy {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "archive" => Ok(RemovalPolicy::Archive),
+            "delete" => Ok(RemovalPolicy::Delete),
+            _ => Err(anyhow::anyhow!("Unknown removal policy: {s}")),
+        }
+    }
+}
+
+impl RemovalPolicy {
+    fn action(&self) -> SyncAuditAction {
+        match self {
+            RemovalPolicy::Archive => SyncAuditAction::Archived,
+            RemovalPolicy::Delete => SyncAuditAction::Deleted,
+        }
+    }
+}
+
+pub struct RemoveItemParams<Redis: RedisClient> {
+    pub route_repo: RouteRepo,
+    pub ride_repo: RideRepo,
+    pub trip_repo: TripRepo,
+    pub sync_audit_entry_repo: SyncAuditEntryRepo,
+    pub simplified_ride_points_fetcher: Arc<SimplifiedRidePointsFetcher<Redis>>,
+    pub simplified_trip_elevation_points_fetcher: Arc<SimplifiedTripElevationPointsFetcher<Redis>>,
+    pub rwgps_id: RwgpsId,
+    pub connection: UserRwgpsConnection,
+    pub policy: RemovalPolicy,
+}
+
+async fn detach_from_trips(
+    trip_repo: &TripRepo,
+    user_id: UserId,
+    ride_id: RideId,
+) -> Result<Vec<TripId>, anyhow::Error> {
+    let trips = trip_repo.filter_models(TripFilter::User(user_id)).await?;
+    let mut trip_ids = vec![];
+
+    for mut trip in trips
+        .into_iter()
+        .filter(|trip| trip.ride_ids.contains(&ride_id))
+    {
+        trip.ride_ids.retain(|id| *id != ride_id);
+        trip_ids.push(trip.id);
+
+        trip_repo.put(trip).await?;
+    }
+
+    Ok(trip_ids)
+}
+
+/// Archives or deletes the user's route or ride synced from `rwgps_id`,
+/// taking it out of any trips first, and records what was done. Returns
+/// `None` if there was nothing left to remove.
+pub async fn remove_item<Redis: RedisClient>(
+    RemoveItemParams {
+        route_repo,
+        ride_repo,
+        trip_repo,
+        sync_audit_entry_repo,
+        simplified_ride_points_fetcher,
+        simplified_trip_elevation_points_fetcher,
+        rwgps_id,
+        connection,
+        policy,
+    }: RemoveItemParams<Redis>,
+) -> Result<Option<SyncAuditEntry>, anyhow::Error> {
+    let user_id = connection.user_id;
+    let external_id = ExternalId::Rwgps(rwgps_id.clone());
+
+    tracing::info!(
+        ?rwgps_id,
+        user_id = %user_id,
+        ?policy,
+        "Removing synced item"
+    );
+
+    let now = Utc::now();
+
+    let (item_id, detached_trip_ids) = match rwgps_id {
+        RwgpsId::Route(rwgps_route_id) => {
+            let routes = route_repo
+                .filter_models(RouteFilter::RwgpsId(rwgps_route_id))
+                .await?;
+            let routes = ExternalRefItemMap::from_externally_reffed(
+                routes.into_iter().filter(|route| route.user_id == user_id),
+            );
+
+            let Some(route) = routes
+                .get(&external_id)
+                .filter(|route| route.archived_at.is_none() || policy == RemovalPolicy::Delete)
+                .cloned()
+            else {
+                tracing::info!("No route left to remove");
+                return Ok(None);
+            };
+
+            match policy {
+                RemovalPolicy::Archive => {
+                    route_repo
+                        .put(Route {
+                            archived_at: Some(now),
+                            ..route.clone()
+                        })
+                        .await?
+                }
+                RemovalPolicy::Delete => route_repo.delete(route.id).await?,
+            }
+
+            // Trips only hold rides
+            (SyncedItemId::Route(route.id), vec![])
+        }
+        RwgpsId::Trip(rwgps_trip_id) => {
+            let rides = ride_repo
+                .filter_models(RideFilter::RwgpsId(rwgps_trip_id))
+                .await?;
+            let rides = ExternalRefItemMap::from_externally_reffed(
+                rides.into_iter().filter(|ride| ride.user_id == user_id),
+            );
+
+            let Some(ride) = rides
+                .get(&external_id)
+                .filter(|ride| ride.archived_at.is_none() || policy == RemovalPolicy::Delete)
+                .cloned()
+            else {
+                tracing::info!("No ride left to remove");
+                return Ok(None);
+            };
+
+            let detached_trip_ids = detach_from_trips(&trip_repo, user_id, ride.id).await?;
+
+            match policy {
+                RemovalPolicy::Archive => {
+                    ride_repo
+                        .put(Ride {
+                            archived_at: Some(now),
+                            ..ride.clone()
+                        })
+                        .await?
+                }
+                RemovalPolicy::Delete => ride_repo.delete(ride.id).await?,
+            }
+
+            simplified_ride_points_fetcher.invalidate(ride.id).await?;
+
+            // The trips' elevation profiles still take in the ride
+            for trip_id in &detached_trip_ids {
+                simplified_trip_elevation_points_fetcher
+                    .invalidate(*trip_id)
+                    .await?;
+            }
+
+            (SyncedItemId::Ride(ride.id), detached_trip_ids)
+        }
+        RwgpsId::Photo(_) => {
+            return Err(anyhow::anyhow!("Photos aren't synced as items"));
+        }
+    };
+
+    let entry = SyncAuditEntry {
+        id: SyncAuditEntryId::new(),
+        created_at: now,
+        user_id,
+        external_id,
+        item_id,
+        action: policy.action(),
+        detached_trip_ids,
+    };
+
+    sync_audit_entry_repo.put(entry.clone()).await?;
+
+    tracing::info!(
+        item_id = ?entry.item_id,
+        action = ?entry.action,
+        detached_trips = entry.detached_trip_ids.len(),
+        "Removed synced item"
+    );
+
+    Ok(Some(entry))
+}
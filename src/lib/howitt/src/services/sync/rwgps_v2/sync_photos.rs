@@ -0,0 +1,151 @@
+use std::sync::Arc;
+
+use chrono::Utc;
+use howitt_client_types::{BucketClient, HttpClient, ObjectParams};
+use rwgps_types::Photo;
+
+use crate::{
+    models::{
+        external_ref::{ExternalId, ExternalRef, ExternalRefItemMap, RwgpsId},
+        media::{Media, MediaFilter, MediaId, MediaRelationId},
+        ride::RideId,
+        user::UserId,
+    },
+    repos::MediaRepo,
+    services::media::{generate_media_key, GenerateMediaKeyParams},
+};
+
+use super::provider::RWGPS_SYNC_VERSION;
+
+pub struct SyncPhotosParams<Http, Bucket> {
+    pub http_client: Arc<Http>,
+    pub bucket_client: Arc<Bucket>,
+    pub media_repo: MediaRepo,
+    pub photos: Vec<Photo>,
+    pub ride_id: RideId,
+    pub user_id: UserId,
+}
+
+/// RWGPS sends coordinates as numbers, or as strings on older photos.
+fn coordinate(value: &serde_json::Value) -> Option<f64> {
+    match value {
+        serde_json::Value::Number(number) => number.as_f64(),
+        serde_json::Value::String(string) => string.parse().ok(),
+        _ => None,
+    }
+}
+
+fn photo_point(photo: &Photo) -> Option<geo::Point> {
+    Some(geo::Point::new(
+        coordinate(&photo.lng)?,
+        coordinate(&photo.lat)?,
+    ))
+}
+
+/// Imports the photos on an RWGPS trip as media on the ride. Photos that were
+/// imported before are only linked to the ride, so syncing a trip again
+/// doesn't download anything twice. Returns the newly imported media, which
+/// still need processing.
+pub async fn sync_photos<Http, Bucket>(
+    SyncPhotosParams {
+        http_client,
+        bucket_client,
+        media_repo,
+        photos,
+        ride_id,
+        user_id,
+    }: SyncPhotosParams<Http, Bucket>,
+) -> Result<Vec<Media>, anyhow::Error>
+where
+    Http: HttpClient + Send + Sync,
+    Http::Error: std::error::Error + Send + Sync + 'static,
+    Bucket: BucketClient + Send + Sync,
+    Bucket::Error: std::error::Error + Send + Sync + 'static,
+{
+    let existing_media = ExternalRefItemMap::from_externally_reffed(
+        media_repo
+            .filter_models(MediaFilter::RwgpsPhotoIds(
+                photos.iter().map(|photo| photo.id).collect(),
+            ))
+            .await?,
+    );
+
+    let mut imported = vec![];
+
+    for photo in photos {
+        let external_id = ExternalId::Rwgps(RwgpsId::Photo(photo.id));
+
+        if let Some(media) = existing_media.get(&external_id) {
+            if media.iter_ride_ids().all(|id| id != ride_id) {
+                tracing::info!(
+                    photo_id = photo.id,
+                    media_id = %media.id,
+                    "Linking previously imported photo to ride"
+                );
+
+                let mut media = media.clone();
+                media.relation_ids.push(MediaRelationId::Ride(ride_id));
+                media_repo.put(media).await?;
+            }
+
+            continue;
+        }
+
+        tracing::info!(photo_id = photo.id, "Downloading photo from RWGPS");
+
+        let url = RwgpsId::Photo(photo.id).canonical_url();
+        let response = http_client.get(url).await?;
+
+        let media_id = MediaId::new();
+        let key = generate_media_key(GenerateMediaKeyParams {
+            media_id,
+            user_id,
+            name: format!("rwgps-{}.jpg", photo.id),
+        });
+
+        bucket_client
+            .put_object(
+                &key,
+                response.body,
+                ObjectParams {
+                    content_type: Some(String::from("image/jpeg")),
+                },
+            )
+            .await?;
+
+        let media = Media {
+            id: media_id,
+            created_at: Utc::now(),
+            user_id,
+            path: key,
+            relation_ids: vec![MediaRelationId::Ride(ride_id)],
+            point: photo_point(&photo),
+            captured_at: photo.captured_at,
+            external_ref: Some(ExternalRef {
+                id: external_id,
+                sync_version: Some(RWGPS_SYNC_VERSION),
+                updated_at: photo.updated_at,
+            }),
+        };
+
+        if let Err(err) = media_repo.put(media.clone()).await {
+            // Don't leave the photo in the bucket with nothing pointing at it
+            if let Err(delete_err) = bucket_client.delete_object(&media.path).await {
+                tracing::warn!(
+                    photo_id = photo.id,
+                    key = %media.path,
+                    error = %delete_err,
+                    "Failed to delete uploaded photo"
+                );
+            }
+
+            return Err(err);
+        }
+
+        tracing::info!(photo_id = photo.id, media_id = %media_id, "Imported photo");
+
+        imported.push(media);
+    }
+
+    Ok(imported)
+}
@@ -1,146 +1,101 @@
+use std::sync::Arc;
+
 use howitt::{
     models::{
-        external_ref::{ExternalId, ExternalRef, RwgpsId},
-        point::TemporalElevationPoint,
-        ride::{Ride, RideId},
+        external_ref::{ExternalId, RwgpsId},
+        media::Media,
+        ride::RideId,
         user::UserRwgpsConnection,
     },
-    repos::{RidePointsRepo, RideRepo},
+    repos::{MediaRepo, RidePointsRepo, RideRepo},
+    services::sync::sync_item::{sync_external_ride, SyncExternalRideParams},
 };
-use rwgps_types::{client::AuthenticatedRwgpsClient, credentials::Credentials};
+use howitt_client_types::{BucketClient, HttpClient};
 use tracing;
 
-pub struct SyncTripParams<RwgpsClient> {
+use super::{
+    provider::{RwgpsItem, RwgpsProvider},
+    sync_photos::{sync_photos, SyncPhotosParams},
+};
+
+pub struct SyncTripParams<RwgpsClient, Http, Bucket> {
     pub client: RwgpsClient,
+    pub http_client: Arc<Http>,
+    pub bucket_client: Arc<Bucket>,
     pub ride_repo: RideRepo,
     pub ride_points_repo: RidePointsRepo,
+    pub media_repo: MediaRepo,
     pub rwgps_trip_id: usize,
     pub connection: UserRwgpsConnection,
 }
 
-pub async fn sync_trip<RwgpsClient: rwgps_types::client::RwgpsClient>(
+pub struct SyncedTrip {
+    pub ride_id: RideId,
+    /// Photos imported for the first time, which still need processing
+    pub imported_media: Vec<Media>,
+}
+
+pub async fn sync_trip<RwgpsClient, Http, Bucket>(
     SyncTripParams {
         client,
+        http_client,
+        bucket_client,
         rwgps_trip_id,
         connection,
         ride_repo,
         ride_points_repo,
-    }: SyncTripParams<RwgpsClient>,
-) -> Result<(), anyhow::Error> {
+        media_repo,
+    }: SyncTripParams<RwgpsClient, Http, Bucket>,
+) -> Result<SyncedTrip, anyhow::Error>
+where
+    RwgpsClient: rwgps_types::client::RwgpsClient + Send + Sync,
+    RwgpsClient::AuthenticatedClient: Send + Sync,
+    Http: HttpClient + Send + Sync,
+    Http::Error: std::error::Error + Send + Sync + 'static,
+    Bucket: BucketClient + Send + Sync,
+    Bucket::Error: std::error::Error + Send + Sync + 'static,
+{
     tracing::info!(
         rwgps_trip_id,
         user_id = %connection.user_id,
         "Starting trip sync"
     );
 
-    // Check for existing ride
-    let existing_ride = ride_repo
-        .find_model(howitt::models::ride::RideFilter::RwgpsId(rwgps_trip_id))
-        .await?;
+    let user_id = connection.user_id;
 
-    tracing::info!(
-        ride_exists = existing_ride.is_some(),
-        "Checked for existing ride"
-    );
-
-    // Create authenticated client
-    let client = client.with_credentials(Credentials::from_token(connection.access_token));
-
-    // Fetch trip details from RWGPS
-    tracing::info!("Fetching trip details from RWGPS");
-    let rwgps_trip = client.trip(rwgps_trip_id).await?;
-
-    // Convert track points to TemporalElevationPoints
-    let points = rwgps_trip
-        .track_points
-        .into_iter()
-        .filter_map(|track_point| {
-            match (
-                geo::Point::try_from(track_point.clone()),
-                track_point.elevation,
-                track_point.datetime,
-            ) {
-                (Ok(point), Some(elevation), Some(datetime)) => Some(TemporalElevationPoint {
-                    point,
-                    elevation,
-                    datetime,
-                }),
-                _ => None,
-            }
-        })
-        .collect::<Vec<_>>();
+    let (ride_id, item) = sync_external_ride(SyncExternalRideParams {
+        provider: RwgpsProvider { client },
+        user_id,
+        connection,
+        id: ExternalId::Rwgps(RwgpsId::Trip(rwgps_trip_id)),
+        ride_repo,
+        ride_points_repo,
+    })
+    .await?;
+
+    let RwgpsItem::Trip(rwgps_trip) = item else {
+        return Err(anyhow::anyhow!(
+            "Fetched a route for RWGPS trip {rwgps_trip_id}"
+        ));
+    };
+
+    let imported_media = sync_photos(SyncPhotosParams {
+        http_client,
+        bucket_client,
+        media_repo,
+        photos: rwgps_trip.photos,
+        ride_id,
+        user_id,
+    })
+    .await?;
 
     tracing::info!(
-        total_points = points.len(),
-        "Converted track points to temporal elevation points"
+        rwgps_trip_id,
+        imported_photos = imported_media.len(),
+        "Trip sync completed successfully"
     );
-
-    // Get the time bounds from the points
-    let started_at = points
-        .iter()
-        .map(|point| point.datetime)
-        .min()
-        .ok_or_else(|| anyhow::anyhow!("No points found in trip"))?;
-
-    let finished_at = points
-        .iter()
-        .map(|point| point.datetime)
-        .max()
-        .ok_or_else(|| anyhow::anyhow!("No points found in trip"))?;
-
-    match existing_ride {
-        Some(mut existing_ride) => {
-            tracing::info!(
-                ride_id = %existing_ride.id,
-                "Updating existing ride"
-            );
-
-            // Update the existing ride
-            existing_ride.external_ref = Some(ExternalRef {
-                id: ExternalId::Rwgps(RwgpsId::Trip(rwgps_trip_id)),
-                sync_version: Some(2),
-                updated_at: rwgps_trip.updated_at,
-            });
-            existing_ride.started_at = started_at;
-            existing_ride.finished_at = finished_at;
-
-            ride_repo.put(existing_ride.clone()).await?;
-
-            ride_points_repo
-                .put(howitt::models::ride::RidePoints {
-                    id: existing_ride.id,
-                    points,
-                })
-                .await?;
-            tracing::info!("Successfully updated ride and points");
-        }
-        None => {
-            tracing::info!("Creating new ride");
-            // Create new ride
-            let id = RideId::new();
-            let ride = Ride {
-                id,
-                name: rwgps_trip.name,
-                user_id: connection.user_id,
-                distance: rwgps_trip.distance,
-                started_at,
-                finished_at,
-                external_ref: Some(ExternalRef {
-                    id: ExternalId::Rwgps(RwgpsId::Trip(rwgps_trip_id)),
-                    sync_version: Some(2),
-                    updated_at: rwgps_trip.updated_at,
-                }),
-            };
-
-            // Save new ride and points
-            ride_repo.put(ride).await?;
-            ride_points_repo
-                .put(howitt::models::ride::RidePoints { id, points })
-                .await?;
-            tracing::info!(ride_id = %id, "Successfully created new ride");
-        }
-    }
-
-    tracing::info!(rwgps_trip_id, "Trip sync completed successfully");
-    Ok(())
+    Ok(SyncedTrip {
+        ride_id,
+        imported_media,
+    })
 }
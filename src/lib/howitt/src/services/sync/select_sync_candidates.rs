@@ -0,0 +1,115 @@
+use crate::{
+    models::{
+        external_ref::{ExternalId, ExternalRefItemMap, ExternalRefMatch},
+        ride::RideFilter,
+        route::RouteFilter,
+        sync_audit_entry::SyncedItemId,
+        sync_run::SyncCursor,
+        user::UserId,
+    },
+    repos::{RideRepo, RouteRepo},
+};
+
+use super::provider::SyncProvider;
+
+pub struct SelectSyncCandidatesParams<'a, Provider: SyncProvider> {
+    pub provider: &'a Provider,
+    pub connection: &'a Provider::Connection,
+    pub user_id: UserId,
+    /// Where the last complete sync got to
+    pub since: Option<SyncCursor>,
+    pub route_repo: RouteRepo,
+    pub ride_repo: RideRepo,
+}
+
+#[derive(Debug)]
+pub struct SyncCandidates {
+    pub ids: Vec<ExternalId>,
+    /// The cursor the candidates were picked from, unless it was ignored and
+    /// everything compared
+    pub since: Option<SyncCursor>,
+    /// The newest change listed, for the next sync to pick up from once these
+    /// have synced. `None` if the provider doesn't track changes.
+    pub cursor: Option<SyncCursor>,
+}
+
+/// Items in the user's account with the provider that haven't been synced, or
+/// whose synced copy is stale. With a cursor from the current sync version,
+/// only items changed since are candidates, and nothing is compared against
+/// what's stored.
+pub async fn select_sync_candidates<Provider: SyncProvider>(
+    SelectSyncCandidatesParams {
+        provider,
+        connection,
+        user_id,
+        since,
+        route_repo,
+        ride_repo,
+    }: SelectSyncCandidatesParams<'_, Provider>,
+) -> Result<SyncCandidates, anyhow::Error> {
+    let external_refs = provider.list(connection).await?;
+
+    let cursor = Provider::PROVIDER
+        .tracks_updates()
+        .then(|| {
+            external_refs
+                .iter()
+                .map(|external_ref| external_ref.updated_at)
+                .max()
+        })
+        .flatten()
+        .map(|updated_at| SyncCursor {
+            updated_at,
+            sync_version: Provider::SYNC_VERSION,
+        })
+        // Nothing changed since, so stay put
+        .or(since);
+
+    if let Some(since) = since.filter(|since| {
+        Provider::PROVIDER.tracks_updates() && since.sync_version == Provider::SYNC_VERSION
+    }) {
+        return Ok(SyncCandidates {
+            ids: external_refs
+                .into_iter()
+                .filter(|external_ref| external_ref.updated_at > since.updated_at)
+                .map(|external_ref| external_ref.id)
+                .collect(),
+            since: Some(since),
+            cursor,
+        });
+    }
+
+    let (routes, rides) = futures::try_join!(
+        route_repo.filter_models(RouteFilter::SyncedForUser(user_id)),
+        ride_repo.filter_models(RideFilter::SyncedForUser(user_id)),
+    )?;
+
+    let synced_items = ExternalRefItemMap::new(
+        routes
+            .into_iter()
+            .filter_map(|route| Some((route.external_ref?, SyncedItemId::Route(route.id))))
+            .chain(
+                rides
+                    .into_iter()
+                    .filter_map(|ride| Some((ride.external_ref?, SyncedItemId::Ride(ride.id)))),
+            ),
+    );
+
+    let ids = external_refs
+        .into_iter()
+        .filter_map(|external_ref| {
+            let id = external_ref.id.clone();
+
+            match synced_items.match_ref(external_ref) {
+                ExternalRefMatch::Fresh(_) => None,
+                ExternalRefMatch::Stale(_) | ExternalRefMatch::NotFound => Some(id),
+            }
+        })
+        .collect();
+
+    Ok(SyncCandidates {
+        ids,
+        since: None,
+        cursor,
+    })
+}
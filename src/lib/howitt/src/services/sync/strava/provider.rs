@@ -0,0 +1,163 @@
+use async_trait::async_trait;
+use chrono::TimeDelta;
+use strava_types::{
+    client::{AuthenticatedStravaClient, StravaClient},
+    webhook::{AspectType, ObjectType, StravaWebhookEvent},
+    DetailedActivity, StreamSet,
+};
+
+use crate::{
+    models::{
+        external_ref::{ExternalId, ExternalProvider, ExternalRef, StravaId},
+        point::TemporalElevationPoint,
+        user::UserId,
+    },
+    services::sync::provider::{
+        ExternalItem, ExternalRide, SyncProvider, WebhookAction, WebhookEvent,
+    },
+};
+
+/// Most activities Strava returns in one page
+const ACTIVITIES_PER_PAGE: usize = 200;
+
+#[derive(Debug, Clone)]
+pub struct StravaConnection {
+    pub user_id: UserId,
+    pub athlete_id: usize,
+    pub access_token: String,
+}
+
+pub struct StravaActivity {
+    pub activity: DetailedActivity,
+    pub streams: StreamSet,
+}
+
+/// Syncs Strava activities as rides. Strava has no routes worth syncing.
+#[derive(Clone)]
+pub struct StravaProvider<Client> {
+    pub client: Client,
+}
+
+impl<Client: StravaClient> StravaProvider<Client> {
+    fn authenticated(&self, connection: &StravaConnection) -> Client::AuthenticatedClient {
+        self.client
+            .with_access_token(connection.access_token.clone())
+    }
+}
+
+#[async_trait]
+impl<Client> SyncProvider for StravaProvider<Client>
+where
+    Client: StravaClient + Send + Sync,
+{
+    type Connection = StravaConnection;
+    type Item = StravaActivity;
+
+    const PROVIDER: ExternalProvider = ExternalProvider::Strava;
+    const SYNC_VERSION: usize = 1;
+
+    async fn list(&self, connection: &StravaConnection) -> Result<Vec<ExternalRef>, anyhow::Error> {
+        let client = self.authenticated(connection);
+        let mut external_refs = vec![];
+
+        for page in 1.. {
+            let activities = client.athlete_activities(page, ACTIVITIES_PER_PAGE).await?;
+            let is_last_page = activities.len() < ACTIVITIES_PER_PAGE;
+
+            // Indoor activities have no track to sync
+            external_refs.extend(
+                activities
+                    .into_iter()
+                    .filter(|activity| !activity.start_latlng.is_empty())
+                    .map(|activity| ExternalRef {
+                        id: ExternalId::Strava(StravaId::Activity(activity.id)),
+                        updated_at: activity.start_date,
+                        sync_version: Some(Self::SYNC_VERSION),
+                    }),
+            );
+
+            if is_last_page {
+                break;
+            }
+        }
+
+        Ok(external_refs)
+    }
+
+    async fn fetch(
+        &self,
+        connection: &StravaConnection,
+        id: &ExternalId,
+    ) -> Result<StravaActivity, anyhow::Error> {
+        let ExternalId::Strava(StravaId::Activity(activity_id)) = id else {
+            return Err(anyhow::anyhow!("{id:?} can't be fetched from Strava"));
+        };
+
+        let client = self.authenticated(connection);
+
+        let (activity, streams) = futures::try_join!(
+            client.activity(*activity_id),
+            client.activity_streams(*activity_id),
+        )?;
+
+        Ok(StravaActivity { activity, streams })
+    }
+
+    fn convert(
+        &self,
+        StravaActivity { activity, streams }: &StravaActivity,
+    ) -> Result<ExternalItem, anyhow::Error> {
+        let (Some(latlng), Some(altitude), Some(time)) =
+            (&streams.latlng, &streams.altitude, &streams.time)
+        else {
+            return Err(anyhow::anyhow!(
+                "Strava activity {} has no GPS track",
+                activity.id
+            ));
+        };
+
+        let points = latlng
+            .data
+            .iter()
+            .zip(&altitude.data)
+            .zip(&time.data)
+            .map(
+                |((&[lat, lng], &elevation), &offset)| TemporalElevationPoint {
+                    point: geo::Point::new(lng, lat),
+                    elevation,
+                    datetime: activity.start_date + TimeDelta::seconds(offset),
+                },
+            )
+            .collect();
+
+        Ok(ExternalItem::Ride(ExternalRide {
+            // Strava doesn't say when an activity was last edited, see
+            // `ExternalProvider::tracks_updates`
+            external_ref: ExternalRef {
+                id: ExternalId::Strava(StravaId::Activity(activity.id)),
+                updated_at: activity.start_date,
+                sync_version: Some(Self::SYNC_VERSION),
+            },
+            name: activity.name.clone(),
+            distance: activity.distance,
+            points,
+        }))
+    }
+
+    fn parse_webhook(&self, body: &[u8]) -> Result<Vec<WebhookEvent>, anyhow::Error> {
+        let event: StravaWebhookEvent = serde_json::from_slice(body)?;
+
+        Ok(match event.object_type {
+            ObjectType::Activity => vec![WebhookEvent {
+                owner_id: event.owner_id,
+                id: ExternalId::Strava(StravaId::Activity(event.object_id)),
+                action: match event.aspect_type {
+                    AspectType::Create | AspectType::Update => WebhookAction::Upsert,
+                    AspectType::Delete => WebhookAction::Remove,
+                },
+            }],
+            // Deauthorisations, which aren't about synced items
+            ObjectType::Athlete => vec![],
+        })
+    }
+}
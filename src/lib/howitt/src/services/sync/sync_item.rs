@@ -0,0 +1,345 @@
+use crate::{
+    models::{
+        course_point::RouteCoursePoints,
+        external_ref::ExternalId,
+        ride::{Ride, RideFilter, RideId, RidePoints},
+        route::{Route, RouteFilter, RouteId, RoutePoints},
+        user::UserId,
+    },
+    repos::{RidePointsRepo, RideRepo, RouteCoursePointsRepo, RoutePointsRepo, RouteRepo},
+    services::{
+        simplify_points::{simplify_points_v2, DetailLevel},
+        slug::generate_slug,
+    },
+};
+
+use super::{
+    dedupe::{find_duplicate_ride, FindDuplicateRideParams},
+    provider::{ExternalItem, ExternalRide, ExternalRoute, SyncProvider},
+};
+
+pub struct SaveExternalRideParams {
+    pub ride_repo: RideRepo,
+    pub ride_points_repo: RidePointsRepo,
+    pub user_id: UserId,
+    pub ride: ExternalRide,
+}
+
+/// Stores a ride from a provider, updating the ride it was synced to before
+/// or a ride recorded from another source over the same time window. A ride
+/// that's already synced from a different provider is left as it is, so two
+/// providers don't keep taking it from each other.
+pub async fn save_external_ride(
+    SaveExternalRideParams {
+        ride_repo,
+        ride_points_repo,
+        user_id,
+        ride:
+            ExternalRide {
+                external_ref,
+                name,
+                distance,
+                points,
+            },
+    }: SaveExternalRideParams,
+) -> Result<RideId, anyhow::Error> {
+    let provider = external_ref.id.provider();
+
+    let existing_ride = ride_repo
+        .find_model(RideFilter::ExternalId(external_ref.id.clone()))
+        .await?;
+
+    tracing::info!(
+        ride_exists = existing_ride.is_some(),
+        "Checked for existing ride"
+    );
+
+    let started_at = points
+        .iter()
+        .map(|point| point.datetime)
+        .min()
+        .ok_or_else(|| anyhow::anyhow!("No points found in ride"))?;
+
+    let finished_at = points
+        .iter()
+        .map(|point| point.datetime)
+        .max()
+        .ok_or_else(|| anyhow::anyhow!("No points found in ride"))?;
+
+    // Fall back to a ride recorded from another source (e.g. a FIT upload)
+    // covering the same time window
+    let existing_ride = match existing_ride {
+        Some(existing_ride) => Some(existing_ride),
+        None => {
+            let duplicate_ride = find_duplicate_ride(FindDuplicateRideParams {
+                ride_repo: ride_repo.clone(),
+                user_id,
+                started_at,
+                finished_at,
+            })
+            .await?;
+
+            tracing::info!(
+                duplicate_exists = duplicate_ride.is_some(),
+                "Checked for duplicate ride within time window"
+            );
+
+            duplicate_ride
+        }
+    };
+
+    match existing_ride {
+        Some(existing_ride)
+            if existing_ride
+                .external_ref
+                .as_ref()
+                .is_some_and(|existing_ref| existing_ref.id.provider() != provider) =>
+        {
+            tracing::info!(
+                ride_id = %existing_ride.id,
+                ?provider,
+                "Ride is synced from another provider, leaving it as it is"
+            );
+
+            Ok(existing_ride.id)
+        }
+        Some(mut existing_ride) => {
+            tracing::info!(
+                ride_id = %existing_ride.id,
+                "Updating existing ride"
+            );
+
+            existing_ride.external_ref = Some(external_ref);
+            existing_ride.started_at = started_at;
+            existing_ride.finished_at = finished_at;
+            // The ride is still on the provider, so bring it back if it was
+            // archived
+            existing_ride.archived_at = None;
+
+            ride_repo.put(existing_ride.clone()).await?;
+
+            ride_points_repo
+                .put(RidePoints {
+                    id: existing_ride.id,
+                    points,
+                })
+                .await?;
+            tracing::info!("Successfully updated ride and points");
+
+            Ok(existing_ride.id)
+        }
+        None => {
+            tracing::info!("Creating new ride");
+
+            let id = RideId::new();
+            let ride = Ride {
+                id,
+                name,
+                user_id,
+                distance,
+                started_at,
+                finished_at,
+                external_ref: Some(external_ref),
+                archived_at: None,
+            };
+
+            ride_repo.put(ride).await?;
+            ride_points_repo.put(RidePoints { id, points }).await?;
+            tracing::info!(ride_id = %id, "Successfully created new ride");
+
+            Ok(id)
+        }
+    }
+}
+
+pub struct SaveExternalRouteParams {
+    pub route_repo: RouteRepo,
+    pub route_points_repo: RoutePointsRepo,
+    pub route_course_points_repo: RouteCoursePointsRepo,
+    pub user_id: UserId,
+    pub route: ExternalRoute,
+}
+
+/// Stores a route from a provider, updating the route it was synced to
+/// before. Names, descriptions and tags are only set when the route is first
+/// created, since they may have been edited here since.
+pub async fn save_external_route(
+    SaveExternalRouteParams {
+        route_repo,
+        route_points_repo,
+        route_course_points_repo,
+        user_id,
+        route:
+            ExternalRoute {
+                external_ref,
+                name,
+                distance,
+                points,
+                course_points,
+            },
+    }: SaveExternalRouteParams,
+) -> Result<RouteId, anyhow::Error> {
+    let existing_route = route_repo
+        .find_model(RouteFilter::ExternalId(external_ref.id.clone()))
+        .await?;
+
+    tracing::info!(
+        route_exists = existing_route.is_some(),
+        "Checked for existing route"
+    );
+
+    let sample_points = simplify_points_v2(points.clone(), DetailLevel::ExtremelyLow);
+    tracing::info!(
+        sample_points = sample_points.len(),
+        "Generated sample points"
+    );
+
+    let route_id = match existing_route {
+        Some(mut existing_route) => {
+            tracing::info!(
+                route_id = %existing_route.id,
+                "Updating existing route"
+            );
+
+            // Update only points and timestamps for existing route
+            existing_route.external_ref = Some(external_ref);
+            existing_route.distance = distance;
+            existing_route.sample_points = Some(sample_points);
+            // The route is still on the provider, so bring it back if it was
+            // archived
+            existing_route.archived_at = None;
+
+            route_repo.put(existing_route.clone()).await?;
+
+            route_points_repo
+                .put(RoutePoints {
+                    id: existing_route.id,
+                    points,
+                })
+                .await?;
+            tracing::info!("Successfully updated route and points");
+
+            existing_route.id
+        }
+        None => {
+            tracing::info!("Creating new route");
+
+            let id = RouteId::new();
+            let route = Route {
+                id,
+                slug: generate_slug(&name),
+                name,
+                user_id,
+                distance,
+                sample_points: Some(sample_points),
+                description: None,
+                external_ref: Some(external_ref),
+                tags: Default::default(),
+                archived_at: None,
+            };
+
+            route_repo.put(route).await?;
+            route_points_repo.put(RoutePoints { id, points }).await?;
+            tracing::info!(route_id = %id, "Successfully created new route");
+
+            id
+        }
+    };
+
+    tracing::info!(course_points = course_points.len(), "Saving course points");
+    route_course_points_repo
+        .put(RouteCoursePoints {
+            id: route_id,
+            course_points,
+        })
+        .await?;
+
+    Ok(route_id)
+}
+
+pub struct SyncExternalRideParams<Provider: SyncProvider> {
+    pub provider: Provider,
+    pub connection: Provider::Connection,
+    pub user_id: UserId,
+    pub id: ExternalId,
+    pub ride_repo: RideRepo,
+    pub ride_points_repo: RidePointsRepo,
+}
+
+/// Fetches a ride from the provider and stores it. The fetched item is
+/// returned too, for anything the provider syncs alongside the ride.
+pub async fn sync_external_ride<Provider: SyncProvider>(
+    SyncExternalRideParams {
+        provider,
+        connection,
+        user_id,
+        id,
+        ride_repo,
+        ride_points_repo,
+    }: SyncExternalRideParams<Provider>,
+) -> Result<(RideId, Provider::Item), anyhow::Error> {
+    tracing::info!(?id, user_id = %user_id, "Fetching ride");
+
+    let item = provider.fetch(&connection, &id).await?;
+
+    let ExternalItem::Ride(ride) = provider.convert(&item)? else {
+        return Err(anyhow::anyhow!("{id:?} isn't a ride"));
+    };
+
+    tracing::info!(total_points = ride.points.len(), "Converted ride");
+
+    let ride_id = save_external_ride(SaveExternalRideParams {
+        ride_repo,
+        ride_points_repo,
+        user_id,
+        ride,
+    })
+    .await?;
+
+    Ok((ride_id, item))
+}
+
+pub struct SyncExternalRouteParams<Provider: SyncProvider> {
+    pub provider: Provider,
+    pub connection: Provider::Connection,
+    pub user_id: UserId,
+    pub id: ExternalId,
+    pub route_repo: RouteRepo,
+    pub route_points_repo: RoutePointsRepo,
+    pub route_course_points_repo: RouteCoursePointsRepo,
+}
+
+/// Fetches a route from the provider and stores it. The fetched item is
+/// returned too, for anything the provider syncs alongside the route.
+pub async fn sync_external_route<Provider: SyncProvider>(
+    SyncExternalRouteParams {
+        provider,
+        connection,
+        user_id,
+        id,
+        route_repo,
+        route_points_repo,
+        route_course_points_repo,
+    }: SyncExternalRouteParams<Provider>,
+) -> Result<(RouteId, Provider::Item), anyhow::Error> {
+    tracing::info!(?id, user_id = %user_id, "Fetching route");
+
+    let item = provider.fetch(&connection, &id).await?;
+
+    let ExternalItem::Route(route) = provider.convert(&item)? else {
+        return Err(anyhow::anyhow!("{id:?} isn't a route"));
+    };
+
+    tracing::info!(total_points = route.points.len(), "Converted route");
+
+    let route_id = save_external_route(SaveExternalRouteParams {
+        route_repo,
+        route_points_repo,
+        route_course_points_repo,
+        user_id,
+        route,
+    })
+    .await?;
+
+    Ok((route_id, item))
+}
@@ -0,0 +1,278 @@
+use std::{
+    collections::{HashMap, HashSet},
+    path::PathBuf,
+    sync::RwLock,
+};
+
+use ::gtfs::{GtfsArchive, GtfsParseError, GtfsTime, GtfsTimetable};
+use chrono::{NaiveDateTime, Timelike};
+use itertools::Itertools;
+
+use crate::models::{
+    external_ref::{ExternalId, GtfsId},
+    point::{
+        delta::{BearingDelta, Delta, DistanceDelta},
+        ElevationPoint, Point,
+    },
+    point_of_interest::{PointOfInterest, PointOfInterestId, PointOfInterestType},
+};
+
+pub const MAX_STOP_DISTANCE_M: f64 = 25_000.0;
+const MAX_STOPS: usize = 3;
+const MAX_DEPARTURES: usize = 5;
+
+/// How far ahead of the current time to look for departures.
+const DEPARTURE_WINDOW_SECONDS: u32 = 12 * 60 * 60;
+
+fn gtfs_stop_ref(poi: &PointOfInterest) -> Option<(&str, &str)> {
+    match &poi.external_ref.as_ref()?.id {
+        ExternalId::Gtfs(GtfsId::Stop { feed, stop_id }) => Some((feed, stop_id)),
+        _ => None,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TransportDeparture {
+    pub route_name: Option<String>,
+    pub headsign: Option<String>,
+    pub departure_time: GtfsTime,
+}
+
+/// Only stop times for stops which have been imported as POIs are kept, so
+/// state-wide feeds stay small in memory.
+fn load_timetables(
+    feeds: Vec<(String, PathBuf)>,
+    pois: &[PointOfInterest],
+) -> Result<HashMap<String, GtfsTimetable>, GtfsParseError> {
+    let mut timetables = HashMap::new();
+
+    for (feed, path) in feeds {
+        let stop_ids: HashSet<String> = pois
+            .iter()
+            .filter_map(gtfs_stop_ref)
+            .filter(|(stop_feed, _)| *stop_feed == feed)
+            .map(|(_, stop_id)| stop_id.to_string())
+            .collect();
+
+        let mut archive = GtfsArchive::new(std::io::BufReader::new(std::fs::File::open(&path)?))?;
+
+        let timetable = GtfsTimetable::from_archive_for_stops(&mut archive, &stop_ids)?;
+
+        tracing::info!(feed, total_stops = stop_ids.len(), "Loaded GTFS timetable");
+
+        timetables.insert(feed, timetable);
+    }
+
+    Ok(timetables)
+}
+
+/// Parsed GTFS timetables keyed by the feed name used when importing their
+/// stops. They can be reloaded in place to pick up newly imported stops.
+#[derive(Debug, Default)]
+pub struct TransportTimetables(RwLock<HashMap<String, GtfsTimetable>>);
+
+impl TransportTimetables {
+    pub fn new(timetables: HashMap<String, GtfsTimetable>) -> TransportTimetables {
+        TransportTimetables(RwLock::new(timetables))
+    }
+
+    /// Loads each feed's timetable for the stops imported as POIs.
+    pub fn load(
+        feeds: Vec<(String, PathBuf)>,
+        pois: &[PointOfInterest],
+    ) -> Result<TransportTimetables, GtfsParseError> {
+        Ok(TransportTimetables::new(load_timetables(feeds, pois)?))
+    }
+
+    /// Replaces the timetables with freshly loaded feeds. The current
+    /// timetables keep serving departures until the new ones are ready, and
+    /// are kept if loading fails.
+    pub fn reload(
+        &self,
+        feeds: Vec<(String, PathBuf)>,
+        pois: &[PointOfInterest],
+    ) -> Result<(), GtfsParseError> {
+        let timetables = load_timetables(feeds, pois)?;
+
+        *self.0.write().unwrap() = timetables;
+
+        Ok(())
+    }
+
+    /// The next departures from a GTFS-derived POI after the given local time.
+    pub fn next_departures(
+        &self,
+        poi: &PointOfInterest,
+        now: NaiveDateTime,
+    ) -> Vec<TransportDeparture> {
+        let Some((feed, stop_id)) = gtfs_stop_ref(poi) else {
+            return vec![];
+        };
+
+        let timetables = self.0.read().unwrap();
+
+        let Some(timetable) = timetables.get(feed) else {
+            return vec![];
+        };
+
+        let from = GtfsTime::from_hms(now.hour(), now.minute(), now.second());
+        let to = GtfsTime(from.0 + DEPARTURE_WINDOW_SECONDS);
+
+        timetable
+            .departures(stop_id, now.date(), from, to)
+            .into_iter()
+            .take(MAX_DEPARTURES)
+            .map(|departure| TransportDeparture {
+                route_name: departure.route.map(|route| route.name().to_string()),
+                headsign: departure.trip.trip_headsign.clone(),
+                departure_time: departure.departure_time,
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct NearbyTransportStop {
+    pub point_of_interest: PointOfInterest,
+    pub distance: f64,
+    pub bearing: f64,
+}
+
+/// The closest public transport stops to a point, nearest first.
+pub fn nearest_transport_stops(
+    point: &ElevationPoint,
+    pois: &[PointOfInterest],
+) -> Vec<NearbyTransportStop> {
+    pois.iter()
+        .filter(|poi| poi.point_of_interest_type == PointOfInterestType::PublicTransportStop)
+        .map(|poi| {
+            let (DistanceDelta(distance), BearingDelta(bearing)) =
+                <(DistanceDelta, BearingDelta)>::delta(point.as_geo_point(), &poi.point);
+
+            NearbyTransportStop {
+                point_of_interest: poi.clone(),
+                distance,
+                bearing,
+            }
+        })
+        .filter(|stop| stop.distance <= MAX_STOP_DISTANCE_M)
+        .sorted_by_key(|stop| ordered_float::OrderedFloat(stop.distance))
+        .take(MAX_STOPS)
+        .collect()
+}
+
+#[derive(Debug, Clone)]
+pub struct TransportAccess {
+    pub point_of_interest: PointOfInterest,
+    pub distance: f64,
+    pub bearing: f64,
+    /// Stop elevation minus terminus elevation, when the stop elevation is known
+    pub elevation_gain_to_stop: Option<f64>,
+    pub next_departures: Vec<TransportDeparture>,
+}
+
+pub struct TransportAccessParams<'a> {
+    pub point: &'a ElevationPoint,
+    pub pois: &'a [PointOfInterest],
+    pub timetables: &'a TransportTimetables,
+    pub stop_elevations: &'a HashMap<PointOfInterestId, f64>,
+    pub now: NaiveDateTime,
+}
+
+pub fn transport_access(
+    TransportAccessParams {
+        point,
+        pois,
+        timetables,
+        stop_elevations,
+        now,
+    }: TransportAccessParams<'_>,
+) -> Vec<TransportAccess> {
+    nearest_transport_stops(point, pois)
+        .into_iter()
+        .map(
+            |NearbyTransportStop {
+                 point_of_interest,
+                 distance,
+                 bearing,
+             }| TransportAccess {
+                elevation_gain_to_stop: stop_elevations
+                    .get(&point_of_interest.id)
+                    .map(|elevation| elevation - point.elevation),
+                next_departures: timetables.next_departures(&point_of_interest, now),
+                point_of_interest,
+                distance,
+                bearing,
+            },
+        )
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::*;
+    use crate::models::{external_ref::ExternalRef, user::UserId};
+
+    fn create_test_stop(stop_id: &str, point: geo::Point) -> PointOfInterest {
+        PointOfInterest {
+            id: PointOfInterestId::new(),
+            user_id: UserId::new(),
+            name: format!("Stop {stop_id}"),
+            slug: format!("stop-{stop_id}"),
+            point,
+            point_of_interest_type: PointOfInterestType::PublicTransportStop,
+            description: None,
+            external_ref: Some(ExternalRef {
+                id: ExternalId::Gtfs(GtfsId::Stop {
+                    feed: String::from("vline"),
+                    stop_id: String::from(stop_id),
+                }),
+                updated_at: chrono::Utc::now(),
+                sync_version: None,
+            }),
+        }
+    }
+
+    #[test]
+    fn test_transport_access_orders_stops_and_elevations() {
+        let terminus = ElevationPoint {
+            point: geo::Point::new(146.96, -36.73),
+            elevation: 300.0,
+        };
+
+        let near = create_test_stop("1", geo::Point::new(146.961, -36.73));
+        let far = create_test_stop("2", geo::Point::new(146.99, -36.73));
+        let too_far = create_test_stop("3", geo::Point::new(147.5, -36.73));
+        let hut = PointOfInterest {
+            point_of_interest_type: PointOfInterestType::Hut,
+            ..create_test_stop("4", geo::Point::new(146.96, -36.73))
+        };
+
+        let stop_elevations = HashMap::from([(near.id, 120.0)]);
+
+        let access = transport_access(TransportAccessParams {
+            point: &terminus,
+            pois: &[far.clone(), hut, too_far, near.clone()],
+            timetables: &TransportTimetables::default(),
+            stop_elevations: &stop_elevations,
+            now: NaiveDate::from_ymd_opt(2024, 1, 2)
+                .unwrap()
+                .and_hms_opt(9, 0, 0)
+                .unwrap(),
+        });
+
+        assert_eq!(
+            access
+                .iter()
+                .map(|access| access.point_of_interest.id)
+                .collect::<Vec<_>>(),
+            vec![near.id, far.id]
+        );
+        assert_eq!(access[0].elevation_gain_to_stop, Some(-180.0));
+        assert_eq!(access[1].elevation_gain_to_stop, None);
+        assert!(access[0].distance > 80.0 && access[0].distance < 100.0);
+        assert!(access[0].next_departures.is_empty());
+    }
+}
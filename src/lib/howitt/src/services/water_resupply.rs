@@ -0,0 +1,246 @@
+use itertools::Itertools;
+
+use crate::models::{
+    cuesheet::CueStop,
+    point::{
+        progress::{DistanceElevationProgress, Progress},
+        ElevationPoint,
+    },
+    point_of_interest::{PointOfInterest, PointOfInterestType},
+    segment_summary::{ElevationSummary, SegmentElevationSummary},
+};
+
+use super::generate_cuesheet::generate_cuesheet;
+
+/// How much water a rider gets through. Climbing is accounted for as extra
+/// riding time, on the basis that a rider drinks at a steady rate per hour
+/// but spends longer on the bike for each hill.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WaterConsumptionModel {
+    pub litres_per_hour: f64,
+    /// Average moving speed on flat ground
+    pub speed_kmh: f64,
+    /// Extra riding time for every 100m climbed
+    pub minutes_per_100m_climb: f64,
+}
+
+impl Default for WaterConsumptionModel {
+    fn default() -> Self {
+        WaterConsumptionModel {
+            litres_per_hour: 0.6,
+            speed_kmh: 15.0,
+            minutes_per_100m_climb: 10.0,
+        }
+    }
+}
+
+impl WaterConsumptionModel {
+    pub fn riding_hours(&self, summary: &SegmentElevationSummary) -> f64 {
+        let flat_hours = (summary.distance_m / 1000.0) / self.speed_kmh;
+        let climbing_hours =
+            (summary.data.elevation_ascent_m / 100.0) * (self.minutes_per_100m_climb / 60.0);
+
+        flat_hours + climbing_hours
+    }
+
+    pub fn litres_needed(&self, summary: &SegmentElevationSummary) -> f64 {
+        self.riding_hours(summary) * self.litres_per_hour
+    }
+}
+
+#[derive(Debug)]
+pub struct WaterSegment {
+    pub origin: CueStop,
+    pub destination: CueStop,
+    /// Distance along the route where the segment starts
+    pub start_distance_m: f64,
+    pub summary: SegmentElevationSummary,
+    pub litres_needed: f64,
+    /// Distance along the route where the rider would run out, if a full
+    /// load isn't enough to reach the destination
+    pub runs_dry_at_m: Option<f64>,
+}
+
+impl WaterSegment {
+    pub fn is_dry(&self) -> bool {
+        self.runs_dry_at_m.is_some()
+    }
+}
+
+#[derive(Debug)]
+pub struct WaterResupplyPlan {
+    pub capacity_litres: f64,
+    pub segments: Vec<WaterSegment>,
+}
+
+impl WaterResupplyPlan {
+    pub fn dry_segments(&self) -> impl Iterator<Item = &WaterSegment> + '_ {
+        self.segments.iter().filter(|segment| segment.is_dry())
+    }
+}
+
+fn summary_between(
+    start: &DistanceElevationProgress<ElevationPoint>,
+    end: &DistanceElevationProgress<ElevationPoint>,
+) -> SegmentElevationSummary {
+    SegmentElevationSummary {
+        distance_m: end.distance_m - start.distance_m,
+        data: ElevationSummary {
+            elevation_ascent_m: end.elevation_gain_m - start.elevation_gain_m,
+            elevation_descent_m: end.elevation_loss_m - start.elevation_loss_m,
+        },
+    }
+}
+
+/// Splits a route at its water source POIs and works out, for each leg, whether
+/// a rider setting out with `capacity_litres` would reach the next refill. The
+/// rider is assumed to fill up at the start and at every water source.
+pub fn plan_water_resupply(
+    route: &[ElevationPoint],
+    pois: &[PointOfInterest],
+    capacity_litres: f64,
+    model: &WaterConsumptionModel,
+) -> WaterResupplyPlan {
+    let water_sources = pois
+        .iter()
+        .filter(|poi| poi.point_of_interest_type == PointOfInterestType::WaterSource)
+        .cloned()
+        .collect_vec();
+
+    let progress = DistanceElevationProgress::from_points(route.to_vec()).collect_vec();
+
+    let mut start_distance_m = 0.0;
+
+    let segments = generate_cuesheet(route, &water_sources, &[])
+        .cues
+        .into_iter()
+        .map(|cue| {
+            let summary = SegmentElevationSummary {
+                distance_m: cue.summary.distance_m,
+                data: ElevationSummary {
+                    elevation_ascent_m: cue.summary.elevation_gain_m,
+                    elevation_descent_m: cue.summary.elevation_loss_m,
+                },
+            };
+
+            let litres_needed = model.litres_needed(&summary);
+            let end_distance_m = start_distance_m + summary.distance_m;
+
+            let runs_dry_at_m = (litres_needed > capacity_litres).then(|| {
+                let segment_points = progress
+                    .iter()
+                    .skip_while(|point| point.distance_m < start_distance_m)
+                    .take_while(|point| point.distance_m <= end_distance_m)
+                    .collect_vec();
+
+                segment_points
+                    .first()
+                    .and_then(|start| {
+                        segment_points.iter().find(|point| {
+                            model.litres_needed(&summary_between(start, point)) > capacity_litres
+                        })
+                    })
+                    .map(|point| point.distance_m)
+                    .unwrap_or(end_distance_m)
+            });
+
+            let segment = WaterSegment {
+                origin: cue.origin,
+                destination: cue.destination,
+                start_distance_m,
+                summary,
+                litres_needed,
+                runs_dry_at_m,
+            };
+
+            start_distance_m = end_distance_m;
+
+            segment
+        })
+        .collect_vec();
+
+    WaterResupplyPlan {
+        capacity_litres,
+        segments,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{point_of_interest::PointOfInterestId, user::UserId};
+
+    fn create_test_route() -> Vec<ElevationPoint> {
+        // ~89km heading east, climbing 1000m over the second half
+        (0..=100)
+            .map(|i| ElevationPoint {
+                point: geo::Point::new(146.0 + i as f64 * 0.01, -37.0),
+                elevation: if i <= 50 {
+                    200.0
+                } else {
+                    200.0 + (i - 50) as f64 * 20.0
+                },
+            })
+            .collect()
+    }
+
+    fn create_test_poi(point_of_interest_type: PointOfInterestType, lng: f64) -> PointOfInterest {
+        PointOfInterest {
+            id: PointOfInterestId::new(),
+            user_id: UserId::new(),
+            name: String::from("Tank"),
+            slug: String::from("tank"),
+            point: geo::Point::new(lng, -37.0),
+            point_of_interest_type,
+            description: None,
+            external_ref: None,
+        }
+    }
+
+    #[test]
+    fn test_climbing_increases_consumption() {
+        let model = WaterConsumptionModel::default();
+
+        let flat = SegmentElevationSummary {
+            distance_m: 30_000.0,
+            data: ElevationSummary::default(),
+        };
+        let hilly = SegmentElevationSummary {
+            data: ElevationSummary {
+                elevation_ascent_m: 600.0,
+                elevation_descent_m: 0.0,
+            },
+            ..flat.clone()
+        };
+
+        assert!((model.litres_needed(&flat) - 1.2).abs() < 0.001);
+        assert!((model.litres_needed(&hilly) - 1.8).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_plan_water_resupply_reports_dry_segments() {
+        let route = create_test_route();
+        let pois = vec![
+            create_test_poi(PointOfInterestType::WaterSource, 146.5),
+            create_test_poi(PointOfInterestType::Hut, 146.25),
+        ];
+
+        let plan = plan_water_resupply(&route, &pois, 2.5, &WaterConsumptionModel::default());
+
+        assert_eq!(plan.segments.len(), 2);
+        assert!(matches!(plan.segments[0].origin, CueStop::Start));
+        assert!(matches!(plan.segments[0].destination, CueStop::POI(_)));
+
+        // The flat first half needs ~1.8l, the climbing second half ~2.7l
+        let dry = plan.dry_segments().collect_vec();
+        assert_eq!(dry.len(), 1);
+        assert!(matches!(dry[0].destination, CueStop::End));
+
+        let runs_dry_at_m = dry[0].runs_dry_at_m.unwrap();
+        assert!(runs_dry_at_m > dry[0].start_distance_m);
+        assert!(runs_dry_at_m < dry[0].start_distance_m + dry[0].summary.distance_m);
+
+        let plan = plan_water_resupply(&route, &pois, 4.0, &WaterConsumptionModel::default());
+        assert_eq!(plan.dry_segments().count(), 0);
+    }
+}
@@ -0,0 +1,213 @@
+use std::collections::HashMap;
+
+use itertools::Itertools;
+
+use crate::{
+    models::{
+        osm_feature::{OsmFeature, OsmFeatureFilter, OsmFeatureId},
+        point::{
+            progress::{DistanceProgress, Progress},
+            ElevationPoint, Point,
+        },
+        route::RouteId,
+        water_beta::{WaterBeta, WaterBetaFilter},
+    },
+    repos::{OsmFeatureRepo, RoutePointsRepo, WaterBetaRepo},
+    services::simplify_points::{simplify_points_v2, DetailLevel},
+};
+use geo::{algorithm::line_measures::metric_spaces::Haversine, Distance};
+
+#[derive(Debug, Clone)]
+pub struct WaterSource {
+    pub osm_feature: OsmFeature,
+    /// Distance along the route to the route point closest to the source
+    pub distance_along_route_m: f64,
+    /// Distance from that route point to the source
+    pub distance_from_route_m: f64,
+    /// Reports for the source, most recently observed first
+    pub reports: Vec<WaterBeta>,
+}
+
+impl WaterSource {
+    pub fn latest_report(&self) -> Option<&WaterBeta> {
+        self.reports.first()
+    }
+}
+
+/// Places water sources along a route, ordered by distance along it. Features
+/// which aren't water sources or are further than `max_distance_m` from every
+/// route point are dropped.
+pub fn locate_water_sources(
+    points: &[ElevationPoint],
+    features: Vec<OsmFeature>,
+    reports: Vec<WaterBeta>,
+    max_distance_m: f64,
+) -> Vec<WaterSource> {
+    let progress = DistanceProgress::from_points(points.to_vec()).collect_vec();
+
+    let mut reports_by_feature: HashMap<OsmFeatureId, Vec<WaterBeta>> = reports
+        .into_iter()
+        .sorted_by_key(|report| std::cmp::Reverse(report.observed_at))
+        .into_group_map_by(|report| report.osm_feature_id);
+
+    features
+        .into_iter()
+        .filter(|feature| {
+            feature
+                .feature_type()
+                .is_some_and(|feature_type| feature_type.is_water_source())
+        })
+        .filter_map(|feature| {
+            let point = feature.point()?;
+
+            let (closest, distance_from_route_m) = progress
+                .iter()
+                .map(|progress| {
+                    (
+                        progress,
+                        Haversine::distance(*progress.point.as_geo_point(), point),
+                    )
+                })
+                .min_by_key(|(_, distance)| ordered_float::OrderedFloat(*distance))?;
+
+            if distance_from_route_m > max_distance_m {
+                return None;
+            }
+
+            Some(WaterSource {
+                distance_along_route_m: closest.distance_m,
+                distance_from_route_m,
+                reports: reports_by_feature.remove(&feature.id).unwrap_or_default(),
+                osm_feature: feature,
+            })
+        })
+        .sorted_by_key(|source| ordered_float::OrderedFloat(source.distance_along_route_m))
+        .collect()
+}
+
+pub struct WaterSourcesNearRouteParams {
+    pub osm_feature_repo: OsmFeatureRepo,
+    pub water_beta_repo: WaterBetaRepo,
+    pub route_points_repo: RoutePointsRepo,
+    pub route_id: RouteId,
+    pub max_distance_m: f64,
+}
+
+pub async fn water_sources_near_route(
+    WaterSourcesNearRouteParams {
+        osm_feature_repo,
+        water_beta_repo,
+        route_points_repo,
+        route_id,
+        max_distance_m,
+    }: WaterSourcesNearRouteParams,
+) -> Result<Vec<WaterSource>, anyhow::Error> {
+    let route_points = route_points_repo.get(route_id).await?;
+
+    // The corridor query only needs the route's shape, so the line sent to
+    // Postgres is simplified; exact distances are computed on the full points.
+    let corridor_points = simplify_points_v2(route_points.points.clone(), DetailLevel::Medium)
+        .into_iter()
+        .map(|point| point.point)
+        .collect_vec();
+
+    let features = osm_feature_repo
+        .filter_models(OsmFeatureFilter::RouteCorridor {
+            points: corridor_points,
+            distance_m: max_distance_m,
+        })
+        .await?;
+
+    let reports = water_beta_repo
+        .filter_models(WaterBetaFilter::ForOsmFeatures(
+            features.iter().map(|feature| feature.id).collect(),
+        ))
+        .await?;
+
+    Ok(locate_water_sources(
+        &route_points.points,
+        features,
+        reports,
+        max_distance_m,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+
+    use super::*;
+    use crate::models::{
+        user::UserId,
+        water_beta::{WaterBetaId, WaterFlowStatus},
+    };
+
+    fn create_test_feature(id: i64, kind: &str, point: geo::Point) -> OsmFeature {
+        let (key, value) = kind.split_once('=').unwrap();
+
+        OsmFeature {
+            id: OsmFeatureId::node(id),
+            geometry: geo::Geometry::Point(point),
+            name: None,
+            tags: HashMap::from([(key.to_string(), value.to_string())]),
+        }
+    }
+
+    fn create_test_report(osm_feature_id: OsmFeatureId, day: u32) -> WaterBeta {
+        WaterBeta {
+            id: WaterBetaId::new(),
+            created_at: Utc::now(),
+            user_id: UserId::new(),
+            osm_feature_id,
+            observed_at: Utc.with_ymd_and_hms(2024, 1, day, 0, 0, 0).unwrap(),
+            flow_status: WaterFlowStatus::Flowing,
+            treatment_needed: false,
+            comment: None,
+            media_ids: vec![],
+        }
+    }
+
+    #[test]
+    fn test_locate_water_sources() {
+        let points = (0..=10)
+            .map(|i| ElevationPoint {
+                point: geo::Point::new(146.0 + i as f64 * 0.01, -37.0),
+                elevation: 500.0,
+            })
+            .collect_vec();
+
+        let spring = create_test_feature(1, "natural=spring", geo::Point::new(146.08, -37.0005));
+        let tap = create_test_feature(2, "amenity=drinking_water", geo::Point::new(146.02, -37.0));
+        let far_tap =
+            create_test_feature(3, "amenity=drinking_water", geo::Point::new(146.05, -37.1));
+        let toilets = create_test_feature(4, "amenity=toilets", geo::Point::new(146.03, -37.0));
+
+        let reports = vec![
+            create_test_report(spring.id, 1),
+            create_test_report(spring.id, 3),
+            create_test_report(far_tap.id, 3),
+        ];
+
+        let sources = locate_water_sources(
+            &points,
+            vec![spring.clone(), tap.clone(), far_tap, toilets],
+            reports,
+            500.0,
+        );
+
+        assert_eq!(
+            sources
+                .iter()
+                .map(|source| source.osm_feature.id)
+                .collect_vec(),
+            vec![tap.id, spring.id]
+        );
+        assert!(sources[0].reports.is_empty());
+        assert_eq!(sources[1].reports.len(), 2);
+        assert_eq!(
+            sources[1].latest_report().unwrap().observed_at,
+            Utc.with_ymd_and_hms(2024, 1, 3, 0, 0, 0).unwrap()
+        );
+        assert!(sources[1].distance_along_route_m > sources[0].distance_along_route_m);
+    }
+}
@@ -0,0 +1,241 @@
+use chrono::{DurationRound, TimeDelta};
+use itertools::Itertools;
+
+use crate::models::{
+    point::{
+        delta::{AccumulatingDelta, DistanceDelta, ElapsedDelta, HeadwindDelta},
+        Point, TemporalElevationPoint, WindPoint, WithDatetime,
+    },
+    ride_weather::{RideWeather, Wind, WindRelation},
+};
+
+use super::ride_weather::heading;
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct WindSegment {
+    /// Distance along the ride where the segment starts
+    pub start_distance_m: f64,
+    pub distance_m: f64,
+    pub elapsed: TimeDelta,
+    /// Moving time within the segment spent riding into a headwind
+    pub headwind_elapsed: TimeDelta,
+    pub bearing: Option<f64>,
+    /// Wind where the segment starts
+    pub wind: Option<Wind>,
+}
+
+impl WindSegment {
+    pub fn relation(&self) -> Option<WindRelation> {
+        self.wind?.relation(self.bearing?)
+    }
+
+    /// Negative when the wind is behind the rider
+    pub fn headwind_kmh(&self) -> Option<f64> {
+        Some(self.wind?.headwind_kmh(self.bearing?))
+    }
+
+    pub fn crosswind_kmh(&self) -> Option<f64> {
+        Some(self.wind?.crosswind_kmh(self.bearing?))
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct WindAnalysis {
+    pub segments: Vec<WindSegment>,
+    /// Moving time over the whole ride spent riding into a headwind
+    pub headwind_elapsed: TimeDelta,
+}
+
+/// Pairs each ride point with the wind for the hour it was recorded in.
+pub fn wind_points(
+    points: Vec<TemporalElevationPoint>,
+    weather: &RideWeather,
+) -> Vec<WindPoint<TemporalElevationPoint>> {
+    points
+        .into_iter()
+        .map(|point| {
+            let hour = point
+                .datetime()
+                .duration_trunc(TimeDelta::hours(1))
+                .unwrap_or(*point.datetime());
+
+            let wind = weather
+                .hours
+                .iter()
+                .find(|weather| weather.datetime == hour)
+                .map(|weather| weather.wind());
+
+            WindPoint { point, wind }
+        })
+        .collect()
+}
+
+/// Breaks a ride into segments of `segment_length_m` and works out how the
+/// wind met the rider on each.
+pub fn analyse_wind(
+    points: Vec<TemporalElevationPoint>,
+    weather: &RideWeather,
+    segment_length_m: f64,
+) -> WindAnalysis {
+    let points = wind_points(points, weather);
+    let totals = <(DistanceDelta, ElapsedDelta, HeadwindDelta)>::running_totals(&points);
+
+    let headwind_elapsed = totals
+        .last()
+        .map(|(_, _, HeadwindDelta(headwind))| *headwind)
+        .unwrap_or_else(TimeDelta::zero);
+
+    if segment_length_m <= 0.0 {
+        return WindAnalysis {
+            segments: vec![],
+            headwind_elapsed,
+        };
+    }
+
+    let segments = points
+        .iter()
+        .zip(totals.iter())
+        .chunk_by(|(_, (DistanceDelta(distance_m), _, _))| {
+            (distance_m / segment_length_m).floor() as usize
+        })
+        .into_iter()
+        .map(|(_, chunk)| chunk.collect_vec())
+        .collect_vec();
+
+    // Each segment runs up to where the next one starts, so nothing between
+    // the chunks goes missing
+    let segments = segments
+        .iter()
+        .enumerate()
+        .filter_map(|(i, chunk)| {
+            let (start, (DistanceDelta(start_m), ElapsedDelta(start_t), HeadwindDelta(start_h))) =
+                chunk.first()?;
+            let (end, (DistanceDelta(end_m), ElapsedDelta(end_t), HeadwindDelta(end_h))) = segments
+                .get(i + 1)
+                .and_then(|next| next.first())
+                .or(chunk.last())?;
+
+            Some(WindSegment {
+                start_distance_m: *start_m,
+                distance_m: end_m - start_m,
+                elapsed: *end_t - *start_t,
+                headwind_elapsed: *end_h - *start_h,
+                bearing: heading(start.as_geo_point(), end.as_geo_point()),
+                wind: start.wind,
+            })
+        })
+        .collect_vec();
+
+    WindAnalysis {
+        segments,
+        headwind_elapsed,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+
+    use super::*;
+    use crate::models::{ride::RideId, ride_weather::HourlyWeather};
+
+    fn create_test_ride() -> Vec<TemporalElevationPoint> {
+        // ~8.9km east over the first hour, then back west over the second
+        (0..=20)
+            .map(|i| {
+                let lng = if i <= 10 {
+                    146.0 + i as f64 * 0.01
+                } else {
+                    146.1 - (i - 10) as f64 * 0.01
+                };
+
+                TemporalElevationPoint {
+                    datetime: Utc.with_ymd_and_hms(2024, 12, 15, 0, 0, 0).unwrap()
+                        + TimeDelta::minutes(i * 6),
+                    point: geo::Point::new(lng, -37.0),
+                    elevation: 200.0,
+                }
+            })
+            .collect()
+    }
+
+    fn hour(hour: u32) -> HourlyWeather {
+        HourlyWeather {
+            datetime: Utc.with_ymd_and_hms(2024, 12, 15, hour, 0, 0).unwrap(),
+            point: geo::Point::new(146.0, -37.0),
+            bearing: None,
+            temperature: 20.0,
+            precipitation: 0.0,
+            wind_speed: 20.0,
+            // Easterly
+            wind_direction: 90.0,
+        }
+    }
+
+    #[test]
+    fn test_analyse_wind() {
+        let weather = RideWeather {
+            id: RideId::new(),
+            hours: vec![hour(0), hour(1)],
+        };
+
+        let analysis = analyse_wind(create_test_ride(), &weather, 4000.0);
+
+        assert_eq!(
+            analysis
+                .segments
+                .iter()
+                .map(|segment| segment.relation())
+                .collect_vec(),
+            vec![
+                Some(WindRelation::Headwind),
+                Some(WindRelation::Headwind),
+                // Turns around where the third segment starts
+                Some(WindRelation::Tailwind),
+                Some(WindRelation::Tailwind),
+                Some(WindRelation::Tailwind),
+            ]
+        );
+
+        // The whole first hour is into the wind
+        assert_eq!(analysis.headwind_elapsed, TimeDelta::hours(1));
+        assert_eq!(
+            analysis
+                .segments
+                .iter()
+                .map(|segment| segment.headwind_elapsed)
+                .sum::<TimeDelta>(),
+            TimeDelta::hours(1)
+        );
+
+        let total_distance_m: f64 = analysis
+            .segments
+            .iter()
+            .map(|segment| segment.distance_m)
+            .sum();
+        assert!((total_distance_m - 17_780.0).abs() < 100.0);
+
+        assert!(analysis.segments[0]
+            .headwind_kmh()
+            .is_some_and(|headwind| (headwind - 20.0).abs() < 0.1));
+        assert!(analysis.segments[4]
+            .headwind_kmh()
+            .is_some_and(|headwind| (headwind + 20.0).abs() < 0.1));
+    }
+
+    #[test]
+    fn test_analyse_wind_without_weather() {
+        let weather = RideWeather {
+            id: RideId::new(),
+            hours: vec![],
+        };
+
+        let analysis = analyse_wind(create_test_ride(), &weather, 4000.0);
+
+        assert_eq!(analysis.headwind_elapsed, TimeDelta::zero());
+        assert!(analysis
+            .segments
+            .iter()
+            .all(|segment| segment.relation().is_none()));
+    }
+}
@@ -0,0 +1,51 @@
+use howitt::models::route::RouteFilter;
+use howitt::models::user::UserId;
+use howitt::repos::Repos;
+use howitt::services::import::gpx::{import_gpx_route, ImportGpxRouteParams};
+use howitt_memory::MemoryRepos;
+
+/// A route with no name anywhere in the file
+const UNTITLED_GPX: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<gpx version="1.1" creator="test" xmlns="http://www.topografix.com/GPX/1/1">
+  <rte>
+    <rtept lat="-37.0" lon="146.0"><ele>100</ele></rtept>
+    <rtept lat="-37.001" lon="146.0"><ele>110</ele></rtept>
+  </rte>
+</gpx>"#;
+
+#[tokio::test]
+async fn test_untitled_routes_get_their_own_slugs() {
+    let repos = Repos::from(MemoryRepos::default());
+    let user_id = UserId::new();
+
+    let mut routes = vec![];
+
+    for _ in 0..2 {
+        let route = import_gpx_route(ImportGpxRouteParams {
+            route_repo: repos.route_repo.clone(),
+            route_points_repo: repos.route_points_repo.clone(),
+            user_id,
+            name: None,
+            data: UNTITLED_GPX.as_bytes(),
+        })
+        .await
+        .unwrap();
+
+        routes.push(route);
+    }
+
+    assert_eq!(routes[0].name, "Imported route");
+    assert_eq!(routes[1].name, "Imported route");
+    assert_eq!(routes[0].slug, "imported-route");
+    assert_eq!(routes[1].slug, "imported-route-2");
+
+    let found = repos
+        .route_repo
+        .filter_models(RouteFilter::Slug(String::from("imported-route-2")))
+        .await
+        .unwrap();
+    assert_eq!(
+        found.iter().map(|route| route.id).collect::<Vec<_>>(),
+        vec![routes[1].id]
+    );
+}
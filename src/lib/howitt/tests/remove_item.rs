@@ -0,0 +1,326 @@
+use std::sync::Arc;
+
+use chrono::{DateTime, TimeZone, Utc};
+use howitt::models::external_ref::{ExternalId, ExternalRef, RwgpsId};
+use howitt::models::point::TemporalElevationPoint;
+use howitt::models::ride::{Ride, RideFilter, RideId, RidePoints};
+use howitt::models::route::{Route, RouteFilter, RouteId};
+use howitt::models::sync_audit_entry::{SyncAuditAction, SyncAuditEntryFilter, SyncedItemId};
+use howitt::models::trip::{Trip, TripId};
+use howitt::models::user::{RwgpsConnectionStatus, UserId, UserRwgpsConnection};
+use howitt::repos::Repos;
+use howitt::services::fetchers::{
+    DistanceElevation, ElevationPointsParams, SimplifiedRidePointsFetcher,
+    SimplifiedTripElevationPointsFetcher,
+};
+use howitt::services::simplify_points::DetailLevel;
+use howitt::services::sync::rwgps_v2::remove_item::{remove_item, RemovalPolicy, RemoveItemParams};
+use howitt_memory::{MemoryRedisClient, MemoryRepos};
+
+fn connection(user_id: UserId) -> UserRwgpsConnection {
+    UserRwgpsConnection {
+        id: uuid::Uuid::now_v7(),
+        user_id,
+        rwgps_user_id: 1,
+        access_token: String::from("token"),
+        created_at: Utc::now(),
+        updated_at: Utc::now(),
+        status: RwgpsConnectionStatus::Ok,
+        sync_cursor: None,
+    }
+}
+
+fn external_ref(rwgps_id: RwgpsId) -> Option<ExternalRef> {
+    Some(ExternalRef {
+        id: ExternalId::Rwgps(rwgps_id),
+        updated_at: Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap(),
+        sync_version: Some(2),
+    })
+}
+
+struct Fetchers {
+    ride_points: Arc<SimplifiedRidePointsFetcher<MemoryRedisClient>>,
+    trip_elevation_points: Arc<SimplifiedTripElevationPointsFetcher<MemoryRedisClient>>,
+}
+
+impl Fetchers {
+    fn new(repos: &Repos) -> Fetchers {
+        Fetchers {
+            ride_points: Arc::new(SimplifiedRidePointsFetcher::new(
+                repos.ride_points_repo.clone(),
+                MemoryRedisClient::new(),
+            )),
+            trip_elevation_points: Arc::new(SimplifiedTripElevationPointsFetcher::new(
+                repos.ride_repo.clone(),
+                repos.ride_points_repo.clone(),
+                MemoryRedisClient::new(),
+            )),
+        }
+    }
+}
+
+fn params_with_fetchers(
+    repos: &Repos,
+    fetchers: &Fetchers,
+    rwgps_id: RwgpsId,
+    user_id: UserId,
+    policy: RemovalPolicy,
+) -> RemoveItemParams<MemoryRedisClient> {
+    RemoveItemParams {
+        route_repo: repos.route_repo.clone(),
+        ride_repo: repos.ride_repo.clone(),
+        trip_repo: repos.trip_repo.clone(),
+        sync_audit_entry_repo: repos.sync_audit_entry_repo.clone(),
+        simplified_ride_points_fetcher: fetchers.ride_points.clone(),
+        simplified_trip_elevation_points_fetcher: fetchers.trip_elevation_points.clone(),
+        rwgps_id,
+        connection: connection(user_id),
+        policy,
+    }
+}
+
+fn params(
+    repos: &Repos,
+    rwgps_id: RwgpsId,
+    user_id: UserId,
+    policy: RemovalPolicy,
+) -> RemoveItemParams<MemoryRedisClient> {
+    params_with_fetchers(repos, &Fetchers::new(repos), rwgps_id, user_id, policy)
+}
+
+/// A ride heading north from `lat` for an hour, with its points.
+fn ride_with_points(
+    user_id: UserId,
+    started_at: DateTime<Utc>,
+    lat: f64,
+    external_ref: Option<ExternalRef>,
+) -> (Ride, RidePoints) {
+    let id = RideId::new();
+
+    let points = (0..=6)
+        .map(|i| TemporalElevationPoint {
+            datetime: started_at + chrono::TimeDelta::minutes(i * 10),
+            point: geo::Point::new(146.9, lat + i as f64 * 0.01),
+            elevation: 400.0 + (i * 50) as f64,
+        })
+        .collect();
+
+    let ride = Ride {
+        id,
+        name: String::from("Day"),
+        user_id,
+        distance: 6_600.0,
+        started_at,
+        finished_at: started_at + chrono::TimeDelta::hours(1),
+        external_ref,
+        archived_at: None,
+    };
+
+    (ride, RidePoints { id, points })
+}
+
+#[tokio::test]
+async fn test_remove_trip_archives_ride() {
+    let repos = Repos::from(MemoryRepos::default());
+    let user_id = UserId::new();
+    let started_at = Utc.with_ymd_and_hms(2025, 1, 4, 8, 0, 0).unwrap();
+
+    let ride = Ride {
+        id: RideId::new(),
+        name: String::from("Day 1"),
+        user_id,
+        distance: 80_000.0,
+        started_at,
+        finished_at: started_at + chrono::TimeDelta::hours(6),
+        external_ref: external_ref(RwgpsId::Trip(42)),
+        archived_at: None,
+    };
+    repos.ride_repo.put(ride.clone()).await.unwrap();
+
+    let trip = Trip {
+        id: TripId::new(),
+        created_at: started_at,
+        user_id,
+        name: String::from("Alpine tour"),
+        slug: String::from("alpine-tour"),
+        year: 2025,
+        description: None,
+        notes: vec![],
+        ride_ids: vec![ride.id],
+        media_ids: vec![],
+        is_published: true,
+    };
+    repos.trip_repo.put(trip.clone()).await.unwrap();
+
+    let entry = remove_item(params(
+        &repos,
+        RwgpsId::Trip(42),
+        user_id,
+        RemovalPolicy::Archive,
+    ))
+    .await
+    .unwrap()
+    .unwrap();
+
+    assert_eq!(entry.item_id, SyncedItemId::Ride(ride.id));
+    assert_eq!(entry.action, SyncAuditAction::Archived);
+    assert_eq!(entry.detached_trip_ids, vec![trip.id]);
+
+    // Kept, but archived and out of the trip
+    let archived = repos.ride_repo.get(ride.id).await.unwrap();
+    assert!(archived.archived_at.is_some());
+    assert!(repos
+        .ride_repo
+        .filter_models(RideFilter::ForTrip(trip.id))
+        .await
+        .unwrap()
+        .is_empty());
+
+    // A repeated webhook has nothing left to do
+    let entry = remove_item(params(
+        &repos,
+        RwgpsId::Trip(42),
+        user_id,
+        RemovalPolicy::Archive,
+    ))
+    .await
+    .unwrap();
+    assert!(entry.is_none());
+
+    let entries = repos
+        .sync_audit_entry_repo
+        .filter_models(SyncAuditEntryFilter::ForUser(user_id))
+        .await
+        .unwrap();
+    assert_eq!(entries.len(), 1);
+}
+
+#[tokio::test]
+async fn test_remove_route_deletes_route() {
+    let repos = Repos::from(MemoryRepos::default());
+    let user_id = UserId::new();
+
+    let route = Route {
+        id: RouteId::new(),
+        name: String::from("Mt Stirling loop"),
+        slug: String::from("mt-stirling-loop"),
+        user_id,
+        distance: 45_000.0,
+        sample_points: None,
+        description: None,
+        external_ref: external_ref(RwgpsId::Route(7)),
+        tags: Default::default(),
+        archived_at: None,
+    };
+    repos.route_repo.put(route.clone()).await.unwrap();
+
+    // Someone else's route can't be removed through this user's webhook
+    let entry = remove_item(params(
+        &repos,
+        RwgpsId::Route(7),
+        UserId::new(),
+        RemovalPolicy::Delete,
+    ))
+    .await
+    .unwrap();
+    assert!(entry.is_none());
+
+    let entry = remove_item(params(
+        &repos,
+        RwgpsId::Route(7),
+        user_id,
+        RemovalPolicy::Delete,
+    ))
+    .await
+    .unwrap()
+    .unwrap();
+
+    assert_eq!(entry.item_id, SyncedItemId::Route(route.id));
+    assert_eq!(entry.action, SyncAuditAction::Deleted);
+    assert!(repos
+        .route_repo
+        .filter_models(RouteFilter::RwgpsId(7))
+        .await
+        .unwrap()
+        .is_empty());
+}
+
+#[tokio::test]
+async fn test_remove_trip_invalidates_cached_points() {
+    let repos = Repos::from(MemoryRepos::default());
+    let fetchers = Fetchers::new(&repos);
+    let user_id = UserId::new();
+    let started_at = Utc.with_ymd_and_hms(2025, 1, 4, 8, 0, 0).unwrap();
+
+    let (removed, removed_points) =
+        ride_with_points(user_id, started_at, -36.9, external_ref(RwgpsId::Trip(42)));
+    let (kept, kept_points) = ride_with_points(
+        user_id,
+        started_at + chrono::TimeDelta::days(1),
+        -36.8,
+        None,
+    );
+
+    for (ride, points) in [(&removed, removed_points), (&kept, kept_points)] {
+        repos.ride_repo.put(ride.clone()).await.unwrap();
+        repos.ride_points_repo.put(points).await.unwrap();
+    }
+
+    let trip = Trip {
+        id: TripId::new(),
+        created_at: started_at,
+        user_id,
+        name: String::from("Alpine tour"),
+        slug: String::from("alpine-tour"),
+        year: 2025,
+        description: None,
+        notes: vec![],
+        ride_ids: vec![removed.id, kept.id],
+        media_ids: vec![],
+        is_published: true,
+    };
+    repos.trip_repo.put(trip.clone()).await.unwrap();
+
+    let trip_distance = |profile: &[_]| match profile.last() {
+        Some(DistanceElevation(distance, _)) => *distance,
+        None => 0.0,
+    };
+
+    // Cache both rides' points and the trip's profile over both of them
+    let profile = fetchers
+        .trip_elevation_points
+        .fetch(trip.id, ElevationPointsParams::default())
+        .await
+        .unwrap();
+    fetchers
+        .ride_points
+        .fetch(removed.id, DetailLevel::Low)
+        .await
+        .unwrap();
+
+    remove_item(params_with_fetchers(
+        &repos,
+        &fetchers,
+        RwgpsId::Trip(42),
+        user_id,
+        RemovalPolicy::Delete,
+    ))
+    .await
+    .unwrap()
+    .unwrap();
+
+    // The deleted ride's points aren't served from the cache
+    assert!(fetchers
+        .ride_points
+        .fetch(removed.id, DetailLevel::Low)
+        .await
+        .is_err());
+
+    // The trip's profile only covers the ride left in it
+    let remaining = fetchers
+        .trip_elevation_points
+        .fetch(trip.id, ElevationPointsParams::default())
+        .await
+        .unwrap();
+    assert!(trip_distance(&remaining) < trip_distance(&profile));
+}
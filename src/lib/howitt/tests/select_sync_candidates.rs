@@ -0,0 +1,231 @@
+use async_trait::async_trait;
+use chrono::{DateTime, TimeZone, Utc};
+use howitt::models::external_ref::{ExternalId, ExternalProvider, ExternalRef, RwgpsId};
+use howitt::models::ride::{Ride, RideId};
+use howitt::models::route::{Route, RouteId};
+use howitt::models::sync_run::{SyncCursor, SyncRun};
+use howitt::models::user::UserId;
+use howitt::repos::Repos;
+use howitt::services::sync::provider::{ExternalItem, SyncProvider, WebhookEvent};
+use howitt::services::sync::select_sync_candidates::{
+    select_sync_candidates, SelectSyncCandidatesParams, SyncCandidates,
+};
+use howitt_memory::MemoryRepos;
+
+/// Lists a fixed set of refs, as RWGPS would
+struct StubProvider {
+    external_refs: Vec<ExternalRef>,
+}
+
+#[async_trait]
+impl SyncProvider for StubProvider {
+    type Connection = ();
+    type Item = ();
+
+    const PROVIDER: ExternalProvider = ExternalProvider::Rwgps;
+    const SYNC_VERSION: usize = 2;
+
+    async fn list(&self, _connection: &()) -> Result<Vec<ExternalRef>, anyhow::Error> {
+        Ok(self.external_refs.clone())
+    }
+
+    async fn fetch(&self, _connection: &(), id: &ExternalId) -> Result<(), anyhow::Error> {
+        Err(anyhow::anyhow!("{id:?} can't be fetched"))
+    }
+
+    fn convert(&self, _item: &()) -> Result<ExternalItem, anyhow::Error> {
+        Err(anyhow::anyhow!("Nothing to convert"))
+    }
+
+    fn parse_webhook(&self, _body: &[u8]) -> Result<Vec<WebhookEvent>, anyhow::Error> {
+        Ok(vec![])
+    }
+}
+
+fn day(day: u32) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2025, 3, day, 0, 0, 0).unwrap()
+}
+
+fn external_ref(rwgps_id: RwgpsId, updated_at: DateTime<Utc>, sync_version: usize) -> ExternalRef {
+    ExternalRef {
+        id: ExternalId::Rwgps(rwgps_id),
+        updated_at,
+        sync_version: Some(sync_version),
+    }
+}
+
+fn cursor(updated_at: DateTime<Utc>, sync_version: usize) -> SyncCursor {
+    SyncCursor {
+        updated_at,
+        sync_version,
+    }
+}
+
+/// A ride for the first trip and a route for the second, both synced on the
+/// second day. The route was synced by an older version.
+async fn synced_repos(user_id: UserId) -> Repos {
+    let repos = Repos::from(MemoryRepos::default());
+
+    repos
+        .ride_repo
+        .put(Ride {
+            id: RideId::new(),
+            name: String::from("Day 1"),
+            user_id,
+            distance: 80_000.0,
+            started_at: day(1),
+            finished_at: day(1) + chrono::TimeDelta::hours(6),
+            external_ref: Some(external_ref(RwgpsId::Trip(1), day(2), 2)),
+            archived_at: None,
+        })
+        .await
+        .unwrap();
+
+    repos
+        .route_repo
+        .put(Route {
+            id: RouteId::new(),
+            name: String::from("Mt Stirling loop"),
+            slug: String::from("mt-stirling-loop"),
+            user_id,
+            distance: 45_000.0,
+            sample_points: None,
+            description: None,
+            external_ref: Some(external_ref(RwgpsId::Route(2), day(2), 1)),
+            tags: Default::default(),
+            archived_at: None,
+        })
+        .await
+        .unwrap();
+
+    repos
+}
+
+async fn select(
+    repos: &Repos,
+    user_id: UserId,
+    external_refs: Vec<ExternalRef>,
+    since: Option<SyncCursor>,
+) -> SyncCandidates {
+    select_sync_candidates(SelectSyncCandidatesParams {
+        provider: &StubProvider { external_refs },
+        connection: &(),
+        user_id,
+        since,
+        route_repo: repos.route_repo.clone(),
+        ride_repo: repos.ride_repo.clone(),
+    })
+    .await
+    .unwrap()
+}
+
+#[tokio::test]
+async fn test_candidates_since_cursor() {
+    let user_id = UserId::new();
+    let repos = synced_repos(user_id).await;
+    let since = cursor(day(2), 2);
+
+    let candidates = select(
+        &repos,
+        user_id,
+        vec![
+            external_ref(RwgpsId::Trip(1), day(2), 2),
+            external_ref(RwgpsId::Route(2), day(2), 2),
+            external_ref(RwgpsId::Trip(3), day(4), 2),
+            external_ref(RwgpsId::Route(4), day(3), 2),
+        ],
+        Some(since),
+    )
+    .await;
+
+    // Only what changed after the cursor, without looking at what's stored
+    assert_eq!(
+        candidates.ids,
+        vec![
+            ExternalId::Rwgps(RwgpsId::Trip(3)),
+            ExternalId::Rwgps(RwgpsId::Route(4)),
+        ]
+    );
+    assert_eq!(candidates.since, Some(since));
+    assert_eq!(candidates.cursor, Some(cursor(day(4), 2)));
+
+    // Nothing changed, so the cursor stays put
+    let candidates = select(
+        &repos,
+        user_id,
+        vec![external_ref(RwgpsId::Trip(1), day(2), 2)],
+        Some(since),
+    )
+    .await;
+
+    assert!(candidates.ids.is_empty());
+    assert_eq!(candidates.cursor, Some(since));
+}
+
+#[tokio::test]
+async fn test_stale_cursor_compares_everything() {
+    let user_id = UserId::new();
+    let repos = synced_repos(user_id).await;
+
+    let candidates = select(
+        &repos,
+        user_id,
+        vec![
+            external_ref(RwgpsId::Trip(1), day(2), 2),
+            external_ref(RwgpsId::Route(2), day(2), 2),
+            external_ref(RwgpsId::Trip(3), day(4), 2),
+        ],
+        Some(cursor(day(3), 1)),
+    )
+    .await;
+
+    // The ride is up to date, the route was synced by an older version
+    assert_eq!(
+        candidates.ids,
+        vec![
+            ExternalId::Rwgps(RwgpsId::Route(2)),
+            ExternalId::Rwgps(RwgpsId::Trip(3)),
+        ]
+    );
+    assert_eq!(candidates.since, None);
+    assert_eq!(candidates.cursor, Some(cursor(day(4), 2)));
+}
+
+#[tokio::test]
+async fn test_failed_item_is_selected_again() {
+    let user_id = UserId::new();
+    let repos = synced_repos(user_id).await;
+    let since = cursor(day(2), 2);
+    let external_refs = vec![
+        external_ref(RwgpsId::Trip(1), day(2), 2),
+        external_ref(RwgpsId::Trip(3), day(3), 2),
+        external_ref(RwgpsId::Trip(5), day(4), 2),
+    ];
+
+    let candidates = select(&repos, user_id, external_refs.clone(), Some(since)).await;
+    assert_eq!(candidates.ids.len(), 2);
+
+    // One item syncs and the other fails, which holds the cursor back
+    let mut run = SyncRun::new(
+        user_id,
+        ExternalProvider::Rwgps,
+        candidates.since,
+        candidates.cursor,
+        candidates.ids.len(),
+        day(5),
+    );
+    run.record(true, day(5));
+    run.record(false, day(5));
+    assert_eq!(run.completed_cursor(), None);
+
+    // So the next sync starts from the same place, and picks up both again
+    let candidates = select(&repos, user_id, external_refs, Some(since)).await;
+    assert_eq!(
+        candidates.ids,
+        vec![
+            ExternalId::Rwgps(RwgpsId::Trip(3)),
+            ExternalId::Rwgps(RwgpsId::Trip(5)),
+        ]
+    );
+    assert_eq!(candidates.cursor, Some(cursor(day(4), 2)));
+}
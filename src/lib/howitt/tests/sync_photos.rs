@@ -0,0 +1,94 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use chrono::{TimeZone, Utc};
+use howitt::models::media::{MediaFilter, MediaRelationId};
+use howitt::models::ride::RideId;
+use howitt::models::user::UserId;
+use howitt::repos::Repos;
+use howitt::services::sync::rwgps_v2::sync_photos::{sync_photos, SyncPhotosParams};
+use howitt_memory::{MemoryBucketClient, MemoryRepos, StubHttpClient};
+
+fn photo(id: usize, lng: serde_json::Value, lat: serde_json::Value) -> rwgps_types::Photo {
+    rwgps_types::Photo {
+        id,
+        lng,
+        lat,
+        captured_at: Some(Utc.with_ymd_and_hms(2025, 1, 4, 10, 0, 0).unwrap()),
+        updated_at: Utc.with_ymd_and_hms(2025, 1, 5, 0, 0, 0).unwrap(),
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn test_sync_photos_is_idempotent() {
+    let repos = Repos::from(MemoryRepos::default());
+    let http_client = Arc::new(StubHttpClient::new(Bytes::from_static(b"jpeg")));
+    let bucket_client = Arc::new(MemoryBucketClient::new());
+    let user_id = UserId::new();
+
+    let photos = vec![
+        photo(101, serde_json::json!(146.94), serde_json::json!(-36.89)),
+        // Older photos have their coordinates as strings
+        photo(
+            102,
+            serde_json::json!("147.28"),
+            serde_json::json!("-36.86"),
+        ),
+        photo(103, serde_json::Value::Null, serde_json::Value::Null),
+    ];
+
+    let sync = |ride_id: RideId| {
+        sync_photos(SyncPhotosParams {
+            http_client: http_client.clone(),
+            bucket_client: bucket_client.clone(),
+            media_repo: repos.media_repo.clone(),
+            photos: photos.clone(),
+            ride_id,
+            user_id,
+        })
+    };
+
+    let ride_id = RideId::new();
+    let imported = sync(ride_id).await.unwrap();
+
+    assert_eq!(imported.len(), 3);
+    assert_eq!(imported[0].point, Some(geo::Point::new(146.94, -36.89)));
+    assert_eq!(imported[1].point, Some(geo::Point::new(147.28, -36.86)));
+    assert_eq!(imported[2].point, None);
+    assert!(imported
+        .iter()
+        .all(|media| { media.user_id == user_id && bucket_client.contains_key(&media.path) }));
+    assert_eq!(
+        http_client.urls()[0],
+        "https://ridewithgps.com/photos/101/large.jpg"
+    );
+
+    // Syncing the trip again downloads nothing new
+    let imported = sync(ride_id).await.unwrap();
+    assert!(imported.is_empty());
+    assert_eq!(http_client.urls().len(), 3);
+
+    let media = repos
+        .media_repo
+        .filter_models(MediaFilter::ForRide(ride_id))
+        .await
+        .unwrap();
+    assert_eq!(media.len(), 3);
+
+    // Photos imported before are linked to whichever ride the trip matches
+    let other_ride_id = RideId::new();
+    let imported = sync(other_ride_id).await.unwrap();
+    assert!(imported.is_empty());
+
+    let media = repos
+        .media_repo
+        .filter_models(MediaFilter::RwgpsPhotoIds(vec![101]))
+        .await
+        .unwrap();
+    assert_eq!(media.len(), 1);
+    assert!(matches!(
+        media[0].relation_ids.as_slice(),
+        [MediaRelationId::Ride(_), MediaRelationId::Ride(_)]
+    ));
+}
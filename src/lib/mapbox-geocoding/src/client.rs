@@ -4,22 +4,33 @@ use url::Url;
 
 const BASE_URL: &str = "https://api.mapbox.com/search/geocode/v6/";
 
+#[derive(Debug, Clone)]
 pub struct MapboxGeocodingClient {
     client: ReqwestClient,
-    _access_token: String,
+    access_token: String,
     base_url: Url,
 }
 
 impl MapboxGeocodingClient {
     pub fn new(access_token: String) -> Self {
         let base_url = Url::parse(BASE_URL).expect("Failed to parse base URL");
+        Self::with_base_url(access_token, base_url)
+    }
+
+    /// Points the client somewhere other than the Mapbox API, such as a stub
+    /// server in tests. `base_url` needs its trailing slash.
+    pub fn with_base_url(access_token: String, base_url: Url) -> Self {
         Self {
             client: ReqwestClient::new(),
-            _access_token: access_token,
+            access_token,
             base_url,
         }
     }
 
+    pub fn access_token(&self) -> &str {
+        &self.access_token
+    }
+
     async fn make_request<T: serde::Serialize, R: serde::de::DeserializeOwned>(
         &self,
         path: &str,
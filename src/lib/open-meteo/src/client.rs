@@ -1,9 +1,49 @@
 use crate::schema::*;
 use reqwest::{Client as ReqwestClient, Result as ReqwestResult};
+use serde::Deserialize;
 use url::Url;
 
 const BASE_URL: &str = "https://archive-api.open-meteo.com";
+const API_BASE_URL: &str = "https://api.open-meteo.com";
 
+async fn make_request<T: serde::Serialize, R: serde::de::DeserializeOwned>(
+    client: &ReqwestClient,
+    base_url: &Url,
+    path: &str,
+    params: &T,
+) -> ReqwestResult<R> {
+    let mut url = base_url.join(path).expect("Failed to join URL path");
+
+    let query = serde_urlencoded::to_string(params).expect("Failed to serialize parameters");
+    url.set_query(Some(&query));
+
+    client
+        .get(url)
+        .send()
+        .await?
+        .error_for_status()?
+        .json::<R>()
+        .await
+}
+
+/// A single coordinate is answered with an object, several with an array.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum OneOrMany<T> {
+    One(T),
+    Many(Vec<T>),
+}
+
+impl<T> From<OneOrMany<T>> for Vec<T> {
+    fn from(value: OneOrMany<T>) -> Self {
+        match value {
+            OneOrMany::One(value) => vec![value],
+            OneOrMany::Many(values) => values,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
 pub struct OpenMeteoHistoryClient {
     client: ReqwestClient,
     base_url: Url,
@@ -18,35 +58,91 @@ impl OpenMeteoHistoryClient {
         }
     }
 
-    async fn make_request<T: serde::Serialize, R: serde::de::DeserializeOwned>(
+    /// One response per coordinate in `params`, in the same order.
+    pub async fn get_historical_weather(
         &self,
-        path: &str,
-        params: &T,
-    ) -> ReqwestResult<R> {
-        let mut url = self.base_url.join(path).expect("Failed to join URL path");
-
-        let query = serde_urlencoded::to_string(params).expect("Failed to serialize parameters");
-        url.set_query(Some(&query));
-
-        self.client
-            .get(url)
-            .send()
-            .await?
-            .error_for_status()?
-            .json::<R>()
+        params: HistoricalWeatherParams,
+    ) -> ReqwestResult<Vec<HistoricalWeatherResponse>> {
+        make_request::<_, OneOrMany<_>>(&self.client, &self.base_url, "/v1/archive", &params)
             .await
+            .map(Vec::from)
     }
+}
 
-    pub async fn get_historical_weather(
+impl Default for OpenMeteoHistoryClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Client for the non-archive endpoints on api.open-meteo.com.
+#[derive(Debug, Clone)]
+pub struct OpenMeteoClient {
+    client: ReqwestClient,
+    base_url: Url,
+}
+
+impl OpenMeteoClient {
+    pub fn new() -> Self {
+        Self::with_base_url(Url::parse(API_BASE_URL).expect("Failed to parse base URL"))
+    }
+
+    pub fn with_base_url(base_url: Url) -> Self {
+        Self {
+            client: ReqwestClient::new(),
+            base_url,
+        }
+    }
+
+    pub async fn get_elevation(&self, params: ElevationParams) -> ReqwestResult<ElevationResponse> {
+        make_request(&self.client, &self.base_url, "/v1/elevation", &params).await
+    }
+
+    /// One response per coordinate in `params`, in the same order.
+    pub async fn get_forecast(
         &self,
-        params: HistoricalWeatherParams,
-    ) -> ReqwestResult<HistoricalWeatherResponse> {
-        self.make_request("/v1/archive", &params).await
+        params: ForecastParams,
+    ) -> ReqwestResult<Vec<ForecastResponse>> {
+        make_request::<_, OneOrMany<_>>(&self.client, &self.base_url, "/v1/forecast", &params)
+            .await
+            .map(Vec::from)
     }
 }
 
-impl Default for OpenMeteoHistoryClient {
+impl Default for OpenMeteoClient {
     fn default() -> Self {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn response(latitude: f64) -> serde_json::Value {
+        serde_json::json!({
+            "latitude": latitude,
+            "longitude": 146.6,
+            "elevation": 1200.0,
+            "generationtime_ms": 0.1,
+            "utc_offset_seconds": 0,
+        })
+    }
+
+    #[test]
+    fn test_one_or_many_responses() {
+        let one: OneOrMany<ForecastResponse> = serde_json::from_value(response(-37.0)).unwrap();
+        let many: OneOrMany<ForecastResponse> =
+            serde_json::from_value(serde_json::json!([response(-37.0), response(-37.1)])).unwrap();
+
+        let latitudes = |responses: Vec<ForecastResponse>| {
+            responses
+                .into_iter()
+                .map(|response| response.latitude)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(latitudes(Vec::from(one)), vec![-37.0]);
+        assert_eq!(latitudes(Vec::from(many)), vec![-37.0, -37.1]);
+    }
+}
@@ -2,6 +2,8 @@ use serde::{Deserialize, Serialize};
 use serde_with::{formats::CommaSeparator, serde_as, StringWithSeparator};
 use std::{collections::HashMap, str::FromStr};
 
+/// Several coordinates can be looked up over the same dates in a single
+/// request, which answers with one response per coordinate.
 #[serde_as]
 #[derive(Debug, Serialize, Deserialize)]
 pub struct HistoricalWeatherParams {
@@ -11,8 +13,10 @@ pub struct HistoricalWeatherParams {
     pub hourly: Option<Vec<HourlyVariable>>,
     #[serde_as(as = "Option<StringWithSeparator::<CommaSeparator, DailyVariable>>")]
     pub daily: Option<Vec<DailyVariable>>,
-    pub latitude: f64,
-    pub longitude: f64,
+    #[serde_as(as = "StringWithSeparator::<CommaSeparator, f64>")]
+    pub latitude: Vec<f64>,
+    #[serde_as(as = "StringWithSeparator::<CommaSeparator, f64>")]
+    pub longitude: Vec<f64>,
     pub temperature_unit: Option<TemperatureUnit>,
     pub wind_speed_unit: Option<WindSpeedUnit>,
     pub timeformat: Option<TimeFormat>,
@@ -190,41 +194,91 @@ pub struct HistoricalWeatherResponse {
     pub daily_units: Option<HashMap<String, String>>,
 }
 
+/// Values are null for hours the archive doesn't have yet, which is roughly
+/// the last five days, and for hours past the end of a forecast.
 #[derive(Debug, Serialize, Deserialize)]
 pub struct HourlyData {
     pub time: Vec<String>,
-    pub temperature_2m: Option<Vec<f64>>,
-    pub relative_humidity_2m: Option<Vec<f64>>,
-    pub dew_point_2m: Option<Vec<f64>>,
-    pub apparent_temperature: Option<Vec<f64>>,
-    pub pressure_msl: Option<Vec<f64>>,
-    pub cloud_cover: Option<Vec<f64>>,
-    pub cloud_cover_low: Option<Vec<f64>>,
-    pub cloud_cover_mid: Option<Vec<f64>>,
-    pub cloud_cover_high: Option<Vec<f64>>,
-    pub wind_speed_10m: Option<Vec<f64>>,
-    pub wind_speed_100m: Option<Vec<f64>>,
-    pub wind_direction_10m: Option<Vec<f64>>,
-    pub wind_direction_100m: Option<Vec<f64>>,
-    pub wind_gusts_10m: Option<Vec<f64>>,
-    pub shortwave_radiation: Option<Vec<f64>>,
-    pub direct_radiation: Option<Vec<f64>>,
-    pub direct_normal_irradiance: Option<Vec<f64>>,
-    pub diffuse_radiation: Option<Vec<f64>>,
-    pub vapour_pressure_deficit: Option<Vec<f64>>,
-    pub et0_fao_evapotranspiration: Option<Vec<f64>>,
-    pub precipitation: Option<Vec<f64>>,
-    pub rain: Option<Vec<f64>>,
-    pub weather_code: Option<Vec<i32>>,
-    pub snowfall: Option<Vec<f64>>,
-    pub soil_temperature_0_to_7cm: Option<Vec<f64>>,
-    pub soil_temperature_7_to_28cm: Option<Vec<f64>>,
-    pub soil_temperature_28_to_100cm: Option<Vec<f64>>,
-    pub soil_temperature_100_to_255cm: Option<Vec<f64>>,
-    pub soil_moisture_0_to_7cm: Option<Vec<f64>>,
-    pub soil_moisture_7_to_28cm: Option<Vec<f64>>,
-    pub soil_moisture_28_to_100cm: Option<Vec<f64>>,
-    pub soil_moisture_100_to_255cm: Option<Vec<f64>>,
+    pub temperature_2m: Option<Vec<Option<f64>>>,
+    pub relative_humidity_2m: Option<Vec<Option<f64>>>,
+    pub dew_point_2m: Option<Vec<Option<f64>>>,
+    pub apparent_temperature: Option<Vec<Option<f64>>>,
+    pub pressure_msl: Option<Vec<Option<f64>>>,
+    pub cloud_cover: Option<Vec<Option<f64>>>,
+    pub cloud_cover_low: Option<Vec<Option<f64>>>,
+    pub cloud_cover_mid: Option<Vec<Option<f64>>>,
+    pub cloud_cover_high: Option<Vec<Option<f64>>>,
+    pub wind_speed_10m: Option<Vec<Option<f64>>>,
+    pub wind_speed_100m: Option<Vec<Option<f64>>>,
+    pub wind_direction_10m: Option<Vec<Option<f64>>>,
+    pub wind_direction_100m: Option<Vec<Option<f64>>>,
+    pub wind_gusts_10m: Option<Vec<Option<f64>>>,
+    pub shortwave_radiation: Option<Vec<Option<f64>>>,
+    pub direct_radiation: Option<Vec<Option<f64>>>,
+    pub direct_normal_irradiance: Option<Vec<Option<f64>>>,
+    pub diffuse_radiation: Option<Vec<Option<f64>>>,
+    pub vapour_pressure_deficit: Option<Vec<Option<f64>>>,
+    pub et0_fao_evapotranspiration: Option<Vec<Option<f64>>>,
+    pub precipitation: Option<Vec<Option<f64>>>,
+    pub rain: Option<Vec<Option<f64>>>,
+    pub weather_code: Option<Vec<Option<i32>>>,
+    pub snowfall: Option<Vec<Option<f64>>>,
+    pub soil_temperature_0_to_7cm: Option<Vec<Option<f64>>>,
+    pub soil_temperature_7_to_28cm: Option<Vec<Option<f64>>>,
+    pub soil_temperature_28_to_100cm: Option<Vec<Option<f64>>>,
+    pub soil_temperature_100_to_255cm: Option<Vec<Option<f64>>>,
+    pub soil_moisture_0_to_7cm: Option<Vec<Option<f64>>>,
+    pub soil_moisture_7_to_28cm: Option<Vec<Option<f64>>>,
+    pub soil_moisture_28_to_100cm: Option<Vec<Option<f64>>>,
+    pub soil_moisture_100_to_255cm: Option<Vec<Option<f64>>>,
+}
+
+/// How hours are formatted in requests and responses, e.g. `2025-02-12T09:00`.
+pub const HOUR_FORMAT: &str = "%Y-%m-%dT%H:%M";
+
+impl HourlyData {
+    /// Position of `time` (as formatted in the response, e.g. `2025-02-12T09:00`)
+    /// in each of the hourly series.
+    pub fn index_of(&self, time: &str) -> Option<usize> {
+        self.time.iter().position(|t| t == time)
+    }
+
+    /// The observation at `time`, if every variable in it was requested and
+    /// has a value for that hour.
+    pub fn observation(&self, time: &str) -> Option<HourlyObservation> {
+        let index = self.index_of(time)?;
+
+        let value = |values: &Option<Vec<Option<f64>>>| values.as_ref()?.get(index).copied()?;
+
+        Some(HourlyObservation {
+            temperature: value(&self.temperature_2m)?,
+            precipitation: value(&self.precipitation)?,
+            wind_speed: value(&self.wind_speed_10m)?,
+            wind_direction: value(&self.wind_direction_10m)?,
+        })
+    }
+}
+
+/// The weather at a place for an hour, as used for ride weather and route
+/// forecasts.
+#[derive(Debug, PartialEq, Clone)]
+pub struct HourlyObservation {
+    pub temperature: f64,
+    pub precipitation: f64,
+    pub wind_speed: f64,
+    pub wind_direction: f64,
+}
+
+impl HourlyObservation {
+    /// The hourly variables to request for [`HourlyData::observation`].
+    pub fn variables() -> Vec<HourlyVariable> {
+        vec![
+            HourlyVariable::Temperature2m,
+            HourlyVariable::Precipitation,
+            HourlyVariable::WindSpeed10m,
+            HourlyVariable::WindDirection10m,
+        ]
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -251,3 +305,86 @@ pub struct ErrorResponse {
     pub error: bool,
     pub reason: String,
 }
+
+/// The forecast only reaches this many days ahead.
+pub const MAX_FORECAST_DAYS: i64 = 16;
+
+/// Several coordinates can be looked up over the same hours in a single
+/// request, which answers with one response per coordinate. The hours can
+/// reach back a few months, covering the days the archive doesn't have yet.
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ForecastParams {
+    #[serde_as(as = "StringWithSeparator::<CommaSeparator, f64>")]
+    pub latitude: Vec<f64>,
+    #[serde_as(as = "StringWithSeparator::<CommaSeparator, f64>")]
+    pub longitude: Vec<f64>,
+    #[serde_as(as = "Option<StringWithSeparator::<CommaSeparator, HourlyVariable>>")]
+    pub hourly: Option<Vec<HourlyVariable>>,
+    #[serde_as(as = "Option<StringWithSeparator::<CommaSeparator, DailyVariable>>")]
+    pub daily: Option<Vec<DailyVariable>>,
+    pub start_hour: Option<String>, // ISO8601 date and hour, e.g. 2025-02-12T09:00
+    pub end_hour: Option<String>,   // ISO8601 date and hour, e.g. 2025-02-12T09:00
+    pub forecast_days: Option<i64>,
+    pub temperature_unit: Option<TemperatureUnit>,
+    pub wind_speed_unit: Option<WindSpeedUnit>,
+    pub timeformat: Option<TimeFormat>,
+    pub timezone: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ForecastResponse {
+    pub latitude: f64,
+    pub longitude: f64,
+    pub elevation: f64,
+    pub generationtime_ms: f64,
+    pub utc_offset_seconds: i32,
+    pub hourly: Option<HourlyData>,
+    pub hourly_units: Option<HashMap<String, String>>,
+    pub daily: Option<DailyData>,
+    pub daily_units: Option<HashMap<String, String>>,
+}
+
+/// Up to 100 coordinates can be looked up in a single request.
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ElevationParams {
+    #[serde_as(as = "StringWithSeparator::<CommaSeparator, f64>")]
+    pub latitude: Vec<f64>,
+    #[serde_as(as = "StringWithSeparator::<CommaSeparator, f64>")]
+    pub longitude: Vec<f64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ElevationResponse {
+    pub elevation: Vec<f64>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hourly_observation() {
+        let hourly: HourlyData = serde_json::from_value(serde_json::json!({
+            "time": ["2025-02-12T09:00", "2025-02-12T10:00"],
+            "temperature_2m": [18.5, null],
+            "precipitation": [0.2, 0.0],
+            "wind_speed_10m": [12.0, 14.0],
+            "wind_direction_10m": [270.0, 280.0],
+        }))
+        .unwrap();
+
+        assert_eq!(
+            hourly.observation("2025-02-12T09:00"),
+            Some(HourlyObservation {
+                temperature: 18.5,
+                precipitation: 0.2,
+                wind_speed: 12.0,
+                wind_direction: 270.0,
+            })
+        );
+        assert_eq!(hourly.observation("2025-02-12T10:00"), None);
+        assert_eq!(hourly.observation("2025-02-12T11:00"), None);
+    }
+}
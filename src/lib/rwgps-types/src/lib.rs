@@ -288,7 +288,7 @@ pub struct Trip {
     pub live_logging: bool,
     pub live_log: Value,
     pub rememberable: bool,
-    pub photos: Vec<Value>,
+    pub photos: Vec<Photo>,
     pub track_points: Vec<TrackPoint>,
     pub course_points: Vec<Value>,
     pub points_of_interest: Vec<Value>,
@@ -490,6 +490,16 @@ pub struct CoursePoint {
     pub lat: f64,
 }
 
+/// A POI placed on a route in the RWGPS route planner.
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RoutePointOfInterest {
+    pub lat: f64,
+    pub lng: f64,
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub poi_type_name: Option<String>,
+}
+
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Gear {
     pub id: usize,
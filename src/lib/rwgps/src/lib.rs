@@ -12,11 +12,14 @@ use reqwest_ext::{ResponseExt, SerdeDebugError};
 use tokio::sync::{Semaphore, SemaphorePermit};
 
 #[derive(Error, Debug)]
-#[error("RWGPS API Error {:?}", _0)]
+#[error("RWGPS API Error {:?}", self)]
 pub enum RwgpsError {
     Reqwest(#[from] reqwest::Error),
     Url(#[from] url::ParseError),
     SerdeDebug(#[from] SerdeDebugError),
+    /// The token was revoked or has expired
+    Unauthorised,
+    RateLimited,
 }
 
 #[derive(Clone)]
@@ -72,9 +75,13 @@ impl RwgpsClient {
             };
         }
 
-        let response = request.send().await?.json_debug().await?;
+        let response = request.send().await?;
 
-        Ok(response)
+        match response.status() {
+            reqwest::StatusCode::UNAUTHORIZED => Err(RwgpsError::Unauthorised),
+            reqwest::StatusCode::TOO_MANY_REQUESTS => Err(RwgpsError::RateLimited),
+            _ => Ok(response.json_debug().await?),
+        }
     }
 }
 
@@ -0,0 +1,28 @@
+use std::error::Error;
+
+use async_trait::async_trait;
+
+use crate::*;
+
+pub trait StravaClient: Clone {
+    type Error: Error + Send + Sync + 'static;
+    type AuthenticatedClient: AuthenticatedStravaClient<Error = Self::Error>;
+
+    fn with_access_token(&self, access_token: String) -> Self::AuthenticatedClient;
+}
+
+#[async_trait]
+pub trait AuthenticatedStravaClient: Clone + Send + Sync {
+    type Error: Error + Send + Sync + 'static;
+
+    /// The athlete's activities, newest first. Pages start at 1.
+    async fn athlete_activities(
+        &self,
+        page: usize,
+        per_page: usize,
+    ) -> Result<Vec<SummaryActivity>, Self::Error>;
+
+    async fn activity(&self, activity_id: usize) -> Result<DetailedActivity, Self::Error>;
+
+    async fn activity_streams(&self, activity_id: usize) -> Result<StreamSet, Self::Error>;
+}
@@ -0,0 +1,61 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+pub mod client;
+pub mod webhook;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MetaAthlete {
+    pub id: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SummaryActivity {
+    pub id: usize,
+    pub name: String,
+    pub athlete: MetaAthlete,
+    /// Metres
+    pub distance: f64,
+    /// Seconds
+    pub moving_time: i64,
+    /// Seconds
+    pub elapsed_time: i64,
+    pub sport_type: String,
+    pub start_date: DateTime<Utc>,
+    /// `[lat, lng]`, empty for activities recorded without GPS
+    #[serde(default)]
+    pub start_latlng: Vec<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DetailedActivity {
+    pub id: usize,
+    pub name: String,
+    pub athlete: MetaAthlete,
+    pub description: Option<String>,
+    /// Metres
+    pub distance: f64,
+    /// Seconds
+    pub moving_time: i64,
+    /// Seconds
+    pub elapsed_time: i64,
+    pub sport_type: String,
+    pub start_date: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Stream<T> {
+    pub data: Vec<T>,
+}
+
+/// Streams for an activity, as returned with `key_by_type=true`. A stream is
+/// missing when the device didn't record it.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct StreamSet {
+    /// `[lat, lng]` pairs
+    pub latlng: Option<Stream<[f64; 2]>>,
+    /// Metres
+    pub altitude: Option<Stream<f64>>,
+    /// Seconds since the activity started
+    pub time: Option<Stream<i64>>,
+}
@@ -0,0 +1,31 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ObjectType {
+    Activity,
+    Athlete,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AspectType {
+    Create,
+    Update,
+    Delete,
+}
+
+/// A push subscription event. Strava sends one event per request.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct StravaWebhookEvent {
+    pub object_type: ObjectType,
+    pub object_id: usize,
+    pub aspect_type: AspectType,
+    /// The athlete the object belongs to
+    pub owner_id: usize,
+    pub subscription_id: usize,
+    /// Unix timestamp
+    pub event_time: i64,
+    #[serde(default)]
+    pub updates: serde_json::Map<String, serde_json::Value>,
+}
@@ -0,0 +1,133 @@
+use reqwest::Url;
+use strava_types::{DetailedActivity, StreamSet, SummaryActivity};
+use thiserror::Error;
+
+const BASE_URL: &str = "https://www.strava.com/api/v3/";
+
+#[derive(Error, Debug)]
+#[error("Strava API Error {:?}", _0)]
+pub enum StravaError {
+    Reqwest(#[from] reqwest::Error),
+    Url(#[from] url::ParseError),
+}
+
+#[derive(Debug, Clone)]
+pub struct StravaClient {
+    client: reqwest::Client,
+    base_url: Url,
+}
+
+impl StravaClient {
+    pub fn new() -> StravaClient {
+        StravaClient::with_base_url(Url::parse(BASE_URL).unwrap())
+    }
+
+    /// Points the client somewhere other than the Strava API, such as a stub
+    /// server in tests. `base_url` needs its trailing slash.
+    pub fn with_base_url(base_url: Url) -> StravaClient {
+        StravaClient {
+            client: reqwest::Client::new(),
+            base_url,
+        }
+    }
+
+    async fn make_request<T: serde::Serialize, R: serde::de::DeserializeOwned>(
+        &self,
+        path: &str,
+        params: Option<&T>,
+        access_token: &str,
+    ) -> Result<R, StravaError> {
+        let url = self.base_url.join(path)?;
+
+        let mut request = self.client.get(url).bearer_auth(access_token);
+
+        if let Some(params) = params {
+            request = request.query(params);
+        }
+
+        let response = request.send().await?.error_for_status()?.json().await?;
+
+        Ok(response)
+    }
+}
+
+impl Default for StravaClient {
+    fn default() -> Self {
+        StravaClient::new()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct AuthenticatedStravaClient {
+    client: StravaClient,
+    access_token: String,
+}
+
+impl AuthenticatedStravaClient {
+    pub async fn athlete_activities(
+        &self,
+        page: usize,
+        per_page: usize,
+    ) -> Result<Vec<SummaryActivity>, StravaError> {
+        self.client
+            .make_request(
+                "athlete/activities",
+                Some(&[("page", page), ("per_page", per_page)]),
+                &self.access_token,
+            )
+            .await
+    }
+
+    pub async fn activity(&self, activity_id: usize) -> Result<DetailedActivity, StravaError> {
+        self.client
+            .make_request(
+                &format!("activities/{activity_id}"),
+                None::<&()>,
+                &self.access_token,
+            )
+            .await
+    }
+
+    pub async fn activity_streams(&self, activity_id: usize) -> Result<StreamSet, StravaError> {
+        self.client
+            .make_request(
+                &format!("activities/{activity_id}/streams"),
+                Some(&[("keys", "latlng,altitude,time"), ("key_by_type", "true")]),
+                &self.access_token,
+            )
+            .await
+    }
+}
+
+impl strava_types::client::StravaClient for StravaClient {
+    type Error = StravaError;
+    type AuthenticatedClient = AuthenticatedStravaClient;
+
+    fn with_access_token(&self, access_token: String) -> Self::AuthenticatedClient {
+        AuthenticatedStravaClient {
+            client: self.clone(),
+            access_token,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl strava_types::client::AuthenticatedStravaClient for AuthenticatedStravaClient {
+    type Error = StravaError;
+
+    async fn athlete_activities(
+        &self,
+        page: usize,
+        per_page: usize,
+    ) -> Result<Vec<SummaryActivity>, StravaError> {
+        self.athlete_activities(page, per_page).await
+    }
+
+    async fn activity(&self, activity_id: usize) -> Result<DetailedActivity, StravaError> {
+        self.activity(activity_id).await
+    }
+
+    async fn activity_streams(&self, activity_id: usize) -> Result<StreamSet, StravaError> {
+        self.activity_streams(activity_id).await
+    }
+}
@@ -0,0 +1,287 @@
+use std::sync::{Arc, Mutex};
+
+use chrono::{TimeDelta, TimeZone, Utc};
+use howitt::models::external_ref::{ExternalId, ExternalRef, RwgpsId, StravaId};
+use howitt::models::ride::{Ride, RideId};
+use howitt::models::user::UserId;
+use howitt::repos::Repos;
+use howitt::services::sync::provider::{SyncProvider, WebhookAction, WebhookEvent};
+use howitt::services::sync::select_sync_candidates::{
+    select_sync_candidates, SelectSyncCandidatesParams,
+};
+use howitt::services::sync::strava::provider::{StravaConnection, StravaProvider};
+use howitt::services::sync::sync_item::{sync_external_ride, SyncExternalRideParams};
+use howitt_memory::{serve_http_stub, MemoryRepos};
+use strava::StravaClient;
+
+const ACTIVITY_ID: usize = 12345678987;
+
+const ACTIVITIES: &str = r#"[
+    {
+        "id": 12345678987,
+        "name": "Morning Ride",
+        "athlete": { "id": 134815 },
+        "distance": 2000.0,
+        "moving_time": 420,
+        "elapsed_time": 600,
+        "sport_type": "GravelRide",
+        "start_date": "2025-02-12T08:00:00Z",
+        "start_latlng": [-36.8917, 146.9397]
+    },
+    {
+        "id": 12345678988,
+        "name": "Trainer",
+        "athlete": { "id": 134815 },
+        "distance": 20000.0,
+        "moving_time": 3600,
+        "elapsed_time": 3600,
+        "sport_type": "VirtualRide",
+        "start_date": "2025-02-11T18:00:00Z",
+        "start_latlng": []
+    }
+]"#;
+
+const ACTIVITY: &str = r#"{
+    "id": 12345678987,
+    "name": "Morning Ride",
+    "athlete": { "id": 134815 },
+    "description": null,
+    "distance": 2000.0,
+    "moving_time": 420,
+    "elapsed_time": 600,
+    "sport_type": "GravelRide",
+    "start_date": "2025-02-12T08:00:00Z"
+}"#;
+
+const STREAMS: &str = r#"{
+    "latlng": {
+        "data": [[-36.8917, 146.9397], [-36.8920, 146.9450], [-36.8930, 146.9500]],
+        "series_type": "distance",
+        "original_size": 3,
+        "resolution": "high"
+    },
+    "altitude": {
+        "data": [520.0, 530.5, 541.0],
+        "series_type": "distance",
+        "original_size": 3,
+        "resolution": "high"
+    },
+    "time": {
+        "data": [0, 300, 600],
+        "series_type": "distance",
+        "original_size": 3,
+        "resolution": "high"
+    }
+}"#;
+
+/// Serves canned Strava API responses, keeping the request line and
+/// authorization header of each request. Returns the base URL to point the
+/// client at.
+async fn serve_stub(requests: Arc<Mutex<Vec<String>>>) -> String {
+    let addr = serve_http_stub(move |request| {
+        let request_line = request.lines().next().unwrap_or_default();
+        let authorization = request
+            .lines()
+            .find_map(|line| line.strip_prefix("authorization: "))
+            .unwrap_or_default();
+
+        requests
+            .lock()
+            .unwrap()
+            .push(format!("{request_line} ({authorization})"));
+
+        let body = if request_line.starts_with("GET /api/v3/athlete/activities?") {
+            ACTIVITIES
+        } else if request_line
+            .starts_with(&format!("GET /api/v3/activities/{ACTIVITY_ID}/streams?"))
+        {
+            STREAMS
+        } else {
+            ACTIVITY
+        };
+
+        body.to_string()
+    })
+    .await;
+
+    format!("http://{addr}/api/v3/")
+}
+
+async fn provider(requests: Arc<Mutex<Vec<String>>>) -> StravaProvider<StravaClient> {
+    StravaProvider {
+        client: StravaClient::with_base_url(serve_stub(requests).await.parse().unwrap()),
+    }
+}
+
+fn connection(user_id: UserId) -> StravaConnection {
+    StravaConnection {
+        user_id,
+        athlete_id: 134815,
+        access_token: String::from("test-token"),
+    }
+}
+
+fn select_params<'a>(
+    provider: &'a StravaProvider<StravaClient>,
+    connection: &'a StravaConnection,
+    repos: &Repos,
+) -> SelectSyncCandidatesParams<'a, StravaProvider<StravaClient>> {
+    SelectSyncCandidatesParams {
+        provider,
+        connection,
+        user_id: connection.user_id,
+        since: None,
+        route_repo: repos.route_repo.clone(),
+        ride_repo: repos.ride_repo.clone(),
+    }
+}
+
+fn sync_params(
+    provider: StravaProvider<StravaClient>,
+    connection: StravaConnection,
+    repos: &Repos,
+) -> SyncExternalRideParams<StravaProvider<StravaClient>> {
+    SyncExternalRideParams {
+        provider,
+        user_id: connection.user_id,
+        connection,
+        id: ExternalId::Strava(StravaId::Activity(ACTIVITY_ID)),
+        ride_repo: repos.ride_repo.clone(),
+        ride_points_repo: repos.ride_points_repo.clone(),
+    }
+}
+
+#[tokio::test]
+async fn test_sync_strava_activity() {
+    let requests = Arc::new(Mutex::new(vec![]));
+    let provider = provider(requests.clone()).await;
+    let repos = Repos::from(MemoryRepos::default());
+    let connection = connection(UserId::new());
+
+    // The trainer ride has no track, so only the outdoor ride is listed
+    let candidates = select_sync_candidates(select_params(&provider, &connection, &repos))
+        .await
+        .unwrap();
+    assert_eq!(
+        candidates.ids,
+        vec![ExternalId::Strava(StravaId::Activity(ACTIVITY_ID))]
+    );
+    // Strava doesn't say what changed, so there's nothing to pick up from
+    assert_eq!(candidates.cursor, None);
+
+    let (ride_id, _) =
+        sync_external_ride(sync_params(provider.clone(), connection.clone(), &repos))
+            .await
+            .unwrap();
+
+    let started_at = Utc.with_ymd_and_hms(2025, 2, 12, 8, 0, 0).unwrap();
+    let ride = repos.ride_repo.get(ride_id).await.unwrap();
+    assert_eq!(ride.name, "Morning Ride");
+    assert_eq!(ride.user_id, connection.user_id);
+    assert_eq!(ride.started_at, started_at);
+    assert_eq!(ride.finished_at, started_at + TimeDelta::minutes(10));
+
+    let ride_points = repos.ride_points_repo.get(ride_id).await.unwrap();
+    assert_eq!(ride_points.points.len(), 3);
+    assert_eq!(
+        ride_points.points[1].point,
+        geo::Point::new(146.9450, -36.8920)
+    );
+    assert_eq!(ride_points.points[1].elevation, 530.5);
+
+    // Synced activities are fresh, even though Strava doesn't say when they
+    // were last edited
+    let candidates = select_sync_candidates(select_params(&provider, &connection, &repos))
+        .await
+        .unwrap();
+    assert!(candidates.ids.is_empty());
+
+    let requests = requests.lock().unwrap();
+    assert_eq!(requests.len(), 4);
+    assert!(requests
+        .iter()
+        .all(|request| request.ends_with("(Bearer test-token)")));
+    assert!(requests[0].starts_with("GET /api/v3/athlete/activities?page=1&per_page=200 "));
+}
+
+#[tokio::test]
+async fn test_strava_leaves_rides_synced_from_rwgps() {
+    let requests = Arc::new(Mutex::new(vec![]));
+    let provider = provider(requests.clone()).await;
+    let repos = Repos::from(MemoryRepos::default());
+    let connection = connection(UserId::new());
+
+    // The same ride, already synced from RWGPS
+    let started_at = Utc.with_ymd_and_hms(2025, 2, 12, 8, 1, 0).unwrap();
+    let rwgps_ref = ExternalRef {
+        id: ExternalId::Rwgps(RwgpsId::Trip(42)),
+        updated_at: started_at,
+        sync_version: Some(2),
+    };
+    let ride = Ride {
+        id: RideId::new(),
+        name: String::from("Harrietville loop"),
+        user_id: connection.user_id,
+        distance: 2000.0,
+        started_at,
+        finished_at: started_at + TimeDelta::minutes(9),
+        external_ref: Some(rwgps_ref.clone()),
+        archived_at: None,
+    };
+    repos.ride_repo.put(ride.clone()).await.unwrap();
+
+    let (ride_id, _) = sync_external_ride(sync_params(provider, connection, &repos))
+        .await
+        .unwrap();
+
+    assert_eq!(ride_id, ride.id);
+    assert_eq!(
+        repos.ride_repo.get(ride.id).await.unwrap().external_ref,
+        Some(rwgps_ref)
+    );
+}
+
+#[tokio::test]
+async fn test_parse_strava_webhook() {
+    let provider = StravaProvider {
+        client: StravaClient::new(),
+    };
+
+    let events = provider
+        .parse_webhook(
+            br#"{
+                "aspect_type": "delete",
+                "event_time": 1739347200,
+                "object_id": 12345678987,
+                "object_type": "activity",
+                "owner_id": 134815,
+                "subscription_id": 120475,
+                "updates": {}
+            }"#,
+        )
+        .unwrap();
+    assert_eq!(
+        events,
+        vec![WebhookEvent {
+            owner_id: 134815,
+            id: ExternalId::Strava(StravaId::Activity(ACTIVITY_ID)),
+            action: WebhookAction::Remove,
+        }]
+    );
+
+    // Deauthorisations aren't about synced items
+    let events = provider
+        .parse_webhook(
+            br#"{
+                "aspect_type": "update",
+                "event_time": 1739347200,
+                "object_id": 134815,
+                "object_type": "athlete",
+                "owner_id": 134815,
+                "subscription_id": 120475,
+                "updates": { "authorized": "false" }
+            }"#,
+        )
+        .unwrap();
+    assert!(events.is_empty());
+}